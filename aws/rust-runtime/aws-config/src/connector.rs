@@ -5,11 +5,14 @@
 
 //! Functionality related to creating new HTTP Connectors
 
+use std::error::Error;
 use std::sync::Arc;
 
 use aws_smithy_async::rt::sleep::AsyncSleep;
 use aws_smithy_client::erase::DynConnector;
 use aws_smithy_client::http_connector::HttpSettings;
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::result::ConnectorError;
 
 // unused when all crate features are disabled
 /// Unwrap an [`Option<DynConnector>`](aws_smithy_client::erase::DynConnector), and panic with a helpful error message if it's `None`
@@ -17,13 +20,28 @@ pub(crate) fn expect_connector(connector: Option<DynConnector>) -> DynConnector
     connector.expect("A connector was not available. Either set a custom connector or enable the `rustls` and `native-tls` crate features.")
 }
 
+/// Create a connector that fails every request with `err`
+///
+/// This is used when a connector can't be created because of invalid configuration, so that the
+/// requests sent with it explain what's wrong instead of failing for lack of a connector.
+pub(crate) fn error_connector(err: impl Error + Send + Sync + 'static) -> DynConnector {
+    let err: Arc<dyn Error + Send + Sync> = Arc::new(err);
+    DynConnector::new(tower::service_fn(move |_: http::Request<SdkBody>| {
+        std::future::ready(Err::<http::Response<SdkBody>, _>(ConnectorError::other(
+            Box::new(err.clone()),
+            None,
+        )))
+    }))
+}
+
 #[cfg(any(feature = "rustls", feature = "native-tls"))]
 fn base(
     settings: &HttpSettings,
     sleep: Option<Arc<dyn AsyncSleep>>,
 ) -> aws_smithy_client::hyper_ext::Builder {
-    let mut hyper =
-        aws_smithy_client::hyper_ext::Adapter::builder().timeout(&settings.http_timeout_config);
+    let mut hyper = aws_smithy_client::hyper_ext::Adapter::builder()
+        .timeout(&settings.http_timeout_config)
+        .pool_config(&settings.pool_config);
     if let Some(sleep) = sleep {
         hyper = hyper.sleep_impl(sleep);
     }
@@ -31,22 +49,43 @@ fn base(
 }
 
 /// Given `HttpSettings` and an `AsyncSleep`, create a `DynConnector` from defaults depending on what cargo features are activated.
+///
+/// The connector uses a separate HTTP client for operations that can only be sent over HTTP/2.
+/// If the TLS configuration of `settings` is invalid, the connector fails every request with the
+/// TLS configuration error.
 #[cfg(feature = "rustls")]
 pub fn default_connector(
     settings: &HttpSettings,
     sleep: Option<Arc<dyn AsyncSleep>>,
 ) -> Option<DynConnector> {
-    let hyper = base(settings, sleep).build(aws_smithy_client::conns::https());
+    let https = match aws_smithy_client::conns::https_with_tls_config(&settings.tls_config) {
+        Ok(https) => https,
+        Err(err) => {
+            tracing::error!(err = %err, "invalid TLS configuration, requests will fail");
+            return Some(error_connector(err));
+        }
+    };
+    let hyper = base(settings, sleep).build_connector_cache(https);
     Some(DynConnector::new(hyper))
 }
 
 /// Given `HttpSettings` and an `AsyncSleep`, create a `DynConnector` from defaults depending on what cargo features are activated.
+///
+/// If the TLS configuration of `settings` is invalid, the connector fails every request with the
+/// TLS configuration error.
 #[cfg(all(not(feature = "rustls"), feature = "native-tls"))]
 pub fn default_connector(
     settings: &HttpSettings,
     sleep: Option<Arc<dyn AsyncSleep>>,
 ) -> Option<DynConnector> {
-    let hyper = base(settings, sleep).build(aws_smithy_client::conns::native_tls());
+    let https = match aws_smithy_client::conns::native_tls_with_tls_config(&settings.tls_config) {
+        Ok(https) => https,
+        Err(err) => {
+            tracing::error!(err = %err, "invalid TLS configuration, requests will fail");
+            return Some(error_connector(err));
+        }
+    };
+    let hyper = base(settings, sleep).build_connector_cache(https);
     Some(DynConnector::new(hyper))
 }
 
@@ -58,3 +97,31 @@ pub fn default_connector(
 ) -> Option<DynConnector> {
     None
 }
+
+#[cfg(all(test, feature = "rustls"))]
+mod test {
+    use super::default_connector;
+    use aws_smithy_client::http_connector::HttpSettings;
+    use aws_smithy_client::tls::TlsConfig;
+    use aws_smithy_http::body::SdkBody;
+    use tower::{Service, ServiceExt};
+
+    #[tokio::test]
+    async fn invalid_tls_config_fails_requests() {
+        let settings =
+            HttpSettings::default().with_tls_config(TlsConfig::new().with_native_roots(false));
+        let mut connector = default_connector(&settings, None).expect("a connector is created");
+        let err = connector
+            .ready()
+            .await
+            .unwrap()
+            .call(http::Request::new(SdkBody::empty()))
+            .await
+            .expect_err("the TLS configuration is invalid");
+        assert!(
+            format!("{}", err).contains("no root certificates"),
+            "{}",
+            err
+        );
+    }
+}
@@ -4,7 +4,7 @@
  */
 
 //! Default Provider chains for [`region`](default_provider::region), [`credentials`](default_provider::credentials),
-//! [retries](default_provider::retry_config), [timeouts](default_provider::timeout_config) and [app name](default_provider::app_name).
+//! [retries](default_provider::retry_config), [timeouts](default_provider::timeout_config), [app name](default_provider::app_name) and [TLS](default_provider::tls_config).
 //!
 //! Typically, this module is used via [`load_from_env`](crate::load_from_env) or [`from_env`](crate::from_env). It should only be used directly
 //! if you need to set custom configuration options to override the default resolution chain.
@@ -33,6 +33,12 @@ pub mod app_name;
 /// if you need to set custom configuration options to override the default resolution chain.
 pub mod timeout_config;
 
+/// Default TLS configuration provider chain
+///
+/// Typically, this module is used via [`load_from_env`](crate::load_from_env) or [`from_env`](crate::from_env). It should only be used directly
+/// if you need to set custom configuration options to override the default resolution chain.
+pub mod tls_config;
+
 /// Default credentials provider chain
 ///
 /// Typically, this module is used via [`load_from_env`](crate::load_from_env) or [`from_env`](crate::from_env). It should only be used directly
@@ -0,0 +1,164 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::environment::ca_bundle::EnvironmentVariableCaBundleProvider;
+use crate::profile::ca_bundle;
+use crate::provider_config::ProviderConfig;
+use aws_smithy_client::tls::{Certificate, TlsConfig};
+use aws_types::os_shim_internal::Fs;
+use std::error::Error;
+use std::fmt;
+
+/// Default TLS configuration provider chain
+///
+/// The path of a PEM encoded CA bundle is loaded from the following sources, in order:
+/// 1. [Environment variables](EnvironmentVariableCaBundleProvider) (`AWS_CA_BUNDLE`)
+/// 2. [Profile file](crate::profile::ca_bundle::ProfileFileCaBundleProvider) (`ca_bundle`)
+///
+/// Like the AWS CLI, the certificates in the CA bundle _replace_ the platform's root
+/// certificates, so the bundle must also contain the public roots needed to reach AWS services.
+pub fn default_provider() -> Builder {
+    Builder::default()
+}
+
+/// Default provider builder for [`TlsConfig`]
+#[derive(Default)]
+pub struct Builder {
+    env_provider: EnvironmentVariableCaBundleProvider,
+    profile_file: ca_bundle::Builder,
+    fs: Fs,
+}
+
+impl Builder {
+    #[doc(hidden)]
+    /// Configure the default chain
+    ///
+    /// Exposed for overriding the environment when unit-testing providers
+    pub fn configure(mut self, configuration: &ProviderConfig) -> Self {
+        self.env_provider = EnvironmentVariableCaBundleProvider::new_with_env(configuration.env());
+        self.profile_file = self.profile_file.configure(configuration);
+        self.fs = configuration.fs();
+        self
+    }
+
+    /// Override the profile name used by this provider
+    pub fn profile_name(mut self, name: &str) -> Self {
+        self.profile_file = self.profile_file.profile_name(name);
+        self
+    }
+
+    /// Build a [`TlsConfig`] from the default chain
+    ///
+    /// Returns `None` if no CA bundle is configured. If a CA bundle is configured but can't be
+    /// read, this returns an error rather than falling back to the platform's root certificates.
+    pub async fn tls_config(self) -> Result<Option<TlsConfig>, CaBundleError> {
+        let path = match self.env_provider.ca_bundle_path() {
+            Some(path) => path,
+            None => match self.profile_file.build().ca_bundle_path().await {
+                Some(path) => path,
+                None => return Ok(None),
+            },
+        };
+        match self.fs.read_to_end(&path).await {
+            Ok(bundle) => Ok(Some(
+                TlsConfig::new()
+                    .with_native_roots(false)
+                    .with_root_certificate(Certificate::from_pem(bundle)),
+            )),
+            Err(source) => Err(CaBundleError { path, source }),
+        }
+    }
+}
+
+/// Error returned when the configured CA bundle can't be read
+#[derive(Debug)]
+pub struct CaBundleError {
+    path: String,
+    source: std::io::Error,
+}
+
+impl CaBundleError {
+    /// The path of the CA bundle
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl fmt::Display for CaBundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to read the configured CA bundle `{}`", self.path)
+    }
+}
+
+impl Error for CaBundleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider_config::ProviderConfig;
+    use crate::test_case::no_traffic_connector;
+    use aws_types::os_shim_internal::{Env, Fs};
+
+    async fn tls_config(fs: Fs, env: Env) -> Result<Option<TlsConfig>, CaBundleError> {
+        Builder::default()
+            .configure(
+                &ProviderConfig::no_configuration()
+                    .with_fs(fs)
+                    .with_env(env)
+                    .with_http_connector(no_traffic_connector()),
+            )
+            .tls_config()
+            .await
+    }
+
+    #[tokio::test]
+    async fn prefer_env_to_profile() {
+        let fs = Fs::from_slice(&[
+            ("test_config", "[default]\nca_bundle = profile.pem"),
+            ("env.pem", "env bundle"),
+        ]);
+        let env = Env::from_slice(&[
+            ("AWS_CONFIG_FILE", "test_config"),
+            ("AWS_CA_BUNDLE", "env.pem"),
+        ]);
+        let tls_config = tls_config(fs, env)
+            .await
+            .unwrap()
+            .expect("bundle configured");
+        assert!(!tls_config.native_roots());
+        assert_eq!(1, tls_config.root_certificates().len());
+    }
+
+    #[tokio::test]
+    async fn load_from_profile() {
+        let fs = Fs::from_slice(&[
+            ("test_config", "[default]\nca_bundle = profile.pem"),
+            ("profile.pem", "profile bundle"),
+        ]);
+        let env = Env::from_slice(&[("AWS_CONFIG_FILE", "test_config")]);
+        assert!(tls_config(fs, env).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn missing_bundle() {
+        let env = Env::from_slice(&[("AWS_CA_BUNDLE", "missing.pem")]);
+        let err = tls_config(Fs::from_slice(&[]), env)
+            .await
+            .expect_err("the bundle can't be read");
+        assert_eq!("missing.pem", err.path());
+    }
+
+    #[tokio::test]
+    async fn no_bundle_configured() {
+        assert!(tls_config(Fs::from_slice(&[]), Env::from_slice(&[]))
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,59 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_types::os_shim_internal::Env;
+
+/// Load the path of a CA bundle from the `AWS_CA_BUNDLE` environment variable.
+#[derive(Debug, Default)]
+pub struct EnvironmentVariableCaBundleProvider {
+    env: Env,
+}
+
+impl EnvironmentVariableCaBundleProvider {
+    /// Create a new `EnvironmentVariableCaBundleProvider`
+    pub fn new() -> Self {
+        Self { env: Env::real() }
+    }
+
+    #[doc(hidden)]
+    /// Create a CA bundle provider from a given `Env`
+    ///
+    /// This method is used for tests that need to override environment variables.
+    pub fn new_with_env(env: Env) -> Self {
+        Self { env }
+    }
+
+    /// Attempts to load the path of a CA bundle from the `AWS_CA_BUNDLE` environment variable.
+    pub fn ca_bundle_path(&self) -> Option<String> {
+        match self.env.get("AWS_CA_BUNDLE") {
+            Ok(path) if !path.is_empty() => Some(path),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::environment::EnvironmentVariableCaBundleProvider;
+    use aws_types::os_shim_internal::Env;
+
+    #[test]
+    fn env_var_not_set() {
+        let provider = EnvironmentVariableCaBundleProvider::new_with_env(Env::from_slice(&[]));
+        assert_eq!(None, provider.ca_bundle_path());
+    }
+
+    #[test]
+    fn env_var_set() {
+        let provider = EnvironmentVariableCaBundleProvider::new_with_env(Env::from_slice(&[(
+            "AWS_CA_BUNDLE",
+            "/etc/pki/internal-ca.pem",
+        )]));
+        assert_eq!(
+            Some("/etc/pki/internal-ca.pem".to_string()),
+            provider.ca_bundle_path()
+        );
+    }
+}
@@ -7,6 +7,10 @@
 pub mod app_name;
 pub use app_name::EnvironmentVariableAppNameProvider;
 
+/// Load the path of a CA bundle from the environment
+pub mod ca_bundle;
+pub use ca_bundle::EnvironmentVariableCaBundleProvider;
+
 /// Load credentials from the environment
 pub mod credentials;
 pub use credentials::EnvironmentVariableCredentialsProvider;
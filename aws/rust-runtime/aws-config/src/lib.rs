@@ -158,9 +158,12 @@ pub use loader::ConfigLoader;
 mod loader {
     use std::sync::Arc;
 
-    use crate::connector::default_connector;
+    use crate::connector::{default_connector, error_connector};
     use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep};
+    use aws_smithy_async::time::{SharedTimeSource, TimeSource};
     use aws_smithy_client::http_connector::{HttpConnector, HttpSettings};
+    use aws_smithy_client::pool::PoolConfig;
+    use aws_smithy_client::tls::TlsConfig;
     use aws_smithy_types::retry::RetryConfig;
     use aws_smithy_types::timeout;
     use aws_types::app_name::AppName;
@@ -168,7 +171,9 @@ mod loader {
     use aws_types::endpoint::ResolveAwsEndpoint;
     use aws_types::SdkConfig;
 
-    use crate::default_provider::{app_name, credentials, region, retry_config, timeout_config};
+    use crate::default_provider::{
+        app_name, credentials, region, retry_config, timeout_config, tls_config,
+    };
     use crate::meta::region::ProvideRegion;
     use crate::provider_config::ProviderConfig;
 
@@ -189,6 +194,9 @@ mod loader {
         timeout_config: Option<timeout::Config>,
         provider_config: Option<ProviderConfig>,
         http_connector: Option<HttpConnector>,
+        time_source: Option<SharedTimeSource>,
+        tls_config: Option<TlsConfig>,
+        pool_config: Option<PoolConfig>,
     }
 
     impl ConfigLoader {
@@ -256,6 +264,64 @@ mod loader {
             self
         }
 
+        /// Override the time source used to build [`SdkConfig`](aws_types::SdkConfig).
+        ///
+        /// The time source is used by the credential providers loaded here, and by clients
+        /// created from the resulting config when signing requests.
+        pub fn time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+            self.time_source = Some(SharedTimeSource::new(time_source));
+            self
+        }
+
+        /// Override the TLS configuration used by the default HTTPS connectors
+        ///
+        /// By default, a CA bundle is loaded from the `AWS_CA_BUNDLE` environment variable or the
+        /// `ca_bundle` profile property. See [`default_provider::tls_config`](crate::default_provider::tls_config).
+        /// If that CA bundle can't be read, requests fail with an error that says so.
+        /// The TLS configuration is used both by clients created from the resulting config and by the
+        /// credential providers loaded here. It has no effect if a custom
+        /// [`http_connector`](ConfigLoader::http_connector) is set.
+        ///
+        /// # Examples
+        /// ```no_run
+        /// # async fn create_config() {
+        /// use aws_smithy_client::tls::{Certificate, TlsConfig};
+        /// let internal_ca = std::fs::read("/etc/pki/internal-ca.pem").unwrap();
+        /// let config = aws_config::from_env()
+        ///     .tls_config(TlsConfig::new().with_root_certificate(Certificate::from_pem(internal_ca)))
+        ///     .load()
+        ///     .await;
+        /// # }
+        /// ```
+        pub fn tls_config(mut self, tls_config: TlsConfig) -> Self {
+            self.tls_config = Some(tls_config);
+            self
+        }
+
+        /// Configure the connection pool of the default HTTPS connectors
+        ///
+        /// The pool configuration is only used by connectors created for clients, not by the
+        /// credential providers loaded here. It has no effect if a custom
+        /// [`http_connector`](ConfigLoader::http_connector) is set.
+        ///
+        /// # Examples
+        /// Close idle connections before the server does, e.g. in AWS Lambda:
+        /// ```no_run
+        /// # async fn create_config() {
+        /// use std::time::Duration;
+        /// use aws_smithy_client::pool::PoolConfig;
+        /// use aws_smithy_types::tristate::TriState;
+        /// let config = aws_config::from_env()
+        ///     .pool_config(PoolConfig::new().with_idle_timeout(TriState::Set(Duration::from_secs(20))))
+        ///     .load()
+        ///     .await;
+        /// # }
+        /// ```
+        pub fn pool_config(mut self, pool_config: PoolConfig) -> Self {
+            self.pool_config = Some(pool_config);
+            self
+        }
+
         /// Override the [`HttpConnector`] used to build [`SdkConfig`](aws_types::SdkConfig).
         pub fn http_connector(mut self, http_connector: HttpConnector) -> Self {
             self.http_connector = Some(http_connector);
@@ -344,7 +410,27 @@ mod loader {
         /// This means that if you provide a region provider that does not return a region, no region will
         /// be set in the resulting [`SdkConfig`](aws_types::SdkConfig)
         pub async fn load(self) -> SdkConfig {
-            let conf = self.provider_config.unwrap_or_default();
+            let mut conf = self.provider_config.unwrap_or_default();
+            if let Some(time_source) = &self.time_source {
+                conf = conf.with_time_source(time_source.clone().into());
+            }
+            let tls_config = if self.tls_config.is_some() {
+                Ok(self.tls_config)
+            } else {
+                tls_config::default_provider()
+                    .configure(&conf)
+                    .tls_config()
+                    .await
+                    .map_err(|err| {
+                        tracing::error!(err = %err, "invalid TLS configuration, requests will fail");
+                        Arc::new(err) as Arc<dyn std::error::Error + Send + Sync>
+                    })
+            };
+            match &tls_config {
+                Ok(Some(tls_config)) => conf = conf.with_tls_config(tls_config.clone()),
+                Ok(None) => {}
+                Err(err) => conf = conf.with_connector_error(err.clone()),
+            }
             let region = if let Some(provider) = self.region {
                 provider.region().await
             } else {
@@ -390,11 +476,15 @@ mod loader {
 
             let http_connector = if let Some(http_connector) = self.http_connector {
                 http_connector
+            } else if let Err(err) = tls_config {
+                HttpConnector::Prebuilt(Some(error_connector(err)))
             } else {
                 let timeouts = self.timeout_config.clone().unwrap_or_default();
                 let settings = HttpSettings::default()
                     .with_http_timeout_config(timeouts.http_timeouts())
-                    .with_tcp_timeout_config(timeouts.tcp_timeouts());
+                    .with_tcp_timeout_config(timeouts.tcp_timeouts())
+                    .with_tls_config(tls_config.ok().flatten().unwrap_or_default())
+                    .with_pool_config(self.pool_config.unwrap_or_default());
                 let sleep_impl = sleep_impl.clone();
                 HttpConnector::Prebuilt(default_connector(&settings, sleep_impl))
             };
@@ -428,6 +518,7 @@ mod loader {
             builder.set_endpoint_resolver(endpoint_resolver);
             builder.set_app_name(app_name);
             builder.set_sleep_impl(sleep_impl);
+            builder.set_time_source(self.time_source);
             builder.build()
         }
     }
@@ -437,9 +528,11 @@ mod loader {
         use crate::from_env;
         use crate::provider_config::ProviderConfig;
         use aws_smithy_client::erase::DynConnector;
+        use aws_smithy_client::http_connector::HttpSettings;
         use aws_smithy_client::never::NeverConnector;
         use aws_types::credentials::ProvideCredentials;
-        use aws_types::os_shim_internal::Env;
+        use aws_types::os_shim_internal::{Env, Fs};
+        use tower::{Service, ServiceExt};
 
         #[tokio::test]
         async fn provider_config_used() {
@@ -470,5 +563,39 @@ mod loader {
                 "akid"
             );
         }
+
+        #[tokio::test]
+        async fn unreadable_ca_bundle_fails_requests() {
+            let env = Env::from_slice(&[
+                ("AWS_REGION", "us-west-4"),
+                ("AWS_CA_BUNDLE", "missing.pem"),
+            ]);
+            let config = from_env()
+                .configure(
+                    ProviderConfig::empty()
+                        .with_env(env)
+                        .with_fs(Fs::from_slice(&[])),
+                )
+                .load()
+                .await;
+            let mut connector = config
+                .http_connector()
+                .expect("connector set")
+                .connector(&HttpSettings::default(), None)
+                .expect("a connector is created");
+            let err = connector
+                .ready()
+                .await
+                .unwrap()
+                .call(http::Request::new(Default::default()))
+                .await
+                .expect_err("the CA bundle can't be read");
+            assert!(
+                format!("{}", err)
+                    .contains("failed to read the configured CA bundle `missing.pem`"),
+                "{}",
+                err
+            );
+        }
     }
 }
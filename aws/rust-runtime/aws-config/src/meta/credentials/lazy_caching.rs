@@ -299,8 +299,10 @@ mod tests {
     use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
     use aws_smithy_async::rt::sleep::TokioSleep;
+    use aws_smithy_async::test_util::manual_time_and_sleep;
     use aws_types::credentials::{self, CredentialsError, ProvideCredentials};
     use aws_types::Credentials;
+    use futures_util::FutureExt;
     use tracing::info;
     use tracing_test::traced_test;
 
@@ -485,4 +487,32 @@ mod tests {
             Err(CredentialsError::ProviderTimedOut { .. })
         ));
     }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn load_timeout_in_virtual_time() {
+        let (time, sleep) = manual_time_and_sleep(epoch_secs(100));
+        let provider = LazyCachingCredentialsProvider::new(
+            TimeSource::from(time.clone()),
+            Arc::new(sleep),
+            Arc::new(provide_credentials_fn(|| async {
+                aws_smithy_async::future::never::Never::new().await;
+                Ok(credentials(1000))
+            })),
+            DEFAULT_LOAD_TIMEOUT,
+            DEFAULT_CREDENTIAL_EXPIRATION,
+            DEFAULT_BUFFER_TIME,
+        );
+
+        let credentials = provider.provide_credentials();
+        tokio::pin!(credentials);
+        assert!((&mut credentials).now_or_never().is_none());
+        assert_eq!(vec![DEFAULT_LOAD_TIMEOUT], time.sleeps());
+
+        time.advance(DEFAULT_LOAD_TIMEOUT);
+        assert!(matches!(
+            credentials.await,
+            Err(CredentialsError::ProviderTimedOut { .. })
+        ));
+    }
 }
@@ -0,0 +1,140 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Load the path of a CA bundle from an AWS profile
+
+use crate::provider_config::ProviderConfig;
+use aws_types::os_shim_internal::{Env, Fs};
+
+/// Loads the path of a CA bundle from a profile file
+///
+/// This provider will attempt to load AWS shared configuration and then read the `ca_bundle`
+/// property from the active profile.
+///
+/// # Examples
+///
+/// **Trusts the certificates in `/etc/pki/internal-ca.pem`**
+/// ```ini
+/// [default]
+/// ca_bundle = /etc/pki/internal-ca.pem
+/// ```
+///
+/// This provider is part of the [default TLS configuration provider chain](crate::default_provider::tls_config).
+#[derive(Debug, Default)]
+pub struct ProfileFileCaBundleProvider {
+    fs: Fs,
+    env: Env,
+    profile_override: Option<String>,
+}
+
+impl ProfileFileCaBundleProvider {
+    /// Create a new [`ProfileFileCaBundleProvider`]
+    ///
+    /// To override the selected profile, set the `AWS_PROFILE` environment variable or use the [`Builder`].
+    pub fn new() -> Self {
+        Self {
+            fs: Fs::real(),
+            env: Env::real(),
+            profile_override: None,
+        }
+    }
+
+    /// [`Builder`] to construct a [`ProfileFileCaBundleProvider`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Parses the profile config and attempts to find the path of a CA bundle.
+    pub async fn ca_bundle_path(&self) -> Option<String> {
+        let profile = super::parser::load(&self.fs, &self.env)
+            .await
+            .map_err(|err| tracing::warn!(err = %err, "failed to parse profile"))
+            .ok()?;
+        let selected_profile_name = self
+            .profile_override
+            .as_deref()
+            .unwrap_or_else(|| profile.selected_profile());
+        let selected_profile = profile.get_profile(selected_profile_name)?;
+        selected_profile
+            .get("ca_bundle")
+            .filter(|path| !path.is_empty())
+            .map(|path| path.to_owned())
+    }
+}
+
+/// Builder for [`ProfileFileCaBundleProvider`]
+#[derive(Default)]
+pub struct Builder {
+    config: Option<ProviderConfig>,
+    profile_override: Option<String>,
+}
+
+impl Builder {
+    /// Override the configuration for this provider
+    pub fn configure(mut self, config: &ProviderConfig) -> Self {
+        self.config = Some(config.clone());
+        self
+    }
+
+    /// Override the profile name used by the [`ProfileFileCaBundleProvider`]
+    pub fn profile_name(mut self, profile_name: impl Into<String>) -> Self {
+        self.profile_override = Some(profile_name.into());
+        self
+    }
+
+    /// Build a [`ProfileFileCaBundleProvider`] from this builder
+    pub fn build(self) -> ProfileFileCaBundleProvider {
+        let conf = self.config.unwrap_or_default();
+        ProfileFileCaBundleProvider {
+            env: conf.env(),
+            fs: conf.fs(),
+            profile_override: self.profile_override,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProfileFileCaBundleProvider;
+    use crate::provider_config::ProviderConfig;
+    use crate::test_case::no_traffic_connector;
+    use aws_types::os_shim_internal::{Env, Fs};
+
+    fn provider_config(config_contents: &str) -> ProviderConfig {
+        let fs = Fs::from_slice(&[("test_config", config_contents)]);
+        let env = Env::from_slice(&[("AWS_CONFIG_FILE", "test_config")]);
+        ProviderConfig::empty()
+            .with_fs(fs)
+            .with_env(env)
+            .with_http_connector(no_traffic_connector())
+    }
+
+    #[tokio::test]
+    async fn no_ca_bundle() {
+        let provider = ProfileFileCaBundleProvider::builder()
+            .configure(&provider_config("[default]\n"))
+            .build();
+        assert_eq!(None, provider.ca_bundle_path().await);
+    }
+
+    #[tokio::test]
+    async fn ca_bundle_other_profile() {
+        let config = "\
+            [default]\n\
+            ca_bundle = default.pem\n\
+            \n\
+            [profile other]\n\
+            ca_bundle = other.pem\n
+        ";
+        let provider = ProfileFileCaBundleProvider::builder()
+            .profile_name("other")
+            .configure(&provider_config(config))
+            .build();
+        assert_eq!(
+            Some("other.pem".to_string()),
+            provider.ca_bundle_path().await
+        );
+    }
+}
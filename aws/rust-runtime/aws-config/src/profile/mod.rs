@@ -13,6 +13,7 @@ mod parser;
 pub use parser::{load, Profile, ProfileParseError, ProfileSet, Property};
 
 pub mod app_name;
+pub mod ca_bundle;
 pub mod credentials;
 pub mod region;
 pub mod retry_config;
@@ -7,6 +7,7 @@
 
 use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep};
 use aws_smithy_client::erase::DynConnector;
+use aws_smithy_client::tls::TlsConfig;
 use aws_types::os_shim_internal::{Env, Fs, TimeSource};
 use aws_types::{
     http_connector::{HttpConnector, HttpSettings},
@@ -17,7 +18,7 @@ use std::error::Error;
 use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
 
-use crate::connector::default_connector;
+use crate::connector::{default_connector, error_connector};
 use http::Uri;
 use hyper::client::connect::Connection;
 use tokio::io::{AsyncRead, AsyncWrite};
@@ -249,7 +250,8 @@ impl ProviderConfig {
     {
         let connector_fn = move |settings: &HttpSettings, sleep: Option<Arc<dyn AsyncSleep>>| {
             let mut builder = aws_smithy_client::hyper_ext::Adapter::builder()
-                .timeout(&settings.http_timeout_config);
+                .timeout(&settings.http_timeout_config)
+                .pool_config(&settings.pool_config);
             if let Some(sleep) = sleep {
                 builder = builder.sleep_impl(sleep);
             };
@@ -261,6 +263,40 @@ impl ProviderConfig {
         }
     }
 
+    /// Use `tls_config` for the connectors created by this configuration
+    ///
+    /// This has no effect on connectors set with [`with_http_connector`](ProviderConfig::with_http_connector)
+    /// or [`with_tcp_connector`](ProviderConfig::with_tcp_connector).
+    pub(crate) fn with_tls_config(self, tls_config: TlsConfig) -> Self {
+        let connector = match self.connector {
+            HttpConnector::ConnectorFn(make_connector) => {
+                HttpConnector::ConnectorFn(Arc::new(move |settings: &HttpSettings, sleep| {
+                    let settings = settings.clone().with_tls_config(tls_config.clone());
+                    make_connector(&settings, sleep)
+                }))
+            }
+            prebuilt => prebuilt,
+        };
+        ProviderConfig { connector, ..self }
+    }
+
+    /// Fail every request sent by the connectors created by this configuration with `err`
+    ///
+    /// This is used when the TLS configuration can't be loaded. Like
+    /// [`with_tls_config`](ProviderConfig::with_tls_config), it has no effect on connectors set
+    /// with [`with_http_connector`](ProviderConfig::with_http_connector).
+    pub(crate) fn with_connector_error(self, err: Arc<dyn Error + Send + Sync>) -> Self {
+        let connector = match self.connector {
+            HttpConnector::ConnectorFn(_) => {
+                HttpConnector::ConnectorFn(Arc::new(move |_: &HttpSettings, _| {
+                    Some(error_connector(err.clone()))
+                }))
+            }
+            prebuilt => prebuilt,
+        };
+        ProviderConfig { connector, ..self }
+    }
+
     /// Override the sleep implementation for this configuration
     pub fn with_sleep(self, sleep: impl AsyncSleep + 'static) -> Self {
         ProviderConfig {
@@ -4,10 +4,15 @@
  */
 //! AWS-specific retry logic
 
+use aws_smithy_async::time::{ClockSkew, SharedTimeSource, TimeSource};
+use aws_smithy_http::operation;
 use aws_smithy_http::result::SdkError;
 use aws_smithy_http::retry::ClassifyResponse;
+use aws_smithy_types::date_time::Format;
 use aws_smithy_types::retry::{ErrorKind, ProvideErrorKind, RetryKind};
-use std::time::Duration;
+use aws_smithy_types::DateTime;
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime};
 
 /// A retry policy that models AWS error codes as outlined in the SEP
 ///
@@ -15,7 +20,10 @@ use std::time::Duration;
 /// 1. The `x-amz-retry-after` header is checked
 /// 2. The modeled error retry mode is checked
 /// 3. The code is checked against a predetermined list of throttling errors & transient error codes
-/// 4. The status code is checked against a predetermined list of status codes
+/// 4. The code is checked against a predetermined list of clock skew errors. If the server's `Date`
+///    header shows that the local clock is skewed, the [`ClockSkew`] in the property bag is corrected
+///    and the request is retried.
+/// 5. The status code is checked against a predetermined list of status codes
 #[non_exhaustive]
 #[derive(Clone, Debug)]
 pub struct AwsErrorRetryPolicy;
@@ -38,6 +46,16 @@ const THROTTLING_ERRORS: &[&str] = &[
     "EC2ThrottledException",
 ];
 const TRANSIENT_ERRORS: &[&str] = &["RequestTimeout", "RequestTimeoutException"];
+const CLOCK_SKEW_ERRORS: &[&str] = &[
+    "RequestTimeTooSkewed",
+    "RequestExpired",
+    "RequestInTheFuture",
+    "InvalidSignatureException",
+    "SignatureDoesNotMatch",
+    "AuthFailure",
+];
+/// Smallest difference between the corrected local clock and the server's clock that is treated as skew
+const CLOCK_SKEW_THRESHOLD: Duration = Duration::from_secs(4 * 60);
 
 impl AwsErrorRetryPolicy {
     /// Create an `AwsErrorRetryPolicy` with the default set of known error & status codes
@@ -90,6 +108,9 @@ where
             if TRANSIENT_ERRORS.contains(&code) {
                 return RetryKind::Error(ErrorKind::TransientError);
             }
+            if CLOCK_SKEW_ERRORS.contains(&code) && correct_clock_skew(response) {
+                return RetryKind::Error(ErrorKind::ClientError);
+            }
         };
         if TRANSIENT_ERROR_STATUS_CODES.contains(&response.http().status().as_u16()) {
             return RetryKind::Error(ErrorKind::TransientError);
@@ -99,6 +120,47 @@ where
     }
 }
 
+/// Updates the [`ClockSkew`] in the property bag from the server's `Date` header
+///
+/// Returns false if there is nothing to correct, either because the skew can't be determined or
+/// because the corrected local clock already agrees with the server.
+fn correct_clock_skew(response: &operation::Response) -> bool {
+    let server_time = match response
+        .http()
+        .headers()
+        .get(http::header::DATE)
+        .and_then(|date| date.to_str().ok())
+        .and_then(|date| DateTime::from_str(date, Format::HttpDate).ok())
+        .and_then(|date| SystemTime::try_from(date).ok())
+    {
+        Some(server_time) => server_time,
+        None => return false,
+    };
+    let properties = response.properties();
+    let skew = match properties.get::<ClockSkew>() {
+        Some(skew) => skew,
+        None => return false,
+    };
+    let local_time = properties
+        .get::<SharedTimeSource>()
+        .map(|time_source| time_source.now())
+        .unwrap_or_else(SystemTime::now);
+    let corrected_time = skew.apply(local_time);
+    let difference = server_time
+        .duration_since(corrected_time)
+        .unwrap_or_else(|behind| behind.duration());
+    if difference < CLOCK_SKEW_THRESHOLD {
+        return false;
+    }
+    tracing::warn!(
+        server_time = ?server_time,
+        local_time = ?local_time,
+        "the local clock is skewed; correcting it to the server's clock"
+    );
+    skew.update(local_time, server_time);
+    true
+}
+
 #[cfg(test)]
 mod test {
     use crate::retry::AwsErrorRetryPolicy;
@@ -253,4 +315,58 @@ mod test {
             RetryKind::Explicit(Duration::from_millis(5000))
         );
     }
+
+    #[test]
+    fn corrects_clock_skew() {
+        use aws_smithy_async::test_util::ManualTimeSource;
+        use aws_smithy_async::time::{ClockSkew, SharedTimeSource};
+        use std::time::UNIX_EPOCH;
+
+        let policy = AwsErrorRetryPolicy::new();
+        // Wed, 20 Jan 2021 16:33:47 GMT
+        let server_time = UNIX_EPOCH + Duration::from_secs(1611160427);
+        let local_time = server_time - Duration::from_secs(20 * 60);
+        let skew = ClockSkew::new();
+        let skewed_response = |code: &'static str| {
+            let raw = http::Response::builder()
+                .status(403)
+                .header("date", "Wed, 20 Jan 2021 16:33:47 GMT")
+                .body("skewed")
+                .unwrap();
+            let mut result = make_err(CodedError { code }, raw);
+            if let Err(SdkError::ServiceError { raw, .. }) = &mut result {
+                let mut properties = raw.properties_mut();
+                properties.insert(SharedTimeSource::new(ManualTimeSource::new(local_time)));
+                properties.insert(skew.clone());
+            }
+            result
+        };
+
+        assert_eq!(
+            policy.classify(skewed_response("RequestTimeTooSkewed").as_ref()),
+            RetryKind::Error(ErrorKind::ClientError)
+        );
+        assert_eq!(server_time, skew.apply(local_time));
+
+        // once corrected, the same error is no longer attributed to clock skew
+        assert_eq!(
+            policy.classify(skewed_response("SignatureDoesNotMatch").as_ref()),
+            RetryKind::UnretryableFailure
+        );
+
+        // without a date header there's nothing to correct
+        let test_response = http::Response::builder().status(403).body("").unwrap();
+        assert_eq!(
+            policy.classify(
+                make_err(
+                    CodedError {
+                        code: "RequestTimeTooSkewed"
+                    },
+                    test_response
+                )
+                .as_ref()
+            ),
+            RetryKind::UnretryableFailure
+        );
+    }
 }
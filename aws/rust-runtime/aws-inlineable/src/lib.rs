@@ -24,6 +24,12 @@ pub mod no_credentials;
 /// Support types required for adding presigning to an operation in a generated service.
 pub mod presigning;
 
+/// Presigned WebSocket URLs for event stream operations.
+pub mod presigning_websocket;
+
+/// Presigned POST policies for browser-based uploads to S3.
+pub mod presigned_post;
+
 /// Special logic for handling S3's error responses.
 pub mod s3_errors;
 
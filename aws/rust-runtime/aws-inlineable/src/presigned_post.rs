@@ -0,0 +1,506 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Presigned POST policies for browser-based uploads to S3
+//!
+//! A presigned POST lets a browser upload an object with an HTML form, without credentials. The
+//! form posts to the [`url`](PresignedPost::url) of the bucket, with every one of the
+//! [`fields`](PresignedPost::fields) as a hidden input, followed by the `file` input. The form
+//! fields include a base64 encoded policy document that lists the conditions the upload must
+//! meet, and a SigV4 signature over that policy.
+
+use crate::presigning::config::PresigningConfig;
+use aws_sigv4::sign::{calculate_signature, generate_signing_key};
+use aws_smithy_json::serialize::{JsonArrayWriter, JsonObjectWriter};
+use aws_smithy_types::date_time::{DateTimeFormatError, Format};
+use aws_smithy_types::{base64, DateTime, Number};
+use aws_types::region::Region;
+use aws_types::Credentials;
+use std::error::Error as StdError;
+use std::fmt;
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+const SERVICE: &str = "s3";
+
+/// A condition of a POST policy that the upload must meet
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Condition {
+    /// The form field `field` must be exactly `value`
+    Equals {
+        /// Name of the form field, e.g. `key` or `Content-Type`
+        field: String,
+        /// Required value of the form field
+        value: String,
+    },
+    /// The form field `field` must start with `prefix`
+    StartsWith {
+        /// Name of the form field, e.g. `key` or `Content-Type`
+        field: String,
+        /// Required prefix of the form field, can be empty to allow any value
+        prefix: String,
+    },
+    /// The size of the uploaded file, in bytes, must be between `min` and `max` (inclusive)
+    ContentLengthRange {
+        /// Minimum size of the file
+        min: u64,
+        /// Maximum size of the file
+        max: u64,
+    },
+}
+
+impl Condition {
+    /// Requires the form field `field` to be exactly `value`.
+    pub fn equals(field: impl Into<String>, value: impl Into<String>) -> Self {
+        Condition::Equals {
+            field: field.into(),
+            value: value.into(),
+        }
+    }
+
+    /// Requires the form field `field` to start with `prefix`.
+    pub fn starts_with(field: impl Into<String>, prefix: impl Into<String>) -> Self {
+        Condition::StartsWith {
+            field: field.into(),
+            prefix: prefix.into(),
+        }
+    }
+
+    /// Requires the uploaded file to be between `min` and `max` bytes long (inclusive).
+    ///
+    /// Signing a policy fails if `min` is larger than `max`.
+    pub fn content_length_range(min: u64, max: u64) -> Self {
+        Condition::ContentLengthRange { min, max }
+    }
+
+    fn write_to(&self, conditions: &mut JsonArrayWriter<'_>) {
+        match self {
+            Condition::Equals { field, value } => {
+                let mut object = conditions.value().start_object();
+                object.key(field).string(value);
+                object.finish();
+            }
+            Condition::StartsWith { field, prefix } => {
+                let mut array = conditions.value().start_array();
+                array.value().string("starts-with");
+                array.value().string(&format!("${}", field));
+                array.value().string(prefix);
+                array.finish();
+            }
+            Condition::ContentLengthRange { min, max } => {
+                let mut array = conditions.value().start_array();
+                array.value().string("content-length-range");
+                array.value().number(Number::PosInt(*min));
+                array.value().number(Number::PosInt(*max));
+                array.finish();
+            }
+        }
+    }
+}
+
+/// A signed POST policy: the URL to post the upload form to and the fields of the form
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct PresignedPost {
+    url: String,
+    fields: Vec<(String, String)>,
+}
+
+impl PresignedPost {
+    /// Returns a new builder for a `PresignedPost`.
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Returns the URL the form must be posted to.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the names and values of the form fields, including the policy and signature.
+    ///
+    /// The form must contain these fields in this order, followed by any fields the policy
+    /// allows with [`Condition::StartsWith`], and then the `file` field, which must be last.
+    pub fn fields(&self) -> &[(String, String)] {
+        &self.fields
+    }
+
+    /// Returns the value of the form field named `name`, if any.
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// `PresignedPost` build errors
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum Error {
+    /// The `PresignedPost` builder requires a value for `bucket`.
+    BucketRequired,
+
+    /// The `PresignedPost` builder requires a value for `endpoint`.
+    EndpointRequired,
+
+    /// A [`Condition::ContentLengthRange`] has a minimum that's larger than its maximum.
+    InvalidContentLengthRange {
+        /// Minimum size of the file
+        min: u64,
+        /// Maximum size of the file
+        max: u64,
+    },
+
+    /// The start or expiration time of the policy can't be formatted.
+    InvalidTime(DateTimeFormatError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::BucketRequired => write!(f, "`bucket` is required"),
+            Error::EndpointRequired => write!(f, "`endpoint` is required"),
+            Error::InvalidContentLengthRange { min, max } => write!(
+                f,
+                "the minimum content length ({}) is larger than the maximum ({})",
+                min, max
+            ),
+            Error::InvalidTime(_) => write!(f, "the policy time can't be formatted"),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::InvalidTime(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Builder used to create a `PresignedPost`.
+#[non_exhaustive]
+#[derive(Clone, Default, Debug)]
+pub struct Builder {
+    bucket: Option<String>,
+    endpoint: Option<String>,
+    fields: Vec<(String, String)>,
+    conditions: Vec<Condition>,
+}
+
+impl Builder {
+    /// Sets the bucket to upload to.
+    ///
+    /// Required.
+    pub fn bucket(mut self, bucket: impl Into<String>) -> Self {
+        self.set_bucket(Some(bucket.into()));
+        self
+    }
+
+    /// Sets the bucket to upload to.
+    ///
+    /// Required.
+    pub fn set_bucket(&mut self, bucket: Option<String>) {
+        self.bucket = bucket;
+    }
+
+    /// Sets the URL the form is posted to.
+    ///
+    /// Required. This is usually the virtual-hosted style URL of the bucket, such as
+    /// `https://<bucket>.s3.<region>.amazonaws.com/`, or its path-style URL for bucket names that
+    /// contain dots. The domain depends on the partition of the region, e.g. `amazonaws.com.cn`
+    /// for the China regions.
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.set_endpoint(Some(endpoint.into()));
+        self
+    }
+
+    /// Sets the URL the form is posted to.
+    ///
+    /// Required. See [`endpoint`](Builder::endpoint).
+    pub fn set_endpoint(&mut self, endpoint: Option<String>) {
+        self.endpoint = endpoint;
+    }
+
+    /// Sets the key of the uploaded object.
+    ///
+    /// This adds a `key` form field and a condition that requires it. To let the browser
+    /// choose the key instead, add a [`Condition::starts_with`] condition for `key`.
+    pub fn key(self, key: impl Into<String>) -> Self {
+        self.field("key", key)
+    }
+
+    /// Adds a form field with a fixed value, and a condition that requires it.
+    ///
+    /// Use this for e.g. `acl`, `Content-Type` or `x-amz-meta-*` fields.
+    pub fn field(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let (name, value) = (name.into(), value.into());
+        self.conditions
+            .push(Condition::equals(name.clone(), value.clone()));
+        self.fields.push((name, value));
+        self
+    }
+
+    /// Adds a condition to the policy.
+    pub fn condition(mut self, condition: Condition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Signs the policy with `credentials` for `region`.
+    ///
+    /// The policy is valid from the start time of `config` until it expires. If `credentials`
+    /// expire before that, the policy expires along with them.
+    pub fn sign(
+        self,
+        credentials: &Credentials,
+        region: &Region,
+        config: &PresigningConfig,
+    ) -> Result<PresignedPost, Error> {
+        let bucket = self.bucket.ok_or(Error::BucketRequired)?;
+        let url = self.endpoint.ok_or(Error::EndpointRequired)?;
+        for condition in &self.conditions {
+            if let Condition::ContentLengthRange { min, max } = *condition {
+                if min > max {
+                    return Err(Error::InvalidContentLengthRange { min, max });
+                }
+            }
+        }
+        let region = region.as_ref();
+        let start_time = config.start_time();
+        let start = DateTime::from_secs(DateTime::from(start_time).secs());
+        let expiration = match credentials.expiry() {
+            Some(expiry) => expiry.min(start_time + config.expires()),
+            None => start_time + config.expires(),
+        };
+        let expiration = DateTime::from_secs(DateTime::from(expiration).secs());
+
+        let date = format_time(&start)?;
+        let credential = format!(
+            "{}/{}/{}/{}/aws4_request",
+            credentials.access_key_id(),
+            &date[..8],
+            region,
+            SERVICE
+        );
+        let mut signing_fields: Vec<(String, String)> = vec![
+            ("x-amz-algorithm".into(), ALGORITHM.into()),
+            ("x-amz-credential".into(), credential),
+            ("x-amz-date".into(), date),
+        ];
+        if let Some(token) = credentials.session_token() {
+            signing_fields.push(("x-amz-security-token".into(), token.into()));
+        }
+
+        let mut conditions = vec![Condition::equals("bucket", bucket.as_str())];
+        conditions.extend(self.conditions);
+        conditions.extend(
+            signing_fields
+                .iter()
+                .map(|(name, value)| Condition::equals(name.as_str(), value.as_str())),
+        );
+        let policy = base64::encode(policy_document(&expiration, &conditions)?);
+
+        let signing_key =
+            generate_signing_key(credentials.secret_access_key(), start_time, region, SERVICE);
+        let signature = calculate_signature(signing_key, policy.as_bytes());
+        let mut fields = self.fields;
+        fields.extend(signing_fields);
+        fields.push(("policy".into(), policy));
+        fields.push(("x-amz-signature".into(), signature));
+        Ok(PresignedPost { url, fields })
+    }
+}
+
+/// Formats `time` in the `YYYYMMDD'T'HHMMSS'Z'` format of the `x-amz-date` field.
+fn format_time(time: &DateTime) -> Result<String, Error> {
+    let formatted = time.fmt(Format::DateTime).map_err(Error::InvalidTime)?;
+    Ok(formatted.replace(['-', ':'], ""))
+}
+
+fn policy_document(expiration: &DateTime, conditions: &[Condition]) -> Result<String, Error> {
+    let mut policy = String::new();
+    let mut object = JsonObjectWriter::new(&mut policy);
+    object
+        .key("expiration")
+        .date_time(expiration, Format::DateTime)
+        .map_err(Error::InvalidTime)?;
+    let mut array = object.key("conditions").start_array();
+    for condition in conditions {
+        condition.write_to(&mut array);
+    }
+    array.finish();
+    object.finish();
+    Ok(policy)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Condition, Error, PresignedPost};
+    use crate::presigning::config::PresigningConfig;
+    use aws_sigv4::sign::{calculate_signature, generate_signing_key};
+    use aws_smithy_types::base64;
+    use aws_types::region::Region;
+    use aws_types::Credentials;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn config() -> PresigningConfig {
+        PresigningConfig::builder()
+            .start_time(UNIX_EPOCH + Duration::from_secs(1_640_995_200))
+            .expires_in(Duration::from_secs(3600))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn sign_policy() {
+        let credentials = Credentials::new("AKID", "secret", None, None, "test");
+        let post = PresignedPost::builder()
+            .bucket("bucket")
+            .endpoint("https://bucket.s3.us-east-1.amazonaws.com/")
+            .key("uploads/photo.png")
+            .field("Content-Type", "image/png")
+            .condition(Condition::content_length_range(1, 1024))
+            .condition(Condition::starts_with("x-amz-meta-tag", ""))
+            .sign(&credentials, &Region::new("us-east-1"), &config())
+            .unwrap();
+
+        assert_eq!("https://bucket.s3.us-east-1.amazonaws.com/", post.url());
+        let names: Vec<_> = post
+            .fields()
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(
+            vec![
+                "key",
+                "Content-Type",
+                "x-amz-algorithm",
+                "x-amz-credential",
+                "x-amz-date",
+                "policy",
+                "x-amz-signature"
+            ],
+            names
+        );
+        assert_eq!(
+            Some("AKID/20220101/us-east-1/s3/aws4_request"),
+            post.field("x-amz-credential")
+        );
+        assert_eq!(Some("20220101T000000Z"), post.field("x-amz-date"));
+
+        let policy = post.field("policy").unwrap();
+        assert_eq!(
+            concat!(
+                r#"{"expiration":"2022-01-01T01:00:00Z","conditions":["#,
+                r#"{"bucket":"bucket"},"#,
+                r#"{"key":"uploads/photo.png"},"#,
+                r#"{"Content-Type":"image/png"},"#,
+                r#"["content-length-range",1,1024],"#,
+                r#"["starts-with","$x-amz-meta-tag",""],"#,
+                r#"{"x-amz-algorithm":"AWS4-HMAC-SHA256"},"#,
+                r#"{"x-amz-credential":"AKID/20220101/us-east-1/s3/aws4_request"},"#,
+                r#"{"x-amz-date":"20220101T000000Z"}]}"#
+            ),
+            String::from_utf8(base64::decode(policy).unwrap()).unwrap()
+        );
+        let signing_key = generate_signing_key(
+            "secret",
+            UNIX_EPOCH + Duration::from_secs(1_640_995_200),
+            "us-east-1",
+            "s3",
+        );
+        assert_eq!(
+            Some(calculate_signature(signing_key, policy.as_bytes()).as_str()),
+            post.field("x-amz-signature")
+        );
+    }
+
+    #[test]
+    fn session_token_is_signed() {
+        let credentials = Credentials::new("AKID", "secret", Some("token".into()), None, "test");
+        let post = PresignedPost::builder()
+            .bucket("bucket")
+            .endpoint("https://s3.us-west-2.amazonaws.com/bucket.with.dots")
+            .sign(&credentials, &Region::new("us-west-2"), &config())
+            .unwrap();
+        assert_eq!(
+            "https://s3.us-west-2.amazonaws.com/bucket.with.dots",
+            post.url()
+        );
+        assert_eq!(Some("token"), post.field("x-amz-security-token"));
+        let policy = base64::decode(post.field("policy").unwrap()).unwrap();
+        assert!(String::from_utf8(policy)
+            .unwrap()
+            .contains(r#"{"x-amz-security-token":"token"}"#));
+    }
+
+    #[test]
+    fn policy_expires_with_credentials() {
+        let policy_expiration = |expiry: Option<u64>| {
+            let credentials = Credentials::new(
+                "AKID",
+                "secret",
+                Some("token".into()),
+                expiry.map(|expiry| UNIX_EPOCH + Duration::from_secs(expiry)),
+                "test",
+            );
+            let post = PresignedPost::builder()
+                .bucket("bucket")
+                .endpoint("https://bucket.s3.us-east-1.amazonaws.com/")
+                .sign(&credentials, &Region::new("us-east-1"), &config())
+                .unwrap();
+            let policy = base64::decode(post.field("policy").unwrap()).unwrap();
+            let policy = String::from_utf8(policy).unwrap();
+            policy[..policy.find(',').unwrap()].to_string()
+        };
+        // Credentials that expire 15 minutes after the start time
+        assert_eq!(
+            r#"{"expiration":"2022-01-01T00:15:00Z""#,
+            policy_expiration(Some(1_640_996_100))
+        );
+        // Credentials that outlive the policy
+        assert_eq!(
+            r#"{"expiration":"2022-01-01T01:00:00Z""#,
+            policy_expiration(Some(1_641_081_600))
+        );
+        assert_eq!(
+            r#"{"expiration":"2022-01-01T01:00:00Z""#,
+            policy_expiration(None)
+        );
+    }
+
+    #[test]
+    fn bucket_and_endpoint_are_required() {
+        let credentials = Credentials::new("AKID", "secret", None, None, "test");
+        let err = PresignedPost::builder()
+            .endpoint("https://bucket.s3.us-east-1.amazonaws.com/")
+            .sign(&credentials, &Region::new("us-east-1"), &config())
+            .expect_err("bucket is required");
+        assert!(matches!(err, Error::BucketRequired));
+        let err = PresignedPost::builder()
+            .bucket("bucket")
+            .sign(&credentials, &Region::new("us-east-1"), &config())
+            .expect_err("endpoint is required");
+        assert!(matches!(err, Error::EndpointRequired));
+    }
+
+    #[test]
+    fn content_length_range_must_not_be_reversed() {
+        let credentials = Credentials::new("AKID", "secret", None, None, "test");
+        let err = PresignedPost::builder()
+            .bucket("bucket")
+            .endpoint("https://bucket.s3.us-east-1.amazonaws.com/")
+            .condition(Condition::content_length_range(1024, 1))
+            .sign(&credentials, &Region::new("us-east-1"), &config())
+            .expect_err("min is larger than max");
+        assert!(matches!(
+            err,
+            Error::InvalidContentLengthRange { min: 1024, max: 1 }
+        ));
+    }
+}
@@ -17,6 +17,7 @@ pub mod config {
     #[derive(Debug, Clone)]
     pub struct PresigningConfig {
         start_time: SystemTime,
+        explicit_start_time: Option<SystemTime>,
         expires_in: Duration,
     }
 
@@ -45,9 +46,20 @@ pub mod config {
 
         /// Returns the start time. The presigned request will be valid between this and the end
         /// time produced by adding the `expires()` value to it.
+        ///
+        /// If no start time was given to the builder, this is the system time when the config
+        /// was built.
         pub fn start_time(&self) -> SystemTime {
             self.start_time
         }
+
+        /// Returns the start time given to the builder, if any.
+        ///
+        /// When this is `None`, presigned requests start at the current time according to the
+        /// time source of the service config.
+        pub fn explicit_start_time(&self) -> Option<SystemTime> {
+            self.explicit_start_time
+        }
     }
 
     /// `PresigningConfig` build errors.
@@ -140,6 +152,7 @@ pub mod config {
             }
             Ok(PresigningConfig {
                 start_time: self.start_time.unwrap_or_else(SystemTime::now),
+                explicit_start_time: self.start_time,
                 expires_in,
             })
         }
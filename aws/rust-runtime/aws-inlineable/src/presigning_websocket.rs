@@ -0,0 +1,381 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Presigned WebSocket URLs and WebSocket framing for event stream operations
+//!
+//! Some streaming services accept a SigV4-presigned WebSocket connection in place of an HTTP/2
+//! event stream. The connection is opened with a `GET` request to a presigned `wss://` URL, and
+//! every event stream message is then sent as the payload of one binary WebSocket frame.
+//!
+//! This module doesn't depend on a WebSocket library: [`to_websocket_uri`] produces the URL to
+//! connect to, [`EncodeFrames`] turns a stream of messages into binary frame payloads to send,
+//! and [`DecodeFrames`] turns received binary frame payloads back into messages. The
+//! `presigned_websocket` method of event stream operations that support WebSockets presigns the
+//! URL.
+
+use crate::presigning::request::PresignedRequest;
+use aws_endpoint::{get_endpoint_resolver, set_endpoint_resolver};
+use aws_smithy_eventstream::error::Error as EventStreamError;
+use aws_smithy_eventstream::frame::Message;
+use aws_smithy_http::endpoint::Endpoint;
+use aws_smithy_http::property_bag::PropertyBag;
+use aws_types::endpoint::{AwsEndpoint, ResolveAwsEndpoint};
+use aws_types::region::Region;
+use bytes::{Bytes, BytesMut};
+use http::uri::{Authority, Scheme};
+use http::{Method, Uri};
+use std::error::Error as StdError;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio_stream::Stream;
+
+type BoxError = Box<dyn StdError + Send + Sync>;
+
+/// Converts the URI of a presigned `GET` request into a WebSocket URI.
+///
+/// The `https` scheme is replaced with `wss` and `http` with `ws`. The host, path and query,
+/// which carries the signature, are kept as is. The request must be presigned with the `GET`
+/// method, since that's the method of the WebSocket opening handshake.
+pub fn to_websocket_uri(request: &PresignedRequest) -> Result<Uri, UriError> {
+    if request.method() != Method::GET {
+        return Err(UriError::UnsupportedMethod(request.method().clone()));
+    }
+    let uri = request.uri();
+    let scheme = match uri.scheme_str() {
+        Some("https") => "wss",
+        Some("http") => "ws",
+        other => return Err(UriError::UnsupportedScheme(other.map(String::from))),
+    };
+    let mut parts = uri.clone().into_parts();
+    parts.scheme = Some(scheme.parse::<Scheme>().expect("valid scheme"));
+    Uri::from_parts(parts).map_err(|err| UriError::InvalidUri(err.into()))
+}
+
+/// Failure to convert a presigned request into a WebSocket URI
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum UriError {
+    /// The request wasn't presigned with the `GET` method
+    UnsupportedMethod(Method),
+    /// The request URI doesn't use the `http` or `https` scheme
+    UnsupportedScheme(Option<String>),
+    /// The WebSocket URI couldn't be built
+    InvalidUri(http::Error),
+}
+
+impl fmt::Display for UriError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UriError::UnsupportedMethod(method) => write!(
+                f,
+                "WebSocket connections must be presigned with GET, not {}",
+                method
+            ),
+            UriError::UnsupportedScheme(Some(scheme)) => {
+                write!(
+                    f,
+                    "cannot convert the {} scheme to a WebSocket scheme",
+                    scheme
+                )
+            }
+            UriError::UnsupportedScheme(None) => write!(f, "the presigned URI has no scheme"),
+            UriError::InvalidUri(_) => write!(f, "invalid WebSocket URI"),
+        }
+    }
+}
+
+impl StdError for UriError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            UriError::InvalidUri(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Makes the request in `properties` connect to `port`, unless its endpoint has a port.
+///
+/// Some services serve their WebSocket API on a different port than their HTTP API. Since the
+/// port is part of the signed `host`, it must be set before the request is signed.
+#[allow(dead_code)]
+pub(crate) fn use_port(properties: &mut PropertyBag, port: u16) {
+    if let Some(inner) = get_endpoint_resolver(properties).cloned() {
+        set_endpoint_resolver(properties, Arc::new(WithPort { inner, port }));
+    }
+}
+
+/// Resolves the endpoints of `inner`, adding `port` to those without a port
+#[derive(Debug)]
+struct WithPort {
+    inner: Arc<dyn ResolveAwsEndpoint>,
+    port: u16,
+}
+
+impl ResolveAwsEndpoint for WithPort {
+    fn resolve_endpoint(&self, region: &Region) -> Result<AwsEndpoint, BoxError> {
+        let endpoint = self.inner.resolve_endpoint(region)?;
+        let mut uri = Uri::from_static("/");
+        endpoint.set_endpoint(&mut uri, None);
+        let host = match uri.authority() {
+            Some(authority) if authority.port().is_none() => authority.host(),
+            _ => return Ok(endpoint),
+        };
+        let mut parts = uri.clone().into_parts();
+        parts.authority = Some(format!("{}:{}", host, self.port).parse::<Authority>()?);
+        Ok(AwsEndpoint::new(
+            Endpoint::immutable(Uri::from_parts(parts)?),
+            endpoint.credential_scope().clone(),
+        ))
+    }
+}
+
+/// Encodes a message into the payload of a binary WebSocket frame.
+pub fn encode_frame(message: &Message) -> Result<Bytes, EventStreamError> {
+    let mut buffer = BytesMut::new();
+    message.write_to(&mut buffer)?;
+    Ok(buffer.freeze())
+}
+
+/// Decodes the payload of a binary WebSocket frame into a message.
+///
+/// Every frame must contain exactly one complete message.
+pub fn decode_frame(mut frame: &[u8]) -> Result<Message, EventStreamError> {
+    let message = Message::read_from(&mut frame)?;
+    if !frame.is_empty() {
+        return Err(EventStreamError::InvalidMessageLength);
+    }
+    Ok(message)
+}
+
+/// Failure to send or receive messages over a WebSocket connection
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum FrameError {
+    /// A message couldn't be encoded or decoded
+    Message(EventStreamError),
+    /// The WebSocket connection failed
+    Transport(BoxError),
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::Message(_) => write!(f, "invalid event stream message"),
+            FrameError::Transport(_) => write!(f, "WebSocket transport error"),
+        }
+    }
+}
+
+impl StdError for FrameError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            FrameError::Message(err) => Some(err),
+            FrameError::Transport(err) => Some(err.as_ref()),
+        }
+    }
+}
+
+/// A stream of binary WebSocket frame payloads, one for every message of the inner stream
+///
+/// Forward this stream into the sink of a WebSocket connection, sending every item as a
+/// binary frame.
+#[derive(Debug)]
+pub struct EncodeFrames<S> {
+    inner: S,
+}
+
+impl<S> EncodeFrames<S> {
+    /// Encodes every message of `inner` into a frame payload.
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the inner stream of messages.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Stream for EncodeFrames<S>
+where
+    S: Stream<Item = Message> + Unpin,
+{
+    type Item = Result<Bytes, FrameError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.inner).poll_next(cx).map(|message| {
+            message.map(|message| encode_frame(&message).map_err(FrameError::Message))
+        })
+    }
+}
+
+/// A stream of the messages decoded from the binary WebSocket frames of the inner stream
+///
+/// The inner stream yields the payloads of the binary frames received from a WebSocket
+/// connection, or the errors of the connection. Control frames such as pings, and the close
+/// frame, should be handled by the WebSocket library and not be passed on.
+#[derive(Debug)]
+pub struct DecodeFrames<S> {
+    inner: S,
+}
+
+impl<S> DecodeFrames<S> {
+    /// Decodes every binary frame payload of `inner` into a message.
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the inner stream of frame payloads.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, B, E> Stream for DecodeFrames<S>
+where
+    S: Stream<Item = Result<B, E>> + Unpin,
+    B: AsRef<[u8]>,
+    E: Into<BoxError>,
+{
+    type Item = Result<Message, FrameError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.inner).poll_next(cx).map(|frame| {
+            frame.map(|frame| match frame {
+                Ok(frame) => decode_frame(frame.as_ref()).map_err(FrameError::Message),
+                Err(err) => Err(FrameError::Transport(err.into())),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{to_websocket_uri, use_port, DecodeFrames, EncodeFrames, FrameError, UriError};
+    use crate::presigning::request::PresignedRequest;
+    use aws_endpoint::{get_endpoint_resolver, set_endpoint_resolver};
+    use aws_smithy_eventstream::frame::{Header, HeaderValue, Message};
+    use aws_smithy_http::endpoint::Endpoint;
+    use aws_smithy_http::property_bag::PropertyBag;
+    use aws_types::region::Region;
+    use bytes::Bytes;
+    use http::Uri;
+    use std::sync::Arc;
+    use tokio_stream::StreamExt;
+
+    fn presigned(method: &str, uri: &str) -> PresignedRequest {
+        PresignedRequest::new(
+            http::Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(())
+                .unwrap(),
+        )
+    }
+
+    fn message(payload: &'static str) -> Message {
+        Message::new(payload).add_header(Header::new(
+            ":event-type",
+            HeaderValue::String("AudioEvent".into()),
+        ))
+    }
+
+    #[test]
+    fn websocket_uri() {
+        let request = presigned(
+            "GET",
+            "https://transcribestreaming.us-east-1.amazonaws.com:8443/stream-transcription-websocket?X-Amz-Signature=abc",
+        );
+        assert_eq!(
+            "wss://transcribestreaming.us-east-1.amazonaws.com:8443/stream-transcription-websocket?X-Amz-Signature=abc",
+            to_websocket_uri(&request).unwrap().to_string()
+        );
+        let request = presigned("GET", "http://localhost:1234/?X-Amz-Signature=abc");
+        assert_eq!(
+            "ws://localhost:1234/?X-Amz-Signature=abc",
+            to_websocket_uri(&request).unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn websocket_uri_requires_get() {
+        let request = presigned("POST", "https://example.com/?X-Amz-Signature=abc");
+        assert!(matches!(
+            to_websocket_uri(&request),
+            Err(UriError::UnsupportedMethod(_))
+        ));
+        let request = presigned("GET", "/?X-Amz-Signature=abc");
+        assert!(matches!(
+            to_websocket_uri(&request),
+            Err(UriError::UnsupportedScheme(None))
+        ));
+    }
+
+    fn resolved_uri(endpoint: &'static str, port: u16) -> String {
+        let mut properties = PropertyBag::new();
+        set_endpoint_resolver(
+            &mut properties,
+            Arc::new(Endpoint::mutable(Uri::from_static(endpoint))),
+        );
+        use_port(&mut properties, port);
+        let endpoint = get_endpoint_resolver(&properties)
+            .unwrap()
+            .resolve_endpoint(&Region::new("us-east-1"))
+            .unwrap();
+        let mut uri = Uri::from_static("/stream-transcription-websocket");
+        endpoint.set_endpoint(&mut uri, None);
+        uri.to_string()
+    }
+
+    #[test]
+    fn port_is_added_to_endpoints_without_one() {
+        assert_eq!(
+            "https://transcribestreaming.us-east-1.amazonaws.com:8443/stream-transcription-websocket",
+            resolved_uri("https://transcribestreaming.us-east-1.amazonaws.com", 8443)
+        );
+        assert_eq!(
+            "http://localhost:1234/stream-transcription-websocket",
+            resolved_uri("http://localhost:1234", 8443)
+        );
+    }
+
+    #[tokio::test]
+    async fn frames_round_trip() {
+        let messages = vec![message("one"), message("two")];
+        let frames: Vec<Bytes> = EncodeFrames::new(tokio_stream::iter(messages.clone()))
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(2, frames.len());
+
+        let decoded: Vec<Message> = DecodeFrames::new(tokio_stream::iter(
+            frames.into_iter().map(Ok::<_, std::io::Error>),
+        ))
+        .map(Result::unwrap)
+        .collect()
+        .await;
+        assert_eq!(messages, decoded);
+    }
+
+    #[tokio::test]
+    async fn frames_must_contain_exactly_one_message() {
+        let one = super::encode_frame(&message("one")).unwrap();
+        let mut two = one.to_vec();
+        two.extend_from_slice(&one);
+        let frames = vec![
+            Ok(one[..one.len() - 1].to_vec()),
+            Ok(two),
+            Err(std::io::Error::new(std::io::ErrorKind::Other, "closed")),
+        ];
+        let results: Vec<_> = DecodeFrames::new(tokio_stream::iter(frames))
+            .collect()
+            .await;
+        assert!(matches!(results[0], Err(FrameError::Message(_))));
+        assert!(matches!(results[1], Err(FrameError::Message(_))));
+        assert!(matches!(results[2], Err(FrameError::Transport(_))));
+    }
+}
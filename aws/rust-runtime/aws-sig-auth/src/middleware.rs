@@ -7,6 +7,7 @@ use crate::signer::{
     OperationSigningConfig, RequestConfig, SigV4Signer, SigningError, SigningRequirements,
 };
 use aws_sigv4::http_request::SignableBody;
+use aws_smithy_async::time::{ClockSkew, SharedTimeSource, TimeSource};
 use aws_smithy_http::middleware::MapRequest;
 use aws_smithy_http::operation::Request;
 use aws_smithy_http::property_bag::PropertyBag;
@@ -48,7 +49,11 @@ impl AsRef<str> for Signature {
 ///
 /// The following fields MAY be present in the property bag:
 /// - [`SystemTime`](SystemTime): The timestamp to use when signing the request. If this field is not present
+///   the current time is used.
+/// - [`SharedTimeSource`](SharedTimeSource): The source of the current time. If this field is not present
 ///   [`SystemTime::now`](SystemTime::now) will be used.
+/// - [`ClockSkew`](ClockSkew): The offset of the server's clock, which is applied to the current time.
+///   It is not applied to an explicit [`SystemTime`](SystemTime).
 #[derive(Clone, Debug)]
 pub struct SigV4SigningStage {
     signer: SigV4Signer,
@@ -128,10 +133,7 @@ fn signing_config(
         .ok_or(SigningStageError::MissingSigningService)?;
     let payload_override = config.get::<SignableBody<'static>>();
     let request_config = RequestConfig {
-        request_ts: config
-            .get::<SystemTime>()
-            .copied()
-            .unwrap_or_else(SystemTime::now),
+        request_ts: request_time(config),
         region,
         payload_override,
         service: signing_service,
@@ -139,6 +141,21 @@ fn signing_config(
     Ok((operation_config, request_config, credentials))
 }
 
+/// Returns the time to sign with, as described on [`SigV4SigningStage`]
+pub(crate) fn request_time(config: &PropertyBag) -> SystemTime {
+    if let Some(time) = config.get::<SystemTime>() {
+        return *time;
+    }
+    let now = config
+        .get::<SharedTimeSource>()
+        .map(|time_source| time_source.now())
+        .unwrap_or_else(SystemTime::now);
+    match config.get::<ClockSkew>() {
+        Some(skew) => skew.apply(now),
+        None => now,
+    }
+}
+
 impl MapRequest for SigV4SigningStage {
     type Error = SigningStageError;
 
@@ -157,10 +174,15 @@ impl MapRequest for SigV4SigningStage {
                     SigningRequirements::Required => signing_config(config)?,
                 };
 
-            let signature = self
+            let (signature, debug_info) = self
                 .signer
-                .sign(operation_config, &request_config, &creds, &mut req)
+                .sign_with_debug_info(operation_config, &request_config, &creds, &mut req)
                 .map_err(|err| SigningStageError::SigningFailure(err))?;
+            tracing::trace!(
+                canonical_request = ?debug_info.canonical_request(),
+                string_to_sign = ?debug_info.string_to_sign(),
+                "signed request"
+            );
             config.insert(signature);
             Ok(req)
         })
@@ -173,9 +195,12 @@ mod test {
     use crate::signer::{OperationSigningConfig, SigV4Signer};
     use aws_endpoint::partition::endpoint::{Protocol, SignatureVersion};
     use aws_endpoint::{set_endpoint_resolver, AwsEndpointStage};
+    use aws_smithy_async::test_util::ManualTimeSource;
+    use aws_smithy_async::time::{ClockSkew, SharedTimeSource};
     use aws_smithy_http::body::SdkBody;
     use aws_smithy_http::middleware::MapRequest;
     use aws_smithy_http::operation;
+    use aws_smithy_http::property_bag::PropertyBag;
     use aws_types::region::{Region, SigningRegion};
     use aws_types::Credentials;
     use aws_types::SigningService;
@@ -183,6 +208,7 @@ mod test {
     use std::convert::Infallible;
     use std::sync::Arc;
     use std::time::{Duration, UNIX_EPOCH};
+    use tracing_test::traced_test;
 
     #[test]
     fn places_signature_in_property_bag() {
@@ -211,6 +237,82 @@ mod test {
         assert!(signature.is_some());
     }
 
+    #[test]
+    #[traced_test]
+    fn traces_canonical_request_and_string_to_sign() {
+        let req = http::Request::builder()
+            .uri("https://test-service.test-region.amazonaws.com/")
+            .body(SdkBody::from(""))
+            .unwrap();
+        let region = Region::new("us-east-1");
+        let req = operation::Request::new(req)
+            .augment(|req, properties| {
+                properties.insert(region.clone());
+                properties.insert(UNIX_EPOCH + Duration::new(1611160427, 0));
+                properties.insert(SigningService::from_static("kinesis"));
+                properties.insert(OperationSigningConfig::default_config());
+                properties.insert(Credentials::new("AKIAfoo", "bar", None, None, "test"));
+                properties.insert(SigningRegion::from(region));
+                Result::<_, Infallible>::Ok(req)
+            })
+            .expect("succeeds");
+
+        let signer = SigV4SigningStage::new(SigV4Signer::new());
+        signer.apply(req).unwrap();
+        assert!(logs_contain("signed request"));
+        assert!(logs_contain("20210120/us-east-1/kinesis/aws4_request"));
+    }
+
+    #[test]
+    fn signs_with_time_source_and_clock_skew() {
+        let signing_time = UNIX_EPOCH + Duration::new(1611160427, 0);
+        let sign = |properties: &dyn Fn(&mut PropertyBag)| {
+            let req = http::Request::builder()
+                .uri("https://test-service.test-region.amazonaws.com/")
+                .body(SdkBody::from(""))
+                .unwrap();
+            let region = Region::new("us-east-1");
+            let req = operation::Request::new(req)
+                .augment(|req, conf| {
+                    conf.insert(SigningService::from_static("kinesis"));
+                    conf.insert(OperationSigningConfig::default_config());
+                    conf.insert(Credentials::new("AKIAfoo", "bar", None, None, "test"));
+                    conf.insert(SigningRegion::from(region));
+                    properties(conf);
+                    Result::<_, Infallible>::Ok(req)
+                })
+                .expect("succeeds");
+            let req = SigV4SigningStage::new(SigV4Signer::new())
+                .apply(req)
+                .expect("signing succeeded");
+            let (req, _) = req.into_parts();
+            req.headers()["x-amz-date"].to_str().unwrap().to_string()
+        };
+
+        let local_time = signing_time - Duration::from_secs(900);
+        let skew = ClockSkew::new();
+        skew.update(local_time, signing_time);
+        let with_time_source = |conf: &mut PropertyBag| {
+            conf.insert(SharedTimeSource::new(ManualTimeSource::new(local_time)));
+        };
+        assert_eq!("20210120T161847Z", sign(&with_time_source));
+        assert_eq!(
+            "20210120T163347Z",
+            sign(&|conf| {
+                with_time_source(conf);
+                conf.insert(skew.clone());
+            })
+        );
+        // an explicit signing time is used as is
+        assert_eq!(
+            "20210120T161847Z",
+            sign(&|conf| {
+                conf.insert(local_time);
+                conf.insert(skew.clone());
+            })
+        );
+    }
+
     // check that the endpoint middleware followed by signing middleware produce the expected result
     #[test]
     fn endpoint_plus_signer() {
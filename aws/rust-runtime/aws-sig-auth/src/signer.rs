@@ -5,8 +5,8 @@
 
 use crate::middleware::Signature;
 use aws_sigv4::http_request::{
-    sign, PayloadChecksumKind, PercentEncodingMode, SignableRequest, SignatureLocation,
-    SigningParams, SigningSettings,
+    sign_with_debug_info, PayloadChecksumKind, PercentEncodingMode, SignableRequest,
+    SignatureLocation, SigningDebugInfo, SigningParams, SigningSettings,
 };
 use aws_smithy_http::body::SdkBody;
 use aws_types::region::SigningRegion;
@@ -184,10 +184,25 @@ impl SigV4Signer {
         credentials: &Credentials,
         request: &mut http::Request<SdkBody>,
     ) -> Result<Signature, SigningError> {
+        self.sign_with_debug_info(operation_config, request_config, credentials, request)
+            .map(|(signature, _)| signature)
+    }
+
+    /// Sign a request using the SigV4 Protocol, and return the canonical request and
+    /// string-to-sign along with the signature
+    ///
+    /// See [`SigningDebugInfo`] for how to use these to debug signature mismatches.
+    pub fn sign_with_debug_info(
+        &self,
+        operation_config: &OperationSigningConfig,
+        request_config: &RequestConfig<'_>,
+        credentials: &Credentials,
+        request: &mut http::Request<SdkBody>,
+    ) -> Result<(Signature, SigningDebugInfo), SigningError> {
         let settings = Self::settings(operation_config);
         let signing_params = Self::signing_params(settings, credentials, request_config);
 
-        let (signing_instructions, signature) = {
+        let (output, debug_info) = {
             // A body that is already in memory can be signed directly. A body that is not in memory
             // (any sort of streaming body or presigned request) will be signed via UNSIGNED-PAYLOAD.
             let signable_body = request_config
@@ -209,13 +224,13 @@ impl SigV4Signer {
                 request.headers(),
                 signable_body,
             );
-            sign(signable_request, &signing_params)?
-        }
-        .into_parts();
+            sign_with_debug_info(signable_request, &signing_params)?
+        };
+        let (signing_instructions, signature) = output.into_parts();
 
         signing_instructions.apply_to_request(request);
 
-        Ok(Signature::new(signature))
+        Ok((Signature::new(signature), debug_info))
     }
 }
 
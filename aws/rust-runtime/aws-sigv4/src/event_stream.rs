@@ -35,12 +35,43 @@
 //! let (signed, signature) =
 //!     sign_message(&message_to_sign, &last_signature, &params).into_parts();
 //! ```
+//!
+//! # Example: Verifying a chain of signed event stream messages
+//!
+//! ```rust
+//! use aws_sigv4::event_stream::{sign_empty_message, sign_message, SignatureVerifier, SigningParams};
+//! use aws_smithy_eventstream::frame::Message;
+//! use std::time::SystemTime;
+//!
+//! let seed_signature = "example298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+//! let params = SigningParams::builder()
+//!     .access_key("example access key")
+//!     .secret_key("example secret key")
+//!     .region("us-east-1")
+//!     .service_name("exampleservice")
+//!     .time(SystemTime::now())
+//!     .settings(())
+//!     .build()
+//!     .unwrap();
+//! let message = Message::new(&b"example"[..]);
+//! let (signed, signature) = sign_message(&message, seed_signature, &params).into_parts();
+//! let (end, _) = sign_empty_message(&signature, &params).into_parts();
+//!
+//! // The signing time of every message is read from its `:date` header
+//! let mut verifier = SignatureVerifier::new(seed_signature, &params);
+//! assert_eq!(Some(message), verifier.verify(&signed).unwrap());
+//! assert_eq!(None, verifier.verify(&end).unwrap());
+//! ```
 
 use crate::date_time::{format_date, format_date_time, truncate_subsecs};
 use crate::sign::{calculate_signature, generate_signing_key, sha256_hex_string};
 use crate::SigningOutput;
+use aws_smithy_eventstream::error::Error as EventStreamError;
 use aws_smithy_eventstream::frame::{write_headers_to, Header, HeaderValue, Message};
 use bytes::Bytes;
+use std::convert::TryFrom;
+use std::error::Error as StdError;
+use std::fmt;
 use std::io::Write;
 use std::time::SystemTime;
 
@@ -139,6 +170,203 @@ fn sign_payload<'a>(
     )
 }
 
+/// Verifies the signatures of a chain of signed Event Stream messages
+///
+/// Every signed message wraps an inner message in its payload, and carries the signature in its
+/// `:chunk-signature` header and the signing time in its `:date` header. The signature of each
+/// message incorporates the signature of the previous message, starting with the seed signature
+/// of the initial HTTP request. A signed message with an empty payload ends the stream.
+///
+/// The time in the signing params is ignored, since every message carries its own signing time.
+#[derive(Debug)]
+pub struct SignatureVerifier {
+    secret_key: String,
+    region: String,
+    service_name: String,
+    last_signature: String,
+    index: usize,
+    ended: bool,
+}
+
+impl SignatureVerifier {
+    /// Creates a verifier for a chain of messages that starts with `seed_signature`
+    pub fn new(seed_signature: impl Into<String>, params: &SigningParams<'_>) -> Self {
+        Self {
+            secret_key: params.secret_key.to_string(),
+            region: params.region.to_string(),
+            service_name: params.service_name.to_string(),
+            last_signature: seed_signature.into(),
+            index: 0,
+            ended: false,
+        }
+    }
+
+    /// Returns the signature of the last message that was successfully verified, or the seed
+    /// signature if no message was verified yet
+    pub fn last_signature(&self) -> &str {
+        &self.last_signature
+    }
+
+    /// Returns true once the empty message that ends the stream was verified
+    pub fn is_ended(&self) -> bool {
+        self.ended
+    }
+
+    /// Verifies the next signed message in the chain
+    ///
+    /// Returns the inner message, or `None` if `signed` is the empty message that ends the
+    /// stream. A message that fails verification doesn't advance the chain, so the next message
+    /// is verified against the signature of the last valid message.
+    pub fn verify(&mut self, signed: &Message) -> Result<Option<Message>, VerificationError> {
+        let index = self.index;
+        self.index += 1;
+        self.verify_inner(signed)
+            .map_err(|kind| VerificationError { index, kind })
+    }
+
+    fn verify_inner(&mut self, signed: &Message) -> Result<Option<Message>, VerificationErrorKind> {
+        if self.ended {
+            return Err(VerificationErrorKind::MessageAfterEnd);
+        }
+        let mut signature = None;
+        let mut date = None;
+        for header in signed.headers() {
+            match (header.name().as_str(), header.value()) {
+                (":chunk-signature", HeaderValue::ByteArray(value)) => {
+                    signature = Some(hex::encode(value))
+                }
+                (":chunk-signature", _) => return Err(VerificationErrorKind::InvalidSignature),
+                (":date", HeaderValue::Timestamp(value)) => {
+                    date = Some(
+                        SystemTime::try_from(*value)
+                            .map_err(|_| VerificationErrorKind::InvalidDate)?,
+                    )
+                }
+                (":date", _) => return Err(VerificationErrorKind::InvalidDate),
+                _ => {}
+            }
+        }
+        let signature = signature.ok_or(VerificationErrorKind::MissingSignature)?;
+        let time = date.ok_or(VerificationErrorKind::MissingDate)?;
+
+        let params = SigningParams {
+            access_key: "",
+            secret_key: &self.secret_key,
+            security_token: None,
+            region: &self.region,
+            service_name: &self.service_name,
+            time,
+            settings: (),
+        };
+        let signing_key =
+            generate_signing_key(params.secret_key, time, params.region, params.service_name);
+        let string_to_sign =
+            calculate_string_to_sign(signed.payload(), &self.last_signature, time, &params);
+        let expected = calculate_signature(signing_key, &string_to_sign);
+        if ring::constant_time::verify_slices_are_equal(expected.as_bytes(), signature.as_bytes())
+            .is_err()
+        {
+            return Err(VerificationErrorKind::SignatureMismatch {
+                expected,
+                actual: signature,
+            });
+        }
+        self.last_signature = signature;
+
+        if signed.payload().is_empty() {
+            self.ended = true;
+            return Ok(None);
+        }
+        Message::read_from(signed.payload().clone())
+            .map(Some)
+            .map_err(VerificationErrorKind::InvalidPayload)
+    }
+}
+
+/// The reason a signed message failed verification
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum VerificationErrorKind {
+    /// The message doesn't have a `:chunk-signature` header
+    MissingSignature,
+    /// The `:chunk-signature` header isn't a byte array
+    InvalidSignature,
+    /// The message doesn't have a `:date` header
+    MissingDate,
+    /// The `:date` header isn't a valid timestamp
+    InvalidDate,
+    /// The signature doesn't match the signature calculated from the message
+    SignatureMismatch {
+        /// The calculated signature, hex encoded
+        expected: String,
+        /// The signature of the message, hex encoded
+        actual: String,
+    },
+    /// The signature is valid, but the payload isn't a valid message
+    InvalidPayload(EventStreamError),
+    /// The message follows the empty message that ends the stream
+    MessageAfterEnd,
+}
+
+/// A signed message failed verification
+#[derive(Debug)]
+pub struct VerificationError {
+    index: usize,
+    kind: VerificationErrorKind,
+}
+
+impl VerificationError {
+    /// The zero-based index of the message that failed verification within the chain
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The reason the message failed verification
+    pub fn kind(&self) -> &VerificationErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "event stream message {} failed verification: ",
+            self.index
+        )?;
+        match &self.kind {
+            VerificationErrorKind::MissingSignature => write!(f, "missing :chunk-signature header"),
+            VerificationErrorKind::InvalidSignature => {
+                write!(f, ":chunk-signature header is not a byte array")
+            }
+            VerificationErrorKind::MissingDate => write!(f, "missing :date header"),
+            VerificationErrorKind::InvalidDate => {
+                write!(f, ":date header is not a valid timestamp")
+            }
+            VerificationErrorKind::SignatureMismatch { expected, actual } => write!(
+                f,
+                "signature {} didn't match expected signature {}",
+                actual, expected
+            ),
+            VerificationErrorKind::InvalidPayload(err) => {
+                write!(f, "payload is not a valid message: {}", err)
+            }
+            VerificationErrorKind::MessageAfterEnd => {
+                write!(f, "message was sent after the end of the stream")
+            }
+        }
+    }
+}
+
+impl StdError for VerificationError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match &self.kind {
+            VerificationErrorKind::InvalidPayload(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -185,6 +413,111 @@ mod tests {
         );
     }
 
+    fn params(time: SystemTime) -> SigningParams<'static> {
+        SigningParams {
+            access_key: "fake access key",
+            secret_key: "fake secret key",
+            security_token: None,
+            region: "us-east-1",
+            service_name: "testservice",
+            time,
+            settings: (),
+        }
+    }
+
+    fn signed_chain(seed: &str, messages: &[Message]) -> Vec<Message> {
+        let mut last_signature = seed.to_string();
+        let mut signed = Vec::new();
+        for (i, message) in messages.iter().enumerate() {
+            let params = params(UNIX_EPOCH + Duration::from_secs(123_456_789 + i as u64));
+            let (message, signature) = sign_message(message, &last_signature, &params).into_parts();
+            signed.push(message);
+            last_signature = signature;
+        }
+        let params = params(UNIX_EPOCH + Duration::from_secs(123_456_789));
+        signed.push(sign_empty_message(&last_signature, &params).into_parts().0);
+        signed
+    }
+
+    #[test]
+    fn verify_chain() {
+        let seed = sha256_hex_string(b"seed");
+        let messages = vec![Message::new(&b"one"[..]), Message::new(&b"two"[..])];
+        let signed = signed_chain(&seed, &messages);
+
+        // The time of the verifier's params is irrelevant
+        let mut verifier = SignatureVerifier::new(seed, &params(UNIX_EPOCH));
+        assert_eq!(
+            Some(messages[0].clone()),
+            verifier.verify(&signed[0]).unwrap()
+        );
+        assert_eq!(
+            Some(messages[1].clone()),
+            verifier.verify(&signed[1]).unwrap()
+        );
+        assert!(!verifier.is_ended());
+        assert_eq!(None, verifier.verify(&signed[2]).unwrap());
+        assert!(verifier.is_ended());
+
+        let err = verifier.verify(&signed[0]).expect_err("stream ended");
+        assert_eq!(3, err.index());
+        assert!(matches!(err.kind(), VerificationErrorKind::MessageAfterEnd));
+    }
+
+    #[test]
+    fn verify_identifies_first_bad_message() {
+        let seed = sha256_hex_string(b"seed");
+        let messages = vec![
+            Message::new(&b"one"[..]),
+            Message::new(&b"two"[..]),
+            Message::new(&b"three"[..]),
+        ];
+        let mut signed = signed_chain(&seed, &messages);
+        // Swapping two messages breaks the chain at the first of them
+        signed.swap(1, 2);
+
+        let mut verifier = SignatureVerifier::new(seed.clone(), &params(UNIX_EPOCH));
+        verifier.verify(&signed[0]).unwrap();
+        let last_signature = verifier.last_signature().to_string();
+        let err = verifier.verify(&signed[1]).expect_err("out of order");
+        assert_eq!(1, err.index());
+        assert!(
+            matches!(err.kind(), VerificationErrorKind::SignatureMismatch { .. }),
+            "{}",
+            err
+        );
+        assert_eq!(last_signature, verifier.last_signature());
+
+        // A different seed signature fails the first message
+        let mut verifier = SignatureVerifier::new(sha256_hex_string(b"other"), &params(UNIX_EPOCH));
+        assert_eq!(0, verifier.verify(&signed[0]).unwrap_err().index());
+    }
+
+    #[test]
+    fn verify_requires_signature_headers() {
+        let mut verifier = SignatureVerifier::new("seed", &params(UNIX_EPOCH));
+        let err = verifier
+            .verify(&Message::new(&b"unsigned"[..]))
+            .expect_err("message is not signed");
+        assert!(matches!(
+            err.kind(),
+            VerificationErrorKind::MissingSignature
+        ));
+        assert_eq!(
+            "event stream message 0 failed verification: missing :chunk-signature header",
+            format!("{}", err)
+        );
+
+        let signed = sign_message(&Message::new(&b"one"[..]), "seed", &params(UNIX_EPOCH))
+            .into_parts()
+            .0;
+        let without_date =
+            Message::new(signed.payload().clone()).add_header(signed.headers()[0].clone());
+        let err = verifier.verify(&without_date).expect_err("no date");
+        assert_eq!(1, err.index());
+        assert!(matches!(err.kind(), VerificationErrorKind::MissingDate));
+    }
+
     #[test]
     fn sign() {
         let message_to_sign = Message::new(&b"test payload"[..]).add_header(Header::new(
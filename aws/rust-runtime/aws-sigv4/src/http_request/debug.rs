@@ -0,0 +1,192 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::fmt;
+
+const CANONICAL_REQUEST_MARKER: &str = "The Canonical String for this request should have been\n'";
+
+/// The canonical request and string-to-sign a signature was calculated from
+///
+/// Returned by [`sign_with_debug_info`](super::sign_with_debug_info) to help debug signature
+/// mismatches. When a service rejects a signature, compare these with the values the service
+/// expected, e.g. with [`diff_canonical_request`](SigningDebugInfo::diff_canonical_request).
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SigningDebugInfo {
+    canonical_request: String,
+    string_to_sign: String,
+}
+
+impl SigningDebugInfo {
+    pub(crate) fn new(canonical_request: String, string_to_sign: String) -> Self {
+        Self {
+            canonical_request,
+            string_to_sign,
+        }
+    }
+
+    /// Returns the canonical request that was signed.
+    pub fn canonical_request(&self) -> &str {
+        &self.canonical_request
+    }
+
+    /// Returns the string-to-sign the signature was calculated over.
+    pub fn string_to_sign(&self) -> &str {
+        &self.string_to_sign
+    }
+
+    /// Compares the signed canonical request with the canonical request a service expected.
+    ///
+    /// Returns `None` if both are the same. Use
+    /// [`canonical_request_from_error_message`] to find the expected canonical request in the
+    /// message of a `SignatureDoesNotMatch` error.
+    pub fn diff_canonical_request(&self, expected: &str) -> Option<CanonicalRequestDiff> {
+        CanonicalRequestDiff::new(&self.canonical_request, expected)
+    }
+}
+
+/// Finds the canonical request a service expected in the message of a `SignatureDoesNotMatch`
+/// error.
+///
+/// Services echo the canonical request they calculated back in the error message, after the
+/// sentence `The Canonical String for this request should have been`. S3 returns it in the
+/// `CanonicalRequest` element of the error instead, which can be compared as is.
+pub fn canonical_request_from_error_message(message: &str) -> Option<&str> {
+    let start = message.find(CANONICAL_REQUEST_MARKER)? + CANONICAL_REQUEST_MARKER.len();
+    let rest = &message[start..];
+    let end = rest
+        .find("'\n\nThe String-to-Sign")
+        .or_else(|| rest.rfind('\''))?;
+    Some(&rest[..end])
+}
+
+/// A line of a [`CanonicalRequestDiff`]
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DiffLine {
+    /// The line is the same in both canonical requests
+    Same(String),
+    /// The line is only in the signed canonical request
+    Signed(String),
+    /// The line is only in the canonical request the service expected
+    Expected(String),
+}
+
+/// A line-by-line diff between the signed canonical request and the one a service expected
+///
+/// The `Display` implementation prints the diff with lines that are only in the signed
+/// canonical request prefixed with `-`, and lines that are only in the expected canonical
+/// request prefixed with `+`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CanonicalRequestDiff {
+    lines: Vec<DiffLine>,
+}
+
+impl CanonicalRequestDiff {
+    fn new(signed: &str, expected: &str) -> Option<Self> {
+        if signed == expected {
+            return None;
+        }
+        let signed: Vec<_> = signed.split('\n').collect();
+        let expected: Vec<_> = expected.split('\n').collect();
+
+        // Longest common subsequence of lines, `lcs[i][j]` is the length for the suffixes
+        // starting at `signed[i]` and `expected[j]`. Canonical requests are short enough for this.
+        let mut lcs = vec![vec![0_usize; expected.len() + 1]; signed.len() + 1];
+        for i in (0..signed.len()).rev() {
+            for j in (0..expected.len()).rev() {
+                lcs[i][j] = if signed[i] == expected[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let mut lines = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < signed.len() || j < expected.len() {
+            if i < signed.len() && j < expected.len() && signed[i] == expected[j] {
+                lines.push(DiffLine::Same(signed[i].into()));
+                i += 1;
+                j += 1;
+            } else if j == expected.len() || (i < signed.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+                lines.push(DiffLine::Signed(signed[i].into()));
+                i += 1;
+            } else {
+                lines.push(DiffLine::Expected(expected[j].into()));
+                j += 1;
+            }
+        }
+        Some(Self { lines })
+    }
+
+    /// Returns the lines of the diff.
+    pub fn lines(&self) -> &[DiffLine] {
+        &self.lines
+    }
+}
+
+impl fmt::Display for CanonicalRequestDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            match line {
+                DiffLine::Same(line) => writeln!(f, "  {}", line)?,
+                DiffLine::Signed(line) => writeln!(f, "- {}", line)?,
+                DiffLine::Expected(line) => writeln!(f, "+ {}", line)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{canonical_request_from_error_message, DiffLine, SigningDebugInfo};
+
+    const SIGNED: &str = "GET\n/\n\nhost:example.amazonaws.com\nx-amz-date:20150830T123600Z\n\nhost;x-amz-date\nUNSIGNED-PAYLOAD";
+
+    #[test]
+    fn diff_canonical_request() {
+        let info = SigningDebugInfo::new(SIGNED.into(), "sts".into());
+        assert_eq!(None, info.diff_canonical_request(SIGNED));
+
+        let expected = "GET\n/\n\nhost:example.amazonaws.com\nx-amz-content-sha256:UNSIGNED-PAYLOAD\nx-amz-date:20150830T123600Z\n\nhost;x-amz-content-sha256;x-amz-date\nUNSIGNED-PAYLOAD";
+        let diff = info
+            .diff_canonical_request(expected)
+            .expect("requests differ");
+        let changed: Vec<_> = diff
+            .lines()
+            .iter()
+            .filter(|line| !matches!(line, DiffLine::Same(_)))
+            .collect();
+        assert_eq!(
+            vec![
+                &DiffLine::Expected("x-amz-content-sha256:UNSIGNED-PAYLOAD".into()),
+                &DiffLine::Signed("host;x-amz-date".into()),
+                &DiffLine::Expected("host;x-amz-content-sha256;x-amz-date".into()),
+            ],
+            changed
+        );
+        assert!(diff
+            .to_string()
+            .contains("\n+ x-amz-content-sha256:UNSIGNED-PAYLOAD\n"));
+    }
+
+    #[test]
+    fn canonical_request_from_message() {
+        let message = format!(
+            "The request signature we calculated does not match the signature you provided.\n\n\
+             The Canonical String for this request should have been\n'{}'\n\n\
+             The String-to-Sign should have been\n'AWS4-HMAC-SHA256\n20150830T123600Z'\n",
+            SIGNED
+        );
+        assert_eq!(Some(SIGNED), canonical_request_from_error_message(&message));
+        assert_eq!(
+            None,
+            canonical_request_from_error_message("The security token is invalid")
+        );
+    }
+}
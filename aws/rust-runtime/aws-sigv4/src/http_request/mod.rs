@@ -42,6 +42,7 @@
 //!
 
 mod canonical_request;
+mod debug;
 mod query_writer;
 mod settings;
 mod sign;
@@ -50,7 +51,10 @@ mod url_escape;
 #[cfg(test)]
 pub(crate) mod test;
 
+pub use debug::{
+    canonical_request_from_error_message, CanonicalRequestDiff, DiffLine, SigningDebugInfo,
+};
 pub use settings::{
     PayloadChecksumKind, PercentEncodingMode, SignatureLocation, SigningParams, SigningSettings,
 };
-pub use sign::{sign, Error, SignableBody, SignableRequest};
+pub use sign::{sign, sign_with_debug_info, Error, SignableBody, SignableRequest};
@@ -7,6 +7,7 @@ use super::{PayloadChecksumKind, SignatureLocation};
 use crate::http_request::canonical_request::header;
 use crate::http_request::canonical_request::param;
 use crate::http_request::canonical_request::{CanonicalRequest, StringToSign, HMAC_256};
+use crate::http_request::debug::SigningDebugInfo;
 use crate::http_request::query_writer::QueryWriter;
 use crate::http_request::SigningParams;
 use crate::sign::{calculate_signature, generate_signing_key, sha256_hex_string};
@@ -153,21 +154,33 @@ pub fn sign<'a>(
     request: SignableRequest<'a>,
     params: &'a SigningParams<'a>,
 ) -> Result<SigningOutput<SigningInstructions>, Error> {
+    sign_with_debug_info(request, params).map(|(output, _)| output)
+}
+
+/// Like [`sign`], but also returns the canonical request and string-to-sign the signature was
+/// calculated from, to debug signature mismatches.
+pub fn sign_with_debug_info<'a>(
+    request: SignableRequest<'a>,
+    params: &'a SigningParams<'a>,
+) -> Result<(SigningOutput<SigningInstructions>, SigningDebugInfo), Error> {
     tracing::trace!(request = ?request, params = ?params, "signing request");
     match params.settings.signature_location {
         SignatureLocation::Headers => {
-            let (signing_headers, signature) =
-                calculate_signing_headers(&request, params)?.into_parts();
-            Ok(SigningOutput::new(
-                SigningInstructions::new(Some(signing_headers), None),
-                signature,
+            let (output, debug_info) = calculate_signing_headers(&request, params)?;
+            let (signing_headers, signature) = output.into_parts();
+            Ok((
+                SigningOutput::new(
+                    SigningInstructions::new(Some(signing_headers), None),
+                    signature,
+                ),
+                debug_info,
             ))
         }
         SignatureLocation::QueryParams => {
-            let (params, signature) = calculate_signing_params(&request, params)?;
-            Ok(SigningOutput::new(
-                SigningInstructions::new(None, Some(params)),
-                signature,
+            let (params, signature, debug_info) = calculate_signing_params(&request, params)?;
+            Ok((
+                SigningOutput::new(SigningInstructions::new(None, Some(params)), signature),
+                debug_info,
             ))
         }
     }
@@ -178,11 +191,11 @@ type CalculatedParams = Vec<(&'static str, Cow<'static, str>)>;
 fn calculate_signing_params<'a>(
     request: &'a SignableRequest<'a>,
     params: &'a SigningParams<'a>,
-) -> Result<(CalculatedParams, String), Error> {
+) -> Result<(CalculatedParams, String, SigningDebugInfo), Error> {
     let creq = CanonicalRequest::from(request, params)?;
-    tracing::trace!(canonical_request = %creq);
 
-    let encoded_creq = &sha256_hex_string(creq.to_string().as_bytes());
+    let creq_string = creq.to_string();
+    let encoded_creq = &sha256_hex_string(creq_string.as_bytes());
     let sts = StringToSign::new(
         params.time,
         params.region,
@@ -195,7 +208,8 @@ fn calculate_signing_params<'a>(
         params.region,
         params.service_name,
     );
-    let signature = calculate_signature(signing_key, sts.to_string().as_bytes());
+    let sts_string = sts.to_string();
+    let signature = calculate_signature(signing_key, sts_string.as_bytes());
 
     let values = creq.values.into_query_params().expect("signing with query");
     let mut signing_params = vec![
@@ -215,7 +229,11 @@ fn calculate_signing_params<'a>(
             Cow::Owned(security_token.to_string()),
         ));
     }
-    Ok((signing_params, signature))
+    Ok((
+        signing_params,
+        signature,
+        SigningDebugInfo::new(creq_string, sts_string),
+    ))
 }
 
 /// Calculates the signature headers that need to get added to the given `request`.
@@ -227,13 +245,13 @@ fn calculate_signing_params<'a>(
 fn calculate_signing_headers<'a>(
     request: &'a SignableRequest<'a>,
     params: &'a SigningParams<'a>,
-) -> Result<SigningOutput<HeaderMap<HeaderValue>>, Error> {
+) -> Result<(SigningOutput<HeaderMap<HeaderValue>>, SigningDebugInfo), Error> {
     // Step 1: https://docs.aws.amazon.com/en_pv/general/latest/gr/sigv4-create-canonical-request.html.
     let creq = CanonicalRequest::from(request, params)?;
-    tracing::trace!(canonical_request = %creq);
 
     // Step 2: https://docs.aws.amazon.com/en_pv/general/latest/gr/sigv4-create-string-to-sign.html.
-    let encoded_creq = &sha256_hex_string(creq.to_string().as_bytes());
+    let creq_string = creq.to_string();
+    let encoded_creq = &sha256_hex_string(creq_string.as_bytes());
     let sts = StringToSign::new(
         params.time,
         params.region,
@@ -248,7 +266,8 @@ fn calculate_signing_headers<'a>(
         params.region,
         params.service_name,
     );
-    let signature = calculate_signature(signing_key, sts.to_string().as_bytes());
+    let sts_string = sts.to_string();
+    let signature = calculate_signature(signing_key, sts_string.as_bytes());
 
     // Step 4: https://docs.aws.amazon.com/en_pv/general/latest/gr/sigv4-add-signature-to-request.html
     let values = creq.values.as_headers().expect("signing with headers");
@@ -268,7 +287,10 @@ fn calculate_signing_headers<'a>(
     if let Some(security_token) = values.security_token {
         add_header(&mut headers, header::X_AMZ_SECURITY_TOKEN, security_token);
     }
-    Ok(SigningOutput::new(headers, signature))
+    Ok((
+        SigningOutput::new(headers, signature),
+        SigningDebugInfo::new(creq_string, sts_string),
+    ))
 }
 
 fn add_header(map: &mut HeaderMap<HeaderValue>, key: &'static str, value: &str) {
@@ -298,12 +320,12 @@ fn build_authorization_header(
 
 #[cfg(test)]
 mod tests {
-    use super::{sign, SigningInstructions};
+    use super::{sign, sign_with_debug_info, SigningInstructions};
     use crate::date_time::test_parsers::parse_date_time;
     use crate::http_request::sign::SignableRequest;
     use crate::http_request::test::{
-        make_headers_comparable, test_request, test_signed_request,
-        test_signed_request_query_params,
+        make_headers_comparable, test_canonical_request, test_request, test_signed_request,
+        test_signed_request_query_params, test_sts,
     };
     use crate::http_request::{SignatureLocation, SigningParams, SigningSettings};
     use http::{HeaderMap, HeaderValue};
@@ -347,6 +369,36 @@ mod tests {
         assert_req_eq!(expected, signed);
     }
 
+    #[test]
+    fn test_sign_with_debug_info() {
+        let settings = SigningSettings::default();
+        let params = SigningParams {
+            access_key: "AKIDEXAMPLE",
+            secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            security_token: None,
+            region: "us-east-1",
+            service_name: "service",
+            time: parse_date_time("20150830T123600Z").unwrap(),
+            settings,
+        };
+
+        let original = test_request("get-vanilla-query-order-key-case");
+        let signable = SignableRequest::from(&original);
+        let (out, debug_info) = sign_with_debug_info(signable, &params).unwrap();
+        assert_eq!(
+            "b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500",
+            out.signature
+        );
+        assert_eq!(
+            test_canonical_request("get-vanilla-query-order-key-case"),
+            debug_info.canonical_request()
+        );
+        assert_eq!(
+            test_sts("get-vanilla-query-order-key-case"),
+            debug_info.string_to_sign()
+        );
+    }
+
     #[test]
     fn test_sign_url_escape() {
         let test = "double-encode-path";
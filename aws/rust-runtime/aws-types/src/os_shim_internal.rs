@@ -16,6 +16,8 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime};
 
+use aws_smithy_async::time::{SharedTimeSource, TimeSource as _};
+
 use crate::os_shim_internal::fs::Fake;
 use crate::os_shim_internal::time_source::Inner;
 
@@ -257,10 +259,25 @@ impl TimeSource {
         match &self.0 {
             Inner::Real => SystemTime::now(),
             Inner::Manual(manual) => manual.now(),
+            Inner::Shared(shared) => shared.now(),
         }
     }
 }
 
+impl From<SharedTimeSource> for TimeSource {
+    fn from(time_source: SharedTimeSource) -> Self {
+        TimeSource(time_source::Inner::Shared(time_source))
+    }
+}
+
+/// Reads time from the same virtual clock as the sleep implementations it hands out
+#[cfg(feature = "test-util")]
+impl From<aws_smithy_async::test_util::ManualTimeSource> for TimeSource {
+    fn from(time_source: aws_smithy_async::test_util::ManualTimeSource) -> Self {
+        SharedTimeSource::new(time_source).into()
+    }
+}
+
 impl Default for TimeSource {
     fn default() -> Self {
         TimeSource::real()
@@ -324,13 +341,13 @@ impl ManualTimeSource {
 
 mod time_source {
     use crate::os_shim_internal::ManualTimeSource;
+    use aws_smithy_async::time::SharedTimeSource;
 
-    // in the future, if needed we can add a time source trait, however, the manual time source
-    // should cover most test use cases.
     #[derive(Debug, Clone)]
     pub(super) enum Inner {
         Real,
         Manual(ManualTimeSource),
+        Shared(SharedTimeSource),
     }
 }
 
@@ -381,4 +398,20 @@ mod test {
         manual.advance(Duration::from_secs(10));
         assert_eq!(ts.now(), UNIX_EPOCH + Duration::from_secs(10));
     }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn controlled_ts_works() {
+        use aws_smithy_async::rt::sleep::AsyncSleep;
+        use aws_smithy_async::test_util::instant_time_and_sleep;
+
+        let (time, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let ts = TimeSource::from(time);
+        assert_eq!(ts.now(), UNIX_EPOCH);
+        let _ = sleep
+            .sleep(Duration::from_secs(10))
+            .now_or_never()
+            .expect("instant sleeps are ready immediately");
+        assert_eq!(ts.now(), UNIX_EPOCH + Duration::from_secs(10));
+    }
 }
@@ -12,6 +12,7 @@
 use std::sync::Arc;
 
 use aws_smithy_async::rt::sleep::AsyncSleep;
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
 use aws_smithy_client::http_connector::HttpConnector;
 use aws_smithy_types::retry::RetryConfig;
 use aws_smithy_types::timeout;
@@ -32,6 +33,7 @@ pub struct SdkConfig {
     sleep_impl: Option<Arc<dyn AsyncSleep>>,
     timeout_config: Option<timeout::Config>,
     http_connector: Option<HttpConnector>,
+    time_source: Option<SharedTimeSource>,
 }
 
 /// Builder for AWS Shared Configuration
@@ -45,6 +47,7 @@ pub struct Builder {
     sleep_impl: Option<Arc<dyn AsyncSleep>>,
     timeout_config: Option<timeout::Config>,
     http_connector: Option<HttpConnector>,
+    time_source: Option<SharedTimeSource>,
 }
 
 impl Builder {
@@ -349,6 +352,44 @@ impl Builder {
         self
     }
 
+    /// Set the time source that clients will use to read the current time
+    ///
+    /// The time source is used wherever the SDK needs wall-clock time, such as when signing
+    /// requests or deciding whether cached credentials have expired. If it isn't set, the
+    /// system clock is used.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use aws_smithy_async::time::TimeSource;
+    /// use aws_types::SdkConfig;
+    /// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    ///
+    /// #[derive(Debug)]
+    /// struct FixedTime;
+    ///
+    /// impl TimeSource for FixedTime {
+    ///     fn now(&self) -> SystemTime {
+    ///         UNIX_EPOCH + Duration::from_secs(1_600_000_000)
+    ///     }
+    /// }
+    ///
+    /// let config = SdkConfig::builder().time_source(FixedTime).build();
+    /// ```
+    pub fn time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.set_time_source(Some(SharedTimeSource::new(time_source)));
+        self
+    }
+
+    /// Set the time source that clients will use to read the current time
+    ///
+    /// The time source is used wherever the SDK needs wall-clock time, such as when signing
+    /// requests or deciding whether cached credentials have expired. If it isn't set, the
+    /// system clock is used.
+    pub fn set_time_source(&mut self, time_source: Option<SharedTimeSource>) -> &mut Self {
+        self.time_source = time_source;
+        self
+    }
+
     /// Build a [`SdkConfig`](SdkConfig) from this builder
     pub fn build(self) -> SdkConfig {
         SdkConfig {
@@ -360,6 +401,7 @@ impl Builder {
             sleep_impl: self.sleep_impl,
             timeout_config: self.timeout_config,
             http_connector: self.http_connector,
+            time_source: self.time_source,
         }
     }
 }
@@ -406,6 +448,11 @@ impl SdkConfig {
         self.http_connector.as_ref()
     }
 
+    /// Configured time source
+    pub fn time_source(&self) -> Option<SharedTimeSource> {
+        self.time_source.clone()
+    }
+
     /// Config builder
     pub fn builder() -> Builder {
         Builder::default()
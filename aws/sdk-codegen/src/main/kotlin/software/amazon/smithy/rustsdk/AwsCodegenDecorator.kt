@@ -9,6 +9,7 @@ import software.amazon.smithy.rust.codegen.smithy.customizations.DocsRsMetadataD
 import software.amazon.smithy.rust.codegen.smithy.customizations.DocsRsMetadataSettings
 import software.amazon.smithy.rust.codegen.smithy.customizations.RetryConfigDecorator
 import software.amazon.smithy.rust.codegen.smithy.customizations.SleepImplDecorator
+import software.amazon.smithy.rust.codegen.smithy.customizations.TimeSourceDecorator
 import software.amazon.smithy.rust.codegen.smithy.customizations.TimeoutConfigDecorator
 import software.amazon.smithy.rust.codegen.smithy.customize.CombinedCodegenDecorator
 import software.amazon.smithy.rustsdk.customize.apigateway.ApiGatewayDecorator
@@ -37,6 +38,7 @@ val DECORATORS = listOf(
     // Smithy specific decorators
     RetryConfigDecorator(),
     SleepImplDecorator(),
+    TimeSourceDecorator(),
     TimeoutConfigDecorator(),
 
     // Service specific decorators
@@ -142,6 +142,7 @@ private class AwsFluentClientExtensions(types: Types) {
                         .middleware(#{DynMiddleware}::new(#{Middleware}::new()));
                     builder.set_retry_config(retry_config.into());
                     builder.set_timeout_config(timeout_config);
+                    builder.set_time_source(Some(conf.time_source.clone()));
                     if let Some(sleep_impl) = sleep_impl {
                         builder.set_sleep_impl(Some(sleep_impl));
                     }
@@ -165,6 +166,7 @@ private class AwsFluentClientExtensions(types: Types) {
                         .middleware(#{DynMiddleware}::new(#{Middleware}::new()));
                     builder.set_retry_config(retry_config.into());
                     builder.set_timeout_config(timeout_config);
+                    builder.set_time_source(Some(conf.time_source.clone()));
                     // the builder maintains a try-state. To avoid suppressing the warning when sleep is unset,
                     // only set it if we actually have a sleep impl.
                     if let Some(sleep_impl) = sleep_impl {
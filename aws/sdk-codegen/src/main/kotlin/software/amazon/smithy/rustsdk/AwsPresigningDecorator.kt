@@ -8,11 +8,13 @@ package software.amazon.smithy.rustsdk
 import software.amazon.smithy.model.Model
 import software.amazon.smithy.model.knowledge.HttpBinding
 import software.amazon.smithy.model.knowledge.HttpBindingIndex
+import software.amazon.smithy.model.pattern.UriPattern
 import software.amazon.smithy.model.shapes.MemberShape
 import software.amazon.smithy.model.shapes.OperationShape
 import software.amazon.smithy.model.shapes.ServiceShape
 import software.amazon.smithy.model.shapes.ShapeId
 import software.amazon.smithy.model.shapes.ToShapeId
+import software.amazon.smithy.model.traits.HttpHeaderTrait
 import software.amazon.smithy.model.traits.HttpQueryTrait
 import software.amazon.smithy.model.traits.HttpTrait
 import software.amazon.smithy.model.transform.ModelTransformer
@@ -29,6 +31,8 @@ import software.amazon.smithy.rust.codegen.rustlang.withBlock
 import software.amazon.smithy.rust.codegen.rustlang.writable
 import software.amazon.smithy.rust.codegen.smithy.CodegenContext
 import software.amazon.smithy.rust.codegen.smithy.RuntimeConfig
+import software.amazon.smithy.rust.codegen.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.smithy.customizations.smithyAsyncTime
 import software.amazon.smithy.rust.codegen.smithy.customize.OperationCustomization
 import software.amazon.smithy.rust.codegen.smithy.customize.OperationSection
 import software.amazon.smithy.rust.codegen.smithy.customize.RustCodegenDecorator
@@ -41,6 +45,8 @@ import software.amazon.smithy.rust.codegen.util.cloneOperation
 import software.amazon.smithy.rust.codegen.util.expectTrait
 import software.amazon.smithy.rust.codegen.util.hasTrait
 import software.amazon.smithy.rustsdk.AwsRuntimeType.defaultMiddleware
+import software.amazon.smithy.rustsdk.AwsRuntimeType.presigning
+import software.amazon.smithy.rustsdk.AwsRuntimeType.presigningWebSocket
 import software.amazon.smithy.rustsdk.traits.PresignableTrait
 import kotlin.streams.toList
 
@@ -54,12 +60,42 @@ private fun syntheticShapeId(shape: ToShapeId): ShapeId =
 
 internal class PresignableOperation(
     val payloadSigningType: PayloadSigningType,
-    val modelTransforms: List<PresignModelTransform> = emptyList()
+    val modelTransforms: List<PresignModelTransform> = emptyList(),
+    /** Set for event stream operations that are presigned as a WebSocket connection */
+    val webSocket: WebSocketPresigning? = null,
 ) {
     fun hasModelTransforms(): Boolean = modelTransforms.isNotEmpty()
 }
 
+/**
+ * WebSocket API of an event stream operation
+ *
+ * The WebSocket connection is opened with a `GET` request to [path], with the header-bound input members moved
+ * into the query string, named after their header without [headerPrefix]. The event stream itself isn't part of
+ * the presigned request.
+ */
+internal class WebSocketPresigning(
+    val path: String,
+    val headerPrefix: String,
+    /** Port of the WebSocket API, if it differs from the port of the service endpoint */
+    val port: Int? = null,
+)
+
+internal fun webSocketOperation(operation: ShapeId, webSocket: WebSocketPresigning) = PresignableOperation(
+    PayloadSigningType.EMPTY,
+    modelTransforms = listOf(
+        OverrideHttpMethodTransform(mapOf(operation to "GET")),
+        OverrideHttpUriTransform(mapOf(operation to webSocket.path)),
+        MoveHeaderMembersToQueryParamsTransform(listOf(operation), webSocket.headerPrefix),
+        RemovePayloadMembersTransform(listOf(operation)),
+    ),
+    webSocket = webSocket,
+)
+
 private val SYNTHESIZE_SPEECH_OP = ShapeId.from("com.amazonaws.polly#SynthesizeSpeech")
+private val START_STREAM_TRANSCRIPTION_OP = ShapeId.from("com.amazonaws.transcribestreaming#StartStreamTranscription")
+private val START_MEDICAL_STREAM_TRANSCRIPTION_OP =
+    ShapeId.from("com.amazonaws.transcribestreaming#StartMedicalStreamTranscription")
 internal val PRESIGNABLE_OPERATIONS by lazy {
     mapOf(
         // S3
@@ -79,6 +115,16 @@ internal val PRESIGNABLE_OPERATIONS by lazy {
                 MoveDocumentMembersToQueryParamsTransform(listOf(SYNTHESIZE_SPEECH_OP)),
             )
         ),
+
+        // Transcribe Streaming
+        START_STREAM_TRANSCRIPTION_OP to webSocketOperation(
+            START_STREAM_TRANSCRIPTION_OP,
+            WebSocketPresigning("/stream-transcription-websocket", "x-amzn-transcribe-", port = 8443),
+        ),
+        START_MEDICAL_STREAM_TRANSCRIPTION_OP to webSocketOperation(
+            START_MEDICAL_STREAM_TRANSCRIPTION_OP,
+            WebSocketPresigning("/medical-stream-transcription-websocket", "x-amzn-transcribe-", port = 8443),
+        ),
     )
 }
 
@@ -96,7 +142,8 @@ class AwsPresigningDecorator internal constructor(
         codegenContext: CodegenContext,
         operation: OperationShape,
         baseCustomizations: List<OperationCustomization>
-    ): List<OperationCustomization> = baseCustomizations + listOf(AwsInputPresignedMethod(codegenContext, operation))
+    ): List<OperationCustomization> =
+        baseCustomizations + listOf(AwsInputPresignedMethod(codegenContext, operation, presignableOperations))
 
     /**
      * Adds presignable trait to known presignable operations and creates synthetic presignable shapes for codegen
@@ -128,23 +175,25 @@ class AwsPresigningDecorator internal constructor(
     }
 }
 
-class AwsInputPresignedMethod(
+class AwsInputPresignedMethod internal constructor(
     private val codegenContext: CodegenContext,
-    private val operationShape: OperationShape
+    private val operationShape: OperationShape,
+    private val presignableOperations: Map<ShapeId, PresignableOperation> = PRESIGNABLE_OPERATIONS,
 ) : OperationCustomization() {
     private val runtimeConfig = codegenContext.runtimeConfig
     private val symbolProvider = codegenContext.symbolProvider
 
     private val codegenScope = arrayOf(
-        "Error" to AwsRuntimeType.Presigning.member("config::Error"),
-        "PresignedRequest" to AwsRuntimeType.Presigning.member("request::PresignedRequest"),
-        "PresignedRequestService" to AwsRuntimeType.Presigning.member("service::PresignedRequestService"),
-        "PresigningConfig" to AwsRuntimeType.Presigning.member("config::PresigningConfig"),
+        "Error" to runtimeConfig.presigning().member("config::Error"),
+        "PresignedRequest" to runtimeConfig.presigning().member("request::PresignedRequest"),
+        "PresignedRequestService" to runtimeConfig.presigning().member("service::PresignedRequestService"),
+        "PresigningConfig" to runtimeConfig.presigning().member("config::PresigningConfig"),
         "SdkError" to CargoDependency.SmithyHttp(runtimeConfig).asType().member("result::SdkError"),
         "aws_sigv4" to runtimeConfig.awsRuntimeDependency("aws-sigv4").asType(),
         "sig_auth" to runtimeConfig.sigAuth().asType(),
         "tower" to CargoDependency.Tower.asType(),
-        "Middleware" to runtimeConfig.defaultMiddleware()
+        "Middleware" to runtimeConfig.defaultMiddleware(),
+        "TimeSource" to smithyAsyncTime(runtimeConfig).member("TimeSource"),
     )
 
     override fun section(section: OperationSection): Writable = writable {
@@ -155,7 +204,7 @@ class AwsInputPresignedMethod(
 
     private fun RustWriter.writeInputPresignedMethod(section: OperationSection.InputImpl) {
         val operationError = operationShape.errorSymbol(symbolProvider)
-        val presignableOp = PRESIGNABLE_OPERATIONS.getValue(operationShape.id)
+        val presignableOp = presignableOperations.getValue(operationShape.id)
 
         val makeOperationOp = if (presignableOp.hasModelTransforms()) {
             codegenContext.model.expectShape(syntheticShapeId(operationShape.id), OperationShape::class.java)
@@ -175,17 +224,19 @@ class AwsInputPresignedMethod(
             includeDefaultPayloadHeaders = false
         ).generateMakeOperation(this, makeOperationOp, section.customizations)
 
-        documentPresignedMethod(hasConfigArg = true)
+        val webSocket = presignableOp.webSocket
+        documentPresignedMethod(hasConfigArg = true, isWebSocket = webSocket != null)
         rustBlockTemplate(
             """
-            pub async fn presigned(
+            pub async fn ${presignedMethodName(webSocket)}(
                 self,
                 config: &crate::config::Config,
                 presigning_config: #{PresigningConfig}
-            ) -> Result<#{PresignedRequest}, #{SdkError}<#{OpError}>>
+            ) -> Result<#{Output}, #{SdkError}<#{OpError}>>
             """,
             *codegenScope,
-            "OpError" to operationError
+            "OpError" to operationError,
+            "Output" to presignedOutput(runtimeConfig, webSocket)
         ) {
             rustTemplate(
                 """
@@ -197,12 +248,17 @@ class AwsInputPresignedMethod(
                 *codegenScope
             )
             rustBlock("") {
-                rust(
+                rustTemplate(
                     """
                     // Change signature type to query params and wire up presigning config
                     let mut props = request.properties_mut();
-                    props.insert(presigning_config.start_time());
-                    """
+                    props.insert(
+                        presigning_config
+                            .explicit_start_time()
+                            .unwrap_or_else(|| #{TimeSource}::now(&config.time_source))
+                    );
+                    """,
+                    *codegenScope
                 )
                 withBlock("props.insert(", ");") {
                     rustTemplate(
@@ -223,6 +279,9 @@ class AwsInputPresignedMethod(
                     """,
                     *codegenScope
                 )
+                webSocket?.port?.also { port ->
+                    rust("#T::use_port(&mut props, $port);", runtimeConfig.presigningWebSocket())
+                }
             }
             rustTemplate(
                 """
@@ -232,41 +291,56 @@ class AwsInputPresignedMethod(
                     .service(#{PresignedRequestService}::new());
 
                 use #{tower}::{Service, ServiceExt};
-                Ok(svc.ready().await?.call(request).await?)
                 """,
                 *codegenScope
             )
+            if (webSocket == null) {
+                rust("Ok(svc.ready().await?.call(request).await?)")
+            } else {
+                rustTemplate(
+                    """
+                    let presigned = svc.ready().await?.call(request).await?;
+                    #{presigning_websocket}::to_websocket_uri(&presigned)
+                        .map_err(|err| #{SdkError}::ConstructionFailure(err.into()))
+                    """,
+                    *codegenScope,
+                    "presigning_websocket" to runtimeConfig.presigningWebSocket()
+                )
+            }
         }
     }
 }
 
 class AwsPresignedFluentBuilderMethod(
-    runtimeConfig: RuntimeConfig,
+    private val runtimeConfig: RuntimeConfig,
 ) : FluentClientCustomization() {
     private val codegenScope = arrayOf(
-        "Error" to AwsRuntimeType.Presigning.member("config::Error"),
-        "PresignedRequest" to AwsRuntimeType.Presigning.member("request::PresignedRequest"),
-        "PresigningConfig" to AwsRuntimeType.Presigning.member("config::PresigningConfig"),
+        "Error" to runtimeConfig.presigning().member("config::Error"),
+        "PresignedRequest" to runtimeConfig.presigning().member("request::PresignedRequest"),
+        "PresigningConfig" to runtimeConfig.presigning().member("config::PresigningConfig"),
         "SdkError" to CargoDependency.SmithyHttp(runtimeConfig).asType().member("result::SdkError")
     )
 
     override fun section(section: FluentClientSection): Writable = writable {
         if (section is FluentClientSection.FluentBuilderImpl && section.operationShape.hasTrait(PresignableTrait::class.java)) {
-            documentPresignedMethod(hasConfigArg = false)
+            val webSocket = PRESIGNABLE_OPERATIONS.getValue(section.operationShape.id).webSocket
+            val methodName = presignedMethodName(webSocket)
+            documentPresignedMethod(hasConfigArg = false, isWebSocket = webSocket != null)
             rustBlockTemplate(
                 """
-                pub async fn presigned(
+                pub async fn $methodName(
                     self,
                     presigning_config: #{PresigningConfig},
-                ) -> Result<#{PresignedRequest}, #{SdkError}<#{OpError}>>
+                ) -> Result<#{Output}, #{SdkError}<#{OpError}>>
                 """,
                 *codegenScope,
-                "OpError" to section.operationErrorType
+                "OpError" to section.operationErrorType,
+                "Output" to presignedOutput(runtimeConfig, webSocket)
             ) {
                 rustTemplate(
                     """
                     let input = self.inner.build().map_err(|err| #{SdkError}::ConstructionFailure(err.into()))?;
-                    input.presigned(&self.handle.conf, presigning_config).await
+                    input.$methodName(&self.handle.conf, presigning_config).await
                     """,
                     *codegenScope
                 )
@@ -345,11 +419,104 @@ class MoveDocumentMembersToQueryParamsTransform(
     }
 }
 
-private fun RustWriter.documentPresignedMethod(hasConfigArg: Boolean) {
+/**
+ * Model transform that overrides the HTTP request URI of the given map of operations.
+ */
+class OverrideHttpUriTransform(
+    httpUriOverrides: Map<ShapeId, String>,
+) : PresignModelTransform {
+    private val overrides = httpUriOverrides.mapKeys { entry -> syntheticShapeId(entry.key) }
+
+    override fun transform(model: Model): Model {
+        return ModelTransformer.create().mapShapes(model) { shape ->
+            if (shape is OperationShape && overrides.containsKey(shape.id)) {
+                check(shape.hasTrait(HttpTrait.ID)) {
+                    "OverrideHttpUriTransform can only be used with REST protocols"
+                }
+                val originalHttpTrait = shape.expectTrait<HttpTrait>()
+                shape.toBuilder()
+                    .removeTrait(HttpTrait.ID)
+                    .addTrait(originalHttpTrait.toBuilder().uri(UriPattern.parse(overrides.getValue(shape.id))).build())
+                    .build()
+            } else {
+                shape
+            }
+        }
+    }
+}
+
+/**
+ * Model transform that moves header members into query parameters for the given list of operations.
+ *
+ * The query parameters are named after the header, without [headerPrefix].
+ */
+class MoveHeaderMembersToQueryParamsTransform(
+    private val presignableOperations: List<ShapeId>,
+    private val headerPrefix: String,
+) : PresignModelTransform {
+    override fun transform(model: Model): Model {
+        val index = HttpBindingIndex(model)
+        val membersToUpdate = presignableOperations.map { id ->
+            val operation = model.expectShape(syntheticShapeId(id), OperationShape::class.java)
+            index.getRequestBindings(operation, HttpBinding.Location.HEADER).map { binding -> binding.member }
+        }.flatten()
+
+        return ModelTransformer.create().mapShapes(model) { shape ->
+            if (shape is MemberShape && membersToUpdate.contains(shape)) {
+                val header = shape.expectTrait<HttpHeaderTrait>().value
+                shape.toBuilder()
+                    .removeTrait(HttpHeaderTrait.ID)
+                    .addTrait(HttpQueryTrait(header.removePrefix(headerPrefix)))
+                    .build()
+            } else {
+                shape
+            }
+        }
+    }
+}
+
+/**
+ * Model transform that removes the payload members, such as event streams, of the given list of operations.
+ */
+class RemovePayloadMembersTransform(
+    private val presignableOperations: List<ShapeId>,
+) : PresignModelTransform {
+    override fun transform(model: Model): Model {
+        val index = HttpBindingIndex(model)
+        val membersToRemove = presignableOperations.map { id ->
+            val operation = model.expectShape(syntheticShapeId(id), OperationShape::class.java)
+            index.getRequestBindings(operation, HttpBinding.Location.PAYLOAD).map { binding -> binding.member }
+        }.flatten()
+        return ModelTransformer.create().removeShapes(model, membersToRemove)
+    }
+}
+
+private fun presignedMethodName(webSocket: WebSocketPresigning?): String =
+    if (webSocket == null) "presigned" else "presigned_websocket"
+
+private fun presignedOutput(runtimeConfig: RuntimeConfig, webSocket: WebSocketPresigning?): RuntimeType =
+    if (webSocket == null) runtimeConfig.presigning().member("request::PresignedRequest") else RuntimeType.http.member("Uri")
+
+private fun RustWriter.documentPresignedMethod(hasConfigArg: Boolean, isWebSocket: Boolean = false) {
     val configBlurb = if (hasConfigArg)
         "The credentials provider from the `config` will be used to generate the request's signature.\n"
     else
         ""
+    if (isWebSocket) {
+        docs(
+            """
+            Creates a presigned WebSocket URL for this operation.
+
+            ${configBlurb}The `presigning_config` provides additional presigning-specific config values, such as the
+            amount of time the URL should be valid for after creation.
+
+            The WebSocket connection takes the place of the event stream of this operation, which isn't part of the
+            URL. Every event stream message is sent and received as a binary WebSocket frame, which the
+            `presigning_websocket` module can encode and decode.
+            """
+        )
+        return
+    }
     docs(
         """
         Creates a presigned request for this operation.
@@ -42,9 +42,34 @@ fun RuntimeConfig.awsRoot(): RuntimeCrateLocation {
 
 object AwsRuntimeType {
     val S3Errors by lazy { RuntimeType.forInlineDependency(InlineAwsDependency.forRustFile("s3_errors")) }
-    val Presigning by lazy {
-        RuntimeType.forInlineDependency(InlineAwsDependency.forRustFile("presigning", public = true))
-    }
+    fun RuntimeConfig.presigning() = RuntimeType.forInlineDependency(
+        InlineAwsDependency.forRustFile("presigning", public = true)
+    )
+
+    fun RuntimeConfig.presigningWebSocket() = RuntimeType.forInlineDependency(
+        InlineAwsDependency.forRustFile(
+            "presigning_websocket", public = true,
+            presigning().dependency!!,
+            CargoDependency.Bytes,
+            CargoDependency.Http,
+            CargoDependency.SmithyEventStream(this),
+            CargoDependency.SmithyHttp(this),
+            CargoDependency.TokioStream,
+            awsEndpoint(),
+            awsTypes(),
+        )
+    )
+
+    fun RuntimeConfig.presignedPost() = RuntimeType.forInlineDependency(
+        InlineAwsDependency.forRustFile(
+            "presigned_post", public = true,
+            presigning().dependency!!,
+            CargoDependency.smithyJson(this),
+            CargoDependency.SmithyTypes(this),
+            awsRuntimeDependency("aws-sigv4"),
+            awsTypes(),
+        )
+    )
 
     fun RuntimeConfig.defaultMiddleware() = RuntimeType.forInlineDependency(
         InlineAwsDependency.forRustFile(
@@ -50,6 +50,7 @@ class SdkConfigDecorator : RustCodegenDecorator {
                         builder.set_retry_config(input.retry_config().cloned());
                         builder.set_timeout_config(input.timeout_config().cloned());
                         builder.set_sleep_impl(input.sleep_impl().clone());
+                        builder.set_time_source(input.time_source());
                         builder.set_credentials_provider(input.credentials_provider().cloned());
                         builder.set_app_name(input.app_name().cloned());
                         builder
@@ -18,6 +18,7 @@ import software.amazon.smithy.rust.codegen.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.rustlang.writable
 import software.amazon.smithy.rust.codegen.smithy.CodegenContext
 import software.amazon.smithy.rust.codegen.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.smithy.RustCrate
 import software.amazon.smithy.rust.codegen.smithy.customize.RustCodegenDecorator
 import software.amazon.smithy.rust.codegen.smithy.generators.LibRsCustomization
 import software.amazon.smithy.rust.codegen.smithy.generators.LibRsSection
@@ -26,6 +27,7 @@ import software.amazon.smithy.rust.codegen.smithy.protocols.ProtocolMap
 import software.amazon.smithy.rust.codegen.smithy.protocols.RestXml
 import software.amazon.smithy.rust.codegen.smithy.protocols.RestXmlFactory
 import software.amazon.smithy.rustsdk.AwsRuntimeType
+import software.amazon.smithy.rustsdk.AwsRuntimeType.presignedPost
 
 /**
  * Top level decorator for S3
@@ -55,6 +57,13 @@ class S3Decorator : RustCodegenDecorator {
             it + S3PubUse()
         }
     }
+
+    override fun extras(codegenContext: CodegenContext, rustCrate: RustCrate) {
+        if (applies(codegenContext.serviceShape.id)) {
+            // Presigned POST policies are only supported by S3, so only S3 gets the `presigned_post` module
+            rustCrate.lib { it.addDependency(codegenContext.runtimeConfig.presignedPost().dependency) }
+        }
+    }
 }
 
 class S3(codegenContext: CodegenContext) : RestXml(codegenContext) {
@@ -6,6 +6,8 @@
 package software.amazon.smithy.rustsdk
 
 import io.kotest.matchers.shouldBe
+import io.kotest.matchers.string.shouldContain
+import io.kotest.matchers.string.shouldNotContain
 import org.junit.jupiter.api.Test
 import software.amazon.smithy.model.Model
 import software.amazon.smithy.model.knowledge.HttpBinding
@@ -13,9 +15,15 @@ import software.amazon.smithy.model.knowledge.HttpBindingIndex
 import software.amazon.smithy.model.shapes.OperationShape
 import software.amazon.smithy.model.shapes.ServiceShape
 import software.amazon.smithy.model.shapes.ShapeId
+import software.amazon.smithy.model.shapes.StructureShape
 import software.amazon.smithy.model.traits.HttpTrait
+import software.amazon.smithy.rust.codegen.rustlang.RustWriter
+import software.amazon.smithy.rust.codegen.smithy.customize.OperationSection
+import software.amazon.smithy.rust.codegen.smithy.protocols.RestJson
+import software.amazon.smithy.rust.codegen.smithy.transformers.OperationNormalizer
 import software.amazon.smithy.rust.codegen.testutil.asSmithyModel
 import software.amazon.smithy.rust.codegen.util.expectTrait
+import software.amazon.smithy.rust.codegen.util.inputShape
 import software.amazon.smithy.rust.codegen.util.orNull
 import software.amazon.smithy.rustsdk.traits.PresignableTrait
 
@@ -187,3 +195,220 @@ class MoveDocumentMembersToQueryParamsTransformTest {
         )
     }
 }
+
+class OverrideHttpUriTransformTest {
+    @Test
+    fun `it should override the HTTP URI for the listed operations`() {
+        val model = """
+            namespace test
+            use aws.protocols#restJson1
+
+            @restJson1
+            service TestService {
+                version: "2019-12-16",
+                operations: ["One", "Two"],
+            }
+
+            structure TestInput { }
+            structure TestOutput { }
+
+            @http(uri: "/one", method: "POST")
+            operation One { input: TestInput, output: TestOutput }
+
+            @http(uri: "/two", method: "POST")
+            operation Two { input: TestInput, output: TestOutput }
+        """.asSmithyModel()
+
+        val serviceShape = model.expectShape(ShapeId.from("test#TestService"), ServiceShape::class.java)
+        val presignableOp = PresignableOperation(
+            PayloadSigningType.EMPTY,
+            listOf(OverrideHttpUriTransform(mapOf(ShapeId.from("test#One") to "/one-websocket")))
+        )
+        val transformed = AwsPresigningDecorator(
+            mapOf(ShapeId.from("test#One") to presignableOp)
+        ).transformModel(serviceShape, model)
+
+        val synthNamespace = "test.synthetic.aws.presigned"
+        val httpTrait = transformed.expectShape(ShapeId.from("$synthNamespace#One")).expectTrait<HttpTrait>()
+        httpTrait.uri.toString() shouldBe "/one-websocket"
+        httpTrait.method shouldBe "POST"
+        transformed.expectShape(ShapeId.from("test#One")).expectTrait<HttpTrait>().uri.toString() shouldBe "/one"
+        transformed.getShape(ShapeId.from("$synthNamespace#Two")).orNull() shouldBe null
+    }
+}
+
+class MoveHeaderMembersToQueryParamsTransformTest {
+    @Test
+    fun `it should move header members to query parameters for the listed operations`() {
+        val model = """
+            namespace test
+            use aws.protocols#restJson1
+
+            @restJson1
+            service TestService {
+                version: "2019-12-16",
+                operations: ["One"],
+            }
+
+            structure OneInputOutput {
+                @httpHeader("x-amzn-test-language")
+                language: String,
+                @httpHeader("x-other")
+                other: String,
+                @httpQuery("query")
+                query: String,
+
+                document: String,
+            }
+
+            @http(uri: "/one", method: "POST")
+            operation One { input: OneInputOutput, output: OneInputOutput }
+        """.asSmithyModel()
+
+        val serviceShape = model.expectShape(ShapeId.from("test#TestService"), ServiceShape::class.java)
+        val presignableOp = PresignableOperation(
+            PayloadSigningType.EMPTY,
+            listOf(MoveHeaderMembersToQueryParamsTransform(listOf(ShapeId.from("test#One")), "x-amzn-test-"))
+        )
+        val transformed = AwsPresigningDecorator(
+            mapOf(ShapeId.from("test#One") to presignableOp)
+        ).transformModel(serviceShape, model)
+
+        val index = HttpBindingIndex(transformed)
+        index.getRequestBindings(ShapeId.from("test.synthetic.aws.presigned#One")).map { (key, value) ->
+            key to (value.location to value.locationName)
+        }.toMap() shouldBe mapOf(
+            "language" to (HttpBinding.Location.QUERY to "language"),
+            "other" to (HttpBinding.Location.QUERY to "x-other"),
+            "query" to (HttpBinding.Location.QUERY to "query"),
+            "document" to (HttpBinding.Location.DOCUMENT to "document"),
+        )
+        index.getRequestBindings(ShapeId.from("test#One")).map { (key, value) ->
+            key to value.location
+        }.toMap() shouldBe mapOf(
+            "language" to HttpBinding.Location.HEADER,
+            "other" to HttpBinding.Location.HEADER,
+            "query" to HttpBinding.Location.QUERY,
+            "document" to HttpBinding.Location.DOCUMENT,
+        )
+    }
+}
+
+class RemovePayloadMembersTransformTest {
+    @Test
+    fun `it should remove payload members of the listed operations`() {
+        val model = """
+            namespace test
+            use aws.protocols#restJson1
+
+            @restJson1
+            service TestService {
+                version: "2019-12-16",
+                operations: ["One"],
+            }
+
+            structure OneInput {
+                @httpHeader("x-header")
+                header: String,
+                @httpPayload
+                stream: InputStream,
+            }
+            structure OneOutput { }
+
+            structure Audio {
+                @eventPayload
+                chunk: Blob,
+            }
+
+            @streaming
+            union InputStream {
+                Audio: Audio,
+            }
+
+            @http(uri: "/one", method: "POST")
+            operation One { input: OneInput, output: OneOutput }
+        """.asSmithyModel()
+
+        val serviceShape = model.expectShape(ShapeId.from("test#TestService"), ServiceShape::class.java)
+        val presignableOp = PresignableOperation(
+            PayloadSigningType.EMPTY,
+            listOf(RemovePayloadMembersTransform(listOf(ShapeId.from("test#One"))))
+        )
+        val transformed = AwsPresigningDecorator(
+            mapOf(ShapeId.from("test#One") to presignableOp)
+        ).transformModel(serviceShape, model)
+
+        val synthInput = ShapeId.from("test.synthetic.aws.presigned#OneInput")
+        transformed.expectShape(synthInput, StructureShape::class.java).memberNames shouldBe listOf("header")
+        transformed.expectShape(ShapeId.from("test#OneInput"), StructureShape::class.java).memberNames shouldBe
+            listOf("header", "stream")
+    }
+}
+
+class AwsInputPresignedMethodTest {
+    private val model = """
+        namespace test
+        use aws.protocols#restJson1
+
+        @restJson1
+        service TestService {
+            version: "2019-12-16",
+            operations: ["StartStream"],
+        }
+
+        structure StartStreamInput {
+            @httpHeader("x-amzn-test-language")
+            language: String,
+            @httpPayload
+            stream: InputStream,
+        }
+        structure StartStreamOutput { }
+
+        structure Audio {
+            @eventPayload
+            chunk: Blob,
+        }
+
+        @streaming
+        union InputStream {
+            Audio: Audio,
+        }
+
+        @http(uri: "/stream", method: "POST")
+        operation StartStream { input: StartStreamInput, output: StartStreamOutput }
+    """.asSmithyModel()
+
+    private fun renderPresignedMethod(webSocket: WebSocketPresigning): String {
+        val operationId = ShapeId.from("test#StartStream")
+        val presignableOperations = mapOf(operationId to webSocketOperation(operationId, webSocket))
+        val serviceShape = model.expectShape(ShapeId.from("test#TestService"), ServiceShape::class.java)
+        val transformed = AwsPresigningDecorator(presignableOperations)
+            .transformModel(serviceShape, OperationNormalizer.transform(model))
+        val codegenContext = awsTestCodegenContext(transformed, serviceShape)
+        val operation = transformed.expectShape(operationId, OperationShape::class.java)
+        val section = OperationSection.InputImpl(
+            emptyList(), operation, operation.inputShape(transformed), RestJson(codegenContext)
+        )
+
+        val writer = RustWriter.forModule("input")
+        AwsInputPresignedMethod(codegenContext, operation, presignableOperations).section(section)(writer)
+        return writer.toString()
+    }
+
+    @Test
+    fun `it should generate a presigned WebSocket URL method for event stream operations`() {
+        val generated = renderPresignedMethod(WebSocketPresigning("/stream-websocket", "x-amzn-test-", port = 8443))
+        generated shouldContain "pub async fn presigned_websocket("
+        generated shouldContain "Result<http::Uri,"
+        generated shouldContain "presigning_websocket::use_port(&mut props, 8443);"
+        generated shouldContain "presigning_websocket::to_websocket_uri(&presigned)"
+        generated shouldNotContain "pub async fn presigned("
+    }
+
+    @Test
+    fun `it should keep the endpoint port when the WebSocket API has no port of its own`() {
+        val generated = renderPresignedMethod(WebSocketPresigning("/stream-websocket", "x-amzn-test-"))
+        generated shouldContain "pub async fn presigned_websocket("
+        generated shouldNotContain "use_port"
+    }
+}
@@ -6,6 +6,7 @@
 package software.amazon.smithy.rustsdk
 
 import software.amazon.smithy.model.Model
+import software.amazon.smithy.model.shapes.ServiceShape
 import software.amazon.smithy.rust.codegen.smithy.RuntimeCrateLocation
 import software.amazon.smithy.rust.codegen.testutil.TestRuntimeConfig
 import software.amazon.smithy.rust.codegen.testutil.asSmithyModel
@@ -22,5 +23,5 @@ val AwsTestRuntimeConfig = TestRuntimeConfig.copy(
     }
 )
 
-fun awsTestCodegenContext(model: Model? = null) =
-    testCodegenContext(model ?: "namespace test".asSmithyModel()).copy(runtimeConfig = AwsTestRuntimeConfig)
+fun awsTestCodegenContext(model: Model? = null, serviceShape: ServiceShape? = null) =
+    testCodegenContext(model ?: "namespace test".asSmithyModel(), serviceShape).copy(runtimeConfig = AwsTestRuntimeConfig)
@@ -5,6 +5,7 @@
 
 use aws_sdk_ec2::{model::InstanceType, Client, Config, Credentials, Region};
 use aws_smithy_client::test_connection::TestConnection;
+use aws_smithy_http::body::SdkBody;
 use tokio_stream::StreamExt;
 
 fn stub_config() -> Config {
@@ -86,3 +87,112 @@ async fn paginators_handle_unset_tokens() {
     assert_eq!(first_item, None);
     conn.assert_requests_match(&[]);
 }
+
+fn spot_price_request(next_token: Option<&str>) -> http::Request<SdkBody> {
+    let next_token = next_token
+        .map(|token| format!("&NextToken={}", token))
+        .unwrap_or_default();
+    http::Request::builder()
+        .uri("https://ec2.us-east-1.amazonaws.com/")
+        .body(
+            format!(
+                "Action=DescribeSpotPriceHistory&Version=2016-11-15&AvailabilityZone=eu-north-1a{}",
+                next_token
+            )
+            .into(),
+        )
+        .unwrap()
+}
+
+fn spot_price_response(next_token: &str) -> http::Response<String> {
+    let body = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+        <DescribeSpotPriceHistoryResponse xmlns="http://ec2.amazonaws.com/doc/2016-11-15/">
+            <requestId>edf3e86c-4baf-47c1-9228-9a5ea09542e8</requestId>
+            <spotPriceHistorySet/>
+            <nextToken>{}</nextToken>
+        </DescribeSpotPriceHistoryResponse>"#,
+        next_token
+    );
+    http::Response::builder().status(200).body(body).unwrap()
+}
+
+#[tokio::test]
+async fn paginators_stop_after_max_pages_and_resume() {
+    let conn = TestConnection::new(vec![
+        (spot_price_request(None), spot_price_response("page2")),
+        (
+            spot_price_request(Some("page2")),
+            spot_price_response("page3"),
+        ),
+        (spot_price_request(Some("page3")), spot_price_response("")),
+    ]);
+    let client = Client::from_conf_conn(stub_config(), conn.clone());
+    let paginator = client
+        .describe_spot_price_history()
+        .availability_zone("eu-north-1a")
+        .into_paginator()
+        .max_pages(2);
+    let token = paginator.continuation_token();
+    let pages = paginator.send().collect::<Vec<_>>().await;
+    assert_eq!(2, pages.len());
+    assert_eq!(Some("page3".to_string()), token.get());
+    assert!(!token.is_finished());
+
+    let paginator = client
+        .describe_spot_price_history()
+        .availability_zone("eu-north-1a")
+        .into_paginator()
+        .resume_from(token.get().unwrap());
+    let token = paginator.continuation_token();
+    let pages = paginator.send().collect::<Vec<_>>().await;
+    assert_eq!(1, pages.len());
+    assert!(token.is_finished());
+    conn.assert_requests_match(&[]);
+}
+
+#[tokio::test]
+async fn paginators_with_zero_max_pages_send_no_requests() {
+    let conn = TestConnection::<&str>::new(vec![]);
+    let client = Client::from_conf_conn(stub_config(), conn.clone());
+    let pages = client
+        .describe_spot_price_history()
+        .availability_zone("eu-north-1a")
+        .into_paginator()
+        .max_pages(0)
+        .send()
+        .collect::<Vec<_>>()
+        .await;
+    assert!(pages.is_empty());
+    assert!(conn.requests().is_empty());
+}
+
+#[tokio::test]
+async fn paginators_error_on_previously_seen_token() {
+    let conn = TestConnection::new(vec![
+        (spot_price_request(None), spot_price_response("page2")),
+        (
+            spot_price_request(Some("page2")),
+            spot_price_response("page3"),
+        ),
+        (
+            spot_price_request(Some("page3")),
+            spot_price_response("page2"),
+        ),
+    ]);
+    let client = Client::from_conf_conn(stub_config(), conn.clone());
+    let mut pages = client
+        .describe_spot_price_history()
+        .availability_zone("eu-north-1a")
+        .into_paginator()
+        .send();
+    pages.try_next().await.expect("first page");
+    pages.try_next().await.expect("second page");
+    let err = pages.try_next().await.expect_err("token repeats");
+    assert!(
+        format!("{}", err).contains("continuation token was returned more than once"),
+        "{}",
+        err
+    );
+    assert_eq!(pages.try_next().await.expect("ok"), None);
+}
@@ -0,0 +1,109 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.smithy.customizations
+
+import software.amazon.smithy.rust.codegen.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.rustlang.writable
+import software.amazon.smithy.rust.codegen.smithy.CodegenContext
+import software.amazon.smithy.rust.codegen.smithy.RuntimeConfig
+import software.amazon.smithy.rust.codegen.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.smithy.customize.RustCodegenDecorator
+import software.amazon.smithy.rust.codegen.smithy.generators.config.ConfigCustomization
+import software.amazon.smithy.rust.codegen.smithy.generators.config.ServiceConfig
+
+/* Example Generated Code */
+/*
+pub struct Config {
+    pub(crate) time_source: aws_smithy_async::time::SharedTimeSource,
+}
+#[derive(Default)]
+pub struct Builder {
+    time_source: Option<aws_smithy_async::time::SharedTimeSource>,
+}
+impl Builder {
+    /// Sets the time source used by this service
+    pub fn time_source(mut self, time_source: impl aws_smithy_async::time::TimeSource + 'static) -> Self {
+        self.set_time_source(Some(aws_smithy_async::time::SharedTimeSource::new(time_source)));
+        self
+    }
+
+    /// Sets the time source used by this service
+    pub fn set_time_source(&mut self, time_source: Option<aws_smithy_async::time::SharedTimeSource>) -> &mut Self {
+        self.time_source = time_source;
+        self
+    }
+    /// Builds a [`Config`].
+    pub fn build(self) -> Config {
+        Config {
+            time_source: self.time_source.unwrap_or_default(),
+        }
+    }
+}
+ */
+
+class TimeSourceDecorator : RustCodegenDecorator {
+    override val name: String = "TimeSource"
+    override val order: Byte = 0
+
+    override fun configCustomizations(
+        codegenContext: CodegenContext,
+        baseCustomizations: List<ConfigCustomization>
+    ): List<ConfigCustomization> {
+        return baseCustomizations + TimeSourceConfig(codegenContext)
+    }
+}
+
+class TimeSourceConfig(codegenContext: CodegenContext) : ConfigCustomization() {
+    private val timeModule = smithyAsyncTime(codegenContext.runtimeConfig)
+    private val codegenScope = arrayOf(
+        "SharedTimeSource" to timeModule.member("SharedTimeSource"),
+        "TimeSource" to timeModule.member("TimeSource"),
+    )
+
+    override fun section(section: ServiceConfig) = writable {
+        when (section) {
+            is ServiceConfig.ConfigStruct -> rustTemplate(
+                "pub(crate) time_source: #{SharedTimeSource},",
+                *codegenScope
+            )
+            is ServiceConfig.ConfigImpl -> emptySection
+            is ServiceConfig.BuilderStruct ->
+                rustTemplate("time_source: Option<#{SharedTimeSource}>,", *codegenScope)
+            ServiceConfig.BuilderImpl ->
+                rustTemplate(
+                    """
+                    /// Sets the time source used by this service
+                    ///
+                    /// The time source is read wherever the client needs the current time, such as
+                    /// when signing requests. If it isn't set, the system clock is used.
+                    pub fn time_source(mut self, time_source: impl #{TimeSource} + 'static) -> Self {
+                        self.set_time_source(Some(#{SharedTimeSource}::new(time_source)));
+                        self
+                    }
+
+                    /// Sets the time source used by this service
+                    ///
+                    /// The time source is read wherever the client needs the current time, such as
+                    /// when signing requests. If it isn't set, the system clock is used.
+                    pub fn set_time_source(&mut self, time_source: Option<#{SharedTimeSource}>) -> &mut Self {
+                        self.time_source = time_source;
+                        self
+                    }
+                    """,
+                    *codegenScope
+                )
+            ServiceConfig.BuilderBuild -> rustTemplate(
+                """time_source: self.time_source.unwrap_or_default(),""",
+                *codegenScope
+            )
+            else -> emptySection
+        }
+    }
+}
+
+// Generate path to the time module in aws_smithy_async
+fun smithyAsyncTime(runtimeConfig: RuntimeConfig) =
+    RuntimeType("time", runtimeConfig.runtimeCrate("async"), "aws_smithy_async")
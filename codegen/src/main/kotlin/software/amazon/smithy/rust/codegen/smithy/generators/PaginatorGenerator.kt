@@ -108,9 +108,12 @@ class PaginatorGenerator private constructor(
             .copy(name = "result::SdkError"),
         "client" to CargoDependency.SmithyClient(runtimeConfig).asType(),
         "fn_stream" to CargoDependency.SmithyAsync(runtimeConfig).asType().member("future::fn_stream"),
+        "pagination" to CargoDependency.SmithyAsync(runtimeConfig).asType().member("future::pagination"),
+        "Token" to inputTokenType(),
 
         // External Types
-        "Stream" to CargoDependency.TokioStream.asType().member("Stream")
+        "Stream" to CargoDependency.TokioStream.asType().member("Stream"),
+        "StreamExt" to CargoDependency.TokioStream.asType().member("StreamExt")
 
     )
 
@@ -126,20 +129,52 @@ class PaginatorGenerator private constructor(
             /// Paginator for #{operation:D}
             pub struct $paginatorName#{generics:W} {
                 handle: std::sync::Arc<crate::client::Handle${generics.inst}>,
-                builder: #{Builder}
+                builder: #{Builder},
+                max_pages: Option<usize>,
+                continuation_token: #{pagination}::ContinuationToken<#{Token}>,
             }
 
             impl${generics.inst} ${paginatorName}${generics.inst} #{bounds:W} {
                 /// Create a new paginator-wrapper
                 pub(crate) fn new(handle: std::sync::Arc<crate::client::Handle${generics.inst}>, builder: #{Builder}) -> Self {
+                    let continuation_token = #{pagination}::ContinuationToken::new(builder.$inputTokenMember.clone());
                     Self {
                         handle,
                         builder,
+                        max_pages: None,
+                        continuation_token,
                     }
                 }
 
                 #{page_size_setter:W}
 
+                /// Stop after `max_pages` pages have been received
+                ///
+                /// With `max_pages(0)`, the paginator doesn't send any requests.
+                /// Use [`continuation_token`](Self::continuation_token) to resume from where the paginator stopped.
+                pub fn max_pages(mut self, max_pages: usize) -> Self {
+                    self.max_pages = Some(max_pages);
+                    self
+                }
+
+                /// Start from the page fetched by `token`, which was read from a previous paginator's [`continuation_token`](Self::continuation_token)
+                ///
+                /// _Note: this method will override any previously set value for `$inputTokenMember`_
+                pub fn resume_from(mut self, token: #{Token}) -> Self {
+                    self.builder.$inputTokenMember = Some(token.clone());
+                    self.continuation_token = #{pagination}::ContinuationToken::new(Some(token));
+                    self
+                }
+
+                /// Returns a handle to the token that fetches the page after the latest one received
+                ///
+                /// The handle is updated as pages are received, so reading it after processing a page gives the
+                /// token to pass to [`resume_from`](Self::resume_from) to continue after that page. It is `None`
+                /// once the last page has been received.
+                pub fn continuation_token(&self) -> #{pagination}::ContinuationToken<#{Token}> {
+                    self.continuation_token.clone()
+                }
+
                 #{items_fn:W}
 
 
@@ -151,13 +186,21 @@ class PaginatorGenerator private constructor(
                     // Move individual fields out of self for the borrow checker
                     let builder = self.builder;
                     let handle = self.handle;
+                    let max_pages = self.max_pages;
+                    let continuation_token = self.continuation_token;
                     #{fn_stream}::FnStream::new(move |tx| Box::pin(async move {
                         // Build the input for the first time. If required fields are missing, this is where we'll produce an early error.
                         let mut input = match builder.build().map_err(|err| #{SdkError}::ConstructionFailure(err.into())) {
                             Ok(input) => input,
                             Err(e) => { let _ = tx.send(Err(e)).await; return; }
                         };
+                        #{init_token_guard:W}
+                        let mut pages = 0;
                         loop {
+                            // Checked before sending so that `max_pages(0)` doesn't send any requests
+                            if max_pages.map(|max| pages >= max).unwrap_or(false) {
+                                return
+                            }
                             let op = match input.make_operation(&handle.conf)
                                 .await
                                 .map_err(|err| #{SdkError}::ConstructionFailure(err.into())) {
@@ -173,11 +216,9 @@ class PaginatorGenerator private constructor(
                                 Ok(ref resp) => {
                                     let new_token = #{output_token}(resp);
                                     let is_empty = ${nextTokenEmpty("new_token")};
-                                    if !is_empty && new_token == input.$inputTokenMember.as_ref() {
-                                        let _ = tx.send(Err(#{SdkError}::ConstructionFailure("next token did not change, aborting paginator. This indicates an SDK or AWS service bug.".into()))).await;
-                                        return;
-                                    }
+                                    #{check_token:W}
                                     input.$inputTokenMember = new_token.cloned();
+                                    continuation_token.set(if is_empty { None } else { input.$inputTokenMember.clone() });
                                     is_empty
                                 },
                                 Err(_) => true,
@@ -186,6 +227,7 @@ class PaginatorGenerator private constructor(
                                 // receiving end was dropped
                                 return
                             }
+                            pages += 1;
                             if done {
                                 return
                             }
@@ -196,10 +238,61 @@ class PaginatorGenerator private constructor(
             """,
             *codegenScope,
             "items_fn" to itemsFn(),
-            "output_token" to outputTokenLens
+            "output_token" to outputTokenLens,
+            "init_token_guard" to initTokenGuard(inputTokenMember),
+            "check_token" to checkToken(inputTokenMember)
         )
     }
 
+    /** Whether the token is a string, which can be tracked by a `TokenGuard` */
+    private fun tokenIsString(): Boolean = model.expectShape(paginationInfo.inputTokenMember.target).isStringShape
+
+    /** Type of the continuation token without the `Option` */
+    private fun inputTokenType(): String =
+        symbolProvider.toSymbol(paginationInfo.inputTokenMember).rustType().stripOuter<RustType.Option>().render(true)
+
+    private fun initTokenGuard(inputTokenMember: String) = writable {
+        if (tokenIsString()) {
+            rustTemplate(
+                """
+                let mut token_guard = #{pagination}::TokenGuard::new();
+                if let Some(token) = &input.$inputTokenMember {
+                    let _ = token_guard.check(token);
+                }
+                """,
+                *codegenScope
+            )
+        }
+    }
+
+    /** Fail if the service returned a token that would request a page that was already received */
+    private fun checkToken(inputTokenMember: String) = writable {
+        if (tokenIsString()) {
+            rustTemplate(
+                """
+                if let Some(token) = new_token.filter(|_| !is_empty) {
+                    if let Err(err) = token_guard.check(token) {
+                        let _ = tx.send(Err(#{SdkError}::ConstructionFailure(err.into()))).await;
+                        return;
+                    }
+                }
+                """,
+                *codegenScope
+            )
+        } else {
+            // Tokens that can't be hashed are only compared to the previous token
+            rustTemplate(
+                """
+                if !is_empty && new_token == input.$inputTokenMember.as_ref() {
+                    let _ = tx.send(Err(#{SdkError}::ConstructionFailure("next token did not change, aborting paginator. This indicates an SDK or AWS service bug.".into()))).await;
+                    return;
+                }
+                """,
+                *codegenScope
+            )
+        }
+    }
+
     /** Type of the inner item of the paginator */
     private fun itemType(): String {
         val members = paginationInfo.itemsMemberPath
@@ -224,7 +317,7 @@ class PaginatorGenerator private constructor(
                 /// This paginator automatically flattens results using `$documentedPath`. Queries to the underlying service
                 /// are dispatched lazily.
                 pub fn items(self) -> #{ItemPaginator}${generics.inst} {
-                    #{ItemPaginator}(self)
+                    #{ItemPaginator}(self, None)
                 }
                 """,
                 "ItemPaginator" to itemPaginatorType
@@ -242,9 +335,18 @@ class PaginatorGenerator private constructor(
                 /// Flattened paginator for `$paginatorName`
                 ///
                 /// This is created with [`.items()`]($paginatorName::items)
-                pub struct ${paginatorName}Items#{generics:W}($paginatorName${generics.inst});
+                pub struct ${paginatorName}Items#{generics:W}($paginatorName${generics.inst}, Option<usize>);
 
                 impl ${generics.inst} ${paginatorName}Items${generics.inst} #{bounds:W} {
+                    /// Stop after `max_items` items have been received
+                    ///
+                    /// _Note: the paginator's [`continuation_token`]($paginatorName::continuation_token) only tracks whole pages,
+                    /// so resuming from it may return items that were already received._
+                    pub fn max_items(mut self, max_items: usize) -> Self {
+                        self.1 = Some(max_items);
+                        self
+                    }
+
                     /// Create the pagination stream
                     ///
                     /// _Note: No requests will be dispatched until the stream is used (eg. with [`.next().await`](tokio_stream::StreamExt::next))._
@@ -252,7 +354,8 @@ class PaginatorGenerator private constructor(
                     /// To read the entirety of the paginator, use [`.collect::<Result<Vec<_>, _>()`](tokio_stream::StreamExt::collect).
                     pub fn send(self) -> impl #{Stream}<Item = std::result::Result<${itemType()}, #{SdkError}<#{Error}>>> + Unpin
                     #{send_bounds:W} {
-                        #{fn_stream}::TryFlatMap::new(self.0.send()).flat_map(|page| #{extract_items}(page).unwrap_or_default().into_iter())
+                        let max_items = self.1.unwrap_or(usize::MAX);
+                        #{StreamExt}::take(#{fn_stream}::TryFlatMap::new(self.0.send()).flat_map(|page| #{extract_items}(page).unwrap_or_default().into_iter()), max_items)
                     }
                 }
 
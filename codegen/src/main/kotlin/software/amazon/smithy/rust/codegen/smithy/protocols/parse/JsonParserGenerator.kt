@@ -76,6 +76,7 @@ class JsonParserGenerator(
         "expect_string_or_null" to smithyJson.member("deserialize::token::expect_string_or_null"),
         "expect_timestamp_or_null" to smithyJson.member("deserialize::token::expect_timestamp_or_null"),
         "json_token_iter" to smithyJson.member("deserialize::json_token_iter"),
+        "JsonTokenIterator" to smithyJson.member("deserialize::JsonTokenIterator"),
         "Peekable" to RuntimeType.std.member("iter::Peekable"),
         "skip_value" to smithyJson.member("deserialize::token::skip_value"),
         "skip_to_end" to smithyJson.member("deserialize::token::skip_to_end"),
@@ -96,14 +97,28 @@ class JsonParserGenerator(
     ): RuntimeType {
         return RuntimeType.forInlineFun(fnName, jsonDeserModule) {
             val unusedMut = if (includedMembers.isEmpty()) "##[allow(unused_mut)] " else ""
+            // Attach the JSON path of the failing value to errors so that they point at the problem.
+            // Errors raised by the parser itself rather than the tokenizer point at the last token read.
+            it.rustTemplate(
+                """
+                pub fn $fnName(value: &[u8], builder: #{Builder}) -> Result<#{Builder}, #{Error}> {
+                    let value = #{or_empty}(value);
+                    let mut tokens = #{json_token_iter}(value);
+                    ${fnName}_tokens(&mut tokens, builder)
+                        .map_err(|err| err.or_offset(tokens.last_token_offset()).with_path_from(value))
+                }
+                """,
+                "Builder" to structureShape.builderSymbol(symbolProvider),
+                *codegenScope
+            )
             it.rustBlockTemplate(
-                "pub fn $fnName(value: &[u8], ${unusedMut}mut builder: #{Builder}) -> Result<#{Builder}, #{Error}>",
+                "fn ${fnName}_tokens(tokens: &mut #{JsonTokenIterator}<'_>, ${unusedMut}mut builder: #{Builder}) -> Result<#{Builder}, #{Error}>",
                 "Builder" to structureShape.builderSymbol(symbolProvider),
                 *codegenScope
             ) {
                 rustTemplate(
                     """
-                    let mut tokens_owned = #{json_token_iter}(#{or_empty}(value)).peekable();
+                    let mut tokens_owned = tokens.peekable();
                     let tokens = &mut tokens_owned;
                     #{expect_start_object}(tokens.next())?;
                     """,
@@ -121,20 +136,31 @@ class JsonParserGenerator(
         check(shape is UnionShape || shape is StructureShape || shape is DocumentShape) { "payload parser should only be used on structures & unions" }
         val fnName = symbolProvider.deserializeFunctionName(shape) + "_payload"
         return RuntimeType.forInlineFun(fnName, jsonDeserModule) {
+            val input = if (shape is DocumentShape) {
+                "input"
+            } else {
+                "#{or_empty}(input)"
+            }
+            it.rustTemplate(
+                """
+                pub fn $fnName(input: &[u8]) -> Result<#{Shape}, #{Error}> {
+                    let input = $input;
+                    let mut tokens = #{json_token_iter}(input);
+                    ${fnName}_tokens(&mut tokens)
+                        .map_err(|err| err.or_offset(tokens.last_token_offset()).with_path_from(input))
+                }
+                """,
+                *codegenScope,
+                "Shape" to symbolProvider.toSymbol(shape)
+            )
             it.rustBlockTemplate(
-                "pub fn $fnName(input: &[u8]) -> Result<#{Shape}, #{Error}>",
+                "fn ${fnName}_tokens(tokens: &mut #{JsonTokenIterator}<'_>) -> Result<#{Shape}, #{Error}>",
                 *codegenScope,
                 "Shape" to symbolProvider.toSymbol(shape)
             ) {
-                val input = if (shape is DocumentShape) {
-                    "input"
-                } else {
-                    "#{or_empty}(input)"
-                }
-
                 rustTemplate(
                     """
-                    let mut tokens_owned = #{json_token_iter}($input).peekable();
+                    let mut tokens_owned = tokens.peekable();
                     let tokens = &mut tokens_owned;
                     """,
                     *codegenScope
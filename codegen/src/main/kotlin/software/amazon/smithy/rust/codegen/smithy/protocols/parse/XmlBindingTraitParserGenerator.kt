@@ -23,6 +23,7 @@ import software.amazon.smithy.model.shapes.UnionShape
 import software.amazon.smithy.model.traits.EnumTrait
 import software.amazon.smithy.model.traits.TimestampFormatTrait
 import software.amazon.smithy.model.traits.XmlFlattenedTrait
+import software.amazon.smithy.model.traits.XmlNamespaceTrait
 import software.amazon.smithy.rust.codegen.rustlang.Attribute
 import software.amazon.smithy.rust.codegen.rustlang.CargoDependency
 import software.amazon.smithy.rust.codegen.rustlang.RustModule
@@ -54,6 +55,7 @@ import software.amazon.smithy.rust.codegen.util.dq
 import software.amazon.smithy.rust.codegen.util.expectMember
 import software.amazon.smithy.rust.codegen.util.hasTrait
 import software.amazon.smithy.rust.codegen.util.inputShape
+import software.amazon.smithy.rust.codegen.util.orNull
 import software.amazon.smithy.rust.codegen.util.outputShape
 
 // The string argument is the name of the XML ScopedDecoder to continue parsing from
@@ -71,10 +73,26 @@ class XmlBindingTraitParserGenerator(
     private val writeOperationWrapper: RustWriter.(OperationWrapperContext, OperationInnerWriteable) -> Unit,
 ) : StructuredDataParserGenerator {
 
-    /** Abstraction to represent an XML element name */
-    data class XmlName(val name: String) {
+    /**
+     * Abstraction to represent an XML element name
+     *
+     * When [namespace] is set, the name's prefix is bound to that namespace URI by an `@xmlNamespace` trait, so
+     * elements are matched by URI rather than by whatever prefix the document happens to use.
+     */
+    data class XmlName(val name: String, val namespace: String? = null) {
+        private val local = name.substringAfter(':')
+
         /** Generates an expression to match a given element against this XML tag name */
-        fun matchExpression(start_el: String) = "$start_el.matches(${this.toString().dq()})"
+        fun matchExpression(start_el: String) = when (namespace) {
+            null -> "$start_el.matches(${this.toString().dq()})"
+            else -> "$start_el.matches_ns(${namespace.dq()}, ${local.dq()})"
+        }
+
+        /** Generates an expression to read the attribute with this name from a given element */
+        fun attrExpression(start_el: String) = when (namespace) {
+            null -> "$start_el.attr(${this.toString().dq()})"
+            else -> "$start_el.attr_ns(${namespace.dq()}, ${local.dq()})"
+        }
 
         override fun toString(): String {
             return name
@@ -384,9 +402,7 @@ class XmlBindingTraitParserGenerator(
         rustBlock("") {
             rustTemplate(
                 """
-                let s = ${ctx.tag}
-                    .start_el()
-                    .attr(${memberShape.xmlName().toString().dq()});
+                let s = ${memberShape.xmlName().attrExpression("${ctx.tag}.start_el()")};
                 """,
                 *codegenScope
             )
@@ -668,7 +684,11 @@ class XmlBindingTraitParserGenerator(
     }
 
     private fun MemberShape.xmlName(): XmlName {
-        return XmlName(xmlIndex.memberName(this))
+        val name = xmlIndex.memberName(this)
+        val prefix = name.substringBefore(':', missingDelimiterValue = "")
+        val namespace = getMemberTrait(model, XmlNamespaceTrait::class.java).orNull()
+            ?.takeIf { prefix.isNotEmpty() && it.prefix.orNull() == prefix }
+        return XmlName(name, namespace?.uri)
     }
 
     private fun MemberShape.isFlattened(): Boolean {
@@ -185,6 +185,16 @@ class JsonParserGeneratorTest {
                 assert_eq!(error_output.message.expect("message should be set"), "hello");
                 """
             )
+
+            writer.unitTest(
+                "error_path",
+                """
+                // errors point at the value that failed to parse, even when the tokens are valid JSON
+                let input = br#"{ "top": { "choice": 5 } }"#;
+                let err = ${writer.format(operationGenerator)}(input, output::op_output::Builder::default()).unwrap_err();
+                assert_eq!("$.top.choice", err.path().expect("path is known").to_string());
+                """
+            )
         }
         project.withModule(RustModule.public("model")) {
             model.lookup<StructureShape>("test#Top").renderWithModelBuilder(model, symbolProvider, it)
@@ -0,0 +1,216 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Utilities used by generated paginators to track continuation tokens.
+//!
+//! [`ContinuationToken`] exposes the token that fetches the next page, so that a long scan can
+//! checkpoint its progress and resume from it later. [`TokenGuard`] detects services that return a
+//! token that was already used, which would otherwise make a paginator loop forever.
+
+use std::collections::{HashSet, VecDeque};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+/// Handle to the continuation token of a paginator
+///
+/// The paginator updates the token after each page is received, so reading it after processing a
+/// page gives the token that resumes pagination at the following page. Clones share the same token.
+///
+/// # Examples
+/// ```rust
+/// use aws_smithy_async::future::pagination::ContinuationToken;
+///
+/// let token = ContinuationToken::new(None);
+/// let handle = token.clone();
+/// // the paginator receives a page with a next token...
+/// token.set(Some("page-2".to_string()));
+/// assert_eq!(Some("page-2".to_string()), handle.get());
+/// // ...then the last page
+/// token.set(None);
+/// assert!(handle.is_finished());
+/// ```
+#[derive(Debug)]
+pub struct ContinuationToken<T> {
+    state: Arc<Mutex<TokenState<T>>>,
+}
+
+#[derive(Debug)]
+struct TokenState<T> {
+    token: Option<T>,
+    finished: bool,
+}
+
+// Need a clone impl that doesn't require `T: Clone`
+impl<T> Clone for ContinuationToken<T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T> ContinuationToken<T> {
+    /// Creates a handle for a paginator that starts at `starting_token`
+    ///
+    /// `None` starts at the first page.
+    pub fn new(starting_token: Option<T>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(TokenState {
+                token: starting_token,
+                finished: false,
+            })),
+        }
+    }
+
+    /// Records the token returned with the latest page
+    ///
+    /// `None` means that the latest page was the last one.
+    pub fn set(&self, token: Option<T>) {
+        let mut state = self.state.lock().unwrap();
+        state.finished = token.is_none();
+        state.token = token;
+    }
+
+    /// Returns true once the paginator has received the last page
+    pub fn is_finished(&self) -> bool {
+        self.state.lock().unwrap().finished
+    }
+}
+
+impl<T: Clone> ContinuationToken<T> {
+    /// Returns the token that resumes pagination after the latest page
+    ///
+    /// Returns the starting token if no page has been received yet, and `None` once the last page
+    /// has been received.
+    pub fn get(&self) -> Option<T> {
+        self.state.lock().unwrap().token.clone()
+    }
+}
+
+/// Number of the most recent tokens that a [`TokenGuard`] remembers
+const MAX_TRACKED_TOKENS: usize = 1000;
+
+/// Detects continuation tokens that a service returns more than once
+///
+/// A repeated token means that the paginator would request a page it has already received, and
+/// most likely loop forever.
+///
+/// Only the 1000 most recent tokens are remembered, so that long scans don't use ever more
+/// memory. A service that cycles through more tokens than that isn't detected.
+#[derive(Debug)]
+pub struct TokenGuard<T> {
+    /// The most recent tokens, oldest first
+    recent: VecDeque<T>,
+    /// The same tokens as `recent`, for fast lookups
+    seen: HashSet<T>,
+    max_tracked: usize,
+    /// Number of tokens checked so far
+    checked: usize,
+}
+
+impl<T> Default for TokenGuard<T> {
+    fn default() -> Self {
+        Self {
+            recent: VecDeque::new(),
+            seen: HashSet::new(),
+            max_tracked: MAX_TRACKED_TOKENS,
+            checked: 0,
+        }
+    }
+}
+
+impl<T: Eq + Hash + Clone> TokenGuard<T> {
+    /// Creates a guard that hasn't seen any tokens
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `token`, failing if it is one of the most recent tokens recorded
+    pub fn check(&mut self, token: &T) -> Result<(), RepeatedTokenError> {
+        if self.seen.contains(token) {
+            return Err(RepeatedTokenError {
+                pages: self.checked,
+            });
+        }
+        self.checked += 1;
+        self.seen.insert(token.clone());
+        self.recent.push_back(token.clone());
+        if self.recent.len() > self.max_tracked {
+            if let Some(oldest) = self.recent.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Error returned by [`TokenGuard::check`] when a continuation token repeats
+#[derive(Debug)]
+pub struct RepeatedTokenError {
+    pages: usize,
+}
+
+impl Display for RepeatedTokenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "a continuation token was returned more than once after {} pages, aborting paginator. \
+             This indicates an SDK or service bug.",
+            self.pages
+        )
+    }
+}
+
+impl Error for RepeatedTokenError {}
+
+#[cfg(test)]
+mod test {
+    use super::{ContinuationToken, TokenGuard};
+
+    #[test]
+    fn continuation_token() {
+        let token = ContinuationToken::new(Some("start"));
+        let handle = token.clone();
+        assert_eq!(Some("start"), handle.get());
+        assert!(!handle.is_finished());
+
+        token.set(Some("next"));
+        assert_eq!(Some("next"), handle.get());
+        assert!(!handle.is_finished());
+
+        token.set(None);
+        assert_eq!(None, handle.get());
+        assert!(handle.is_finished());
+    }
+
+    #[test]
+    fn token_guard() {
+        let mut guard = TokenGuard::new();
+        guard.check(&"a".to_string()).unwrap();
+        guard.check(&"b".to_string()).unwrap();
+        let err = guard.check(&"a".to_string()).unwrap_err();
+        assert_eq!(
+            "a continuation token was returned more than once after 2 pages, aborting paginator. \
+             This indicates an SDK or service bug.",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn token_guard_only_remembers_recent_tokens() {
+        let mut guard = TokenGuard::new();
+        guard.max_tracked = 2;
+        for token in ["a", "b", "c"] {
+            guard.check(&token).unwrap();
+        }
+        assert_eq!(2, guard.seen.len());
+        // "a" was forgotten, but "c" is still remembered
+        guard.check(&"a").unwrap();
+        let err = guard.check(&"c").unwrap_err();
+        assert!(err.to_string().contains("after 4 pages"), "{}", err);
+    }
+}
@@ -13,10 +13,18 @@
 //! Future utilities and runtime-agnostic abstractions for smithy-rs.
 //!
 //! Async runtime specific code is abstracted behind async traits, and implementations are
-//! provided via feature flag. For now, only Tokio runtime implementations are provided.
+//! provided via feature flag: `rt-tokio` for Tokio, `rt-async-std` for async-std, and `rt-smol` for
+//! smol.
+//!
+//! The `test-util` feature enables [`test_util`], a virtual clock and matching sleep
+//! implementations for testing time-dependent code deterministically.
 
 pub mod future;
 pub mod rt;
+pub mod time;
+
+#[cfg(feature = "test-util")]
+pub mod test_util;
 
 /// Given an `Instant` and a `Duration`, assert time elapsed since `Instant` is equal to `Duration`.
 /// This macro allows for a 5ms margin of error.
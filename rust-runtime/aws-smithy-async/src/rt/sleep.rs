@@ -39,16 +39,12 @@ where
     }
 }
 
-#[cfg(feature = "rt-tokio")]
 /// Returns a default sleep implementation based on the features enabled
+///
+/// When more than one runtime feature is enabled, Tokio is preferred over async-std, and async-std
+/// is preferred over smol. Returns `None` when no runtime feature is enabled.
 pub fn default_async_sleep() -> Option<Arc<dyn AsyncSleep>> {
-    Some(sleep_tokio())
-}
-
-#[cfg(not(feature = "rt-tokio"))]
-/// Returns a default sleep implementation based on the features enabled
-pub fn default_async_sleep() -> Option<Arc<dyn AsyncSleep>> {
-    None
+    sleep_tokio().or_else(sleep_async_std).or_else(sleep_smol)
 }
 
 /// Future returned by [`AsyncSleep`].
@@ -100,6 +96,112 @@ impl AsyncSleep for TokioSleep {
 }
 
 #[cfg(feature = "rt-tokio")]
-fn sleep_tokio() -> Arc<dyn AsyncSleep> {
-    Arc::new(TokioSleep::new())
+fn sleep_tokio() -> Option<Arc<dyn AsyncSleep>> {
+    Some(Arc::new(TokioSleep::new()))
+}
+
+#[cfg(not(feature = "rt-tokio"))]
+fn sleep_tokio() -> Option<Arc<dyn AsyncSleep>> {
+    None
+}
+
+/// Implementation of [`AsyncSleep`] for async-std.
+#[non_exhaustive]
+#[cfg(feature = "rt-async-std")]
+#[derive(Debug, Default)]
+pub struct AsyncStdSleep;
+
+#[cfg(feature = "rt-async-std")]
+impl AsyncStdSleep {
+    /// Create a new [`AsyncSleep`] implementation using the async-std sleep implementation
+    pub fn new() -> AsyncStdSleep {
+        Default::default()
+    }
+}
+
+#[cfg(feature = "rt-async-std")]
+impl AsyncSleep for AsyncStdSleep {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Sleep::new(async_std::task::sleep(duration))
+    }
+}
+
+#[cfg(feature = "rt-async-std")]
+fn sleep_async_std() -> Option<Arc<dyn AsyncSleep>> {
+    Some(Arc::new(AsyncStdSleep::new()))
+}
+
+#[cfg(not(feature = "rt-async-std"))]
+fn sleep_async_std() -> Option<Arc<dyn AsyncSleep>> {
+    None
+}
+
+/// Implementation of [`AsyncSleep`] for smol.
+///
+/// Timers are driven by the `async-io` reactor that smol runs on, so this works under any executor.
+#[non_exhaustive]
+#[cfg(feature = "rt-smol")]
+#[derive(Debug, Default)]
+pub struct SmolSleep;
+
+#[cfg(feature = "rt-smol")]
+impl SmolSleep {
+    /// Create a new [`AsyncSleep`] implementation using the smol timer implementation
+    pub fn new() -> SmolSleep {
+        Default::default()
+    }
+}
+
+#[cfg(feature = "rt-smol")]
+impl AsyncSleep for SmolSleep {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        let timer = async_io::Timer::after(duration);
+        Sleep::new(async move {
+            timer.await;
+        })
+    }
+}
+
+#[cfg(feature = "rt-smol")]
+fn sleep_smol() -> Option<Arc<dyn AsyncSleep>> {
+    Some(Arc::new(SmolSleep::new()))
+}
+
+#[cfg(not(feature = "rt-smol"))]
+fn sleep_smol() -> Option<Arc<dyn AsyncSleep>> {
+    None
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(any(feature = "rt-async-std", feature = "rt-smol"))]
+    use super::AsyncSleep;
+    #[cfg(any(feature = "rt-async-std", feature = "rt-smol"))]
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn default_sleep_matches_features() {
+        let enabled = cfg!(any(
+            feature = "rt-tokio",
+            feature = "rt-async-std",
+            feature = "rt-smol"
+        ));
+        assert_eq!(enabled, super::default_async_sleep().is_some());
+    }
+
+    #[cfg(feature = "rt-async-std")]
+    #[test]
+    fn async_std_sleep() {
+        let start = Instant::now();
+        async_std::task::block_on(super::AsyncStdSleep::new().sleep(Duration::from_millis(10)));
+        assert!(start.elapsed() >= Duration::from_millis(10));
+    }
+
+    #[cfg(feature = "rt-smol")]
+    #[test]
+    fn smol_sleep() {
+        let start = Instant::now();
+        async_io::block_on(super::SmolSleep::new().sleep(Duration::from_millis(10)));
+        assert!(start.elapsed() >= Duration::from_millis(10));
+    }
 }
@@ -0,0 +1,341 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Test utilities for controlling time deterministically.
+//!
+//! A [`ManualTimeSource`] is a virtual clock that only moves when told to. It implements
+//! [`TimeSource`] so that it can be shared with anything that needs to read the current time,
+//! and it hands out [`AsyncSleep`] implementations that are driven by that same clock:
+//! - [`ManualSleep`] sleeps until the test advances the clock past their deadline.
+//! - [`InstantSleep`] sleeps finish immediately, advancing the clock by the requested duration.
+//!
+//! Both record every requested sleep, so tests can assert on retry backoff and timeouts without
+//! any real waiting.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aws_smithy_async::rt::sleep::AsyncSleep;
+//! use aws_smithy_async::test_util::instant_time_and_sleep;
+//! use std::time::{Duration, UNIX_EPOCH};
+//!
+//! # async fn example() {
+//! let (time, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+//! sleep.sleep(Duration::from_secs(5)).await;
+//! assert_eq!(UNIX_EPOCH + Duration::from_secs(5), time.now());
+//! assert_eq!(vec![Duration::from_secs(5)], time.sleeps());
+//! # }
+//! ```
+
+use crate::rt::sleep::{AsyncSleep, Sleep};
+use crate::time::TimeSource;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, SystemTime};
+
+/// Creates a [`ManualTimeSource`] starting at `start_time` and a [`ManualSleep`] driven by it
+pub fn manual_time_and_sleep(start_time: SystemTime) -> (ManualTimeSource, ManualSleep) {
+    let time = ManualTimeSource::new(start_time);
+    let sleep = time.manual_sleep();
+    (time, sleep)
+}
+
+/// Creates a [`ManualTimeSource`] starting at `start_time` and an [`InstantSleep`] driven by it
+pub fn instant_time_and_sleep(start_time: SystemTime) -> (ManualTimeSource, InstantSleep) {
+    let time = ManualTimeSource::new(start_time);
+    let sleep = time.instant_sleep();
+    (time, sleep)
+}
+
+/// Virtual clock shared between a test and the code under test
+///
+/// Clones share the same clock.
+#[derive(Clone, Debug)]
+pub struct ManualTimeSource {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    now: SystemTime,
+    sleeps: Vec<Duration>,
+    pending: Vec<PendingSleep>,
+    next_id: u64,
+}
+
+#[derive(Debug)]
+struct PendingSleep {
+    id: u64,
+    deadline: SystemTime,
+    waker: Option<Waker>,
+}
+
+impl ManualTimeSource {
+    /// Creates a clock that reads `start_time` until it is advanced
+    pub fn new(start_time: SystemTime) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                now: start_time,
+                sleeps: vec![],
+                pending: vec![],
+                next_id: 0,
+            })),
+        }
+    }
+
+    /// Returns the current virtual time
+    pub fn now(&self) -> SystemTime {
+        self.state().now
+    }
+
+    /// Moves the clock forward by `duration`, waking every [`ManualSleep`] that is now due
+    pub fn advance(&self, duration: Duration) {
+        let state = self.state();
+        let now = state.now + duration;
+        Self::set_locked(state, now);
+    }
+
+    /// Sets the clock to `time`, waking every [`ManualSleep`] that is now due
+    ///
+    /// Setting the clock backwards is allowed, but doesn't wake anything.
+    pub fn set_time(&self, time: SystemTime) {
+        Self::set_locked(self.state(), time);
+    }
+
+    /// Returns the duration of every sleep requested so far, in the order they were requested
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.state().sleeps.clone()
+    }
+
+    /// Returns the number of [`ManualSleep`] sleeps that haven't finished and haven't been dropped
+    pub fn pending_sleeps(&self) -> usize {
+        self.state().pending.len()
+    }
+
+    /// Returns an [`AsyncSleep`] that waits for this clock to be advanced
+    pub fn manual_sleep(&self) -> ManualSleep {
+        ManualSleep { time: self.clone() }
+    }
+
+    /// Returns an [`AsyncSleep`] that advances this clock instead of waiting
+    pub fn instant_sleep(&self) -> InstantSleep {
+        InstantSleep { time: self.clone() }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    fn set_locked(mut state: MutexGuard<'_, State>, time: SystemTime) {
+        state.now = time;
+        let wakers: Vec<Waker> = state
+            .pending
+            .iter_mut()
+            .filter(|sleep| sleep.deadline <= time)
+            .filter_map(|sleep| sleep.waker.take())
+            .collect();
+        // Wake after releasing the lock so that woken tasks polled inline don't deadlock
+        drop(state);
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+impl TimeSource for ManualTimeSource {
+    fn now(&self) -> SystemTime {
+        ManualTimeSource::now(self)
+    }
+}
+
+/// [`AsyncSleep`] implementation that waits until its [`ManualTimeSource`] is advanced
+///
+/// The deadline of each sleep is computed from the virtual time at which it was requested, so a
+/// sleep of zero finishes immediately.
+#[derive(Clone, Debug)]
+pub struct ManualSleep {
+    time: ManualTimeSource,
+}
+
+impl ManualSleep {
+    /// Returns the clock that drives this sleep implementation
+    pub fn time_source(&self) -> &ManualTimeSource {
+        &self.time
+    }
+}
+
+impl AsyncSleep for ManualSleep {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        let mut state = self.time.state();
+        state.sleeps.push(duration);
+        let id = state.next_id;
+        state.next_id += 1;
+        let deadline = state.now + duration;
+        state.pending.push(PendingSleep {
+            id,
+            deadline,
+            waker: None,
+        });
+        Sleep::new(ManualSleepFuture {
+            state: self.time.state.clone(),
+            id,
+            deadline,
+        })
+    }
+}
+
+struct ManualSleepFuture {
+    state: Arc<Mutex<State>>,
+    id: u64,
+    deadline: SystemTime,
+}
+
+impl Future for ManualSleepFuture {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.now >= self.deadline {
+            state.pending.retain(|sleep| sleep.id != self.id);
+            return Poll::Ready(());
+        }
+        if let Some(sleep) = state.pending.iter_mut().find(|sleep| sleep.id == self.id) {
+            sleep.waker = Some(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for ManualSleepFuture {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.pending.retain(|sleep| sleep.id != self.id);
+        }
+    }
+}
+
+/// [`AsyncSleep`] implementation that finishes immediately, advancing its [`ManualTimeSource`]
+/// by the requested duration when first polled
+///
+/// This makes code that sleeps between retries run instantly while still observing the passage
+/// of time. Note that a timeout built on this sleep fires as soon as the future it guards is
+/// pending.
+#[derive(Clone, Debug)]
+pub struct InstantSleep {
+    time: ManualTimeSource,
+}
+
+impl InstantSleep {
+    /// Returns the clock that drives this sleep implementation
+    pub fn time_source(&self) -> &ManualTimeSource {
+        &self.time
+    }
+}
+
+impl AsyncSleep for InstantSleep {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        self.time.state().sleeps.push(duration);
+        let time = self.time.clone();
+        Sleep::new(async move { time.advance(duration) })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{instant_time_and_sleep, manual_time_and_sleep};
+    use crate::future::timeout::Timeout;
+    use crate::rt::sleep::AsyncSleep;
+    use futures_util::FutureExt;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[tokio::test]
+    async fn manual_sleep_waits_for_advance() {
+        let (time, sleep) = manual_time_and_sleep(UNIX_EPOCH);
+        let mut short = sleep.sleep(Duration::from_secs(1));
+        let mut long = sleep.sleep(Duration::from_secs(5));
+        assert!((&mut short).now_or_never().is_none());
+        assert!((&mut long).now_or_never().is_none());
+        assert_eq!(2, time.pending_sleeps());
+
+        let waiter = tokio::spawn(long);
+        tokio::task::yield_now().await;
+        time.advance(Duration::from_secs(1));
+        assert!(short.now_or_never().is_some());
+        assert_eq!(1, time.pending_sleeps());
+
+        time.advance(Duration::from_secs(4));
+        waiter.await.unwrap();
+        assert_eq!(0, time.pending_sleeps());
+        assert_eq!(UNIX_EPOCH + Duration::from_secs(5), time.now());
+        assert_eq!(
+            vec![Duration::from_secs(1), Duration::from_secs(5)],
+            time.sleeps()
+        );
+    }
+
+    #[test]
+    fn manual_sleep_deadline_starts_at_request() {
+        let (time, sleep) = manual_time_and_sleep(UNIX_EPOCH);
+        assert!(sleep.sleep(Duration::ZERO).now_or_never().is_some());
+
+        time.advance(Duration::from_secs(10));
+        let mut pending = sleep.sleep(Duration::from_secs(3));
+        time.set_time(UNIX_EPOCH + Duration::from_secs(12));
+        assert!((&mut pending).now_or_never().is_none());
+        time.advance(Duration::from_secs(1));
+        assert!(pending.now_or_never().is_some());
+    }
+
+    #[test]
+    fn dropped_sleeps_are_not_pending() {
+        let (time, sleep) = manual_time_and_sleep(UNIX_EPOCH);
+        let pending = sleep.sleep(Duration::from_secs(1));
+        assert_eq!(1, time.pending_sleeps());
+        drop(pending);
+        assert_eq!(0, time.pending_sleeps());
+    }
+
+    #[test]
+    fn manual_timeout() {
+        let (time, sleep) = manual_time_and_sleep(UNIX_EPOCH);
+        let mut timeout = Timeout::new(
+            crate::future::never::Never::new(),
+            sleep.sleep(Duration::from_millis(250)),
+        );
+        assert!((&mut timeout).now_or_never().is_none());
+        time.advance(Duration::from_millis(250));
+        assert!(timeout.now_or_never().unwrap().is_err());
+    }
+
+    #[test]
+    fn instant_sleep_advances_time() {
+        let (time, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+        let first = sleep.sleep(Duration::from_secs(1));
+        // time only moves once the sleep is polled
+        assert_eq!(UNIX_EPOCH, time.now());
+        assert!(first.now_or_never().is_some());
+        assert!(sleep.sleep(Duration::from_secs(2)).now_or_never().is_some());
+        assert_eq!(UNIX_EPOCH + Duration::from_secs(3), time.now());
+        assert_eq!(
+            vec![Duration::from_secs(1), Duration::from_secs(2)],
+            time.sleeps()
+        );
+    }
+
+    #[test]
+    fn instant_sleep_wakes_manual_sleeps() {
+        let time = super::ManualTimeSource::new(UNIX_EPOCH);
+        let mut waiting = time.manual_sleep().sleep(Duration::from_secs(2));
+        assert!((&mut waiting).now_or_never().is_none());
+        assert!(time
+            .instant_sleep()
+            .sleep(Duration::from_secs(2))
+            .now_or_never()
+            .is_some());
+        assert!(waiting.now_or_never().is_some());
+    }
+}
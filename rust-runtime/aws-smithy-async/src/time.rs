@@ -0,0 +1,174 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Provides a [`TimeSource`] trait so that wall-clock time can be injected, and a [`ClockSkew`]
+//! for correcting the local clock to match a server's clock.
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// Trait with a `now()` function returning the current time
+pub trait TimeSource: Debug + Send + Sync {
+    /// Returns the current time
+    fn now(&self) -> SystemTime;
+}
+
+impl<T> TimeSource for Box<T>
+where
+    T: TimeSource,
+    T: ?Sized,
+{
+    fn now(&self) -> SystemTime {
+        T::now(self)
+    }
+}
+
+impl<T> TimeSource for Arc<T>
+where
+    T: TimeSource,
+    T: ?Sized,
+{
+    fn now(&self) -> SystemTime {
+        T::now(self)
+    }
+}
+
+/// Implementation of [`TimeSource`] that reads the system clock with [`SystemTime::now`]
+#[non_exhaustive]
+#[derive(Debug, Default)]
+pub struct SystemTimeSource;
+
+impl SystemTimeSource {
+    /// Creates a new [`TimeSource`] that reads the system clock
+    pub fn new() -> SystemTimeSource {
+        Default::default()
+    }
+}
+
+impl TimeSource for SystemTimeSource {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Cheaply cloneable [`TimeSource`], defaulting to [`SystemTimeSource`]
+#[derive(Clone, Debug)]
+pub struct SharedTimeSource(Arc<dyn TimeSource>);
+
+impl SharedTimeSource {
+    /// Wraps `time_source` so that it can be shared
+    pub fn new(time_source: impl TimeSource + 'static) -> Self {
+        SharedTimeSource(Arc::new(time_source))
+    }
+}
+
+impl Default for SharedTimeSource {
+    fn default() -> Self {
+        SharedTimeSource::new(SystemTimeSource::new())
+    }
+}
+
+impl TimeSource for SharedTimeSource {
+    fn now(&self) -> SystemTime {
+        self.0.now()
+    }
+}
+
+/// Offset between the local clock and a server's clock
+///
+/// Requests signed with a clock that is too far from the server's clock are rejected. When a
+/// server reports its time, the offset can be recorded with [`ClockSkew::update`] and applied
+/// to subsequent timestamps with [`ClockSkew::apply`]. Clones share the same offset.
+#[derive(Clone, Debug, Default)]
+pub struct ClockSkew {
+    offset_millis: Arc<AtomicI64>,
+}
+
+impl ClockSkew {
+    /// Creates a `ClockSkew` with no offset
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the offset between `local_time` and `server_time`, taken at the same instant
+    ///
+    /// `local_time` should be read from the uncorrected clock, since it replaces any previously
+    /// recorded offset.
+    pub fn update(&self, local_time: SystemTime, server_time: SystemTime) {
+        let offset_millis = match server_time.duration_since(local_time) {
+            Ok(ahead) => ahead.as_millis() as i64,
+            Err(behind) => -(behind.duration().as_millis() as i64),
+        };
+        self.offset_millis.store(offset_millis, Ordering::Relaxed);
+    }
+
+    /// Returns how far the server's clock is ahead of the local clock, or behind it if negative,
+    /// in milliseconds
+    pub fn offset_millis(&self) -> i64 {
+        self.offset_millis.load(Ordering::Relaxed)
+    }
+
+    /// Corrects `local_time` to the server's clock
+    pub fn apply(&self, local_time: SystemTime) -> SystemTime {
+        let offset_millis = self.offset_millis();
+        let offset = Duration::from_millis(offset_millis.unsigned_abs());
+        if offset_millis >= 0 {
+            local_time + offset
+        } else {
+            local_time - offset
+        }
+    }
+
+    /// Discards the recorded offset
+    pub fn reset(&self) {
+        self.offset_millis.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ClockSkew, SharedTimeSource, TimeSource};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[derive(Debug)]
+    struct FixedTime(SystemTime);
+
+    impl TimeSource for FixedTime {
+        fn now(&self) -> SystemTime {
+            self.0
+        }
+    }
+
+    #[test]
+    fn shared_time_source() {
+        let time = SharedTimeSource::new(FixedTime(UNIX_EPOCH));
+        assert_eq!(UNIX_EPOCH, time.clone().now());
+        // no panics
+        let _ = SharedTimeSource::default().now();
+    }
+
+    #[test]
+    fn clock_skew() {
+        let local = UNIX_EPOCH + Duration::from_secs(1000);
+        let skew = ClockSkew::new();
+        assert_eq!(local, skew.apply(local));
+
+        skew.update(local, local + Duration::from_millis(900_500));
+        assert_eq!(900_500, skew.offset_millis());
+        assert_eq!(
+            local + Duration::from_millis(900_500),
+            skew.clone().apply(local)
+        );
+
+        skew.update(local, local - Duration::from_secs(600));
+        assert_eq!(-600_000, skew.offset_millis());
+        assert_eq!(local - Duration::from_secs(600), skew.apply(local));
+
+        skew.reset();
+        assert_eq!(local, skew.apply(local));
+    }
+}
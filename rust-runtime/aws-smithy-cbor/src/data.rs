@@ -0,0 +1,58 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Constants from the CBOR data model shared by the encoder and decoder.
+
+/// CBOR major types (the high three bits of an initial byte).
+pub(crate) mod major {
+    pub(crate) const UNSIGNED: u8 = 0;
+    pub(crate) const NEGATIVE: u8 = 1;
+    pub(crate) const BYTES: u8 = 2;
+    pub(crate) const TEXT: u8 = 3;
+    pub(crate) const ARRAY: u8 = 4;
+    pub(crate) const MAP: u8 = 5;
+    pub(crate) const TAG: u8 = 6;
+    pub(crate) const SIMPLE: u8 = 7;
+}
+
+/// Additional information values (the low five bits of an initial byte).
+pub(crate) mod info {
+    pub(crate) const ONE_BYTE: u8 = 24;
+    pub(crate) const TWO_BYTES: u8 = 25;
+    pub(crate) const FOUR_BYTES: u8 = 26;
+    pub(crate) const EIGHT_BYTES: u8 = 27;
+    pub(crate) const INDEFINITE: u8 = 31;
+}
+
+/// Simple values and floats (major type 7).
+pub(crate) mod simple {
+    pub(crate) const FALSE: u8 = 20;
+    pub(crate) const TRUE: u8 = 21;
+    pub(crate) const NULL: u8 = 22;
+    pub(crate) const UNDEFINED: u8 = 23;
+    pub(crate) const FLOAT16: u8 = 25;
+    pub(crate) const FLOAT32: u8 = 26;
+    pub(crate) const FLOAT64: u8 = 27;
+}
+
+/// Semantic tags understood by Smithy.
+pub(crate) mod tag {
+    /// Epoch-based date/time, used for Smithy timestamps.
+    pub(crate) const EPOCH_DATE_TIME: u64 = 1;
+    /// Unsigned bignum.
+    pub(crate) const POSITIVE_BIGNUM: u64 = 2;
+    /// Negative bignum.
+    pub(crate) const NEGATIVE_BIGNUM: u64 = 3;
+    /// Decimal fraction, used for Smithy `bigDecimal`.
+    pub(crate) const DECIMAL_FRACTION: u64 = 4;
+}
+
+/// The "break" stop code that terminates indefinite-length items.
+pub(crate) const BREAK: u8 = 0xff;
+
+/// Builds an initial byte from a major type and additional information.
+pub(crate) const fn initial_byte(major: u8, info: u8) -> u8 {
+    (major << 5) | info
+}
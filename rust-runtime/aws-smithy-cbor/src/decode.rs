@@ -0,0 +1,835 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::data::{info, major, simple, tag, BREAK};
+use aws_smithy_types::{DateTime, Number};
+use std::borrow::Cow;
+
+mod error;
+pub mod token;
+
+pub use error::{Error, ErrorReason};
+pub use token::{BigInteger, Offset, Token};
+
+use ErrorReason::*;
+
+/// Maximum number of tags that can be nested around a single data item
+///
+/// Tags are decoded recursively, so this keeps untrusted input made of nothing but tags from
+/// overflowing the stack.
+const MAX_TAG_DEPTH: usize = 16;
+
+/// CBOR token parser as a Rust iterator
+///
+/// This parser will parse and yield exactly one [`Token`] per iterator `next()` call.
+/// Validation is done on the fly, so it is possible for it to parse an invalid CBOR document
+/// until it gets to the first [`Error`].
+///
+/// Definite-length text and byte strings are yielded as slices of the original `input`, so no
+/// allocation takes place for them. Indefinite-length strings are concatenated into an owned
+/// value since their chunks aren't contiguous in the input.
+///
+/// Map keys are required to be text strings, as is the case for every map produced by the
+/// Smithy RPC v2 CBOR protocol, and are yielded as [`Token::MapKey`].
+///
+/// The semantic tags that Smithy relies on are folded into the tagged value: tag 1 is yielded
+/// as [`Token::ValueTimestamp`], tags 2 and 3 as [`Token::ValueBigInteger`], and tag 4 as
+/// [`Token::ValueBigDecimal`]. Any other tag is skipped and the tagged value is yielded as-is.
+///
+/// The parser *will* accept multiple CBOR data items in sequence. It is the responsibility of
+/// the caller to handle this for their use-case.
+pub fn cbor_token_iter(input: &[u8]) -> CborTokenIterator<'_> {
+    CborTokenIterator {
+        input,
+        index: 0,
+        state_stack: vec![State::Initial],
+        tag_depth: 0,
+    }
+}
+
+/// Internal parser state for the iterator. Used to context between successive `next` calls.
+///
+/// `remaining` is `None` for indefinite-length items, which are terminated by a break code.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum State {
+    /// Entry point. Expecting any CBOR data item.
+    Initial,
+    /// Expecting the next value in an array, or the end of the array.
+    ArrayValueOrEnd { remaining: Option<u64> },
+    /// Expecting the next key in a map, or the end of the map.
+    MapKeyOrEnd { remaining: Option<u64> },
+    /// Expecting the value of a field in a map.
+    MapValue { remaining: Option<u64> },
+}
+
+/// An iterator over a `&[u8]` that yields `Result<Token, Error>` with [Token] being CBOR tokens.
+/// Construct with [cbor_token_iter].
+pub struct CborTokenIterator<'a> {
+    input: &'a [u8],
+    index: usize,
+    state_stack: Vec<State>,
+    /// Number of tags around the data item being read
+    tag_depth: usize,
+}
+
+impl<'a> CborTokenIterator<'a> {
+    /// Previews the next byte.
+    fn peek_byte(&self) -> Option<u8> {
+        self.input.get(self.index).copied()
+    }
+
+    /// Expects there to be another byte coming up, and returns it while advancing.
+    /// If there isn't, an `UnexpectedEOS` error is returned.
+    fn next_expect(&mut self) -> Result<u8, Error> {
+        let byte = self.peek_byte().ok_or_else(|| self.error(UnexpectedEos))?;
+        self.index += 1;
+        Ok(byte)
+    }
+
+    /// Returns the next `len` bytes while advancing past them.
+    fn take(&mut self, len: u64) -> Result<&'a [u8], Error> {
+        let available = (self.input.len() - self.index) as u64;
+        if len > available {
+            return Err(self.error_at(self.input.len(), UnexpectedEos));
+        }
+        let start = self.index;
+        self.index += len as usize;
+        Ok(&self.input[start..self.index])
+    }
+
+    /// Creates an error at the given `offset` in the stream.
+    fn error_at(&self, offset: usize, reason: ErrorReason) -> Error {
+        Error::new(reason, Some(offset))
+    }
+
+    /// Creates an error at the current offset in the stream.
+    fn error(&self, reason: ErrorReason) -> Error {
+        self.error_at(self.index, reason)
+    }
+
+    /// Returns the top of the state stack (current state).
+    fn state(&self) -> State {
+        self.state_stack[self.state_stack.len() - 1]
+    }
+
+    /// Replaces the top of the state stack with a new `state`.
+    fn replace_state(&mut self, state: State) {
+        self.state_stack.pop();
+        self.state_stack.push(state);
+    }
+
+    /// Returns current offset
+    fn offset(&self) -> Offset {
+        Offset(self.index)
+    }
+
+    /// Reads an initial byte and returns its `(major_type, additional_info)`.
+    fn read_initial_byte(&mut self) -> Result<(u8, u8), Error> {
+        let byte = self.next_expect()?;
+        Ok((byte >> 5, byte & 0x1f))
+    }
+
+    /// Reads the argument that follows an initial byte. Returns `None` for indefinite-length items.
+    fn read_argument(&mut self, additional_info: u8) -> Result<Option<u64>, Error> {
+        let len = match additional_info {
+            0..=23 => return Ok(Some(additional_info as u64)),
+            info::ONE_BYTE => 1,
+            info::TWO_BYTES => 2,
+            info::FOUR_BYTES => 4,
+            info::EIGHT_BYTES => 8,
+            info::INDEFINITE => return Ok(None),
+            _ => return Err(self.error_at(self.index - 1, InvalidAdditionalInfo(additional_info))),
+        };
+        let bytes = self.take(len)?;
+        Ok(Some(
+            bytes
+                .iter()
+                .fold(0u64, |value, byte| (value << 8) | *byte as u64),
+        ))
+    }
+
+    /// Reads an argument that must have a definite value.
+    fn read_definite_argument(&mut self, additional_info: u8) -> Result<u64, Error> {
+        let offset = self.index - 1;
+        self.read_argument(additional_info)?
+            .ok_or_else(|| self.error_at(offset, InvalidAdditionalInfo(additional_info)))
+    }
+
+    /// Reads the contents of a byte string (`major::BYTES`) or text string (`major::TEXT`)
+    /// whose initial byte has already been consumed.
+    fn read_bytes(&mut self, major_type: u8, additional_info: u8) -> Result<Cow<'a, [u8]>, Error> {
+        match self.read_argument(additional_info)? {
+            Some(len) => Ok(Cow::Borrowed(self.take(len)?)),
+            None => {
+                let expected = if major_type == major::TEXT {
+                    "definite-length text string"
+                } else {
+                    "definite-length byte string"
+                };
+                let mut value = Vec::new();
+                loop {
+                    if self.peek_byte() == Some(BREAK) {
+                        self.index += 1;
+                        return Ok(Cow::Owned(value));
+                    }
+                    let offset = self.index;
+                    let (chunk_major, chunk_info) = self.read_initial_byte()?;
+                    if chunk_major != major_type || chunk_info == info::INDEFINITE {
+                        return Err(self.error_at(offset, InvalidChunk(expected)));
+                    }
+                    let len = self.read_definite_argument(chunk_info)?;
+                    value.extend_from_slice(self.take(len)?);
+                }
+            }
+        }
+    }
+
+    /// Reads a text string whose initial byte has already been consumed.
+    fn read_text(&mut self, additional_info: u8) -> Result<Cow<'a, str>, Error> {
+        let offset = self.index - 1;
+        match self.read_bytes(major::TEXT, additional_info)? {
+            Cow::Borrowed(bytes) => std::str::from_utf8(bytes)
+                .map(Cow::Borrowed)
+                .map_err(|err| self.error_at(offset, err.into())),
+            Cow::Owned(bytes) => String::from_utf8(bytes)
+                .map(Cow::Owned)
+                .map_err(|err| self.error_at(offset, err.utf8_error().into())),
+        }
+    }
+
+    /// Reads a negative integer whose initial byte has already been consumed.
+    fn read_negative(&mut self, additional_info: u8) -> Result<Number, Error> {
+        let value = self.read_definite_argument(additional_info)?;
+        // CBOR encodes negative integers as `-1 - value`. If that overflows an i64,
+        // then stuff it into an f64
+        Ok(if value <= i64::MAX as u64 {
+            Number::NegInt(-1 - value as i64)
+        } else {
+            Number::Float(-1.0 - value as f64)
+        })
+    }
+
+    /// Reads a simple value or float (major type 7) whose initial byte has already been consumed.
+    fn read_simple(&mut self, offset: Offset, additional_info: u8) -> Result<Token<'a>, Error> {
+        Ok(match additional_info {
+            simple::FALSE => Token::ValueBool {
+                offset,
+                value: false,
+            },
+            simple::TRUE => Token::ValueBool {
+                offset,
+                value: true,
+            },
+            // Smithy doesn't distinguish between `null` and `undefined`
+            simple::NULL | simple::UNDEFINED => Token::ValueNull { offset },
+            simple::FLOAT16 => {
+                let bytes = self.take(2)?;
+                Token::ValueNumber {
+                    offset,
+                    value: Number::Float(f16_to_f64(u16::from_be_bytes([bytes[0], bytes[1]]))),
+                }
+            }
+            simple::FLOAT32 => {
+                let bytes = self.take(4)?;
+                let mut buf = [0u8; 4];
+                buf.copy_from_slice(bytes);
+                Token::ValueNumber {
+                    offset,
+                    value: Number::Float(f32::from_be_bytes(buf) as f64),
+                }
+            }
+            simple::FLOAT64 => {
+                let bytes = self.take(8)?;
+                let mut buf = [0u8; 8];
+                buf.copy_from_slice(bytes);
+                Token::ValueNumber {
+                    offset,
+                    value: Number::Float(f64::from_be_bytes(buf)),
+                }
+            }
+            info::INDEFINITE => return Err(self.error_at(offset.0, UnexpectedBreak)),
+            info::ONE_BYTE => {
+                let value = self.next_expect()?;
+                return Err(self.error_at(offset.0, UnsupportedSimpleValue(value)));
+            }
+            other => return Err(self.error_at(offset.0, UnsupportedSimpleValue(other))),
+        })
+    }
+
+    /// Reads the data item following a tag that has already been consumed.
+    fn read_tagged(&mut self, offset: Offset, tag: u64) -> Result<Token<'a>, Error> {
+        match tag {
+            tag::EPOCH_DATE_TIME => {
+                let value = match self.read_value()? {
+                    Token::ValueNumber {
+                        value: Number::PosInt(secs),
+                        ..
+                    } => i64::try_from(secs)
+                        .map(DateTime::from_secs)
+                        .map_err(|_| self.error_at(offset.0, InvalidNumber))?,
+                    Token::ValueNumber {
+                        value: Number::NegInt(secs),
+                        ..
+                    } => DateTime::from_secs(secs),
+                    Token::ValueNumber {
+                        value: Number::Float(secs),
+                        ..
+                    } if secs.is_finite() => DateTime::from_secs_f64(secs),
+                    _ => {
+                        return Err(self.error_at(
+                            offset.0,
+                            Custom(Cow::Borrowed("expected a finite number after tag 1")),
+                        ))
+                    }
+                };
+                Ok(Token::ValueTimestamp { offset, value })
+            }
+            tag::POSITIVE_BIGNUM | tag::NEGATIVE_BIGNUM => {
+                let value = self.read_bignum(tag == tag::NEGATIVE_BIGNUM)?;
+                Ok(Token::ValueBigInteger { offset, value })
+            }
+            tag::DECIMAL_FRACTION => {
+                let array_offset = self.index;
+                let (array_major, array_info) = self.read_initial_byte()?;
+                if array_major != major::ARRAY || self.read_argument(array_info)? != Some(2) {
+                    return Err(self.error_at(
+                        array_offset,
+                        Custom(Cow::Borrowed(
+                            "expected an array of two integers after tag 4",
+                        )),
+                    ));
+                }
+                let exponent = match self.read_value()? {
+                    Token::ValueNumber {
+                        value: Number::PosInt(value),
+                        ..
+                    } => {
+                        i64::try_from(value).map_err(|_| self.error_at(offset.0, InvalidNumber))?
+                    }
+                    Token::ValueNumber {
+                        value: Number::NegInt(value),
+                        ..
+                    } => value,
+                    _ => return Err(self.error_at(offset.0, InvalidNumber)),
+                };
+                let mantissa = match self.read_value()? {
+                    Token::ValueNumber {
+                        value: Number::PosInt(value),
+                        ..
+                    } => BigInteger::from(value),
+                    Token::ValueNumber {
+                        value: Number::NegInt(value),
+                        ..
+                    } => BigInteger::from(value),
+                    Token::ValueBigInteger { value, .. } => value,
+                    _ => return Err(self.error_at(offset.0, InvalidNumber)),
+                };
+                Ok(Token::ValueBigDecimal {
+                    offset,
+                    exponent,
+                    mantissa,
+                })
+            }
+            // Tags that Smithy doesn't assign meaning to are ignored
+            _ => self.read_value(),
+        }
+    }
+
+    /// Reads the byte string that follows a bignum tag.
+    fn read_bignum(&mut self, negative: bool) -> Result<BigInteger<'a>, Error> {
+        let offset = self.index;
+        let (major_type, additional_info) = self.read_initial_byte()?;
+        if major_type != major::BYTES {
+            return Err(self.error_at(offset, UnexpectedMajorType(major_type, "byte string")));
+        }
+        let magnitude = self.read_bytes(major::BYTES, additional_info)?;
+        Ok(BigInteger::new(negative, magnitude))
+    }
+
+    /// Reads a data item from the stream and returns the next token. For maps and arrays,
+    /// the entire map or array will not be ready, but rather, a [Token::StartMap]/[Token::StartArray]
+    /// will be returned.
+    fn read_value(&mut self) -> Result<Token<'a>, Error> {
+        let offset = self.offset();
+        let (major_type, additional_info) = self.read_initial_byte()?;
+        match major_type {
+            major::UNSIGNED => Ok(Token::ValueNumber {
+                offset,
+                value: Number::PosInt(self.read_definite_argument(additional_info)?),
+            }),
+            major::NEGATIVE => Ok(Token::ValueNumber {
+                offset,
+                value: self.read_negative(additional_info)?,
+            }),
+            major::BYTES => Ok(Token::ValueBlob {
+                offset,
+                value: self.read_bytes(major::BYTES, additional_info)?,
+            }),
+            major::TEXT => Ok(Token::ValueString {
+                offset,
+                value: self.read_text(additional_info)?,
+            }),
+            major::ARRAY => {
+                let len = self.read_argument(additional_info)?;
+                self.state_stack
+                    .push(State::ArrayValueOrEnd { remaining: len });
+                Ok(Token::StartArray { offset, len })
+            }
+            major::MAP => {
+                let len = self.read_argument(additional_info)?;
+                self.state_stack.push(State::MapKeyOrEnd { remaining: len });
+                Ok(Token::StartMap { offset, len })
+            }
+            major::TAG => {
+                let tag = self.read_definite_argument(additional_info)?;
+                if self.tag_depth == MAX_TAG_DEPTH {
+                    return Err(
+                        self.error_at(offset.0, Custom(Cow::Borrowed("too many nested tags")))
+                    );
+                }
+                self.tag_depth += 1;
+                let result = self.read_tagged(offset, tag);
+                self.tag_depth -= 1;
+                result
+            }
+            _ => self.read_simple(offset, additional_info),
+        }
+    }
+
+    /// Consumes a break code if the indefinite-length item at the top of the stack is ending.
+    fn at_end(&mut self, remaining: Option<u64>) -> Result<bool, Error> {
+        match remaining {
+            Some(remaining) => Ok(remaining == 0),
+            None => match self.peek_byte() {
+                Some(BREAK) => {
+                    self.index += 1;
+                    Ok(true)
+                }
+                Some(_) => Ok(false),
+                None => Err(self.error(UnexpectedEos)),
+            },
+        }
+    }
+
+    /// Handles the [State::ArrayValueOrEnd] state.
+    fn state_array_value_or_end(&mut self, remaining: Option<u64>) -> Result<Token<'a>, Error> {
+        let offset = self.offset();
+        if self.at_end(remaining)? {
+            self.state_stack.pop();
+            return Ok(Token::EndArray { offset });
+        }
+        self.replace_state(State::ArrayValueOrEnd {
+            remaining: remaining.map(|r| r - 1),
+        });
+        self.read_value()
+    }
+
+    /// Handles the [State::MapKeyOrEnd] state.
+    fn state_map_key_or_end(&mut self, remaining: Option<u64>) -> Result<Token<'a>, Error> {
+        let offset = self.offset();
+        if self.at_end(remaining)? {
+            self.state_stack.pop();
+            return Ok(Token::EndMap { offset });
+        }
+        let (major_type, additional_info) = self.read_initial_byte()?;
+        if major_type != major::TEXT {
+            return Err(self.error_at(offset.0, UnexpectedMajorType(major_type, "text string")));
+        }
+        let key = self.read_text(additional_info)?;
+        self.replace_state(State::MapValue {
+            remaining: remaining.map(|r| r - 1),
+        });
+        Ok(Token::MapKey { offset, key })
+    }
+
+    /// Handles the [State::MapValue] state.
+    fn state_map_value(&mut self, remaining: Option<u64>) -> Result<Token<'a>, Error> {
+        if self.peek_byte() == Some(BREAK) {
+            return Err(self.error(UnexpectedBreak));
+        }
+        self.replace_state(State::MapKeyOrEnd { remaining });
+        self.read_value()
+    }
+}
+
+impl<'a> Iterator for CborTokenIterator<'a> {
+    type Item = Result<Token<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        debug_assert!(self.index <= self.input.len());
+        let result = match self.state() {
+            State::Initial => {
+                if self.index == self.input.len() {
+                    return None;
+                }
+                self.read_value()
+            }
+            State::ArrayValueOrEnd { remaining } => self.state_array_value_or_end(remaining),
+            State::MapKeyOrEnd { remaining } => self.state_map_key_or_end(remaining),
+            State::MapValue { remaining } => self.state_map_value(remaining),
+        };
+        // Invalidate the stream if we encountered an error
+        if result.is_err() {
+            self.index = self.input.len();
+            self.state_stack.truncate(1);
+        }
+        Some(result)
+    }
+}
+
+/// Converts an IEEE 754 half-precision float to an `f64`.
+///
+/// See [RFC 8949 Appendix D](https://www.rfc-editor.org/rfc/rfc8949.html#name-half-precision).
+fn f16_to_f64(half: u16) -> f64 {
+    let exponent = (half >> 10) & 0x1f;
+    let mantissa = (half & 0x3ff) as f64;
+    let value = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (mantissa + 1024.0) * 2f64.powi(exponent as i32 - 25),
+    };
+    if half & 0x8000 != 0 {
+        -value
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::decode::token::test::{
+        end_array, end_map, map_key, start_array, start_map, value_bool, value_null, value_number,
+        value_string,
+    };
+    use crate::decode::{cbor_token_iter, BigInteger, Error, ErrorReason, Offset, Token};
+    use aws_smithy_types::{DateTime, Number};
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(None, cbor_token_iter(b"").next());
+    }
+
+    #[test]
+    fn test_integers() {
+        let cases: &[(&[u8], Number)] = &[
+            (&[0x00], Number::PosInt(0)),
+            (&[0x17], Number::PosInt(23)),
+            (&[0x18, 0x18], Number::PosInt(24)),
+            (&[0x19, 0x03, 0xe8], Number::PosInt(1000)),
+            (&[0x1a, 0x00, 0x0f, 0x42, 0x40], Number::PosInt(1000000)),
+            (
+                &[0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+                Number::PosInt(u64::MAX),
+            ),
+            (&[0x20], Number::NegInt(-1)),
+            (&[0x38, 0x63], Number::NegInt(-100)),
+            (
+                &[0x3b, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+                Number::NegInt(i64::MIN),
+            ),
+            (
+                &[0x3b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+                Number::Float(-18446744073709551616.0),
+            ),
+        ];
+        for (input, expected) in cases {
+            let mut iter = cbor_token_iter(input);
+            assert_eq!(value_number(0, *expected), iter.next());
+            assert_eq!(None, iter.next());
+        }
+    }
+
+    #[test]
+    fn test_floats() {
+        let cases: &[(&[u8], f64)] = &[
+            (&[0xf9, 0x00, 0x00], 0.0),
+            (&[0xf9, 0x3c, 0x00], 1.0),
+            (&[0xf9, 0x3e, 0x00], 1.5),
+            (&[0xf9, 0x7b, 0xff], 65504.0),
+            (&[0xf9, 0x00, 0x01], 5.960464477539063e-8),
+            (&[0xf9, 0xc4, 0x00], -4.0),
+            (&[0xf9, 0x7c, 0x00], f64::INFINITY),
+            (&[0xfa, 0x47, 0xc3, 0x50, 0x00], 100000.0),
+            (&[0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a], 1.1),
+            (&[0xfb, 0xff, 0xf0, 0, 0, 0, 0, 0, 0], f64::NEG_INFINITY),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                value_number(0, Number::Float(*expected)),
+                cbor_token_iter(input).next()
+            );
+        }
+        match cbor_token_iter(&[0xf9, 0x7e, 0x00]).next() {
+            Some(Ok(Token::ValueNumber {
+                value: Number::Float(value),
+                ..
+            })) => assert!(value.is_nan()),
+            other => panic!("expected NaN, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_simple_values() {
+        let mut iter = cbor_token_iter(&[0xf4, 0xf5, 0xf6, 0xf7]);
+        assert_eq!(value_bool(0, false), iter.next());
+        assert_eq!(value_bool(1, true), iter.next());
+        assert_eq!(value_null(2), iter.next());
+        assert_eq!(value_null(3), iter.next());
+        assert_eq!(None, iter.next());
+
+        assert_eq!(
+            Some(Err(Error::new(
+                ErrorReason::UnsupportedSimpleValue(16),
+                Some(0)
+            ))),
+            cbor_token_iter(&[0xf0]).next()
+        );
+        assert_eq!(
+            Some(Err(Error::new(ErrorReason::UnexpectedBreak, Some(0)))),
+            cbor_token_iter(&[0xff]).next()
+        );
+    }
+
+    #[test]
+    fn test_strings() {
+        let mut iter = cbor_token_iter(b"\x60\x64IETF\x62\xc3\xbc");
+        assert_eq!(value_string(0, ""), iter.next());
+        assert_eq!(value_string(1, "IETF"), iter.next());
+        assert_eq!(value_string(6, "ü"), iter.next());
+        assert_eq!(None, iter.next());
+
+        // Definite-length strings are borrowed from the input
+        match cbor_token_iter(b"\x64IETF").next() {
+            Some(Ok(Token::ValueString {
+                value: Cow::Borrowed(_),
+                ..
+            })) => {}
+            other => panic!("expected borrowed string, got {:?}", other),
+        }
+
+        let mut iter = cbor_token_iter(b"\x7f\x65strea\x64ming\xff");
+        assert_eq!(value_string(0, "streaming"), iter.next());
+        assert_eq!(None, iter.next());
+
+        assert_eq!(
+            Some(Err(Error::new(ErrorReason::InvalidUtf8, Some(0)))),
+            cbor_token_iter(b"\x62\xc3\x28").next()
+        );
+        assert_eq!(
+            Some(Err(Error::new(
+                ErrorReason::InvalidChunk("definite-length text string"),
+                Some(1)
+            ))),
+            cbor_token_iter(b"\x7f\x41a\xff").next()
+        );
+        assert_eq!(
+            Some(Err(Error::new(ErrorReason::UnexpectedEos, Some(3)))),
+            cbor_token_iter(b"\x64IE").next()
+        );
+    }
+
+    #[test]
+    fn test_blobs() {
+        let mut iter = cbor_token_iter(b"\x44\x01\x02\x03\x04\x5f\x42\x01\x02\x43\x03\x04\x05\xff");
+        assert_eq!(
+            Some(Ok(Token::ValueBlob {
+                offset: Offset(0),
+                value: Cow::Borrowed(&[1, 2, 3, 4])
+            })),
+            iter.next()
+        );
+        assert_eq!(
+            Some(Ok(Token::ValueBlob {
+                offset: Offset(5),
+                value: Cow::Owned(vec![1, 2, 3, 4, 5])
+            })),
+            iter.next()
+        );
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn test_arrays() {
+        let mut iter = cbor_token_iter(&[0x80]);
+        assert_eq!(start_array(0, Some(0)), iter.next());
+        assert_eq!(end_array(1), iter.next());
+        assert_eq!(None, iter.next());
+
+        let mut iter = cbor_token_iter(&[0x83, 0x01, 0x82, 0x02, 0x03, 0x9f, 0x04, 0xff]);
+        assert_eq!(start_array(0, Some(3)), iter.next());
+        assert_eq!(value_number(1, Number::PosInt(1)), iter.next());
+        assert_eq!(start_array(2, Some(2)), iter.next());
+        assert_eq!(value_number(3, Number::PosInt(2)), iter.next());
+        assert_eq!(value_number(4, Number::PosInt(3)), iter.next());
+        assert_eq!(end_array(5), iter.next());
+        assert_eq!(start_array(5, None), iter.next());
+        assert_eq!(value_number(6, Number::PosInt(4)), iter.next());
+        assert_eq!(end_array(7), iter.next());
+        assert_eq!(end_array(8), iter.next());
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn test_unclosed_array() {
+        let mut iter = cbor_token_iter(&[0x82, 0x01]);
+        assert_eq!(start_array(0, Some(2)), iter.next());
+        assert_eq!(value_number(1, Number::PosInt(1)), iter.next());
+        assert_eq!(
+            Some(Err(Error::new(ErrorReason::UnexpectedEos, Some(2)))),
+            iter.next()
+        );
+        assert_eq!(None, iter.next());
+
+        let mut iter = cbor_token_iter(&[0x9f]);
+        assert_eq!(start_array(0, None), iter.next());
+        assert_eq!(
+            Some(Err(Error::new(ErrorReason::UnexpectedEos, Some(1)))),
+            iter.next()
+        );
+    }
+
+    #[test]
+    fn test_maps() {
+        let mut iter = cbor_token_iter(b"\xa2\x61a\x01\x61b\xbf\x61c\xf6\xff");
+        assert_eq!(start_map(0, Some(2)), iter.next());
+        assert_eq!(map_key(1, "a"), iter.next());
+        assert_eq!(value_number(3, Number::PosInt(1)), iter.next());
+        assert_eq!(map_key(4, "b"), iter.next());
+        assert_eq!(start_map(6, None), iter.next());
+        assert_eq!(map_key(7, "c"), iter.next());
+        assert_eq!(value_null(9), iter.next());
+        assert_eq!(end_map(10), iter.next());
+        assert_eq!(end_map(11), iter.next());
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn test_map_non_string_key() {
+        let mut iter = cbor_token_iter(&[0xa1, 0x01, 0x02]);
+        assert_eq!(start_map(0, Some(1)), iter.next());
+        assert_eq!(
+            Some(Err(Error::new(
+                ErrorReason::UnexpectedMajorType(0, "text string"),
+                Some(1)
+            ))),
+            iter.next()
+        );
+    }
+
+    #[test]
+    fn test_map_break_before_value() {
+        let mut iter = cbor_token_iter(b"\xbf\x61a\xff");
+        assert_eq!(start_map(0, None), iter.next());
+        assert_eq!(map_key(1, "a"), iter.next());
+        assert_eq!(
+            Some(Err(Error::new(ErrorReason::UnexpectedBreak, Some(3)))),
+            iter.next()
+        );
+    }
+
+    #[test]
+    fn test_timestamps() {
+        let mut iter = cbor_token_iter(&[
+            0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0, 0xc1, 0xfb, 0x41, 0xd4, 0x52, 0xd9, 0xec, 0x20,
+            0x00, 0x00,
+        ]);
+        assert_eq!(
+            Some(Ok(Token::ValueTimestamp {
+                offset: Offset(0),
+                value: DateTime::from_secs(1363896240)
+            })),
+            iter.next()
+        );
+        assert_eq!(
+            Some(Ok(Token::ValueTimestamp {
+                offset: Offset(6),
+                value: DateTime::from_secs_f64(1363896240.5)
+            })),
+            iter.next()
+        );
+        assert_eq!(None, iter.next());
+
+        assert!(cbor_token_iter(b"\xc1\x61a").next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_big_numbers() {
+        let mut iter = cbor_token_iter(&[
+            0xc2, 0x49, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ]);
+        let value = BigInteger::new(false, Cow::Borrowed(&[1, 0, 0, 0, 0, 0, 0, 0, 0]));
+        assert_eq!(Some(18446744073709551616), value.to_i128());
+        assert_eq!(
+            Some(Ok(Token::ValueBigInteger {
+                offset: Offset(0),
+                value
+            })),
+            iter.next()
+        );
+
+        let mut iter = cbor_token_iter(&[0xc3, 0x41, 0x00]);
+        match iter.next() {
+            Some(Ok(Token::ValueBigInteger { value, .. })) => {
+                assert_eq!(Some(-1), value.to_i128())
+            }
+            other => panic!("expected big integer, got {:?}", other),
+        }
+
+        // 273.15
+        let mut iter = cbor_token_iter(&[0xc4, 0x82, 0x21, 0x19, 0x6a, 0xb3]);
+        assert_eq!(
+            Some(Ok(Token::ValueBigDecimal {
+                offset: Offset(0),
+                exponent: -2,
+                mantissa: BigInteger::from(27315u64),
+            })),
+            iter.next()
+        );
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn test_unknown_tags_are_skipped() {
+        let mut iter = cbor_token_iter(b"\xd8\x20\x63abc");
+        assert_eq!(value_string(2, "abc"), iter.next());
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn test_deeply_nested_tags_are_rejected() {
+        let mut input = vec![0xc6; 100_000];
+        input.push(0x01);
+        assert_eq!(
+            Some(Err(Error::new(
+                ErrorReason::Custom("too many nested tags".into()),
+                Some(16)
+            ))),
+            cbor_token_iter(&input).next()
+        );
+        let mut input = vec![0xc1; 100_000];
+        input.push(0x01);
+        assert!(matches!(cbor_token_iter(&input).next(), Some(Err(_))));
+    }
+
+    #[test]
+    fn test_invalid_additional_info() {
+        assert_eq!(
+            Some(Err(Error::new(
+                ErrorReason::InvalidAdditionalInfo(28),
+                Some(0)
+            ))),
+            cbor_token_iter(&[0x1c]).next()
+        );
+        assert_eq!(
+            Some(Err(Error::new(
+                ErrorReason::InvalidAdditionalInfo(31),
+                Some(0)
+            ))),
+            cbor_token_iter(&[0x1f]).next()
+        );
+    }
+}
@@ -0,0 +1,82 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::borrow::Cow;
+use std::fmt;
+use std::str::Utf8Error;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ErrorReason {
+    Custom(Cow<'static, str>),
+    InvalidAdditionalInfo(u8),
+    InvalidChunk(&'static str),
+    InvalidNumber,
+    InvalidUtf8,
+    UnexpectedBreak,
+    UnexpectedEos,
+    UnexpectedMajorType(u8, &'static str),
+    UnsupportedSimpleValue(u8),
+}
+use ErrorReason::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Error {
+    reason: ErrorReason,
+    offset: Option<usize>,
+}
+
+impl Error {
+    pub fn new(reason: ErrorReason, offset: Option<usize>) -> Self {
+        Error { reason, offset }
+    }
+
+    /// Returns a custom error without an offset.
+    pub fn custom(message: impl Into<Cow<'static, str>>) -> Error {
+        Error::new(ErrorReason::Custom(message.into()), None)
+    }
+
+    /// Returns the reason for the error.
+    pub fn reason(&self) -> &ErrorReason {
+        &self.reason
+    }
+
+    /// Returns the byte offset at which the error occurred, if known.
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(offset) = self.offset {
+            write!(f, "Error at offset {}: ", offset)?;
+        }
+        match &self.reason {
+            Custom(msg) => write!(f, "failed to parse CBOR: {}", msg),
+            InvalidAdditionalInfo(info) => write!(f, "invalid additional information: {}", info),
+            InvalidChunk(expected) => write!(
+                f,
+                "invalid chunk in indefinite-length string. Expected {}",
+                expected
+            ),
+            InvalidNumber => write!(f, "invalid number"),
+            InvalidUtf8 => write!(f, "invalid UTF-8 codepoint in CBOR text string"),
+            UnexpectedBreak => write!(f, "unexpected break outside of indefinite-length item"),
+            UnexpectedEos => write!(f, "unexpected end of stream"),
+            UnexpectedMajorType(major, expected) => {
+                write!(f, "unexpected major type {}. Expected {}", major, expected)
+            }
+            UnsupportedSimpleValue(value) => write!(f, "unsupported simple value: {}", value),
+        }
+    }
+}
+
+impl From<Utf8Error> for ErrorReason {
+    fn from(_: Utf8Error) -> Self {
+        InvalidUtf8
+    }
+}
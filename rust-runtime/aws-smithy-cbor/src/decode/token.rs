@@ -0,0 +1,603 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::decode::error::{Error, ErrorReason};
+use aws_smithy_types::{Blob, DateTime, Document, Number};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::iter::Peekable;
+
+/// Represents the location of a token
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Offset(pub usize);
+
+impl Offset {
+    /// Creates a custom error from the offset
+    pub fn error(&self, msg: Cow<'static, str>) -> Error {
+        Error::new(ErrorReason::Custom(msg), Some(self.0))
+    }
+}
+
+/// An arbitrary-precision integer, as carried by CBOR bignum tags 2 and 3.
+///
+/// Following the CBOR data model, the `magnitude` is an unsigned big-endian integer `n`,
+/// and a negative big integer represents the value `-1 - n`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BigInteger<'a> {
+    negative: bool,
+    magnitude: Cow<'a, [u8]>,
+}
+
+impl<'a> BigInteger<'a> {
+    pub fn new(negative: bool, magnitude: Cow<'a, [u8]>) -> BigInteger<'a> {
+        BigInteger {
+            negative,
+            magnitude,
+        }
+    }
+
+    /// Returns true if this integer is negative.
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Returns the big-endian magnitude bytes as they appear on the wire.
+    pub fn magnitude(&self) -> &[u8] {
+        &self.magnitude
+    }
+
+    /// Converts to an owned value that doesn't borrow from the input.
+    pub fn into_owned(self) -> BigInteger<'static> {
+        BigInteger {
+            negative: self.negative,
+            magnitude: Cow::Owned(self.magnitude.into_owned()),
+        }
+    }
+
+    /// Converts to an `i128`, returning `None` if the value doesn't fit.
+    pub fn to_i128(&self) -> Option<i128> {
+        let significant = match self.magnitude.iter().position(|byte| *byte != 0) {
+            Some(start) => &self.magnitude[start..],
+            None => &[],
+        };
+        if significant.len() > 16 {
+            return None;
+        }
+        let magnitude = significant
+            .iter()
+            .fold(0u128, |value, byte| (value << 8) | *byte as u128);
+        let magnitude = i128::try_from(magnitude).ok()?;
+        Some(if self.negative {
+            -1 - magnitude
+        } else {
+            magnitude
+        })
+    }
+
+    /// Converts to a [`Number`], returning `None` if the value doesn't fit into an integer variant.
+    pub fn to_number(&self) -> Option<Number> {
+        let value = self.to_i128()?;
+        if value < 0 {
+            i64::try_from(value).ok().map(Number::NegInt)
+        } else {
+            u64::try_from(value).ok().map(Number::PosInt)
+        }
+    }
+}
+
+impl From<u64> for BigInteger<'static> {
+    fn from(value: u64) -> Self {
+        BigInteger::new(false, Cow::Owned(value.to_be_bytes().to_vec()))
+    }
+}
+
+impl From<i64> for BigInteger<'static> {
+    fn from(value: i64) -> Self {
+        if value < 0 {
+            BigInteger::new(true, Cow::Owned((-1 - value).to_be_bytes().to_vec()))
+        } else {
+            BigInteger::from(value as u64)
+        }
+    }
+}
+
+/// Enum representing the different CBOR tokens that can be returned by
+/// [`crate::decode::cbor_token_iter`].
+#[derive(Debug, PartialEq)]
+pub enum Token<'a> {
+    StartArray {
+        offset: Offset,
+        /// Number of items in the array, or `None` for indefinite-length arrays
+        len: Option<u64>,
+    },
+    EndArray {
+        offset: Offset,
+    },
+    MapKey {
+        offset: Offset,
+        key: Cow<'a, str>,
+    },
+    StartMap {
+        offset: Offset,
+        /// Number of entries in the map, or `None` for indefinite-length maps
+        len: Option<u64>,
+    },
+    EndMap {
+        offset: Offset,
+    },
+    ValueBigDecimal {
+        offset: Offset,
+        exponent: i64,
+        mantissa: BigInteger<'a>,
+    },
+    ValueBigInteger {
+        offset: Offset,
+        value: BigInteger<'a>,
+    },
+    ValueBlob {
+        offset: Offset,
+        value: Cow<'a, [u8]>,
+    },
+    ValueBool {
+        offset: Offset,
+        value: bool,
+    },
+    ValueNull {
+        offset: Offset,
+    },
+    ValueNumber {
+        offset: Offset,
+        value: Number,
+    },
+    ValueString {
+        offset: Offset,
+        value: Cow<'a, str>,
+    },
+    ValueTimestamp {
+        offset: Offset,
+        value: DateTime,
+    },
+}
+
+impl<'a> Token<'a> {
+    pub fn offset(&self) -> Offset {
+        use Token::*;
+        *match self {
+            StartArray { offset, .. } => offset,
+            EndArray { offset } => offset,
+            MapKey { offset, .. } => offset,
+            StartMap { offset, .. } => offset,
+            EndMap { offset } => offset,
+            ValueBigDecimal { offset, .. } => offset,
+            ValueBigInteger { offset, .. } => offset,
+            ValueBlob { offset, .. } => offset,
+            ValueBool { offset, .. } => offset,
+            ValueNull { offset } => offset,
+            ValueNumber { offset, .. } => offset,
+            ValueString { offset, .. } => offset,
+            ValueTimestamp { offset, .. } => offset,
+        }
+    }
+
+    /// Builds an error from the token's offset
+    pub fn error(&self, msg: Cow<'static, str>) -> Error {
+        self.offset().error(msg)
+    }
+}
+
+macro_rules! expect_fn {
+    ($name:ident, $token:ident, $doc:tt) => {
+        #[doc=$doc]
+        pub fn $name(token_result: Option<Result<Token<'_>, Error>>) -> Result<(), Error> {
+            match token_result.transpose()? {
+                Some(Token::$token { .. }) => Ok(()),
+                Some(token) => {
+                    Err(token.error(Cow::Borrowed(concat!("expected ", stringify!($token)))))
+                }
+                None => Err(Error::new(
+                    ErrorReason::Custom(Cow::Borrowed(concat!("expected ", stringify!($token)))),
+                    None,
+                )),
+            }
+        }
+    };
+}
+
+expect_fn!(
+    expect_start_map,
+    StartMap,
+    "Expects a [Token::StartMap] token and returns an error if it's not present."
+);
+expect_fn!(
+    expect_start_array,
+    StartArray,
+    "Expects a [Token::StartArray] token and returns an error if it's not present."
+);
+
+macro_rules! expect_value_or_null_fn {
+    ($name:ident, $token:ident, $typ:ty, $doc:tt) => {
+        #[doc=$doc]
+        pub fn $name<'a>(token: Option<Result<Token<'a>, Error>>) -> Result<Option<$typ>, Error> {
+            match token.transpose()? {
+                Some(Token::ValueNull { .. }) => Ok(None),
+                Some(Token::$token { value, .. }) => Ok(Some(value)),
+                Some(token) => Err(token.error(Cow::Borrowed(concat!(
+                    "expected ",
+                    stringify!($token),
+                    " or ValueNull"
+                )))),
+                None => Err(Error::custom(concat!(
+                    "expected ",
+                    stringify!($token),
+                    " or ValueNull"
+                ))),
+            }
+        }
+    };
+}
+
+expect_value_or_null_fn!(expect_bool_or_null, ValueBool, bool, "Expects a [Token::ValueBool] or [Token::ValueNull], and returns the bool value if it's not null.");
+expect_value_or_null_fn!(expect_string_or_null, ValueString, Cow<'a, str>, "Expects a [Token::ValueString] or [Token::ValueNull], and returns the string value if it's not null.");
+expect_value_or_null_fn!(expect_number_or_null, ValueNumber, Number, "Expects a [Token::ValueNumber] or [Token::ValueNull], and returns the [Number] value if it's not null.");
+expect_value_or_null_fn!(expect_timestamp_or_null, ValueTimestamp, DateTime, "Expects a [Token::ValueTimestamp] or [Token::ValueNull], and returns the [DateTime] value if it's not null.");
+expect_value_or_null_fn!(expect_big_integer_or_null, ValueBigInteger, BigInteger<'a>, "Expects a [Token::ValueBigInteger] or [Token::ValueNull], and returns the [BigInteger] value if it's not null.");
+
+/// Expects a [Token::ValueBlob] or [Token::ValueNull], and returns the [Blob] value if it's not null.
+pub fn expect_blob_or_null(token: Option<Result<Token<'_>, Error>>) -> Result<Option<Blob>, Error> {
+    match token.transpose()? {
+        Some(Token::ValueNull { .. }) => Ok(None),
+        Some(Token::ValueBlob { value, .. }) => Ok(Some(Blob::new(value.into_owned()))),
+        Some(token) => Err(token.error(Cow::Borrowed("expected ValueBlob or ValueNull"))),
+        None => Err(Error::custom("expected ValueBlob or ValueNull")),
+    }
+}
+
+/// Expects and parses a complete document value.
+///
+/// Blobs and timestamps have no document representation, so they result in an error.
+/// Big integers are accepted if they fit into a [`Number`].
+pub fn expect_document<'a, I>(tokens: &mut Peekable<I>) -> Result<Document, Error>
+where
+    I: Iterator<Item = Result<Token<'a>, Error>>,
+{
+    expect_document_inner(tokens, 0)
+}
+
+/// Maximum nesting of maps and arrays when parsing a document or skipping a value, so that
+/// deeply nested input can't overflow the stack
+const MAX_RECURSION_DEPTH: usize = 256;
+
+fn expect_document_inner<'a, I>(tokens: &mut Peekable<I>, depth: usize) -> Result<Document, Error>
+where
+    I: Iterator<Item = Result<Token<'a>, Error>>,
+{
+    if depth >= MAX_RECURSION_DEPTH {
+        return Err(Error::custom(
+            "exceeded max recursion depth while parsing document",
+        ));
+    }
+    match tokens.next().transpose()? {
+        Some(Token::ValueNull { .. }) => Ok(Document::Null),
+        Some(Token::ValueBool { value, .. }) => Ok(Document::Bool(value)),
+        Some(Token::ValueNumber { value, .. }) => Ok(Document::Number(value)),
+        Some(Token::ValueString { value, .. }) => Ok(Document::String(value.into_owned())),
+        Some(Token::ValueBigInteger { value, offset }) => value
+            .to_number()
+            .map(Document::Number)
+            .ok_or_else(|| offset.error("big integer is too large for a document".into())),
+        Some(token @ Token::ValueBigDecimal { .. })
+        | Some(token @ Token::ValueBlob { .. })
+        | Some(token @ Token::ValueTimestamp { .. }) => Err(token.error(
+            "blobs, timestamps and big decimals cannot be represented in a document".into(),
+        )),
+        Some(Token::StartMap { .. }) => {
+            let mut object = HashMap::new();
+            loop {
+                match tokens.next().transpose()? {
+                    Some(Token::EndMap { .. }) => break,
+                    Some(Token::MapKey { key, .. }) => {
+                        let key = key.into_owned();
+                        let value = expect_document_inner(tokens, depth + 1)?;
+                        object.insert(key, value);
+                    }
+                    _ => return Err(Error::custom("expected map key or end map")),
+                }
+            }
+            Ok(Document::Object(object))
+        }
+        Some(Token::StartArray { .. }) => {
+            let mut array = Vec::new();
+            loop {
+                match tokens.peek() {
+                    Some(Ok(Token::EndArray { .. })) => {
+                        tokens.next().transpose().unwrap();
+                        break;
+                    }
+                    _ => array.push(expect_document_inner(tokens, depth + 1)?),
+                }
+            }
+            Ok(Document::Array(array))
+        }
+        Some(Token::EndMap { .. }) | Some(Token::MapKey { .. }) => {
+            unreachable!("end map and map key are handled in start map")
+        }
+        Some(Token::EndArray { .. }) => unreachable!("end array is handled in start array"),
+        None => Err(Error::custom("expected value")),
+    }
+}
+
+/// Skips an entire value in the token stream. Errors if it isn't a value.
+pub fn skip_value<'a>(
+    tokens: &mut impl Iterator<Item = Result<Token<'a>, Error>>,
+) -> Result<(), Error> {
+    skip_inner(0, tokens)
+}
+
+/// Assumes a start map/array token has already been consumed and skips tokens until
+/// until its corresponding end map/array token is found.
+pub fn skip_to_end<'a>(
+    tokens: &mut impl Iterator<Item = Result<Token<'a>, Error>>,
+) -> Result<(), Error> {
+    skip_inner(1, tokens)
+}
+
+fn skip_inner<'a>(
+    depth: usize,
+    tokens: &mut impl Iterator<Item = Result<Token<'a>, Error>>,
+) -> Result<(), Error> {
+    if depth >= MAX_RECURSION_DEPTH {
+        return Err(Error::custom(
+            "exceeded max recursion depth while skipping value",
+        ));
+    }
+    loop {
+        match tokens.next().transpose()? {
+            Some(Token::StartMap { .. }) | Some(Token::StartArray { .. }) => {
+                skip_inner(depth + 1, tokens)?;
+                if depth == 0 {
+                    break;
+                }
+            }
+            Some(Token::EndMap { .. }) | Some(Token::EndArray { .. }) => {
+                debug_assert!(depth > 0);
+                break;
+            }
+            Some(Token::ValueBigDecimal { .. })
+            | Some(Token::ValueBigInteger { .. })
+            | Some(Token::ValueBlob { .. })
+            | Some(Token::ValueBool { .. })
+            | Some(Token::ValueNull { .. })
+            | Some(Token::ValueNumber { .. })
+            | Some(Token::ValueString { .. })
+            | Some(Token::ValueTimestamp { .. }) => {
+                if depth == 0 {
+                    break;
+                }
+            }
+            Some(Token::MapKey { .. }) => {}
+            _ => return Err(Error::custom("expected value")),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::decode::cbor_token_iter;
+
+    pub fn start_array<'a>(offset: usize, len: Option<u64>) -> Option<Result<Token<'a>, Error>> {
+        Some(Ok(Token::StartArray {
+            offset: Offset(offset),
+            len,
+        }))
+    }
+
+    pub fn end_array<'a>(offset: usize) -> Option<Result<Token<'a>, Error>> {
+        Some(Ok(Token::EndArray {
+            offset: Offset(offset),
+        }))
+    }
+
+    pub fn start_map<'a>(offset: usize, len: Option<u64>) -> Option<Result<Token<'a>, Error>> {
+        Some(Ok(Token::StartMap {
+            offset: Offset(offset),
+            len,
+        }))
+    }
+
+    pub fn end_map<'a>(offset: usize) -> Option<Result<Token<'a>, Error>> {
+        Some(Ok(Token::EndMap {
+            offset: Offset(offset),
+        }))
+    }
+
+    pub fn map_key(offset: usize, key: &str) -> Option<Result<Token<'_>, Error>> {
+        Some(Ok(Token::MapKey {
+            offset: Offset(offset),
+            key: Cow::Borrowed(key),
+        }))
+    }
+
+    pub fn value_bool<'a>(offset: usize, boolean: bool) -> Option<Result<Token<'a>, Error>> {
+        Some(Ok(Token::ValueBool {
+            offset: Offset(offset),
+            value: boolean,
+        }))
+    }
+
+    pub fn value_number<'a>(offset: usize, number: Number) -> Option<Result<Token<'a>, Error>> {
+        Some(Ok(Token::ValueNumber {
+            offset: Offset(offset),
+            value: number,
+        }))
+    }
+
+    pub fn value_null<'a>(offset: usize) -> Option<Result<Token<'a>, Error>> {
+        Some(Ok(Token::ValueNull {
+            offset: Offset(offset),
+        }))
+    }
+
+    pub fn value_string(offset: usize, string: &str) -> Option<Result<Token<'_>, Error>> {
+        Some(Ok(Token::ValueString {
+            offset: Offset(offset),
+            value: Cow::Borrowed(string),
+        }))
+    }
+
+    #[test]
+    fn skip_simple_value() {
+        let mut tokens = cbor_token_iter(&[0xf6, 0xf5]);
+        skip_value(&mut tokens).unwrap();
+        assert!(matches!(
+            tokens.next(),
+            Some(Ok(Token::ValueBool { value: true, .. }))
+        ))
+    }
+
+    #[test]
+    fn skip_array() {
+        let mut tokens = cbor_token_iter(&[0x84, 0x01, 0x02, 0x03, 0x04, 0xf5]);
+        skip_value(&mut tokens).unwrap();
+        assert!(matches!(
+            tokens.next(),
+            Some(Ok(Token::ValueBool { value: true, .. }))
+        ))
+    }
+
+    #[test]
+    fn skip_nested_map() {
+        let mut tokens = cbor_token_iter(b"\xbf\x61a\xa1\x61b\x9f\x01\xff\x61c\x40\xff\xf5");
+        skip_value(&mut tokens).unwrap();
+        assert!(matches!(
+            tokens.next(),
+            Some(Ok(Token::ValueBool { value: true, .. }))
+        ))
+    }
+
+    #[test]
+    fn skip_to_end_of_map() {
+        let mut tokens = cbor_token_iter(b"\xa2\x61a\x01\x61b\xc1\x00\xf5");
+        expect_start_map(tokens.next()).unwrap();
+        skip_to_end(&mut tokens).unwrap();
+        assert!(matches!(
+            tokens.next(),
+            Some(Ok(Token::ValueBool { value: true, .. }))
+        ))
+    }
+
+    #[test]
+    fn skip_recursion_limit() {
+        let mut value = vec![0x81; 300];
+        value.push(0xf6);
+        assert_eq!(
+            Err(Error::custom(
+                "exceeded max recursion depth while skipping value"
+            )),
+            skip_value(&mut cbor_token_iter(&value))
+        );
+        let mut tokens = cbor_token_iter(&value);
+        expect_start_array(tokens.next()).unwrap();
+        assert!(skip_to_end(&mut tokens).is_err());
+        // Values that are nested just below the limit can be skipped
+        let mut value = vec![0x81; 255];
+        value.extend_from_slice(&[0xf6, 0xf5]);
+        let mut tokens = cbor_token_iter(&value);
+        skip_value(&mut tokens).unwrap();
+        assert!(matches!(
+            tokens.next(),
+            Some(Ok(Token::ValueBool { value: true, .. }))
+        ))
+    }
+
+    #[test]
+    fn expect_values_or_null() {
+        assert_eq!(None, expect_bool_or_null(value_null(0)).unwrap());
+        assert_eq!(
+            Some(true),
+            expect_bool_or_null(value_bool(0, true)).unwrap()
+        );
+        assert_eq!(
+            Some(Cow::Borrowed("test")),
+            expect_string_or_null(value_string(0, "test")).unwrap()
+        );
+        assert_eq!(
+            Some(Number::NegInt(-5)),
+            expect_number_or_null(value_number(0, Number::NegInt(-5))).unwrap()
+        );
+        assert_eq!(
+            Some(Blob::new(vec![1, 2])),
+            expect_blob_or_null(cbor_token_iter(&[0x42, 0x01, 0x02]).next()).unwrap()
+        );
+        assert_eq!(
+            Some(DateTime::from_secs(5)),
+            expect_timestamp_or_null(cbor_token_iter(&[0xc1, 0x05]).next()).unwrap()
+        );
+        assert_eq!(
+            Err(Error::new(
+                ErrorReason::Custom("expected ValueBool or ValueNull".into()),
+                Some(0)
+            )),
+            expect_bool_or_null(value_string(0, "true"))
+        );
+    }
+
+    #[test]
+    fn big_integer_conversions() {
+        assert_eq!(Some(0), BigInteger::from(0u64).to_i128());
+        assert_eq!(Some(-1), BigInteger::from(-1i64).to_i128());
+        assert_eq!(Some(i64::MIN as i128), BigInteger::from(i64::MIN).to_i128());
+        assert_eq!(
+            Some(Number::PosInt(u64::MAX)),
+            BigInteger::from(u64::MAX).to_number()
+        );
+        assert_eq!(
+            None,
+            BigInteger::new(false, Cow::Borrowed(&[0xff; 17])).to_i128()
+        );
+        // Leading zeroes don't count towards the size
+        let mut padded = vec![0; 20];
+        padded.push(7);
+        assert_eq!(
+            Some(-8),
+            BigInteger::new(true, Cow::Owned(padded)).to_i128()
+        );
+    }
+
+    #[test]
+    fn test_document_recursion_limit() {
+        let mut value = vec![0x81; 300];
+        value.push(0xf6);
+        let mut tokens = cbor_token_iter(&value).peekable();
+        assert_eq!(
+            Err(Error::custom(
+                "exceeded max recursion depth while parsing document"
+            )),
+            expect_document(&mut tokens)
+        );
+    }
+
+    #[test]
+    fn test_document() {
+        let input = b"\xa3\x63num\x18\x64\x63arr\x82\xf5\xf6\x63str\x61s";
+        let mut tokens = cbor_token_iter(input).peekable();
+        let document = expect_document(&mut tokens).unwrap();
+        let expected: HashMap<String, Document> = vec![
+            ("num".to_string(), Document::Number(Number::PosInt(100))),
+            (
+                "arr".to_string(),
+                Document::Array(vec![Document::Bool(true), Document::Null]),
+            ),
+            ("str".to_string(), Document::String("s".into())),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(Document::Object(expected), document);
+        assert!(tokens.next().is_none());
+
+        assert!(expect_document(&mut cbor_token_iter(&[0x41, 0x00]).peekable()).is_err());
+    }
+}
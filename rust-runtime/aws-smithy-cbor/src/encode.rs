@@ -0,0 +1,467 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::data::{info, initial_byte, major, simple, tag, BREAK};
+use crate::decode::BigInteger;
+use aws_smithy_types::{Blob, DateTime, Document, Number};
+
+/// Writes the initial byte and argument for a data item, using the shortest possible encoding.
+fn write_header(output: &mut Vec<u8>, major_type: u8, argument: u64) {
+    if argument < info::ONE_BYTE as u64 {
+        output.push(initial_byte(major_type, argument as u8));
+    } else if argument <= u8::MAX as u64 {
+        output.push(initial_byte(major_type, info::ONE_BYTE));
+        output.push(argument as u8);
+    } else if argument <= u16::MAX as u64 {
+        output.push(initial_byte(major_type, info::TWO_BYTES));
+        output.extend_from_slice(&(argument as u16).to_be_bytes());
+    } else if argument <= u32::MAX as u64 {
+        output.push(initial_byte(major_type, info::FOUR_BYTES));
+        output.extend_from_slice(&(argument as u32).to_be_bytes());
+    } else {
+        output.push(initial_byte(major_type, info::EIGHT_BYTES));
+        output.extend_from_slice(&argument.to_be_bytes());
+    }
+}
+
+pub struct CborValueWriter<'a> {
+    output: &'a mut Vec<u8>,
+}
+
+impl<'a> CborValueWriter<'a> {
+    pub fn new(output: &'a mut Vec<u8>) -> Self {
+        CborValueWriter { output }
+    }
+
+    /// Writes a null value.
+    pub fn null(self) {
+        self.output.push(initial_byte(major::SIMPLE, simple::NULL));
+    }
+
+    /// Writes the boolean `value`.
+    pub fn boolean(self, value: bool) {
+        self.output.push(initial_byte(
+            major::SIMPLE,
+            match value {
+                true => simple::TRUE,
+                _ => simple::FALSE,
+            },
+        ));
+    }
+
+    /// Writes a document `value`.
+    pub fn document(self, value: &Document) {
+        match value {
+            Document::Array(values) => {
+                let mut array = self.start_array_with_len(values.len());
+                for value in values {
+                    array.value().document(value);
+                }
+                array.finish();
+            }
+            Document::Bool(value) => self.boolean(*value),
+            Document::Null => self.null(),
+            Document::Number(value) => self.number(*value),
+            Document::Object(values) => {
+                let mut object = self.start_object_with_len(values.len());
+                for (key, value) in values {
+                    object.key(key).document(value);
+                }
+                object.finish();
+            }
+            Document::String(value) => self.string(value),
+        }
+    }
+
+    /// Writes a text string `value`.
+    pub fn string(self, value: &str) {
+        write_header(self.output, major::TEXT, value.len() as u64);
+        self.output.extend_from_slice(value.as_bytes());
+    }
+
+    /// Writes a byte string `value`.
+    pub fn blob(self, value: &Blob) {
+        self.bytes(value.as_ref())
+    }
+
+    /// Writes a byte string from the raw `value`.
+    pub fn bytes(self, value: &[u8]) {
+        write_header(self.output, major::BYTES, value.len() as u64);
+        self.output.extend_from_slice(value);
+    }
+
+    /// Writes a number `value`.
+    ///
+    /// Integers are written with the shortest possible encoding, and floats are always written
+    /// as double precision so that they round-trip exactly.
+    pub fn number(self, value: Number) {
+        match value {
+            Number::PosInt(value) => write_header(self.output, major::UNSIGNED, value),
+            Number::NegInt(value) if value >= 0 => {
+                write_header(self.output, major::UNSIGNED, value as u64)
+            }
+            // CBOR encodes negative integers as `-1 - argument`
+            Number::NegInt(value) => write_header(self.output, major::NEGATIVE, !(value as u64)),
+            Number::Float(value) => self.double(value),
+        }
+    }
+
+    /// Writes a double precision float `value`.
+    pub fn double(self, value: f64) {
+        self.output
+            .push(initial_byte(major::SIMPLE, simple::FLOAT64));
+        self.output.extend_from_slice(&value.to_be_bytes());
+    }
+
+    /// Writes a single precision float `value`.
+    pub fn float(self, value: f32) {
+        self.output
+            .push(initial_byte(major::SIMPLE, simple::FLOAT32));
+        self.output.extend_from_slice(&value.to_be_bytes());
+    }
+
+    /// Writes a big integer `value` as a bignum (tag 2 or 3).
+    pub fn big_integer(self, value: &BigInteger<'_>) {
+        let tag = match value.is_negative() {
+            true => tag::NEGATIVE_BIGNUM,
+            false => tag::POSITIVE_BIGNUM,
+        };
+        write_header(self.output, major::TAG, tag);
+        self.bytes(value.magnitude());
+    }
+
+    /// Writes a big decimal as a decimal fraction (tag 4) with value `mantissa * 10^exponent`.
+    ///
+    /// The mantissa is written as an integer if it fits into one, and as a bignum otherwise.
+    pub fn big_decimal(self, exponent: i64, mantissa: &BigInteger<'_>) {
+        write_header(self.output, major::TAG, tag::DECIMAL_FRACTION);
+        let mut array = self.start_array_with_len(2);
+        array.value().number(Number::NegInt(exponent));
+        match mantissa.to_number() {
+            Some(number) => array.value().number(number),
+            None => array.value().big_integer(mantissa),
+        }
+        array.finish();
+    }
+
+    /// Writes a date-time `value` as epoch seconds tagged with tag 1.
+    ///
+    /// Timestamps without sub-second precision are written as integers; all others are
+    /// written as double precision floats.
+    pub fn date_time(self, date_time: &DateTime) {
+        write_header(self.output, major::TAG, tag::EPOCH_DATE_TIME);
+        if date_time.has_subsec_nanos() {
+            self.double(date_time.as_secs_f64())
+        } else {
+            self.number(Number::NegInt(date_time.secs()))
+        }
+    }
+
+    /// Starts an indefinite-length array.
+    pub fn start_array(self) -> CborArrayWriter<'a> {
+        CborArrayWriter::new(self.output)
+    }
+
+    /// Starts an array with `len` items.
+    pub fn start_array_with_len(self, len: usize) -> CborArrayWriter<'a> {
+        CborArrayWriter::with_len(self.output, len)
+    }
+
+    /// Starts an indefinite-length object (CBOR map with text string keys).
+    pub fn start_object(self) -> CborObjectWriter<'a> {
+        CborObjectWriter::new(self.output)
+    }
+
+    /// Starts an object with `len` entries.
+    pub fn start_object_with_len(self, len: usize) -> CborObjectWriter<'a> {
+        CborObjectWriter::with_len(self.output, len)
+    }
+}
+
+pub struct CborObjectWriter<'a> {
+    cbor: &'a mut Vec<u8>,
+    /// Number of entries remaining for definite-length objects
+    remaining: Option<usize>,
+}
+
+impl<'a> CborObjectWriter<'a> {
+    /// Starts an indefinite-length object that will be terminated by [`CborObjectWriter::finish`].
+    pub fn new(output: &'a mut Vec<u8>) -> Self {
+        output.push(initial_byte(major::MAP, info::INDEFINITE));
+        Self {
+            cbor: output,
+            remaining: None,
+        }
+    }
+
+    /// Starts an object that must have exactly `len` entries.
+    pub fn with_len(output: &'a mut Vec<u8>, len: usize) -> Self {
+        write_header(output, major::MAP, len as u64);
+        Self {
+            cbor: output,
+            remaining: Some(len),
+        }
+    }
+
+    /// Starts a value with the given `key`.
+    pub fn key(&mut self, key: &str) -> CborValueWriter<'_> {
+        if let Some(remaining) = self.remaining.as_mut() {
+            debug_assert!(*remaining > 0, "wrote more entries than the object length");
+            *remaining = remaining.saturating_sub(1);
+        }
+        CborValueWriter::new(self.cbor).string(key);
+        CborValueWriter::new(self.cbor)
+    }
+
+    /// Finishes the object.
+    pub fn finish(self) {
+        match self.remaining {
+            None => self.cbor.push(BREAK),
+            Some(remaining) => {
+                debug_assert_eq!(0, remaining, "wrote fewer entries than the object length")
+            }
+        }
+    }
+}
+
+pub struct CborArrayWriter<'a> {
+    cbor: &'a mut Vec<u8>,
+    /// Number of items remaining for definite-length arrays
+    remaining: Option<usize>,
+}
+
+impl<'a> CborArrayWriter<'a> {
+    /// Starts an indefinite-length array that will be terminated by [`CborArrayWriter::finish`].
+    pub fn new(output: &'a mut Vec<u8>) -> Self {
+        output.push(initial_byte(major::ARRAY, info::INDEFINITE));
+        Self {
+            cbor: output,
+            remaining: None,
+        }
+    }
+
+    /// Starts an array that must have exactly `len` items.
+    pub fn with_len(output: &'a mut Vec<u8>, len: usize) -> Self {
+        write_header(output, major::ARRAY, len as u64);
+        Self {
+            cbor: output,
+            remaining: Some(len),
+        }
+    }
+
+    /// Starts a new value in the array.
+    pub fn value(&mut self) -> CborValueWriter<'_> {
+        if let Some(remaining) = self.remaining.as_mut() {
+            debug_assert!(*remaining > 0, "wrote more items than the array length");
+            *remaining = remaining.saturating_sub(1);
+        }
+        CborValueWriter::new(self.cbor)
+    }
+
+    /// Finishes the array.
+    pub fn finish(self) {
+        match self.remaining {
+            None => self.cbor.push(BREAK),
+            Some(remaining) => {
+                debug_assert_eq!(0, remaining, "wrote fewer items than the array length")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CborArrayWriter, CborObjectWriter, CborValueWriter};
+    use crate::decode::token::expect_document;
+    use crate::decode::{cbor_token_iter, BigInteger, Token};
+    use aws_smithy_types::{Blob, DateTime, Document, Number};
+    use proptest::proptest;
+    use std::borrow::Cow;
+
+    fn write(f: impl FnOnce(CborValueWriter)) -> Vec<u8> {
+        let mut output = Vec::new();
+        f(CborValueWriter::new(&mut output));
+        output
+    }
+
+    #[test]
+    fn empty() {
+        let mut output = Vec::new();
+        CborObjectWriter::new(&mut output).finish();
+        assert_eq!(vec![0xbf, 0xff], output);
+
+        let mut output = Vec::new();
+        CborArrayWriter::new(&mut output).finish();
+        assert_eq!(vec![0x9f, 0xff], output);
+
+        let mut output = Vec::new();
+        CborObjectWriter::with_len(&mut output, 0).finish();
+        assert_eq!(vec![0xa0], output);
+    }
+
+    #[test]
+    fn object() {
+        let mut output = Vec::new();
+        let mut object = CborObjectWriter::new(&mut output);
+        object.key("a").boolean(true);
+        object.key("b").null();
+        let mut array = object.key("c").start_array_with_len(2);
+        array.value().string("x");
+        array.value().number(Number::NegInt(-2));
+        array.finish();
+        object.finish();
+
+        assert_eq!(
+            b"\xbf\x61a\xf5\x61b\xf6\x61c\x82\x61x\x21\xff".to_vec(),
+            output
+        );
+    }
+
+    #[test]
+    fn number_encoding() {
+        let cases: &[(Number, &[u8])] = &[
+            (Number::PosInt(0), &[0x00]),
+            (Number::PosInt(23), &[0x17]),
+            (Number::PosInt(24), &[0x18, 0x18]),
+            (Number::PosInt(1000), &[0x19, 0x03, 0xe8]),
+            (Number::PosInt(1000000), &[0x1a, 0x00, 0x0f, 0x42, 0x40]),
+            (
+                Number::PosInt(u64::MAX),
+                &[0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+            ),
+            (Number::NegInt(5), &[0x05]),
+            (Number::NegInt(-1), &[0x20]),
+            (Number::NegInt(-1000), &[0x39, 0x03, 0xe7]),
+            (
+                Number::NegInt(i64::MIN),
+                &[0x3b, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+            ),
+            (
+                Number::Float(1.1),
+                &[0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a],
+            ),
+        ];
+        for (number, expected) in cases {
+            assert_eq!(
+                expected.to_vec(),
+                write(|w| w.number(*number)),
+                "{:?}",
+                number
+            );
+        }
+        assert_eq!(
+            vec![0xfa, 0x47, 0xc3, 0x50, 0x00],
+            write(|w| w.float(100000.0))
+        );
+    }
+
+    #[test]
+    fn strings_and_blobs() {
+        assert_eq!(vec![0x60], write(|w| w.string("")));
+        assert_eq!(b"\x64IETF".to_vec(), write(|w| w.string("IETF")));
+        assert_eq!(
+            vec![0x43, 0x01, 0x02, 0x03],
+            write(|w| w.blob(&Blob::new(vec![1, 2, 3])))
+        );
+        let long = "a".repeat(300);
+        let output = write(|w| w.string(&long));
+        assert_eq!(&[0x79, 0x01, 0x2c], &output[..3]);
+        assert_eq!(303, output.len());
+    }
+
+    #[test]
+    fn date_times() {
+        assert_eq!(
+            vec![0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0],
+            write(|w| w.date_time(&DateTime::from_secs(1363896240)))
+        );
+        assert_eq!(
+            vec![0xc1, 0xfb, 0x41, 0xd4, 0x52, 0xd9, 0xec, 0x20, 0x00, 0x00],
+            write(|w| w.date_time(&DateTime::from_secs_f64(1363896240.5)))
+        );
+        assert_eq!(
+            vec![0xc1, 0x20],
+            write(|w| w.date_time(&DateTime::from_secs(-1)))
+        );
+    }
+
+    #[test]
+    fn big_numbers() {
+        let value = BigInteger::new(false, Cow::Borrowed(&[1, 0, 0, 0, 0, 0, 0, 0, 0]));
+        let output = write(|w| w.big_integer(&value));
+        assert_eq!(vec![0xc2, 0x49, 1, 0, 0, 0, 0, 0, 0, 0, 0], output);
+
+        assert_eq!(
+            vec![0xc4, 0x82, 0x21, 0x19, 0x6a, 0xb3],
+            write(|w| w.big_decimal(-2, &BigInteger::from(27315u64)))
+        );
+        let output = write(|w| w.big_decimal(3, &value));
+        match cbor_token_iter(&output).next() {
+            Some(Ok(Token::ValueBigDecimal {
+                exponent, mantissa, ..
+            })) => {
+                assert_eq!(3, exponent);
+                assert_eq!(value, mantissa);
+            }
+            other => panic!("expected big decimal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn document_round_trip() {
+        let document = Document::Object(
+            vec![
+                (
+                    "list".to_string(),
+                    Document::Array(vec![
+                        Document::Number(Number::PosInt(1)),
+                        Document::Number(Number::NegInt(-1)),
+                        Document::Number(Number::Float(2.5)),
+                        Document::Null,
+                    ]),
+                ),
+                ("flag".to_string(), Document::Bool(false)),
+                ("name".to_string(), Document::String("smithy".into())),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let output = write(|w| w.document(&document));
+        let parsed = expect_document(&mut cbor_token_iter(&output).peekable()).unwrap();
+        assert_eq!(document, parsed);
+    }
+
+    proptest! {
+        #[test]
+        fn round_trips_integers(value: i64) {
+            let output = write(|w| w.number(Number::NegInt(value)));
+            let expected = if value >= 0 { Number::PosInt(value as u64) } else { Number::NegInt(value) };
+            match cbor_token_iter(&output).next() {
+                Some(Ok(Token::ValueNumber { value, .. })) => assert_eq!(expected, value),
+                other => panic!("expected number, got {:?}", other),
+            }
+        }
+
+        #[test]
+        fn round_trips_floats(value: f64) {
+            let output = write(|w| w.number(Number::Float(value)));
+            match cbor_token_iter(&output).next() {
+                Some(Ok(Token::ValueNumber { value: Number::Float(parsed), .. })) => {
+                    assert_eq!(value.to_bits(), parsed.to_bits())
+                }
+                other => panic!("expected float, got {:?}", other),
+            }
+        }
+
+        #[test]
+        fn round_trips_strings(value in ".*") {
+            let output = write(|w| w.string(&value));
+            match cbor_token_iter(&output).next() {
+                Some(Ok(Token::ValueString { value: parsed, .. })) => assert_eq!(value, parsed),
+                other => panic!("expected string, got {:?}", other),
+            }
+        }
+    }
+}
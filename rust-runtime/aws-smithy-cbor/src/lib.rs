@@ -0,0 +1,13 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! CBOR Abstractions for Smithy
+//!
+//! [RFC 8949](https://www.rfc-editor.org/rfc/rfc8949.html) encoding and decoding as used by the
+//! [Smithy RPC v2 CBOR](https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html) protocol.
+
+mod data;
+pub mod decode;
+pub mod encode;
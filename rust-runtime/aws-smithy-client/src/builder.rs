@@ -7,7 +7,10 @@ use std::sync::Arc;
 
 use crate::{bounds, erase, retry, Client, TriState, MISSING_SLEEP_IMPL_RECOMMENDATION};
 use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep};
+use aws_smithy_async::time::{ClockSkew, SharedTimeSource, TimeSource};
 use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::interceptor::{Interceptor, Interceptors};
+use aws_smithy_http::metrics::{RecordMetrics, SharedMetrics};
 use aws_smithy_http::result::ConnectorError;
 use aws_smithy_types::timeout;
 
@@ -23,6 +26,9 @@ pub struct Builder<C = (), M = (), R = retry::Standard> {
     retry_policy: R,
     timeout_config: timeout::Config,
     sleep_impl: TriState<Arc<dyn AsyncSleep>>,
+    time_source: SharedTimeSource,
+    metrics: SharedMetrics,
+    interceptors: Interceptors,
 }
 
 // It'd be nice to include R where R: Default here, but then the caller ends up always having to
@@ -62,6 +68,9 @@ impl<M, R> Builder<(), M, R> {
             middleware: self.middleware,
             timeout_config: self.timeout_config,
             sleep_impl: self.sleep_impl,
+            time_source: self.time_source,
+            metrics: self.metrics,
+            interceptors: self.interceptors,
         }
     }
 
@@ -118,6 +127,9 @@ impl<C, R> Builder<C, (), R> {
             timeout_config: self.timeout_config,
             middleware,
             sleep_impl: self.sleep_impl,
+            time_source: self.time_source,
+            metrics: self.metrics,
+            interceptors: self.interceptors,
         }
     }
 
@@ -169,6 +181,9 @@ impl<C, M> Builder<C, M, retry::Standard> {
             timeout_config: self.timeout_config,
             middleware: self.middleware,
             sleep_impl: self.sleep_impl,
+            time_source: self.time_source,
+            metrics: self.metrics,
+            interceptors: self.interceptors,
         }
     }
 }
@@ -203,6 +218,77 @@ impl<C, M> Builder<C, M> {
 }
 
 impl<C, M, R> Builder<C, M, R> {
+    /// Set the [`TimeSource`] that the [`Client`] will use to read the current time, such as
+    /// when signing requests.
+    ///
+    /// If `None` is passed, the system clock is used.
+    pub fn set_time_source(&mut self, time_source: Option<SharedTimeSource>) {
+        self.time_source = time_source.unwrap_or_default();
+    }
+
+    /// Set the [`TimeSource`] that the [`Client`] will use to read the current time, such as
+    /// when signing requests.
+    pub fn time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.set_time_source(Some(SharedTimeSource::new(time_source)));
+        self
+    }
+
+    /// Set the recorder for the metrics emitted while the [`Client`] sends operations
+    ///
+    /// If `None` is passed, metrics are discarded. See [`aws_smithy_http::metrics`] for the
+    /// emitted metrics.
+    pub fn set_metrics(&mut self, metrics: Option<SharedMetrics>) {
+        self.metrics = metrics.unwrap_or_default();
+    }
+
+    /// Set the recorder for the metrics emitted while the [`Client`] sends operations
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use aws_smithy_client::Builder;
+    /// use aws_smithy_http::metrics::InMemoryMetrics;
+    /// # fn example(connector: aws_smithy_client::erase::DynConnector) {
+    /// let metrics = InMemoryMetrics::new();
+    /// let client = Builder::new()
+    ///     .connector(connector)
+    ///     .middleware(tower::layer::util::Identity::new())
+    ///     .metrics(metrics.clone())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn metrics(mut self, metrics: impl RecordMetrics + 'static) -> Self {
+        self.set_metrics(Some(SharedMetrics::new(metrics)));
+        self
+    }
+
+    /// Register an [`Interceptor`] that is called for every operation the [`Client`] sends
+    ///
+    /// Interceptors are called in the order they were registered, before any interceptors
+    /// registered on the operation itself. See [`aws_smithy_http::interceptor`] for when the
+    /// hooks are called.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use aws_smithy_client::Builder;
+    /// use aws_smithy_http::interceptor::Interceptor;
+    ///
+    /// #[derive(Debug)]
+    /// struct Audit;
+    /// impl Interceptor for Audit {}
+    ///
+    /// # fn example(connector: aws_smithy_client::erase::DynConnector) {
+    /// let client = Builder::new()
+    ///     .connector(connector)
+    ///     .middleware(tower::layer::util::Identity::new())
+    ///     .interceptor(Audit)
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn interceptor(mut self, interceptor: impl Interceptor + 'static) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
     /// Use a connector that wraps the current connector.
     pub fn map_connector<F, C2>(self, map: F) -> Builder<C2, M, R>
     where
@@ -214,6 +300,9 @@ impl<C, M, R> Builder<C, M, R> {
             retry_policy: self.retry_policy,
             timeout_config: self.timeout_config,
             sleep_impl: self.sleep_impl,
+            time_source: self.time_source,
+            metrics: self.metrics,
+            interceptors: self.interceptors,
         }
     }
 
@@ -228,6 +317,9 @@ impl<C, M, R> Builder<C, M, R> {
             retry_policy: self.retry_policy,
             timeout_config: self.timeout_config,
             sleep_impl: self.sleep_impl,
+            time_source: self.time_source,
+            metrics: self.metrics,
+            interceptors: self.interceptors,
         }
     }
 
@@ -256,6 +348,10 @@ impl<C, M, R> Builder<C, M, R> {
             middleware: self.middleware,
             timeout_config: self.timeout_config,
             sleep_impl: self.sleep_impl,
+            time_source: self.time_source,
+            metrics: self.metrics,
+            interceptors: self.interceptors,
+            clock_skew: ClockSkew::new(),
         }
     }
 }
@@ -15,10 +15,16 @@ use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 
 use aws_smithy_types::base64;
+pub use event_stream::{EventStreamHeader, EventStreamHeaderValue, EventStreamMessage};
+pub use matching::{BodyMatch, MatchRules};
 pub use record::RecordingConnection;
+pub use redact::{Redaction, REDACTED};
 pub use replay::ReplayingConnection;
 
+mod event_stream;
+mod matching;
 mod record;
+mod redact;
 mod replay;
 
 /// A complete traffic recording
@@ -156,6 +162,16 @@ pub enum Action {
         direction: Direction,
     },
 
+    /// Pause before the next data segment
+    ///
+    /// Only recorded by [`RecordingConnection::record_delays`].
+    Delay {
+        /// Milliseconds since the previous data segment in the same direction
+        delay_ms: u64,
+        /// Direction: request vs. response
+        direction: Direction,
+    },
+
     /// End of data
     Eof {
         /// Succesful vs. failed termination
@@ -168,7 +184,7 @@ pub enum Action {
 /// Event direction
 ///
 /// During replay, this is used to replay data in the right direction
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, Hash, Eq, PartialEq)]
 pub enum Direction {
     /// Request phase
     Request,
@@ -189,7 +205,8 @@ impl Direction {
 /// HTTP Body Data Abstraction
 ///
 /// When the data is a UTF-8 encoded string, it will be serialized as a string for readability.
-/// Otherwise, it will be base64 encoded.
+/// Otherwise, it will be base64 encoded. Event stream messages can also be recorded in a
+/// decoded form (see [`RecordingConnection::decode_event_streams`]).
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 #[non_exhaustive]
 pub enum BodyData {
@@ -198,22 +215,41 @@ pub enum BodyData {
 
     /// Base64 encoded binary data
     Base64(String),
+
+    /// A single event stream message
+    EventStream(EventStreamMessage),
 }
 
 impl BodyData {
     /// Convert [`BodyData`](BodyData) into Bytes
+    ///
+    /// # Panics
+    /// Panics if the data is invalid, like [`copy_to_vec`](BodyData::copy_to_vec).
     pub fn into_bytes(self) -> Vec<u8> {
         match self {
             BodyData::Utf8(string) => string.into_bytes(),
-            BodyData::Base64(string) => base64::decode(string).unwrap(),
+            data => data.copy_to_vec(),
         }
     }
 
     /// Copy [`BodyData`](BodyData) into a `Vec<u8>`
+    ///
+    /// # Panics
+    /// Panics if the data is invalid. Use [`try_copy_to_vec`](BodyData::try_copy_to_vec) for
+    /// recordings that may have been edited by hand.
     pub fn copy_to_vec(&self) -> Vec<u8> {
+        self.try_copy_to_vec().unwrap()
+    }
+
+    /// Copy [`BodyData`](BodyData) into a `Vec<u8>`, failing if it's invalid base64 or an invalid
+    /// event stream message
+    pub fn try_copy_to_vec(&self) -> Result<Vec<u8>, String> {
         match self {
-            BodyData::Utf8(string) => string.as_bytes().into(),
-            BodyData::Base64(string) => base64::decode(string).unwrap(),
+            BodyData::Utf8(string) => Ok(string.as_bytes().into()),
+            BodyData::Base64(string) => {
+                base64::decode(string).map_err(|err| format!("invalid base64 data: {}", err))
+            }
+            BodyData::EventStream(message) => message.encode(),
         }
     }
 }
@@ -235,9 +271,71 @@ mod tests {
     use aws_smithy_http::body::SdkBody;
     use aws_smithy_http::byte_stream::ByteStream;
 
-    use crate::dvr::{NetworkTraffic, RecordingConnection, ReplayingConnection};
+    use crate::dvr::{
+        Action, BodyData, ConnectionId, Direction, Event, MatchRules, NetworkTraffic,
+        RecordingConnection, Redaction, ReplayingConnection, Request, Response, REDACTED,
+    };
     use bytes::Bytes;
     use http::Uri;
+    use std::collections::HashMap;
+    use tower::Service;
+
+    /// Recording of a GET request to each `path` that responded with `path` as its body
+    fn get_recording(paths: &[&str]) -> Vec<Event> {
+        let mut events = vec![];
+        for (id, path) in paths.iter().enumerate() {
+            let actions = vec![
+                Action::Request {
+                    request: Request {
+                        uri: format!("https://example.com{}", path),
+                        headers: HashMap::new(),
+                        method: "GET".into(),
+                    },
+                },
+                Action::Eof {
+                    ok: true,
+                    direction: Direction::Request,
+                },
+                Action::Response {
+                    response: Ok(Response {
+                        status: 200,
+                        version: "HTTP/1.1".into(),
+                        headers: HashMap::new(),
+                    }),
+                },
+                Action::Data {
+                    data: BodyData::Utf8(path.to_string()),
+                    direction: Direction::Response,
+                },
+                Action::Eof {
+                    ok: true,
+                    direction: Direction::Response,
+                },
+            ];
+            events.extend(actions.into_iter().map(|action| Event {
+                connection_id: ConnectionId(id),
+                action,
+            }));
+        }
+        events
+    }
+
+    async fn get(connection: &ReplayingConnection, path: &str) -> Result<String, String> {
+        let req = http::Request::get(format!("https://example.com{}", path))
+            .body(SdkBody::empty())
+            .unwrap();
+        let resp = connection
+            .clone()
+            .call(req)
+            .await
+            .map_err(|err| err.to_string())?;
+        let data = ByteStream::new(resp.into_body())
+            .collect()
+            .await
+            .unwrap()
+            .into_bytes();
+        Ok(String::from_utf8(data.to_vec()).unwrap())
+    }
 
     #[tokio::test]
     async fn turtles_all_the_way_down() -> Result<(), Box<dyn Error>> {
@@ -250,7 +348,6 @@ mod tests {
         let req = http::Request::post("https://www.example.com")
             .body(SdkBody::from("hello world"))
             .unwrap();
-        use tower::Service;
         let mut resp = connection.call(req).await.expect("ok");
         let body = std::mem::replace(resp.body_mut(), SdkBody::taken());
         let data = ByteStream::new(body).collect().await.unwrap().into_bytes();
@@ -273,4 +370,244 @@ mod tests {
         );
         Ok(())
     }
+    #[tokio::test]
+    async fn match_requests_out_of_order() {
+        let connection = ReplayingConnection::with_match_rules(
+            get_recording(&["/a", "/b", "/a"]),
+            MatchRules::new(),
+        );
+        let (b, a) = tokio::join!(get(&connection, "/b"), get(&connection, "/a"));
+        assert_eq!(Ok("/b".to_string()), b);
+        assert_eq!(Ok("/a".to_string()), a);
+        assert_eq!(Ok("/a".to_string()), get(&connection, "/a").await);
+
+        let err = get(&connection, "/a").await.unwrap_err();
+        assert!(
+            err.contains("All recorded connections have already been replayed"),
+            "{}",
+            err
+        );
+        connection
+            .validate(&[], |expected, actual| {
+                assert_eq!(expected, actual);
+                Ok(())
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn match_failure_lists_closest_recordings() {
+        let connection =
+            ReplayingConnection::with_match_rules(get_recording(&["/a", "/b"]), MatchRules::new());
+        let err = get(&connection, "/c").await.unwrap_err();
+        assert!(
+            err.contains("no recorded request matches GET https://example.com/c"),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains("connection 0 (GET https://example.com/a):\n    uri: expected https://example.com/a but got https://example.com/c"),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains("connection 1 (GET https://example.com/b)"),
+            "{}",
+            err
+        );
+
+        // The recordings can still be used after a failed match
+        assert_eq!(Ok("/b".to_string()), get(&connection, "/b").await);
+        assert_eq!(
+            vec![Uri::from_static("https://example.com/b")],
+            connection
+                .take_requests()
+                .await
+                .iter()
+                .map(|req| req.uri().clone())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn redacted_recording() {
+        let signed_request = |signature: &str| {
+            http::Request::get("https://example.com/a")
+                .header(
+                    "authorization",
+                    format!(
+                        "AWS4-HMAC-SHA256 Credential=AKID/20210101/us-east-1/s3/aws4_request, \
+                         SignedHeaders=host, Signature={}",
+                        signature
+                    ),
+                )
+                .header("x-amz-security-token", "token")
+                .body(SdkBody::empty())
+                .unwrap()
+        };
+        let mut connection =
+            RecordingConnection::new(ReplayingConnection::new(get_recording(&["/a"])))
+                .with_redaction(Redaction::aws_defaults());
+        let resp = connection.call(signed_request("abcd")).await.unwrap();
+        ByteStream::new(resp.into_body()).collect().await.unwrap();
+
+        let traffic = connection.network_traffic();
+        let request = match &traffic.events[0].action {
+            Action::Request { request } => request,
+            other => panic!("expected a request, got {:?}", other),
+        };
+        assert_eq!(
+            vec![format!(
+                "AWS4-HMAC-SHA256 Credential={r}/20000101/us-east-1/s3/aws4_request, \
+                 SignedHeaders=host, Signature={r}",
+                r = REDACTED
+            )],
+            request.headers["authorization"]
+        );
+        assert_eq!(vec![REDACTED], request.headers["x-amz-security-token"]);
+        // The unredacted traffic is still available
+        assert!(connection.events()[0] != traffic.events[0]);
+
+        let no_body_check = |_: &[u8], _: &[u8]| Ok(());
+        let replay = || async {
+            let replay = ReplayingConnection::new(traffic.events.clone());
+            replay.clone().call(signed_request("ef01")).await.unwrap();
+            replay
+        };
+        assert!(replay()
+            .await
+            .validate(&["authorization"], no_body_check)
+            .await
+            .is_err());
+        replay()
+            .await
+            .validate_redacted(
+                &Redaction::aws_defaults(),
+                &["authorization", "x-amz-security-token"],
+                no_body_check,
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn event_stream_with_delays() {
+        use aws_smithy_async::rt::sleep::TokioSleep;
+        use aws_smithy_eventstream::frame::{Header, HeaderValue, Message};
+        use std::sync::Arc;
+        use std::time::Duration;
+        use tokio::time::Instant;
+
+        let frame = |payload: &'static str| {
+            let mut out = vec![];
+            Message::new(payload)
+                .add_header(Header::new(
+                    ":event-type",
+                    HeaderValue::String("Records".into()),
+                ))
+                .write_to(&mut out)
+                .unwrap();
+            out
+        };
+        let (first, second) = (frame("one"), frame("two"));
+        let mut body = first.clone();
+        body.extend_from_slice(&second);
+
+        // Sends the first message split across two chunks with a delay in between, then the
+        // second message
+        let chunks = vec![body[..5].to_vec(), body[5..].to_vec()];
+        let inner = tower::service_fn(move |_req: http::Request<SdkBody>| {
+            let chunks = chunks.clone();
+            async move {
+                let (mut sender, body) = hyper::Body::channel();
+                tokio::spawn(async move {
+                    for (index, chunk) in chunks.into_iter().enumerate() {
+                        if index > 0 {
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                        }
+                        sender.send_data(chunk.into()).await.unwrap();
+                    }
+                });
+                Ok::<_, std::convert::Infallible>(
+                    http::Response::builder()
+                        .header("content-type", "application/vnd.amazon.eventstream")
+                        .body(body)
+                        .unwrap(),
+                )
+            }
+        });
+        let mut connection = RecordingConnection::new(inner)
+            .record_delays(true)
+            .decode_event_streams(true);
+        let req = http::Request::get("https://example.com")
+            .body(SdkBody::empty())
+            .unwrap();
+        let resp = connection.call(req).await.unwrap();
+        let data = ByteStream::new(resp.into_body()).collect().await.unwrap();
+        assert_eq!(body, data.into_bytes());
+
+        let traffic = connection.network_traffic();
+        let recorded: Vec<_> = traffic
+            .events
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event.action,
+                    Action::Data {
+                        direction: Direction::Response,
+                        ..
+                    } | Action::Delay {
+                        direction: Direction::Response,
+                        ..
+                    }
+                )
+            })
+            .map(|event| event.action.clone())
+            .collect();
+        assert_eq!(3, recorded.len(), "{:?}", recorded);
+        // Both messages completed with the second chunk, 50ms after the first chunk
+        assert!(matches!(recorded[0], Action::Delay { delay_ms: 50, .. }));
+        let data: Vec<_> = recorded[1..]
+            .iter()
+            .map(|action| match action {
+                Action::Data { data, .. } => data.clone(),
+                other => panic!("expected data, got {:?}", other),
+            })
+            .collect();
+        assert!(matches!(data[0], BodyData::EventStream(_)));
+        assert_eq!(first, data[0].copy_to_vec());
+        assert_eq!(second, data[1].copy_to_vec());
+        let json = serde_json::to_string(&traffic).unwrap();
+        assert!(json.contains(r#""value":{"String":"Records"}"#), "{}", json);
+
+        // Replaying produces one chunk per message, with the recorded delays
+        let traffic: NetworkTraffic = serde_json::from_str(&json).unwrap();
+        let replayer =
+            ReplayingConnection::new(traffic.events).with_delays(Arc::new(TokioSleep::new()));
+        let start = Instant::now();
+        let req = http::Request::get("https://example.com")
+            .body(SdkBody::empty())
+            .unwrap();
+        let mut resp = replayer.clone().call(req).await.unwrap();
+        let mut chunks = vec![];
+        while let Some(chunk) = http_body::Body::data(resp.body_mut()).await {
+            chunks.push(chunk.unwrap().to_vec());
+        }
+        assert_eq!(vec![first, second], chunks);
+        assert_eq!(Duration::from_millis(50), start.elapsed());
+    }
+
+    #[tokio::test]
+    async fn invalid_recordings_fail_the_replay() {
+        let mut events = get_recording(&["/a"]);
+        for event in events.iter_mut() {
+            if let Action::Data { data, .. } = &mut event.action {
+                *data = BodyData::Base64("not base64!".into());
+            }
+        }
+        let connection = ReplayingConnection::new(events);
+        let err = get(&connection, "/a").await.unwrap_err();
+        assert!(err.contains("recorded connection 0 is invalid"), "{}", err);
+    }
 }
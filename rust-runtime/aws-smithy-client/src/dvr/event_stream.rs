@@ -0,0 +1,264 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::dvr::BodyData;
+use aws_smithy_eventstream::frame::{Header, HeaderValue, Message};
+use aws_smithy_types::{base64, DateTime};
+use bytes::{Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+
+/// Content type of `aws-smithy-eventstream` bodies
+pub(crate) const EVENT_STREAM_CONTENT_TYPE: &str = "application/vnd.amazon.eventstream";
+
+/// Event stream message in a human-readable form
+///
+/// Signed event stream messages carry the message being signed as their payload, so `payload`
+/// may itself be a [`BodyData::EventStream`].
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct EventStreamMessage {
+    headers: Vec<EventStreamHeader>,
+    payload: Box<BodyData>,
+}
+
+/// Event stream message header
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct EventStreamHeader {
+    name: String,
+    value: EventStreamHeaderValue,
+}
+
+/// Event stream message header value
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum EventStreamHeaderValue {
+    /// Boolean
+    Bool(bool),
+    /// 8-bit integer
+    Byte(i8),
+    /// 16-bit integer
+    Int16(i16),
+    /// 32-bit integer
+    Int32(i32),
+    /// 64-bit integer
+    Int64(i64),
+    /// Base64 encoded bytes
+    ByteArray(String),
+    /// String
+    String(String),
+    /// Milliseconds since the Unix epoch
+    Timestamp(i64),
+    /// UUID as 32 hex digits
+    Uuid(String),
+}
+
+impl EventStreamMessage {
+    /// Converts `bytes` into a readable message if it is exactly one event stream message
+    ///
+    /// Returns `None` if `bytes` isn't a message, or if it wouldn't be encoded back into the same
+    /// bytes during replay.
+    pub(crate) fn decode(bytes: &Bytes) -> Option<Self> {
+        let message = Message::read_from(bytes.clone()).ok()?;
+        let headers = message
+            .headers()
+            .iter()
+            .map(EventStreamHeader::from_header)
+            .collect::<Option<_>>()?;
+        let payload = match EventStreamMessage::decode(message.payload()) {
+            Some(inner) => BodyData::EventStream(inner),
+            None => BodyData::from(message.payload().clone()),
+        };
+        let decoded = EventStreamMessage {
+            headers,
+            payload: Box::new(payload),
+        };
+        match decoded.encode().ok()? == bytes.as_ref() {
+            true => Some(decoded),
+            false => None,
+        }
+    }
+
+    /// Encodes this message into its wire format
+    pub(crate) fn encode(&self) -> Result<Vec<u8>, String> {
+        let headers = self
+            .headers
+            .iter()
+            .map(EventStreamHeader::to_header)
+            .collect::<Result<_, _>>()?;
+        let message = Message::new_from_parts(headers, self.payload.try_copy_to_vec()?);
+        let mut out = Vec::new();
+        message
+            .write_to(&mut out)
+            .map_err(|err| format!("invalid event stream message: {}", err))?;
+        Ok(out)
+    }
+}
+
+impl EventStreamHeader {
+    fn from_header(header: &Header) -> Option<Self> {
+        use EventStreamHeaderValue as Value;
+        let value = match header.value() {
+            HeaderValue::Bool(value) => Value::Bool(*value),
+            HeaderValue::Byte(value) => Value::Byte(*value),
+            HeaderValue::Int16(value) => Value::Int16(*value),
+            HeaderValue::Int32(value) => Value::Int32(*value),
+            HeaderValue::Int64(value) => Value::Int64(*value),
+            HeaderValue::ByteArray(value) => Value::ByteArray(base64::encode(value)),
+            HeaderValue::String(value) => Value::String(value.as_str().into()),
+            HeaderValue::Timestamp(value) => Value::Timestamp(value.to_millis().ok()?),
+            HeaderValue::Uuid(value) => Value::Uuid(format!("{:032x}", value)),
+            _ => return None,
+        };
+        Some(Self {
+            name: header.name().as_str().into(),
+            value,
+        })
+    }
+
+    fn to_header(&self) -> Result<Header, String> {
+        use EventStreamHeaderValue as Value;
+        let invalid = |kind: &str| format!("invalid {} in header `{}`", kind, self.name);
+        let value = match &self.value {
+            Value::Bool(value) => HeaderValue::Bool(*value),
+            Value::Byte(value) => HeaderValue::Byte(*value),
+            Value::Int16(value) => HeaderValue::Int16(*value),
+            Value::Int32(value) => HeaderValue::Int32(*value),
+            Value::Int64(value) => HeaderValue::Int64(*value),
+            Value::ByteArray(value) => {
+                HeaderValue::ByteArray(base64::decode(value).map_err(|_| invalid("base64"))?.into())
+            }
+            Value::String(value) => HeaderValue::String(value.clone().into()),
+            Value::Timestamp(value) => HeaderValue::Timestamp(DateTime::from_millis(*value)),
+            Value::Uuid(value) => {
+                HeaderValue::Uuid(u128::from_str_radix(value, 16).map_err(|_| invalid("UUID"))?)
+            }
+        };
+        Ok(Header::new(self.name.clone(), value))
+    }
+}
+
+/// Splits a recorded event stream body into one chunk per message
+#[derive(Debug, Default)]
+pub(crate) struct FrameSplitter {
+    buffer: BytesMut,
+}
+
+impl FrameSplitter {
+    /// Adds `chunk` to the body and returns the data of each message it completes
+    pub(crate) fn push(&mut self, chunk: &[u8]) -> Vec<BodyData> {
+        /// Size of the total length at the start of every message
+        const LENGTH_BYTES: usize = 4;
+
+        self.buffer.extend_from_slice(chunk);
+        let mut frames = vec![];
+        while self.buffer.len() >= LENGTH_BYTES {
+            let mut length = [0; LENGTH_BYTES];
+            length.copy_from_slice(&self.buffer[..LENGTH_BYTES]);
+            let length = u32::from_be_bytes(length) as usize;
+            if length < LENGTH_BYTES || self.buffer.len() < length {
+                break;
+            }
+            let frame = self.buffer.split_to(length).freeze();
+            frames.push(match EventStreamMessage::decode(&frame) {
+                Some(message) => BodyData::EventStream(message),
+                None => BodyData::from(frame),
+            });
+        }
+        frames
+    }
+
+    /// Returns the incomplete data remaining at the end of the body, if any
+    pub(crate) fn finish(&mut self) -> Option<BodyData> {
+        match self.buffer.is_empty() {
+            true => None,
+            false => Some(BodyData::from(self.buffer.split().freeze())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventStreamMessage, FrameSplitter};
+    use crate::dvr::BodyData;
+    use aws_smithy_eventstream::frame::{Header, HeaderValue, Message};
+    use aws_smithy_types::DateTime;
+    use bytes::Bytes;
+
+    fn message(payload: impl Into<Bytes>) -> Vec<u8> {
+        let message = Message::new(payload)
+            .add_header(Header::new(
+                ":event-type",
+                HeaderValue::String("Records".into()),
+            ))
+            .add_header(Header::new("flag", HeaderValue::Bool(true)))
+            .add_header(Header::new(
+                "bytes",
+                HeaderValue::ByteArray(Bytes::from_static(&[0, 255])),
+            ))
+            .add_header(Header::new(
+                ":date",
+                HeaderValue::Timestamp(DateTime::from_millis(1_600_000_000_123)),
+            ))
+            .add_header(Header::new("id", HeaderValue::Uuid(0xabcdef)));
+        let mut out = Vec::new();
+        message.write_to(&mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn readable_round_trip() {
+        let inner = message("hello");
+        let outer = message(inner.clone());
+        let decoded = EventStreamMessage::decode(&Bytes::from(outer.clone())).unwrap();
+        match decoded.payload.as_ref() {
+            BodyData::EventStream(inner_decoded) => {
+                assert_eq!(inner, inner_decoded.encode().unwrap());
+                assert_eq!(
+                    &BodyData::Utf8("hello".into()),
+                    inner_decoded.payload.as_ref()
+                );
+            }
+            other => panic!("expected a nested message, got {:?}", other),
+        }
+        assert_eq!(outer, decoded.encode().unwrap());
+
+        let json = serde_json::to_string(&decoded).unwrap();
+        assert!(json.contains(r#"{"name":":event-type","value":{"String":"Records"}}"#));
+        assert!(
+            json.contains(r#"{"name":"id","value":{"Uuid":"00000000000000000000000000abcdef"}}"#)
+        );
+        let parsed: EventStreamMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(outer, parsed.encode().unwrap());
+    }
+
+    #[test]
+    fn not_a_message() {
+        assert_eq!(
+            None,
+            EventStreamMessage::decode(&Bytes::from_static(b"hello"))
+        );
+    }
+
+    #[test]
+    fn split_frames() {
+        let (first, second) = (message("one"), message("two"));
+        let mut body = first.clone();
+        body.extend_from_slice(&second);
+
+        let mut splitter = FrameSplitter::default();
+        assert!(splitter.push(&body[..5]).is_empty());
+        let frames = splitter.push(&body[5..first.len() + 3]);
+        assert_eq!(1, frames.len());
+        assert_eq!(first, frames[0].copy_to_vec());
+        let frames = splitter.push(&body[first.len() + 3..]);
+        assert_eq!(second, frames[0].copy_to_vec());
+        assert_eq!(None, splitter.finish());
+
+        splitter.push(b"\0\0\0\xfftruncated");
+        assert_eq!(
+            Some(b"\0\0\0\xfftruncated".to_vec()),
+            splitter.finish().map(|data| data.copy_to_vec())
+        );
+    }
+}
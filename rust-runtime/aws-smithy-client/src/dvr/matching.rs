@@ -0,0 +1,346 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::dvr::Redaction;
+use bytes::Bytes;
+use http::{HeaderMap, Request, Uri};
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+
+type BodyComparer = dyn Fn(&[u8], &[u8]) -> bool + Send + Sync;
+
+/// How request bodies are compared when matching requests to recordings
+#[derive(Clone)]
+pub enum BodyMatch {
+    /// Bodies aren't compared
+    Ignore,
+    /// Bodies must be byte-for-byte identical
+    Exact,
+    /// Bodies match if the function returns true when called with the recorded body and the
+    /// actual body, in that order
+    ///
+    /// This is useful for bodies that contain timestamps, random tokens, or JSON with
+    /// unstable key ordering.
+    Custom(Arc<BodyComparer>),
+}
+
+impl BodyMatch {
+    /// Creates a [`BodyMatch::Custom`] from a comparison function
+    pub fn custom(matches: impl Fn(&[u8], &[u8]) -> bool + Send + Sync + 'static) -> Self {
+        BodyMatch::Custom(Arc::new(matches))
+    }
+}
+
+impl fmt::Debug for BodyMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BodyMatch::Ignore => write!(f, "Ignore"),
+            BodyMatch::Exact => write!(f, "Exact"),
+            BodyMatch::Custom(_) => write!(f, "Custom(<function>)"),
+        }
+    }
+}
+
+/// Rules for deciding whether an incoming request matches a recorded request
+///
+/// Used by [`ReplayingConnection::with_match_rules`](super::ReplayingConnection::with_match_rules)
+/// to pick which recording to replay for each request. By default, the method, URI, and body
+/// must match exactly, and no headers are compared. Query parameters are compared without regard
+/// to their order.
+///
+/// When the recording was made with a [`Redaction`], pass the same rules to
+/// [`with_redaction`](MatchRules::with_redaction) so that the values it replaced are ignored.
+///
+/// ```rust
+/// use aws_smithy_client::dvr::{BodyMatch, MatchRules, Redaction};
+/// let rules = MatchRules::new()
+///     .with_redaction(Redaction::aws_defaults())
+///     .match_header("authorization")
+///     .match_header("content-type")
+///     .match_header("x-amz-target")
+///     .match_body(BodyMatch::Ignore);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MatchRules {
+    method: bool,
+    uri: bool,
+    headers: Vec<String>,
+    body: BodyMatch,
+    redaction: Option<Redaction>,
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        Self {
+            method: true,
+            uri: true,
+            headers: vec![],
+            body: BodyMatch::Exact,
+            redaction: None,
+        }
+    }
+}
+
+impl MatchRules {
+    /// Creates the default match rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the HTTP method must match
+    pub fn match_method(mut self, match_method: bool) -> Self {
+        self.method = match_method;
+        self
+    }
+
+    /// Sets whether the URI (scheme, authority, path and query parameters) must match
+    pub fn match_uri(mut self, match_uri: bool) -> Self {
+        self.uri = match_uri;
+        self
+    }
+
+    /// Adds a header whose values must match
+    ///
+    /// A header that is absent from both requests is considered to match.
+    pub fn match_header(mut self, name: impl Into<String>) -> Self {
+        self.headers.push(name.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Sets how request bodies are compared
+    pub fn match_body(mut self, body: BodyMatch) -> Self {
+        self.body = body;
+        self
+    }
+
+    /// Redacts requests with `redaction` before they're compared
+    ///
+    /// The redaction is applied to both the incoming request and the recorded request, so
+    /// the recording doesn't need to have been redacted with the same rules.
+    pub fn with_redaction(mut self, redaction: Redaction) -> Self {
+        self.redaction = Some(redaction);
+        self
+    }
+
+    /// Returns a description of each way in which `actual` fails to match `expected`
+    ///
+    /// The request matches if the returned list is empty.
+    pub(crate) fn mismatches(
+        &self,
+        expected: &Request<Bytes>,
+        actual: &Request<Bytes>,
+    ) -> Vec<String> {
+        match &self.redaction {
+            Some(redaction) => self.compare(
+                &redaction.http_request(expected),
+                &redaction.http_request(actual),
+            ),
+            None => self.compare(expected, actual),
+        }
+    }
+
+    fn compare(&self, expected: &Request<Bytes>, actual: &Request<Bytes>) -> Vec<String> {
+        let mut mismatches = vec![];
+        if self.method && expected.method() != actual.method() {
+            mismatches.push(format!(
+                "method: expected {} but got {}",
+                expected.method(),
+                actual.method()
+            ));
+        }
+        if self.uri && !uris_match(expected.uri(), actual.uri()) {
+            mismatches.push(format!(
+                "uri: expected {} but got {}",
+                expected.uri(),
+                actual.uri()
+            ));
+        }
+        for name in &self.headers {
+            let (expected, actual) = (
+                header_values(expected.headers(), name),
+                header_values(actual.headers(), name),
+            );
+            if expected != actual {
+                mismatches.push(format!(
+                    "header `{}`: expected {:?} but got {:?}",
+                    name, expected, actual
+                ));
+            }
+        }
+        let bodies_match = match &self.body {
+            BodyMatch::Ignore => true,
+            BodyMatch::Exact => expected.body() == actual.body(),
+            BodyMatch::Custom(matches) => matches(expected.body(), actual.body()),
+        };
+        if !bodies_match {
+            mismatches.push(format!(
+                "body: expected {} but got {}",
+                describe_body(expected.body()),
+                describe_body(actual.body())
+            ));
+        }
+        mismatches
+    }
+}
+
+fn uris_match(expected: &Uri, actual: &Uri) -> bool {
+    let query_params = |uri: &Uri| {
+        let mut params: Vec<String> = uri
+            .query()
+            .unwrap_or_default()
+            .split('&')
+            .filter(|param| !param.is_empty())
+            .map(String::from)
+            .collect();
+        params.sort();
+        params
+    };
+    expected.scheme() == actual.scheme()
+        && expected.authority() == actual.authority()
+        && expected.path() == actual.path()
+        && query_params(expected) == query_params(actual)
+}
+
+fn header_values<'a>(headers: &'a HeaderMap, name: &str) -> Vec<Cow<'a, str>> {
+    headers
+        .get_all(name)
+        .iter()
+        .map(|value| String::from_utf8_lossy(value.as_bytes()))
+        .collect()
+}
+
+/// Short description of a body for diagnostics
+fn describe_body(body: &[u8]) -> String {
+    const PREVIEW_LEN: usize = 64;
+    match std::str::from_utf8(body) {
+        Ok(body) if body.len() <= PREVIEW_LEN => format!("{:?}", body),
+        Ok(body) => {
+            let end = (0..=PREVIEW_LEN)
+                .rev()
+                .find(|index| body.is_char_boundary(*index))
+                .unwrap_or_default();
+            format!("{:?}... ({} bytes)", &body[..end], body.len())
+        }
+        Err(_) => format!("<{} bytes of binary data>", body.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BodyMatch, MatchRules};
+    use crate::dvr::{Redaction, REDACTED};
+    use bytes::Bytes;
+
+    fn request(method: &str, uri: &str, content_type: &str, body: &str) -> http::Request<Bytes> {
+        http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", content_type)
+            .body(Bytes::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[test]
+    fn default_rules() {
+        let rules = MatchRules::new();
+        let expected = request("GET", "https://example.com/a?x=1&y=2", "text/plain", "hi");
+        assert!(rules
+            .mismatches(
+                &expected,
+                &request(
+                    "GET",
+                    "https://example.com/a?y=2&x=1",
+                    "application/json",
+                    "hi"
+                )
+            )
+            .is_empty());
+        assert_eq!(
+            vec![
+                "method: expected GET but got PUT".to_string(),
+                "uri: expected https://example.com/a?x=1&y=2 but got https://example.com/b"
+                    .to_string(),
+                "body: expected \"hi\" but got \"bye\"".to_string(),
+            ],
+            rules.mismatches(
+                &expected,
+                &request("PUT", "https://example.com/b", "text/plain", "bye")
+            )
+        );
+    }
+
+    #[test]
+    fn configured_rules() {
+        let rules = MatchRules::new()
+            .match_method(false)
+            .match_uri(false)
+            .match_header("Content-Type")
+            .match_body(BodyMatch::custom(|expected, actual| {
+                expected.len() == actual.len()
+            }));
+        let expected = request("GET", "https://example.com/a", "text/plain", "abc");
+        assert!(rules
+            .mismatches(
+                &expected,
+                &request("PUT", "https://example.com/b", "text/plain", "xyz")
+            )
+            .is_empty());
+        assert_eq!(
+            vec![
+                "header `content-type`: expected [\"text/plain\"] but got [\"text/xml\"]"
+                    .to_string(),
+                "body: expected \"abc\" but got \"ab\"".to_string(),
+            ],
+            rules.mismatches(
+                &expected,
+                &request("GET", "https://example.com/a", "text/xml", "ab")
+            )
+        );
+    }
+
+    #[test]
+    fn redacted_rules() {
+        let signed_request = |date: &str, signature: &str| {
+            http::Request::get(format!(
+                "https://example.com/a?X-Amz-Date={}&X-Amz-Signature={}",
+                date, signature
+            ))
+            .header("x-amz-date", date)
+            .header("x-amz-security-token", "token")
+            .body(Bytes::from(format!(r#"{{"Nonce":"{}"}}"#, signature)))
+            .unwrap()
+        };
+        let recorded = http::Request::get(format!(
+            "https://example.com/a?X-Amz-Date=20000101T000000Z&X-Amz-Signature={}",
+            REDACTED
+        ))
+        .header("x-amz-date", "20000101T000000Z")
+        .header("x-amz-security-token", REDACTED)
+        .body(Bytes::from(format!(r#"{{"Nonce":"{}"}}"#, REDACTED)))
+        .unwrap();
+        let actual = signed_request("20210101T000000Z", "abcd");
+
+        let rules = MatchRules::new()
+            .match_header("x-amz-date")
+            .match_header("x-amz-security-token");
+        assert_eq!(4, rules.mismatches(&recorded, &actual).len());
+        let redaction = Redaction::aws_defaults().redact_json_body_path("$.Nonce");
+        let rules = rules.with_redaction(redaction.clone());
+        assert!(rules.mismatches(&recorded, &actual).is_empty());
+        // Recordings that weren't redacted are redacted the same way
+        assert!(rules
+            .mismatches(&signed_request("20220202T000000Z", "ef01"), &actual)
+            .is_empty());
+        assert_eq!(
+            vec!["method: expected GET but got PUT".to_string()],
+            rules.mismatches(&recorded, &{
+                let mut put = signed_request("20210101T000000Z", "abcd");
+                *put.method_mut() = http::Method::PUT;
+                put
+            })
+        );
+    }
+}
@@ -11,11 +11,15 @@ use std::task::{Context, Poll};
 
 use http_body::Body;
 use tokio::task::JoinHandle;
+use tokio::time::Instant;
 use tower::Service;
 
 use aws_smithy_http::body::SdkBody;
 
-use crate::dvr::{self, Action, BodyData, ConnectionId, Direction, Error, NetworkTraffic, Version};
+use crate::dvr::event_stream::{FrameSplitter, EVENT_STREAM_CONTENT_TYPE};
+use crate::dvr::{
+    self, Action, BodyData, ConnectionId, Direction, Error, NetworkTraffic, Redaction, Version,
+};
 
 use super::Event;
 use std::fmt::Display;
@@ -28,6 +32,29 @@ pub struct RecordingConnection<S> {
     pub(crate) data: Arc<Mutex<Vec<Event>>>,
     pub(crate) num_events: Arc<AtomicUsize>,
     pub(crate) inner: S,
+    pub(crate) redaction: Redaction,
+    pub(crate) settings: BodySettings,
+}
+
+/// How request and response bodies are recorded
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct BodySettings {
+    record_delays: bool,
+    decode_event_streams: bool,
+}
+
+impl BodySettings {
+    /// Returns how to record a body sent with `headers`
+    fn body_options(&self, headers: &http::HeaderMap) -> BodyOptions {
+        BodyOptions {
+            record_delays: self.record_delays,
+            split_frames: self.decode_event_streams
+                && headers
+                    .get(http::header::CONTENT_TYPE)
+                    .map(|content_type| content_type == EVENT_STREAM_CONTENT_TYPE)
+                    .unwrap_or_default(),
+        }
+    }
 }
 
 impl RecordingConnection<crate::conns::Https> {
@@ -38,6 +65,8 @@ impl RecordingConnection<crate::conns::Https> {
             data: Default::default(),
             inner: crate::conns::https(),
             num_events: Arc::new(AtomicUsize::new(0)),
+            redaction: Redaction::default(),
+            settings: BodySettings::default(),
         }
     }
 }
@@ -49,18 +78,52 @@ impl<S> RecordingConnection<S> {
             data: Default::default(),
             inner: connection,
             num_events: Arc::new(AtomicUsize::new(0)),
+            redaction: Redaction::default(),
+            settings: BodySettings::default(),
         }
     }
 
+    /// Record how long each chunk of a body took to arrive after the previous one
+    ///
+    /// Each delay is recorded as an [`Action::Delay`](super::Action::Delay) before the chunk it
+    /// preceded. Recorded delays can be replayed with
+    /// [`ReplayingConnection::with_delays`](super::ReplayingConnection::with_delays).
+    pub fn record_delays(mut self, record_delays: bool) -> Self {
+        self.settings.record_delays = record_delays;
+        self
+    }
+
+    /// Record event stream bodies as one readable [`BodyData::EventStream`] per message
+    ///
+    /// This applies to bodies with the `application/vnd.amazon.eventstream` content type.
+    /// Instead of the chunks the body was received in, one chunk is recorded for each message,
+    /// which is also how the body will be chunked during replay. Other bodies keep their
+    /// original chunking.
+    pub fn decode_event_streams(mut self, decode_event_streams: bool) -> Self {
+        self.settings.decode_event_streams = decode_event_streams;
+        self
+    }
+
+    /// Redact secrets and unstable values from the recorded traffic with `redaction`
+    ///
+    /// Redaction is applied by [`network_traffic`](RecordingConnection::network_traffic).
+    /// [`events`](RecordingConnection::events) always returns the traffic as it was sent.
+    pub fn with_redaction(mut self, redaction: Redaction) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
     /// Return the traffic recorded by this connection
     pub fn events(&self) -> MutexGuard<'_, Vec<Event>> {
         self.data.lock().unwrap()
     }
 
     /// NetworkTraffic struct suitable for serialization
+    ///
+    /// Any [redaction](RecordingConnection::with_redaction) is applied to the returned traffic.
     pub fn network_traffic(&self) -> NetworkTraffic {
         NetworkTraffic {
-            events: self.events().clone(),
+            events: self.redaction.events(&self.events()),
             docs: Some("todo docs".into()),
             version: Version::V0,
         }
@@ -71,28 +134,78 @@ impl<S> RecordingConnection<S> {
     }
 }
 
+/// How a single body is recorded
+#[derive(Clone, Copy, Debug)]
+struct BodyOptions {
+    record_delays: bool,
+    /// Record one chunk per event stream message instead of the chunks as they were received
+    split_frames: bool,
+}
+
 fn record_body(
     body: &mut SdkBody,
     event_id: ConnectionId,
     direction: Direction,
     event_bus: Arc<Mutex<Vec<Event>>>,
+    options: BodyOptions,
 ) -> JoinHandle<()> {
     let (sender, output_body) = hyper::Body::channel();
     let real_body = std::mem::replace(body, SdkBody::from(output_body));
+    let mut last_event = Instant::now();
+    let mut delay_ms = move || {
+        let now = Instant::now();
+        let delay = now.duration_since(last_event);
+        last_event = now;
+        match options.record_delays {
+            true => Some(delay.as_millis() as u64),
+            false => None,
+        }
+    };
+    let mut frames = match options.split_frames {
+        true => Some(FrameSplitter::default()),
+        false => None,
+    };
+    let push_data = move |event_bus: &Mutex<Vec<Event>>, data: BodyData, delay_ms| {
+        let mut event_bus = event_bus.lock().unwrap();
+        if let Some(delay_ms) = delay_ms {
+            event_bus.push(Event {
+                connection_id: event_id,
+                action: Action::Delay {
+                    delay_ms,
+                    direction,
+                },
+            });
+        }
+        event_bus.push(Event {
+            connection_id: event_id,
+            action: Action::Data { data, direction },
+        })
+    };
     tokio::spawn(async move {
         let mut real_body = real_body;
         let mut sender = sender;
         loop {
             let data = real_body.data().await;
+            // Anything left over from an incomplete event stream message is recorded as is
+            if !matches!(data, Some(Ok(_))) {
+                if let Some(rest) = frames.as_mut().and_then(FrameSplitter::finish) {
+                    push_data(&event_bus, rest, delay_ms());
+                }
+            }
             match data {
                 Some(Ok(data)) => {
-                    event_bus.lock().unwrap().push(Event {
-                        connection_id: event_id,
-                        action: Action::Data {
-                            data: BodyData::from(data.clone()),
-                            direction,
-                        },
-                    });
+                    let chunks = match &mut frames {
+                        Some(frames) => frames.push(&data),
+                        None => vec![BodyData::from(data.clone())],
+                    };
+                    if !chunks.is_empty() {
+                        let delay = delay_ms();
+                        for (index, chunk) in chunks.into_iter().enumerate() {
+                            // Messages that completed in the same chunk arrived at the same time
+                            let delay = if index == 0 { delay } else { None };
+                            push_data(&event_bus, chunk, delay);
+                        }
+                    }
                     // This happens if the real connection is closed during recording.
                     // Need to think more carefully if this is the correct thing to log in this
                     // case.
@@ -172,13 +285,16 @@ where
         // Phase 2: Swap out the real request body for one that will log all traffic that passes
         // through it
         // This will also handle phase three when the request body runs out of data.
+        let request_options = self.settings.body_options(req.headers());
         record_body(
             req.body_mut(),
             event_id,
             Direction::Request,
             self.data.clone(),
+            request_options,
         );
         let events = self.data.clone();
+        let settings = self.settings;
         // create a channel we'll use to stream the data while reading it
         let resp_fut = self.inner.call(req);
         let fut = async move {
@@ -197,7 +313,14 @@ where
                     });
 
                     // instrument the body and record traffic
-                    record_body(resp.body_mut(), event_id, Direction::Response, events);
+                    let options = settings.body_options(resp.headers());
+                    record_body(
+                        resp.body_mut(),
+                        event_id,
+                        Direction::Response,
+                        events,
+                        options,
+                    );
                     Ok(resp)
                 }
                 Err(e) => {
@@ -3,7 +3,8 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use crate::dvr::{Action, ConnectionId, Direction, Event};
+use crate::dvr::{Action, ConnectionId, Direction, Event, MatchRules, Redaction};
+use aws_smithy_async::rt::sleep::AsyncSleep;
 use aws_smithy_http::body::SdkBody;
 use aws_smithy_http::result::ConnectorError;
 use bytes::{Bytes, BytesMut};
@@ -14,6 +15,7 @@ use std::error::Error;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
+use std::time::Duration;
 use tokio::task::JoinHandle;
 
 /// Wrapper type to enable optionally waiting for a future to complete
@@ -39,15 +41,39 @@ impl<T> Waitable<T> {
             Waitable::Value(_) => {}
         }
     }
+
+    /// Returns the value once [`wait`](Waitable::wait) has completed
+    fn get(&self) -> &T {
+        match self {
+            Waitable::Loading(_) => panic!("`wait` must be called before `get`"),
+            Waitable::Value(value) => value,
+        }
+    }
+}
+
+/// How a [`ReplayingConnection`] picks the recorded connection to replay for a request
+#[derive(Clone, Debug)]
+enum ReplayMode {
+    /// Replay recorded connections in the order that requests are made
+    InOrder,
+    /// Replay the first unused recorded connection whose request matches
+    MatchRequest(MatchRules),
 }
 
 /// Replay traffic recorded by a [`RecordingConnection`](super::RecordingConnection)
+///
+/// By default, recorded connections are replayed in order: the first request receives the
+/// response of the first recorded connection, and so on. Use
+/// [`with_match_rules`](ReplayingConnection::with_match_rules) for code that sends requests
+/// concurrently or in a nondeterministic order.
 #[derive(Clone, Debug)]
 pub struct ReplayingConnection {
     live_events: Arc<Mutex<HashMap<ConnectionId, VecDeque<Event>>>>,
     verifiable_events: Arc<HashMap<ConnectionId, Request<Bytes>>>,
     num_events: Arc<AtomicUsize>,
     recorded_requests: Arc<Mutex<HashMap<ConnectionId, Waitable<http::Request<Bytes>>>>>,
+    mode: ReplayMode,
+    sleep_impl: Option<Arc<dyn AsyncSleep>>,
 }
 
 impl ReplayingConnection {
@@ -60,11 +86,25 @@ impl ReplayingConnection {
         self,
         checked_headers: &[&str],
         body_comparer: impl Fn(&[u8], &[u8]) -> Result<(), Box<dyn Error>>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.validate_redacted(&Redaction::default(), checked_headers, body_comparer)
+            .await
+    }
+
+    /// Validate actual requests against expected requests that were recorded with `redaction`
+    ///
+    /// The same redaction is applied to both the actual and expected requests before they're
+    /// compared, so the values it replaces are ignored.
+    pub async fn validate_redacted(
+        self,
+        redaction: &Redaction,
+        checked_headers: &[&str],
+        body_comparer: impl Fn(&[u8], &[u8]) -> Result<(), Box<dyn Error>>,
     ) -> Result<(), Box<dyn Error>> {
         let mut actual_requests = self.recorded_requests.lock().unwrap();
         for conn_id in 0..self.verifiable_events.len() {
             let conn_id = ConnectionId(conn_id);
-            let expected = self.verifiable_events.get(&conn_id).unwrap();
+            let expected = redaction.http_request(self.verifiable_events.get(&conn_id).unwrap());
             let actual = actual_requests
                 .remove(&conn_id)
                 .ok_or(format!(
@@ -73,6 +113,7 @@ impl ReplayingConnection {
                 ))?
                 .take()
                 .await;
+            let actual = redaction.http_request(&actual);
             aws_smithy_protocol_test::assert_uris_match(actual.uri(), expected.uri());
             body_comparer(expected.body().as_ref(), actual.body().as_ref())?;
             let expected_headers = checked_headers
@@ -97,13 +138,17 @@ impl ReplayingConnection {
     }
 
     /// Return all the recorded requests for further analysis
+    ///
+    /// Requests are ordered by the recorded connection they were replayed against.
     pub async fn take_requests(self) -> Vec<http::Request<Bytes>> {
         let mut recorded_requests = self.recorded_requests.lock().unwrap();
-        let mut out = Vec::with_capacity(recorded_requests.len());
-        for conn_id in 0..recorded_requests.len() {
+        let mut conn_ids: Vec<_> = recorded_requests.keys().copied().collect();
+        conn_ids.sort_by_key(|conn_id| conn_id.0);
+        let mut out = Vec::with_capacity(conn_ids.len());
+        for conn_id in conn_ids {
             out.push(
                 recorded_requests
-                    .remove(&ConnectionId(conn_id))
+                    .remove(&conn_id)
                     .expect("should exist")
                     .take()
                     .await,
@@ -129,7 +174,8 @@ impl ReplayingConnection {
                         data,
                     } = &event.action
                     {
-                        body.extend_from_slice(&data.copy_to_vec());
+                        // Invalid data fails the replay of this connection instead
+                        body.extend_from_slice(&data.try_copy_to_vec().unwrap_or_default());
                     }
                 }
                 let initial_request = events.iter().next().expect("must have one event");
@@ -149,27 +195,124 @@ impl ReplayingConnection {
             num_events: Arc::new(AtomicUsize::new(0)),
             recorded_requests: Default::default(),
             verifiable_events,
+            mode: ReplayMode::InOrder,
+            sleep_impl: None,
+        }
+    }
+
+    /// Replay the recorded delays between chunks of response bodies by sleeping with `sleep_impl`
+    ///
+    /// By default, recorded delays are ignored and response bodies are replayed as fast as
+    /// they're read. Delays are only present in recordings made with
+    /// [`RecordingConnection::record_delays`](super::RecordingConnection::record_delays).
+    pub fn with_delays(mut self, sleep_impl: Arc<dyn AsyncSleep>) -> Self {
+        self.sleep_impl = Some(sleep_impl);
+        self
+    }
+
+    /// Build a replay connection that picks the recorded connection to replay for each request
+    /// by matching the request against the recorded requests with `rules`
+    ///
+    /// Each recorded connection is replayed at most once. If several unused recordings match a
+    /// request, the one that was recorded first is replayed. When no recording matches, the
+    /// request fails with an error that describes how the closest recordings differ from it.
+    ///
+    /// Since the request body may need to be compared, it's read in full before the response
+    /// is replayed.
+    pub fn with_match_rules(events: Vec<Event>, rules: MatchRules) -> Self {
+        Self {
+            mode: ReplayMode::MatchRequest(rules),
+            ..Self::new(events)
+        }
+    }
+
+    /// Removes and returns the events of the first unused recorded connection that matches
+    /// `request`
+    fn claim_match(
+        &self,
+        rules: &MatchRules,
+        request: &http::Request<Bytes>,
+    ) -> Result<(ConnectionId, VecDeque<Event>), ConnectorError> {
+        /// Number of non-matching recordings to include in the error message
+        const CLOSEST_COUNT: usize = 3;
+
+        let mut live_events = self.live_events.lock().unwrap();
+        let mut candidates: Vec<_> = live_events.keys().copied().collect();
+        candidates.sort_by_key(|conn_id| conn_id.0);
+        let mut near_misses = Vec::new();
+        for conn_id in candidates {
+            let expected = &self.verifiable_events[&conn_id];
+            let mismatches = rules.mismatches(expected, request);
+            if mismatches.is_empty() {
+                let events = live_events.remove(&conn_id).expect("candidate exists");
+                return Ok((conn_id, events));
+            }
+            near_misses.push((conn_id, expected, mismatches));
+        }
+
+        let mut message = format!(
+            "no recorded request matches {} {}.",
+            request.method(),
+            request.uri()
+        );
+        if near_misses.is_empty() {
+            message.push_str(" All recorded connections have already been replayed.");
+        } else {
+            // Stable sort, so ties are listed in recording order
+            near_misses.sort_by_key(|(_, _, mismatches)| mismatches.len());
+            message.push_str(" Closest unused recordings:");
+            for (conn_id, expected, mismatches) in near_misses.iter().take(CLOSEST_COUNT) {
+                message.push_str(&format!(
+                    "\n  connection {} ({} {}):",
+                    conn_id.0,
+                    expected.method(),
+                    expected.uri()
+                ));
+                for mismatch in mismatches {
+                    message.push_str(&format!("\n    {}", mismatch));
+                }
+            }
         }
+        Err(ConnectorError::other(message.into(), None))
     }
 }
 
-async fn replay_body(events: VecDeque<Event>, mut sender: hyper::body::Sender) {
+async fn replay_body(
+    events: VecDeque<Event>,
+    mut sender: hyper::body::Sender,
+    sleep_impl: Option<Arc<dyn AsyncSleep>>,
+) {
     for event in events {
         match event.action {
             Action::Request { .. } => panic!(),
             Action::Response { .. } => panic!(),
+            Action::Delay {
+                delay_ms,
+                direction: Direction::Response,
+            } => {
+                if let Some(sleep_impl) = &sleep_impl {
+                    sleep_impl.sleep(Duration::from_millis(delay_ms)).await;
+                }
+            }
+            Action::Delay {
+                direction: Direction::Request,
+                ..
+            } => {}
             Action::Data {
                 data,
                 direction: Direction::Response,
             } => {
+                let data = data
+                    .try_copy_to_vec()
+                    .expect("the data was validated by `replay_events`");
                 sender
-                    .send_data(Bytes::from(data.into_bytes()))
+                    .send_data(Bytes::from(data))
                     .await
                     .expect("this is in memory traffic that should not fail to send");
             }
             Action::Data {
-                data: _data,
                 direction: Direction::Request,
+                ..
             } => {}
             Action::Eof {
                 direction: Direction::Request,
@@ -216,9 +359,26 @@ impl tower::Service<http::Request<SdkBody>> for ReplayingConnection {
         Poll::Ready(Ok(()))
     }
 
-    fn call(&mut self, mut req: Request<SdkBody>) -> Self::Future {
+    fn call(&mut self, req: Request<SdkBody>) -> Self::Future {
+        if let ReplayMode::MatchRequest(rules) = &self.mode {
+            let (connection, rules) = (self.clone(), rules.clone());
+            return Box::pin(async move {
+                let mut recorded_request = record_request(req);
+                recorded_request.wait().await;
+                let (event_id, events) = connection.claim_match(&rules, recorded_request.get())?;
+                replay_events(
+                    event_id,
+                    events,
+                    recorded_request,
+                    connection.recorded_requests,
+                    connection.sleep_impl,
+                )
+                .await
+            });
+        }
+
         let event_id = self.next_id();
-        let mut events = match self.live_events.lock().unwrap().remove(&event_id) {
+        let events = match self.live_events.lock().unwrap().remove(&event_id) {
             Some(traffic) => traffic,
             None => {
                 return Box::pin(std::future::ready(Err(ConnectorError::other(
@@ -227,76 +387,102 @@ impl tower::Service<http::Request<SdkBody>> for ReplayingConnection {
                 ))))
             }
         };
+        Box::pin(replay_events(
+            event_id,
+            events,
+            record_request(req),
+            self.recorded_requests.clone(),
+            self.sleep_impl.clone(),
+        ))
+    }
+}
 
-        let _initial_request = events.pop_front().unwrap();
-        let (sender, response_body) = hyper::Body::channel();
-        let body = SdkBody::from(response_body);
-        let recording = self.recorded_requests.clone();
-        let recorded_request = tokio::spawn(async move {
-            let mut data_read = vec![];
-            while let Some(data) = req.body_mut().data().await {
-                data_read
-                    .extend_from_slice(data.expect("in memory request should not fail").as_ref())
+/// Reads the request body in the background so that it can be validated later
+fn record_request(mut req: Request<SdkBody>) -> Waitable<http::Request<Bytes>> {
+    Waitable::Loading(tokio::spawn(async move {
+        let mut data_read = vec![];
+        while let Some(data) = req.body_mut().data().await {
+            data_read.extend_from_slice(data.expect("in memory request should not fail").as_ref())
+        }
+        req.map(|_| Bytes::from(data_read))
+    }))
+}
+
+/// Replays the response `events` of a recorded connection
+async fn replay_events(
+    event_id: ConnectionId,
+    mut events: VecDeque<Event>,
+    mut recorded_request: Waitable<http::Request<Bytes>>,
+    recording: Arc<Mutex<HashMap<ConnectionId, Waitable<http::Request<Bytes>>>>>,
+    sleep_impl: Option<Arc<dyn AsyncSleep>>,
+) -> Result<http::Response<SdkBody>, ConnectorError> {
+    let _initial_request = events.pop_front().unwrap();
+    let invalid_data = events.iter().find_map(|event| match &event.action {
+        Action::Data { data, .. } => data.try_copy_to_vec().err(),
+        _ => None,
+    });
+    if let Some(err) = invalid_data {
+        recording.lock().unwrap().insert(event_id, recorded_request);
+        return Err(ConnectorError::other(
+            format!("recorded connection {} is invalid: {}", event_id.0, err).into(),
+            None,
+        ));
+    }
+    let (sender, response_body) = hyper::Body::channel();
+    let body = SdkBody::from(response_body);
+    let resp = loop {
+        let event = events
+            .pop_front()
+            .expect("no events, needed a response event");
+        match event.action {
+            // to ensure deterministic behavior if the request EOF happens first in the log,
+            // wait for the request body to be done before returning a response.
+            Action::Eof {
+                direction: Direction::Request,
+                ..
+            } => {
+                recorded_request.wait().await;
             }
-            req.map(|_| Bytes::from(data_read))
-        });
-        let mut recorded_request = Waitable::Loading(recorded_request);
-        let fut = async move {
-            let resp = loop {
-                let event = events
-                    .pop_front()
-                    .expect("no events, needed a response event");
-                match event.action {
-                    // to ensure deterministic behavior if the request EOF happens first in the log,
-                    // wait for the request body to be done before returning a response.
-                    Action::Eof {
-                        direction: Direction::Request,
-                        ..
-                    } => {
-                        recorded_request.wait().await;
-                    }
-                    Action::Request { .. } => panic!("invalid"),
-                    Action::Response {
-                        response: Err(error),
-                    } => break Err(ConnectorError::other(error.0.into(), None)),
-                    Action::Response {
-                        response: Ok(response),
-                    } => {
-                        let mut builder = http::Response::builder()
-                            .status(response.status)
-                            .version(convert_version(&response.version));
-                        for (name, values) in response.headers {
-                            for value in values {
-                                builder = builder.header(&name, &value);
-                            }
-                        }
-                        tokio::spawn(async move {
-                            replay_body(events, sender).await;
-                            // insert the finalized body into
-                        });
-                        break Ok(builder.body(body).expect("valid builder"));
+            Action::Request { .. } => panic!("invalid"),
+            Action::Response {
+                response: Err(error),
+            } => break Err(ConnectorError::other(error.0.into(), None)),
+            Action::Response {
+                response: Ok(response),
+            } => {
+                let mut builder = http::Response::builder()
+                    .status(response.status)
+                    .version(convert_version(&response.version));
+                for (name, values) in response.headers {
+                    for value in values {
+                        builder = builder.header(&name, &value);
                     }
+                }
+                tokio::spawn(async move {
+                    replay_body(events, sender, sleep_impl).await;
+                    // insert the finalized body into
+                });
+                break Ok(builder.body(body).expect("valid builder"));
+            }
 
-                    Action::Data {
-                        direction: Direction::Request,
-                        data: _data,
-                    } => {
-                        tracing::info!("get request data");
-                    }
-                    Action::Eof {
-                        direction: Direction::Response,
-                        ..
-                    } => panic!("got eof before response"),
+            Action::Data {
+                direction: Direction::Request,
+                ..
+            } => {
+                tracing::info!("get request data");
+            }
+            Action::Delay { .. } => {}
+            Action::Eof {
+                direction: Direction::Response,
+                ..
+            } => panic!("got eof before response"),
 
-                    Action::Data {
-                        data: _,
-                        direction: Direction::Response,
-                    } => panic!("got response data before response"),
-                }
-            };
-            recording.lock().unwrap().insert(event_id, recorded_request);
-            resp
-        };
-        Box::pin(fut)
-    }
+            Action::Data {
+                direction: Direction::Response,
+                ..
+            } => panic!("got response data before response"),
+        }
+    };
+    recording.lock().unwrap().insert(event_id, recorded_request);
+    resp
 }
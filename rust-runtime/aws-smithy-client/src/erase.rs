@@ -58,6 +58,10 @@ where
             retry_policy: self.retry_policy,
             timeout_config: self.timeout_config,
             sleep_impl: self.sleep_impl,
+            time_source: self.time_source,
+            metrics: self.metrics,
+            interceptors: self.interceptors,
+            clock_skew: self.clock_skew,
         }
     }
 }
@@ -98,6 +102,10 @@ where
             retry_policy: self.retry_policy,
             timeout_config: self.timeout_config,
             sleep_impl: self.sleep_impl,
+            time_source: self.time_source,
+            metrics: self.metrics,
+            interceptors: self.interceptors,
+            clock_skew: self.clock_skew,
         }
     }
 
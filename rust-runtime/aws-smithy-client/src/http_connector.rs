@@ -7,9 +7,18 @@
 //! that enable passing HTTP connectors around.
 
 use crate::erase::DynConnector;
+use crate::pool::PoolConfig;
+use crate::tls::TlsConfig;
 use aws_smithy_async::rt::sleep::AsyncSleep;
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::http_versions::{HttpVersionList, DEFAULT_HTTP_VERSION_LIST};
+use aws_smithy_http::result::ConnectorError;
 use aws_smithy_types::timeout;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
 use std::{fmt::Debug, sync::Arc};
+use tower::{Service, ServiceExt};
 
 /// Type alias for a Connector factory function.
 pub type MakeConnectorFn =
@@ -57,12 +66,16 @@ impl HttpConnector {
 
 /// HttpSettings for HTTP Connectors
 #[non_exhaustive]
-#[derive(Default, Debug)]
+#[derive(Clone, Default, Debug)]
 pub struct HttpSettings {
     /// Timeout configuration used when making HTTP connections
     pub http_timeout_config: timeout::Http,
     /// Timeout configuration used when creating TCP connections
     pub tcp_timeout_config: timeout::Tcp,
+    /// TLS configuration used by HTTPS connectors
+    pub tls_config: TlsConfig,
+    /// Configuration of the pool of idle connections
+    pub pool_config: PoolConfig,
 }
 
 impl HttpSettings {
@@ -77,4 +90,142 @@ impl HttpSettings {
         self.tcp_timeout_config = tcp_timeout_config;
         self
     }
+
+    /// Set the TLS configuration to be used by HTTPS connectors
+    pub fn with_tls_config(mut self, tls_config: TlsConfig) -> Self {
+        self.tls_config = tls_config;
+        self
+    }
+
+    /// Set the configuration of the pool of idle connections
+    pub fn with_pool_config(mut self, pool_config: PoolConfig) -> Self {
+        self.pool_config = pool_config;
+        self
+    }
+}
+
+/// Type alias for a function that creates a connector for requests that want a list of HTTP versions.
+pub type MakeVersionedConnectorFn = dyn Fn(&HttpVersionList) -> DynConnector + Send + Sync;
+
+/// A connector that hands out a suitably configured connector per [`HttpVersionList`]
+///
+/// The version list of a request is read from its extensions, where the dispatch layer places the
+/// list an operation declared in its property bag. Requests without a version list use
+/// [`DEFAULT_HTTP_VERSION_LIST`]. Connectors are created on first use and cached, so requests that
+/// want the same versions share a connection pool.
+///
+/// When using hyper, create a cache with
+/// [`hyper_ext::Builder::build_connector_cache`](crate::hyper_ext::Builder::build_connector_cache).
+#[derive(Clone)]
+pub struct ConnectorCache {
+    make_connector: Arc<MakeVersionedConnectorFn>,
+    connectors: Arc<Mutex<HashMap<HttpVersionList, DynConnector>>>,
+}
+
+impl Debug for ConnectorCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let versions: Vec<_> = self.connectors.lock().unwrap().keys().cloned().collect();
+        f.debug_struct("ConnectorCache")
+            .field("cached_versions", &versions)
+            .finish()
+    }
+}
+
+impl ConnectorCache {
+    /// Create a cache that uses `make_connector` to create the connector for a version list
+    pub fn new(
+        make_connector: impl Fn(&HttpVersionList) -> DynConnector + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            make_connector: Arc::new(make_connector),
+            connectors: Default::default(),
+        }
+    }
+
+    /// Return the connector for requests that want `versions`, creating it if necessary
+    pub fn connector(&self, versions: &HttpVersionList) -> DynConnector {
+        let mut connectors = self.connectors.lock().unwrap();
+        if let Some(connector) = connectors.get(versions) {
+            return connector.clone();
+        }
+        let connector = (self.make_connector)(versions);
+        connectors.insert(versions.clone(), connector.clone());
+        connector
+    }
+}
+
+impl Service<http::Request<SdkBody>> for ConnectorCache {
+    type Response = http::Response<SdkBody>;
+    type Error = ConnectorError;
+    type Future = tower::util::Oneshot<DynConnector, http::Request<SdkBody>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // readiness is checked on the selected connector when the request is sent
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<SdkBody>) -> Self::Future {
+        let connector = match req.extensions().get::<HttpVersionList>() {
+            Some(versions) => self.connector(versions),
+            None => self.connector(&DEFAULT_HTTP_VERSION_LIST),
+        };
+        connector.oneshot(req)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ConnectorCache;
+    use crate::erase::DynConnector;
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::http_versions::HttpVersionList;
+    use aws_smithy_http::result::ConnectorError;
+    use http::Version;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tower::{service_fn, ServiceExt};
+
+    /// Creates a cache whose connectors respond with the versions they were created for
+    fn cache(created: Arc<AtomicUsize>) -> ConnectorCache {
+        ConnectorCache::new(move |versions| {
+            created.fetch_add(1, Ordering::SeqCst);
+            let body = format!("{:?}", versions);
+            DynConnector::new(service_fn(move |_req: http::Request<SdkBody>| {
+                let body = body.clone();
+                async move { Ok::<_, ConnectorError>(http::Response::new(SdkBody::from(body))) }
+            }))
+        })
+    }
+
+    async fn send(cache: &ConnectorCache, versions: Option<HttpVersionList>) -> String {
+        let mut req = http::Request::new(SdkBody::empty());
+        if let Some(versions) = versions {
+            req.extensions_mut().insert(versions);
+        }
+        let resp = cache.clone().oneshot(req).await.unwrap();
+        String::from_utf8(resp.body().bytes().unwrap().to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn connectors_are_created_once_per_version_list() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let cache = cache(created.clone());
+
+        assert_eq!("[HTTP/1.1]", send(&cache, None).await);
+        assert_eq!(
+            "[HTTP/1.1]",
+            send(&cache, Some(vec![Version::HTTP_11])).await
+        );
+        assert_eq!(1, created.load(Ordering::SeqCst));
+
+        assert_eq!(
+            "[HTTP/2.0]",
+            send(&cache, Some(vec![Version::HTTP_2])).await
+        );
+        assert_eq!(
+            "[HTTP/2.0]",
+            send(&cache, Some(vec![Version::HTTP_2])).await
+        );
+        assert_eq!(2, created.load(Ordering::SeqCst));
+    }
 }
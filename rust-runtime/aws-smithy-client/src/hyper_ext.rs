@@ -38,6 +38,31 @@
 //! // once you have a connector, use it to construct a Smithy client:
 //! let client = Client::<DynConnector, MyMiddleware>::new(DynConnector::new(connector));
 //! ```
+//!
+//! ### HTTP/2
+//! The rustls connector negotiates HTTP/2 with servers that support it via ALPN. Operations that
+//! can only be sent over HTTP/2, like bidirectional event streams, need a connector that never
+//! falls back to HTTP/1.1. [`Builder::build_connector_cache`] creates a connector that picks a
+//! suitably configured client based on the HTTP versions each operation declares:
+//! ```no_run
+//! use aws_smithy_client::{Client, conns, hyper_ext};
+//! use aws_smithy_client::erase::DynConnector;
+//!
+//! let connector = hyper_ext::Adapter::builder().build_connector_cache(conns::https());
+//! // Replace this with your middleware
+//! type MyMiddleware = tower::layer::util::Identity;
+//! let client = Client::<DynConnector, MyMiddleware>::new(DynConnector::new(connector));
+//! ```
+//!
+//! To talk HTTP/2 to a server without TLS (h2c), e.g. a local stand-in for a service, enable
+//! [prior knowledge](Builder::http2_prior_knowledge):
+//! ```no_run
+//! use aws_smithy_client::hyper_ext;
+//!
+//! let connector = hyper_ext::Adapter::builder()
+//!     .http2_prior_knowledge(true)
+//!     .build(hyper::client::HttpConnector::new());
+//! ```
 
 use std::error::Error;
 use std::sync::Arc;
@@ -50,15 +75,19 @@ use tower::{BoxError, Service};
 use aws_smithy_async::future::timeout::TimedOutError;
 use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep};
 use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::http_versions::requires_http2;
 use aws_smithy_http::result::ConnectorError;
-use aws_smithy_types::retry::ErrorKind;
 use aws_smithy_types::timeout;
 use aws_smithy_types::tristate::TriState;
 
 use crate::erase::DynConnector;
+use crate::http_connector::ConnectorCache;
 use crate::never::stream::EmptyStream;
+use crate::pool::PoolConfig;
+use crate::tls::{TlsConfig, TlsConfigError};
 use crate::Builder as ClientBuilder;
 
+use self::pool_metrics::{ConnectionUse, MeteredConnector};
 use self::timeout_middleware::{ConnectTimeout, HttpReadTimeout, HttpTimeoutError};
 
 /// Adapter from a [`hyper::Client`](hyper::Client) to a connector usable by a Smithy [`Client`](crate::Client).
@@ -67,7 +96,7 @@ use self::timeout_middleware::{ConnectTimeout, HttpReadTimeout, HttpTimeoutError
 /// see [the module documentation](crate::hyper_ext).
 #[derive(Clone, Debug)]
 #[non_exhaustive]
-pub struct Adapter<C>(HttpReadTimeout<hyper::Client<ConnectTimeout<C>, SdkBody>>);
+pub struct Adapter<C>(HttpReadTimeout<hyper::Client<ConnectTimeout<MeteredConnector<C>>, SdkBody>>);
 
 impl<C> Service<http::Request<SdkBody>> for Adapter<C>
 where
@@ -94,7 +123,13 @@ where
 
     fn call(&mut self, req: http::Request<SdkBody>) -> Self::Future {
         let fut = self.0.call(req);
-        Box::pin(async move { Ok(fut.await.map_err(downcast_error)?.map(SdkBody::from)) })
+        Box::pin(async move {
+            let mut resp = fut.await.map_err(downcast_error)?;
+            if let Some(connection) = resp.extensions_mut().remove::<ConnectionUse>() {
+                connection.record_response();
+            }
+            Ok(resp.map(SdkBody::from))
+        })
     }
 }
 
@@ -136,13 +171,17 @@ fn to_connector_error(err: hyper::Error) -> ConnectorError {
         ConnectorError::timeout(err.into())
     } else if err.is_user() {
         ConnectorError::user(err.into())
-    } else if err.is_closed() || err.is_canceled() || find_source::<std::io::Error>(&err).is_some()
+    }
+    // An incomplete message means the server closed the connection before sending a complete
+    // response. We sometimes receive this from S3, and it's what sending a request over a pooled
+    // connection that the server has already closed looks like. Classify it like other connection
+    // failures so that it's retried.
+    else if err.is_closed()
+        || err.is_canceled()
+        || err.is_incomplete_message()
+        || find_source::<std::io::Error>(&err).is_some()
     {
         ConnectorError::io(err.into())
-    }
-    // We sometimes receive this from S3: hyper::Error(IncompleteMessage)
-    else if err.is_incomplete_message() {
-        ConnectorError::other(err.into(), Some(ErrorKind::TransientError))
     } else {
         tracing::warn!(err = ?err, "unrecognized error from Hyper. If this error should be retried, please file an issue.");
         ConnectorError::other(err.into(), None)
@@ -160,7 +199,7 @@ fn find_source<'a, E: Error + 'static>(err: &'a (dyn Error + 'static)) -> Option
     None
 }
 
-#[derive(Default, Debug)]
+#[derive(Clone, Default, Debug)]
 /// Builder for [`hyper_ext::Adapter`](Adapter)
 ///
 /// Unlike a Smithy client, the [`tower::Service`] inside a [`hyper_ext::Adapter`](Adapter) is actually a service that
@@ -185,6 +224,9 @@ pub struct Builder {
     http_timeout_config: timeout::Http,
     sleep: Option<Arc<dyn AsyncSleep>>,
     client_builder: hyper::client::Builder,
+    tls_config: TlsConfig,
+    http2_prior_knowledge: bool,
+    pool_config: PoolConfig,
 }
 
 impl Builder {
@@ -197,8 +239,9 @@ impl Builder {
         C::Future: Unpin + Send + 'static,
         C::Error: Into<BoxError>,
     {
-        // if we are using Hyper, Tokio must already be enabled so we can fallback to Tokio.
+        // fall back to the sleep implementation of whichever runtime feature is enabled
         let sleep = self.sleep.or_else(default_async_sleep);
+        let connector = MeteredConnector::new(connector, self.pool_config.metrics().cloned());
         let connector = match self.http_timeout_config.connect_timeout() {
             TriState::Set(duration) => ConnectTimeout::new(
                 connector,
@@ -210,7 +253,23 @@ impl Builder {
             // Some day, we could provide a default timeout if none is set. Today is not that day.
             TriState::Unset | TriState::Disabled => ConnectTimeout::no_timeout(connector),
         };
-        let base = self.client_builder.build(connector);
+        let mut client_builder = self.client_builder;
+        if self.http2_prior_knowledge {
+            client_builder.http2_only(true);
+        }
+        match self.pool_config.idle_timeout() {
+            TriState::Set(duration) => {
+                client_builder.pool_idle_timeout(duration);
+            }
+            TriState::Disabled => {
+                client_builder.pool_idle_timeout(None);
+            }
+            TriState::Unset => {}
+        }
+        if let Some(max_idle_per_host) = self.pool_config.max_idle_per_host() {
+            client_builder.pool_max_idle_per_host(max_idle_per_host);
+        }
+        let base = client_builder.build(connector);
         let http_timeout = match self.http_timeout_config.read_timeout() {
             TriState::Set(duration) => HttpReadTimeout::new(
                 base,
@@ -225,6 +284,48 @@ impl Builder {
         Adapter(http_timeout)
     }
 
+    /// Create a HyperAdapter from this builder with a rustls HTTPS connector
+    ///
+    /// The connector uses the TLS configuration set with [`tls_config`](Builder::tls_config).
+    #[cfg(feature = "rustls")]
+    pub fn build_https(self) -> Result<crate::conns::Rustls, TlsConfigError> {
+        let connector = crate::conns::https_with_tls_config(&self.tls_config)?;
+        Ok(self.build(connector))
+    }
+
+    /// Create a HyperAdapter from this builder with a native-tls HTTPS connector
+    ///
+    /// The connector uses the TLS configuration set with [`tls_config`](Builder::tls_config).
+    #[cfg(feature = "native-tls")]
+    pub fn build_native_tls(self) -> Result<Adapter<crate::conns::NativeTls>, TlsConfigError> {
+        let connector = crate::conns::native_tls_with_tls_config(&self.tls_config)?;
+        Ok(self.build(connector))
+    }
+
+    /// Create a [`ConnectorCache`] from this builder and a given connector
+    ///
+    /// The cache builds one HyperAdapter per list of HTTP versions that requests want to use.
+    /// Requests that can only be sent over HTTP/2 use an adapter that only speaks HTTP/2, all
+    /// other requests use an adapter that negotiates the version with the server.
+    pub fn build_connector_cache<C>(self, connector: C) -> ConnectorCache
+    where
+        C: Clone + Send + Sync + 'static,
+        C: tower::Service<Uri>,
+        C::Response: Connection + AsyncRead + AsyncWrite + Send + Unpin + 'static,
+        C::Future: Unpin + Send + 'static,
+        C::Error: Into<BoxError>,
+    {
+        ConnectorCache::new(move |versions| {
+            let builder = self.clone();
+            let builder = if requires_http2(versions) {
+                builder.http2_prior_knowledge(true)
+            } else {
+                builder
+            };
+            DynConnector::new(builder.build(connector.clone()))
+        })
+    }
+
     /// Set the async sleep implementation used for timeouts
     ///
     /// Calling this is only necessary for testing or to use something other than
@@ -246,6 +347,39 @@ impl Builder {
         }
     }
 
+    /// Configure TLS for the connectors built by [`build_https`](Builder::build_https) and
+    /// `build_native_tls`
+    ///
+    /// This has no effect on connectors passed to [`build`](Builder::build).
+    pub fn tls_config(self, tls_config: &TlsConfig) -> Self {
+        Self {
+            tls_config: tls_config.clone(),
+            ..self
+        }
+    }
+
+    /// Configure the connection pool of the HyperAdapter
+    ///
+    /// The pool settings take precedence over the ones of a client builder set with
+    /// [`hyper_builder`](Builder::hyper_builder).
+    pub fn pool_config(self, pool_config: &PoolConfig) -> Self {
+        Self {
+            pool_config: pool_config.clone(),
+            ..self
+        }
+    }
+
+    /// Only use HTTP/2, without negotiating the version with the server first
+    ///
+    /// This is required to talk HTTP/2 to servers that don't use TLS (h2c). Servers that don't
+    /// support HTTP/2 will fail every request. Defaults to `false`.
+    pub fn http2_prior_knowledge(self, http2_prior_knowledge: bool) -> Self {
+        Self {
+            http2_prior_knowledge,
+            ..self
+        }
+    }
+
     /// Override the Hyper client [`Builder`](hyper::client::Builder) used to construct this client.
     ///
     /// This enables changing settings like forcing HTTP2 and modifying other default client behavior.
@@ -326,6 +460,221 @@ impl<M, R> ClientBuilder<(), M, R> {
     }
 }
 
+mod pool_metrics {
+    use std::future::Future;
+    use std::io;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+
+    use http::Uri;
+    use hyper::client::connect::{Connected, Connection};
+    use pin_project_lite::pin_project;
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    use crate::pool::PoolMetrics;
+
+    /// Connector wrapper that records connections in [`PoolMetrics`]
+    #[derive(Clone, Debug)]
+    pub(super) struct MeteredConnector<I> {
+        inner: I,
+        metrics: Option<PoolMetrics>,
+    }
+
+    impl<I> MeteredConnector<I> {
+        pub(super) fn new(inner: I, metrics: Option<PoolMetrics>) -> Self {
+            Self { inner, metrics }
+        }
+    }
+
+    impl<I> tower::Service<Uri> for MeteredConnector<I>
+    where
+        I: tower::Service<Uri>,
+    {
+        type Response = MeteredConnection<I::Response>;
+        type Error = I::Error;
+        type Future = MeteredConnectFuture<I::Future>;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.inner.poll_ready(cx)
+        }
+
+        fn call(&mut self, req: Uri) -> Self::Future {
+            MeteredConnectFuture {
+                future: self.inner.call(req),
+                metrics: self.metrics.clone(),
+            }
+        }
+    }
+
+    pin_project! {
+        pub(super) struct MeteredConnectFuture<F> {
+            #[pin]
+            future: F,
+            metrics: Option<PoolMetrics>,
+        }
+    }
+
+    impl<F, T, E> Future for MeteredConnectFuture<F>
+    where
+        F: Future<Output = Result<T, E>>,
+    {
+        type Output = Result<MeteredConnection<T>, E>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let this = self.project();
+            this.future.poll(cx).map_ok(|inner| {
+                let guard = this.metrics.take().map(|metrics| {
+                    metrics.record_opened();
+                    ConnectionGuard(Arc::new(ConnectionState {
+                        metrics,
+                        used: AtomicBool::new(false),
+                        closed_by_peer: AtomicBool::new(false),
+                    }))
+                });
+                MeteredConnection { inner, guard }
+            })
+        }
+    }
+
+    #[derive(Debug)]
+    struct ConnectionState {
+        metrics: PoolMetrics,
+        used: AtomicBool,
+        closed_by_peer: AtomicBool,
+    }
+
+    /// Records the connection as closed when it is dropped
+    #[derive(Debug)]
+    struct ConnectionGuard(Arc<ConnectionState>);
+
+    impl Drop for ConnectionGuard {
+        fn drop(&mut self) {
+            self.0
+                .metrics
+                .record_closed(self.0.closed_by_peer.load(Ordering::Relaxed));
+        }
+    }
+
+    /// Added to the extensions of every response received over a metered connection
+    ///
+    /// Hyper copies the extras of a connection into each of its responses, which is how a
+    /// response is attributed to a connection to detect reuse.
+    #[derive(Clone, Debug)]
+    pub(super) struct ConnectionUse(Arc<ConnectionState>);
+
+    impl ConnectionUse {
+        pub(super) fn record_response(&self) {
+            if self.0.used.swap(true, Ordering::Relaxed) {
+                self.0.metrics.record_reused();
+            }
+        }
+    }
+
+    pin_project! {
+        /// A connection that tracks whether it was closed by the peer
+        pub(super) struct MeteredConnection<T> {
+            #[pin]
+            inner: T,
+            guard: Option<ConnectionGuard>,
+        }
+    }
+
+    impl<T> MeteredConnection<T> {
+        fn record_result<R>(guard: &Option<ConnectionGuard>, result: &io::Result<R>) {
+            if let (Some(guard), Err(err)) = (guard, result) {
+                if is_closed_by_peer(err) {
+                    guard.0.closed_by_peer.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    /// Returns true if `err` means that the peer closed the connection
+    pub(super) fn is_closed_by_peer(err: &io::Error) -> bool {
+        matches!(
+            err.kind(),
+            io::ErrorKind::ConnectionReset
+                | io::ErrorKind::ConnectionAborted
+                | io::ErrorKind::BrokenPipe
+                | io::ErrorKind::UnexpectedEof
+        )
+    }
+
+    impl<T: Connection> Connection for MeteredConnection<T> {
+        fn connected(&self) -> Connected {
+            let connected = self.inner.connected();
+            match &self.guard {
+                Some(guard) => connected.extra(ConnectionUse(guard.0.clone())),
+                None => connected,
+            }
+        }
+    }
+
+    impl<T: AsyncRead> AsyncRead for MeteredConnection<T> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            let this = self.project();
+            let filled = buf.filled().len();
+            let result = this.inner.poll_read(cx, buf);
+            if let Poll::Ready(result) = &result {
+                Self::record_result(this.guard, result);
+                // reading nothing into a non-empty buffer means the peer closed the connection
+                if let (Ok(()), Some(guard)) = (result, this.guard.as_ref()) {
+                    if buf.filled().len() == filled && buf.remaining() > 0 {
+                        guard.0.closed_by_peer.store(true, Ordering::Relaxed);
+                    }
+                }
+            }
+            result
+        }
+    }
+
+    impl<T: AsyncWrite> AsyncWrite for MeteredConnection<T> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            let this = self.project();
+            let result = this.inner.poll_write(cx, buf);
+            if let Poll::Ready(result) = &result {
+                Self::record_result(this.guard, result);
+            }
+            result
+        }
+
+        fn poll_write_vectored(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bufs: &[io::IoSlice<'_>],
+        ) -> Poll<io::Result<usize>> {
+            let this = self.project();
+            let result = this.inner.poll_write_vectored(cx, bufs);
+            if let Poll::Ready(result) = &result {
+                Self::record_result(this.guard, result);
+            }
+            result
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            self.inner.is_write_vectored()
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.project().inner.poll_flush(cx)
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.project().inner.poll_shutdown(cx)
+        }
+    }
+}
+
 mod timeout_middleware {
     use std::error::Error;
     use std::fmt::Formatter;
@@ -639,10 +988,13 @@ mod test {
     use tower::BoxError;
 
     use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::http_versions::HttpVersionList;
+    use tower::ServiceExt;
 
     use super::ClientBuilder;
     use crate::erase::DynConnector;
     use crate::hyper_ext::Adapter;
+    use crate::pool::{PoolConfig, PoolMetrics};
 
     #[test]
     fn builder_connection_helpers_are_dyn() {
@@ -671,6 +1023,177 @@ mod test {
         assert!(err.is_io(), "{:?}", err);
     }
 
+    /// Starts a server that responds with the request's HTTP version
+    ///
+    /// With `http2_only`, it's an h2c server that only speaks HTTP/2.
+    async fn test_server(http2_only: bool) -> Uri {
+        let make_service = hyper::service::make_service_fn(|_| async {
+            Ok::<_, std::convert::Infallible>(hyper::service::service_fn(
+                |req: http::Request<hyper::Body>| async move {
+                    Ok::<_, std::convert::Infallible>(http::Response::new(hyper::Body::from(
+                        format!("{:?}", req.version()),
+                    )))
+                },
+            ))
+        });
+        let server = hyper::Server::bind(&([127, 0, 0, 1], 0).into())
+            .http2_only(http2_only)
+            .serve(make_service);
+        let uri = format!("http://{}", server.local_addr()).parse().unwrap();
+        tokio::spawn(server);
+        uri
+    }
+
+    fn request(uri: &Uri, versions: Option<HttpVersionList>) -> http::Request<SdkBody> {
+        let mut req = http::Request::builder()
+            .uri(uri)
+            .body(SdkBody::empty())
+            .unwrap();
+        if let Some(versions) = versions {
+            req.extensions_mut().insert(versions);
+        }
+        req
+    }
+
+    #[tokio::test]
+    async fn http2_prior_knowledge() {
+        let uri = test_server(true).await;
+        let adapter = Adapter::builder()
+            .http2_prior_knowledge(true)
+            .build(hyper::client::HttpConnector::new());
+        let resp = adapter.oneshot(request(&uri, None)).await.unwrap();
+        assert_eq!(http::Version::HTTP_2, resp.version());
+        let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+        assert_eq!("HTTP/2.0", body);
+    }
+
+    #[tokio::test]
+    async fn connector_cache_uses_http2_when_required() {
+        let uri = test_server(true).await;
+        let cache = Adapter::builder().build_connector_cache(hyper::client::HttpConnector::new());
+
+        let resp = cache
+            .clone()
+            .oneshot(request(&uri, Some(vec![http::Version::HTTP_2])))
+            .await
+            .unwrap();
+        assert_eq!(http::Version::HTTP_2, resp.version());
+
+        // requests that accept HTTP/1.1 don't assume the server speaks HTTP/2
+        cache
+            .oneshot(request(&uri, Some(vec![http::Version::HTTP_11])))
+            .await
+            .expect_err("the server only supports HTTP/2");
+    }
+
+    async fn send_and_read(adapter: &Adapter<hyper::client::HttpConnector>, uri: &Uri) {
+        let resp = adapter.clone().oneshot(request(uri, None)).await.unwrap();
+        // reading the whole body returns the connection to the pool
+        hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    }
+
+    /// Waits for connections closed in the background to be recorded
+    async fn wait_for_closed(metrics: &PoolMetrics, closed: usize) {
+        for _ in 0..100 {
+            if metrics.connections_closed() >= closed {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(closed, metrics.connections_closed());
+    }
+
+    #[tokio::test]
+    async fn pool_metrics() {
+        let uri = test_server(false).await;
+        let metrics = PoolMetrics::new();
+        let adapter = Adapter::builder()
+            .pool_config(&PoolConfig::new().with_metrics(metrics.clone()))
+            .build(hyper::client::HttpConnector::new());
+
+        send_and_read(&adapter, &uri).await;
+        send_and_read(&adapter, &uri).await;
+        assert_eq!(1, metrics.connections_opened());
+        assert_eq!(1, metrics.connections_reused());
+
+        drop(adapter);
+        wait_for_closed(&metrics, 1).await;
+        assert_eq!(0, metrics.connections_closed_by_peer());
+    }
+
+    #[tokio::test]
+    async fn pool_config_disables_reuse() {
+        let uri = test_server(false).await;
+        let metrics = PoolMetrics::new();
+        let pool_config = PoolConfig::new()
+            .with_max_idle_per_host(Some(0))
+            .with_metrics(metrics.clone());
+        let adapter = Adapter::builder()
+            .pool_config(&pool_config)
+            .build(hyper::client::HttpConnector::new());
+
+        send_and_read(&adapter, &uri).await;
+        send_and_read(&adapter, &uri).await;
+        assert_eq!(2, metrics.connections_opened());
+        assert_eq!(0, metrics.connections_reused());
+        wait_for_closed(&metrics, 2).await;
+    }
+
+    #[tokio::test]
+    async fn connections_closed_by_peer_are_retryable() {
+        let metrics = PoolMetrics::new();
+        let adapter = Adapter::builder()
+            .pool_config(&PoolConfig::new().with_metrics(metrics.clone()))
+            .build(TestConnection {
+                inner: ClosedStream,
+            });
+        let err = adapter
+            .oneshot(request(&"http://amazon.com".parse().unwrap(), None))
+            .await
+            .expect_err("the connection is closed");
+        assert!(err.is_io(), "{:?}", err);
+        wait_for_closed(&metrics, 1).await;
+        assert_eq!(1, metrics.connections_closed_by_peer());
+    }
+
+    // ---- machinery to make a Hyper connector whose connection was closed by the server
+    #[derive(Clone)]
+    struct ClosedStream;
+
+    impl Connection for ClosedStream {
+        fn connected(&self) -> Connected {
+            Connected::new()
+        }
+    }
+
+    impl AsyncRead for ClosedStream {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            _buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl AsyncWrite for ClosedStream {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<Result<usize, Error>> {
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
     // ---- machinery to make a Hyper connector that responds with an IO Error
     #[derive(Clone)]
     struct HangupStream;
@@ -0,0 +1,467 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Hyper connectors for async runtimes other than Tokio
+//!
+//! Hyper itself is runtime agnostic, but the connectors in [`conns`](crate::conns) open their sockets
+//! with Tokio and rely on a Tokio runtime being present. The `rt-async-std` and `rt-smol` features
+//! provide an [`HttpsConnector`] and a Hyper executor for async-std and smol respectively, so that a
+//! Smithy client can run without ever starting a Tokio runtime. TLS is provided by `rustls` with the
+//! platform's native root certificates, unless a [`TlsConfig`] is applied with
+//! [`HttpsConnector::with_tls_config`].
+//!
+//! # Examples
+//! ### Construct a Smithy client that runs on async-std
+//! ```no_run
+//! # #[cfg(feature = "rt-async-std")]
+//! # fn example() {
+//! use aws_smithy_client::Builder;
+//!
+//! // Replace this with your middleware type
+//! type MyMiddleware = tower::layer::util::Identity;
+//! let client = Builder::new()
+//!     .async_std_https()
+//!     .middleware(MyMiddleware::new())
+//!     // With `rt-tokio` disabled, this picks the async-std sleep implementation
+//!     .default_async_sleep()
+//!     .build();
+//! # }
+//! ```
+//!
+//! ### Create a smol connector with a custom timeout
+//! ```no_run
+//! # #[cfg(feature = "rt-smol")]
+//! # fn example() {
+//! use std::time::Duration;
+//! use aws_smithy_async::rt::sleep::SmolSleep;
+//! use aws_smithy_client::{hyper_ext, hyper_rt};
+//! use aws_smithy_types::timeout;
+//!
+//! let timeout = timeout::Http::new().with_connect_timeout(Some(Duration::from_secs(1)).into());
+//! let connector = hyper_ext::Adapter::builder()
+//!     .hyper_builder(hyper_rt::smol_hyper_builder())
+//!     .sleep_impl(SmolSleep::new())
+//!     .timeout(&timeout)
+//!     .build(hyper_rt::HttpsConnector::smol());
+//! # }
+//! ```
+
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_io::{AsyncRead as FuturesRead, AsyncWrite as FuturesWrite};
+use futures_rustls::rustls::Session;
+use http::Uri;
+use hyper::client::connect::{Connected, Connection};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+#[cfg(feature = "rt-async-std")]
+use aws_smithy_async::rt::sleep::AsyncStdSleep;
+#[cfg(feature = "rt-smol")]
+use aws_smithy_async::rt::sleep::SmolSleep;
+
+use crate::erase::DynConnector;
+use crate::hyper_ext::Adapter;
+use crate::tls::{TlsConfig, TlsConfigError};
+use crate::Builder as ClientBuilder;
+
+/// Socket that a runtime's TCP stream or a TLS stream over it can be stored as
+trait Io: FuturesRead + FuturesWrite + Send + Unpin {}
+
+impl<T> Io for T where T: FuturesRead + FuturesWrite + Send + Unpin {}
+
+type ConnectFuture = Pin<Box<dyn Future<Output = io::Result<Box<dyn Io>>> + Send>>;
+
+lazy_static::lazy_static! {
+    // Loading the native roots is slow on some platforms, so share them between connectors
+    static ref TLS_NATIVE_ROOTS: futures_rustls::TlsConnector = {
+        let config = TlsConfig::new()
+            .rustls_client_config()
+            .unwrap_or_else(|err| panic!("cannot access native cert store: {}", err));
+        Arc::new(config).into()
+    };
+}
+
+/// Connector that opens HTTP and HTTPS connections with a non-Tokio async runtime
+///
+/// This is the `Uri` to stream service that a [`hyper_ext::Adapter`](crate::hyper_ext::Adapter)
+/// is built from. Cloning it is cheap.
+#[derive(Clone)]
+pub struct HttpsConnector {
+    runtime: &'static str,
+    connect_tcp: fn(String, u16) -> ConnectFuture,
+    tls: futures_rustls::TlsConnector,
+}
+
+impl Debug for HttpsConnector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpsConnector")
+            .field("runtime", &self.runtime)
+            .finish()
+    }
+}
+
+impl HttpsConnector {
+    /// Creates a connector that opens sockets with async-std
+    #[cfg(feature = "rt-async-std")]
+    pub fn async_std() -> Self {
+        Self {
+            runtime: "async-std",
+            connect_tcp: |host, port| {
+                Box::pin(async move {
+                    let stream = async_std::net::TcpStream::connect((host.as_str(), port)).await?;
+                    stream.set_nodelay(true)?;
+                    Ok(Box::new(stream) as Box<dyn Io>)
+                })
+            },
+            tls: TLS_NATIVE_ROOTS.clone(),
+        }
+    }
+
+    /// Creates a connector that opens sockets with smol
+    #[cfg(feature = "rt-smol")]
+    pub fn smol() -> Self {
+        Self {
+            runtime: "smol",
+            connect_tcp: |host, port| {
+                Box::pin(async move {
+                    let stream = smol::net::TcpStream::connect((host.as_str(), port)).await?;
+                    stream.set_nodelay(true)?;
+                    Ok(Box::new(stream) as Box<dyn Io>)
+                })
+            },
+            tls: TLS_NATIVE_ROOTS.clone(),
+        }
+    }
+
+    /// Use `tls_config` for HTTPS connections
+    ///
+    /// Like the Tokio connectors, HTTP/2 and HTTP/1.1 are offered with ALPN. Default
+    /// configurations keep the shared connector that trusts the platform's root certificates.
+    pub fn with_tls_config(mut self, tls_config: &TlsConfig) -> Result<Self, TlsConfigError> {
+        if !tls_config.is_default() {
+            self.tls = Arc::new(tls_config.rustls_client_config()?).into();
+        }
+        Ok(self)
+    }
+}
+
+impl tower::Service<Uri> for HttpsConnector {
+    type Response = ConnectedStream;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<ConnectedStream>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let connect_tcp = self.connect_tcp;
+        let tls = self.tls.clone();
+        Box::pin(async move {
+            let invalid_uri = |reason: &str| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("cannot connect to `{}`: {}", uri, reason),
+                )
+            };
+            let https = match uri.scheme_str() {
+                Some("https") => true,
+                Some("http") => false,
+                _ => return Err(invalid_uri("scheme must be `http` or `https`")),
+            };
+            let host = uri
+                .host()
+                .ok_or_else(|| invalid_uri("missing host"))?
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_string();
+            let port = uri.port_u16().unwrap_or(if https { 443 } else { 80 });
+            let stream = connect_tcp(host.clone(), port).await?;
+            if !https {
+                return Ok(ConnectedStream {
+                    io: stream,
+                    negotiated_h2: false,
+                });
+            }
+            let dns_name = webpki::DNSNameRef::try_from_ascii_str(&host)
+                .map_err(|_| invalid_uri("host is not a valid DNS name"))?;
+            let stream = tls.connect(dns_name, stream).await?;
+            let negotiated_h2 = stream.get_ref().1.get_alpn_protocol() == Some(b"h2");
+            Ok(ConnectedStream {
+                io: Box::new(stream),
+                negotiated_h2,
+            })
+        })
+    }
+}
+
+/// Connection opened by an [`HttpsConnector`]
+pub struct ConnectedStream {
+    io: Box<dyn Io>,
+    negotiated_h2: bool,
+}
+
+impl Debug for ConnectedStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ConnectedStream")
+    }
+}
+
+impl Connection for ConnectedStream {
+    fn connected(&self) -> Connected {
+        if self.negotiated_h2 {
+            Connected::new().negotiated_h2()
+        } else {
+            Connected::new()
+        }
+    }
+}
+
+impl AsyncRead for ConnectedStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let read = Pin::new(&mut self.io).poll_read(cx, buf.initialize_unfilled());
+        match read {
+            Poll::Ready(Ok(len)) => {
+                buf.advance(len);
+                Poll::Ready(Ok(()))
+            }
+            Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl AsyncWrite for ConnectedStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.io).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_close(cx)
+    }
+}
+
+/// Hyper executor that spawns background connection tasks onto async-std
+#[cfg(feature = "rt-async-std")]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AsyncStdExecutor;
+
+#[cfg(feature = "rt-async-std")]
+impl<F> hyper::rt::Executor<F> for AsyncStdExecutor
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    fn execute(&self, future: F) {
+        async_std::task::spawn(future);
+    }
+}
+
+/// Hyper executor that spawns background connection tasks onto smol's global executor
+#[cfg(feature = "rt-smol")]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SmolExecutor;
+
+#[cfg(feature = "rt-smol")]
+impl<F> hyper::rt::Executor<F> for SmolExecutor
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    fn execute(&self, future: F) {
+        smol::spawn(future).detach();
+    }
+}
+
+/// Returns a Hyper client builder that spawns its tasks onto async-std
+///
+/// Idle connections are never expired, since Hyper's idle connection reaper requires Tokio.
+#[cfg(feature = "rt-async-std")]
+pub fn async_std_hyper_builder() -> hyper::client::Builder {
+    let mut builder = hyper::Client::builder();
+    builder.executor(AsyncStdExecutor).pool_idle_timeout(None);
+    builder
+}
+
+/// Returns a Hyper client builder that spawns its tasks onto smol
+///
+/// Idle connections are never expired, since Hyper's idle connection reaper requires Tokio.
+#[cfg(feature = "rt-smol")]
+pub fn smol_hyper_builder() -> hyper::client::Builder {
+    let mut builder = hyper::Client::builder();
+    builder.executor(SmolExecutor).pool_idle_timeout(None);
+    builder
+}
+
+/// Returns an HTTPS connector that runs on async-std
+#[cfg(feature = "rt-async-std")]
+pub fn async_std_https() -> Adapter<HttpsConnector> {
+    Adapter::builder()
+        .hyper_builder(async_std_hyper_builder())
+        .sleep_impl(AsyncStdSleep::new())
+        .build(HttpsConnector::async_std())
+}
+
+/// Returns an HTTPS connector that runs on smol
+#[cfg(feature = "rt-smol")]
+pub fn smol_https() -> Adapter<HttpsConnector> {
+    Adapter::builder()
+        .hyper_builder(smol_hyper_builder())
+        .sleep_impl(SmolSleep::new())
+        .build(HttpsConnector::smol())
+}
+
+#[cfg(feature = "rt-async-std")]
+impl<M, R> ClientBuilder<(), M, R> {
+    /// Connect to the service over HTTPS with async-std, using dynamic dispatch.
+    ///
+    /// This doesn't set the client's sleep implementation. See [`hyper_rt`](crate::hyper_rt).
+    pub fn async_std_https(self) -> ClientBuilder<DynConnector, M, R> {
+        self.connector(DynConnector::new(async_std_https()))
+    }
+}
+
+#[cfg(feature = "rt-smol")]
+impl<M, R> ClientBuilder<(), M, R> {
+    /// Connect to the service over HTTPS with smol, using dynamic dispatch.
+    ///
+    /// This doesn't set the client's sleep implementation. See [`hyper_rt`](crate::hyper_rt).
+    pub fn smol_https(self) -> ClientBuilder<DynConnector, M, R> {
+        self.connector(DynConnector::new(smol_https()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use aws_smithy_http::body::SdkBody;
+    use tower::{Service, ServiceExt};
+
+    fn request(uri: &str) -> http::Request<SdkBody> {
+        http::Request::builder()
+            .uri(uri)
+            .body(SdkBody::empty())
+            .unwrap()
+    }
+
+    const RESPONSE: &[u8] = b"HTTP/1.1 200 OK\r\ncontent-length: 5\r\n\r\nhello";
+
+    #[cfg(feature = "rt-async-std")]
+    #[test]
+    fn async_std_round_trip() {
+        use async_std::io::{ReadExt, WriteExt};
+
+        async_std::task::block_on(async {
+            let listener = async_std::net::TcpListener::bind("127.0.0.1:0")
+                .await
+                .unwrap();
+            let addr = listener.local_addr().unwrap();
+            let server = async_std::task::spawn(async move {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = [0; 1024];
+                let len = stream.read(&mut request).await.unwrap();
+                stream.write_all(RESPONSE).await.unwrap();
+                String::from_utf8_lossy(&request[..len]).to_string()
+            });
+
+            let mut connector = super::async_std_https();
+            let response = connector
+                .ready()
+                .await
+                .unwrap()
+                .call(request(&format!("http://{}/greeting", addr)))
+                .await
+                .unwrap();
+            assert_eq!(200, response.status().as_u16());
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            assert_eq!("hello", body);
+            assert!(server.await.starts_with("GET /greeting HTTP/1.1\r\n"));
+        });
+    }
+
+    #[cfg(feature = "rt-smol")]
+    #[test]
+    fn smol_round_trip() {
+        use smol::io::{AsyncReadExt, AsyncWriteExt};
+
+        smol::block_on(async {
+            let listener = smol::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let server = smol::spawn(async move {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = [0; 1024];
+                let len = stream.read(&mut request).await.unwrap();
+                stream.write_all(RESPONSE).await.unwrap();
+                String::from_utf8_lossy(&request[..len]).to_string()
+            });
+
+            let mut connector = super::smol_https();
+            let response = connector
+                .ready()
+                .await
+                .unwrap()
+                .call(request(&format!("http://{}/greeting", addr)))
+                .await
+                .unwrap();
+            assert_eq!(200, response.status().as_u16());
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            assert_eq!("hello", body);
+            assert!(server.await.starts_with("GET /greeting HTTP/1.1\r\n"));
+        });
+    }
+
+    #[cfg(feature = "rt-smol")]
+    #[test]
+    fn unsupported_scheme() {
+        let err = smol::block_on(
+            super::HttpsConnector::smol().call("ftp://example.com".parse().unwrap()),
+        )
+        .expect_err("ftp is not supported");
+        assert_eq!(std::io::ErrorKind::InvalidInput, err.kind());
+        assert_eq!(
+            "cannot connect to `ftp://example.com/`: scheme must be `http` or `https`",
+            err.to_string()
+        );
+    }
+
+    #[cfg(feature = "rt-smol")]
+    #[test]
+    fn with_tls_config() {
+        use crate::tls::{Certificate, TlsConfig};
+
+        let err = super::HttpsConnector::smol()
+            .with_tls_config(&TlsConfig::new().with_native_roots(false))
+            .expect_err("a root certificate is required");
+        assert!(err.to_string().contains("no root certificates"), "{}", err);
+
+        super::HttpsConnector::smol()
+            .with_tls_config(
+                &TlsConfig::new()
+                    .with_native_roots(false)
+                    .with_root_certificate(Certificate::from_pem(
+                        &include_bytes!("../test-data/tls-test-cert.pem")[..],
+                    )),
+            )
+            .expect("valid config");
+    }
+}
@@ -0,0 +1,160 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Per-attempt [`Interceptor`] hooks
+//!
+//! Interceptors are registered on the [`Client`](crate::Client) or on an individual operation, see
+//! [`aws_smithy_http::interceptor`]. The client calls the hooks that run once per operation
+//! itself, [`InterceptorService`] calls the hooks that run around every attempt, and the
+//! dispatch service calls the hooks that run around transmitting a request.
+
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+pub use aws_smithy_http::interceptor::{Interceptor, Interceptors};
+
+use aws_smithy_http::operation::{Operation, Request};
+use aws_smithy_http::property_bag::SharedPropertyBag;
+use aws_smithy_http::result::{SdkError, SdkSuccess};
+use pin_project_lite::pin_project;
+use tower::Layer;
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+/// A service that calls the per-attempt hooks of the [`Interceptors`] registered in the property
+/// bag of an operation
+///
+/// [`modify_before_signing`](Interceptor::modify_before_signing) is called before the operation
+/// is passed to the inner service, and [`read_after_attempt`](Interceptor::read_after_attempt)
+/// once the inner service completed.
+#[derive(Clone, Debug)]
+pub struct InterceptorService<S> {
+    inner: S,
+}
+
+/// A layer that wraps services in an [`InterceptorService`]
+#[non_exhaustive]
+#[derive(Clone, Debug, Default)]
+pub struct InterceptorLayer;
+
+impl InterceptorLayer {
+    /// Create a new `InterceptorLayer`
+    pub fn new() -> Self {
+        InterceptorLayer
+    }
+}
+
+impl<S> Layer<S> for InterceptorLayer {
+    type Service = InterceptorService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        InterceptorService { inner }
+    }
+}
+
+pin_project! {
+    #[non_exhaustive]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    #[allow(missing_docs)]
+    #[project = InterceptorServiceFutureProj]
+    /// A future generated by an [`InterceptorService`]
+    pub enum InterceptorServiceFuture<F> {
+        /// The attempt was rejected by an interceptor before it was sent
+        Rejected {
+            error: Option<BoxError>,
+        },
+        /// The attempt is in progress and no interceptors are registered
+        NoInterceptors {
+            #[pin]
+            future: F,
+        },
+        /// The attempt is in progress
+        Attempt {
+            #[pin]
+            future: F,
+            interceptors: Interceptors,
+            properties: SharedPropertyBag,
+        },
+    }
+}
+
+impl<F, T, E> Future for InterceptorServiceFuture<F>
+where
+    F: Future<Output = Result<SdkSuccess<T>, SdkError<E>>>,
+{
+    type Output = Result<SdkSuccess<T>, SdkError<E>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project() {
+            InterceptorServiceFutureProj::Rejected { error } => Poll::Ready(Err(
+                SdkError::ConstructionFailure(error.take().expect("polled after completion")),
+            )),
+            InterceptorServiceFutureProj::NoInterceptors { future } => future.poll(cx),
+            InterceptorServiceFutureProj::Attempt {
+                future,
+                interceptors,
+                properties,
+            } => {
+                let result = match future.poll(cx) {
+                    Poll::Ready(result) => result,
+                    Poll::Pending => return Poll::Pending,
+                };
+                interceptors.read_after_attempt(raw_response(&result), &properties.acquire());
+                Poll::Ready(result)
+            }
+        }
+    }
+}
+
+/// The HTTP response of an operation's result, if a response was received
+pub(crate) fn raw_response<T, E>(
+    result: &Result<SdkSuccess<T>, SdkError<E>>,
+) -> Option<&http::Response<aws_smithy_http::body::SdkBody>> {
+    match result {
+        Ok(success) => Some(success.raw.http()),
+        Err(SdkError::ResponseError { raw, .. }) | Err(SdkError::ServiceError { raw, .. }) => {
+            Some(raw.http())
+        }
+        Err(_) => None,
+    }
+}
+
+impl<H, R, S, T, E> tower::Service<Operation<H, R>> for InterceptorService<S>
+where
+    S: tower::Service<Operation<H, R>, Response = SdkSuccess<T>, Error = SdkError<E>>,
+{
+    type Response = SdkSuccess<T>;
+    type Error = SdkError<E>;
+    type Future = InterceptorServiceFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Operation<H, R>) -> Self::Future {
+        let interceptors = Interceptors::from_properties(&req.properties());
+        if interceptors.is_empty() {
+            return InterceptorServiceFuture::NoInterceptors {
+                future: self.inner.call(req),
+            };
+        }
+        let (request, parts) = req.into_request_response();
+        let (mut request, properties) = request.into_parts();
+        let modified =
+            interceptors.modify_before_signing(&mut request, &mut properties.acquire_mut());
+        if let Err(error) = modified {
+            interceptors.read_after_attempt(None, &properties.acquire());
+            return InterceptorServiceFuture::Rejected { error: Some(error) };
+        }
+        let req = Operation::from_parts(Request::from_parts(request, properties.clone()), parts);
+        InterceptorServiceFuture::Attempt {
+            future: self.inner.call(req),
+            interceptors,
+            properties,
+        }
+    }
+}
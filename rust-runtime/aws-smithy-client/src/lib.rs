@@ -9,6 +9,8 @@
 //! |-------------------|-------------|
 //! | `event-stream`    | Provides Sender/Receiver implementations for Event Stream codegen. |
 //! | `rt-tokio`        | Run async code with the `tokio` runtime |
+//! | `rt-async-std`    | Run async code with the `async-std` runtime, and connect with [`hyper_rt`] |
+//! | `rt-smol`         | Run async code with the `smol` runtime, and connect with [`hyper_rt`] |
 //! | `test-util`       | Include various testing utils |
 //! | `native-tls`      | Use `native-tls` as the HTTP client's TLS implementation |
 //! | `rustls`          | Use `rustls` as the HTTP client's TLS implementation |
@@ -36,10 +38,14 @@ pub mod dvr;
 pub mod test_connection;
 
 pub mod http_connector;
+pub mod interceptor;
 
 #[cfg(feature = "client-hyper")]
 pub mod hyper_ext;
 
+#[cfg(any(feature = "rt-async-std", feature = "rt-smol"))]
+pub mod hyper_rt;
+
 // The types in this module are only used to write the bounds in [`Client::check`]. Customers will
 // not need them. But the module and its types must be public so that we can call `check` from
 // doc-tests.
@@ -47,7 +53,9 @@ pub mod hyper_ext;
 pub mod static_tests;
 
 pub mod never;
+pub mod pool;
 pub mod timeout;
+pub mod tls;
 pub use timeout::TimeoutLayer;
 
 /// Type aliases for standard connection types.
@@ -71,11 +79,40 @@ pub mod conns {
         HTTPS_NATIVE_ROOTS.clone()
     }
 
+    /// Create a rustls HTTPS connector that uses `tls_config`
+    ///
+    /// Default configurations reuse the cached connector returned by [`https`].
+    #[cfg(feature = "rustls")]
+    pub fn https_with_tls_config(
+        tls_config: &crate::tls::TlsConfig,
+    ) -> Result<Https, crate::tls::TlsConfigError> {
+        if tls_config.is_default() {
+            return Ok(https());
+        }
+        let mut http = hyper::client::HttpConnector::new();
+        http.enforce_http(false);
+        Ok((http, tls_config.rustls_client_config()?).into())
+    }
+
     #[cfg(feature = "native-tls")]
     pub fn native_tls() -> NativeTls {
         hyper_tls::HttpsConnector::new()
     }
 
+    /// Create a native-tls HTTPS connector that uses `tls_config`
+    #[cfg(feature = "native-tls")]
+    pub fn native_tls_with_tls_config(
+        tls_config: &crate::tls::TlsConfig,
+    ) -> Result<NativeTls, crate::tls::TlsConfigError> {
+        if tls_config.is_default() {
+            return Ok(native_tls());
+        }
+        let mut http = hyper::client::HttpConnector::new();
+        http.enforce_http(false);
+        let tls = tokio_native_tls::TlsConnector::from(tls_config.native_tls_connector()?);
+        Ok((http, tls).into())
+    }
+
     #[cfg(feature = "native-tls")]
     pub type NativeTls = hyper_tls::HttpsConnector<hyper::client::HttpConnector>;
 
@@ -86,12 +123,16 @@ pub mod conns {
 
 use std::error::Error;
 use std::sync::Arc;
+use std::time::Instant;
 use tower::{Layer, Service, ServiceBuilder, ServiceExt};
 
 use crate::timeout::generate_timeout_service_params_from_timeout_config;
 use aws_smithy_async::rt::sleep::AsyncSleep;
+use aws_smithy_async::time::{ClockSkew, SharedTimeSource};
 use aws_smithy_http::body::SdkBody;
-use aws_smithy_http::operation::Operation;
+use aws_smithy_http::interceptor::{Interceptor, Interceptors};
+use aws_smithy_http::metrics::{names, MetricTags, OperationMetrics, RecordMetrics, SharedMetrics};
+use aws_smithy_http::operation::{Operation, Request};
 use aws_smithy_http::response::ParseHttpResponse;
 pub use aws_smithy_http::result::{SdkError, SdkSuccess};
 use aws_smithy_http::retry::ClassifyResponse;
@@ -99,6 +140,7 @@ use aws_smithy_http_tower::dispatch::DispatchLayer;
 use aws_smithy_http_tower::parse_response::ParseResponseLayer;
 use aws_smithy_types::retry::ProvideErrorKind;
 use aws_smithy_types::tristate::TriState;
+use interceptor::InterceptorLayer;
 
 /// Smithy service client.
 ///
@@ -130,6 +172,10 @@ pub struct Client<
     retry_policy: RetryPolicy,
     timeout_config: aws_smithy_types::timeout::Config,
     sleep_impl: TriState<Arc<dyn AsyncSleep>>,
+    time_source: SharedTimeSource,
+    clock_skew: ClockSkew,
+    metrics: SharedMetrics,
+    interceptors: Interceptors,
 }
 
 // Quick-create for people who just want "the default".
@@ -189,12 +235,73 @@ impl<C, M, R> Client<C, M, R> {
         self.set_sleep_impl(Some(sleep_impl));
         self
     }
+
+    /// Returns the offset between the local clock and the server's clock that has been observed
+    /// by this client.
+    ///
+    /// Retry policies may update it when a request is rejected because of clock skew. The client
+    /// places it, along with its [`TimeSource`](aws_smithy_async::time::TimeSource), in the
+    /// property bag of every operation that doesn't already have one.
+    pub fn clock_skew(&self) -> &ClockSkew {
+        &self.clock_skew
+    }
+
+    /// Set the recorder for the metrics emitted while sending operations
+    ///
+    /// The client places it in the property bag of every operation that doesn't already have one.
+    /// If `None` is passed, metrics are discarded. See [`aws_smithy_http::metrics`] for the
+    /// emitted metrics.
+    pub fn set_metrics(&mut self, metrics: Option<SharedMetrics>) {
+        self.metrics = metrics.unwrap_or_default();
+    }
+
+    /// Set the recorder for the metrics emitted while sending operations
+    pub fn with_metrics(mut self, metrics: impl RecordMetrics + 'static) -> Self {
+        self.set_metrics(Some(SharedMetrics::new(metrics)));
+        self
+    }
+
+    /// Register an [`Interceptor`] that is called for every operation sent by this client
+    ///
+    /// Interceptors are called in the order they were registered, before any interceptors
+    /// registered on the operation itself with
+    /// [`Operation::with_interceptor`](aws_smithy_http::operation::Operation::with_interceptor).
+    /// See [`aws_smithy_http::interceptor`] for when the hooks are called.
+    pub fn add_interceptor(&mut self, interceptor: impl Interceptor + 'static) {
+        self.interceptors.push(interceptor);
+    }
+
+    /// Register an [`Interceptor`] that is called for every operation sent by this client
+    pub fn with_interceptor(mut self, interceptor: impl Interceptor + 'static) -> Self {
+        self.add_interceptor(interceptor);
+        self
+    }
 }
 
 fn check_send_sync<T: Send + Sync>(t: T) -> T {
     t
 }
 
+/// Tag the [`CALLS`](names::CALLS) metric with the outcome of a call
+fn call_tags<T, E>(tags: MetricTags, result: &Result<SdkSuccess<T>, SdkError<E>>) -> MetricTags {
+    let (status, error_kind) = match result {
+        Ok(success) => (Some(success.raw.http().status()), None),
+        Err(SdkError::ConstructionFailure(_)) => (None, Some("construction")),
+        Err(SdkError::TimeoutError(_)) => (None, Some("timeout")),
+        Err(SdkError::DispatchFailure(_)) => (None, Some("dispatch")),
+        Err(SdkError::ResponseError { raw, .. }) => (Some(raw.http().status()), Some("response")),
+        Err(SdkError::ServiceError { raw, .. }) => (Some(raw.http().status()), Some("service")),
+    };
+    let tags = match status {
+        Some(status) => tags.with_status(status.as_u16()),
+        None => tags,
+    };
+    match error_kind {
+        Some(error_kind) => tags.with_error_kind(error_kind),
+        None => tags,
+    }
+}
+
 impl<C, M, R> Client<C, M, R>
 where
     C: bounds::SmithyConnector,
@@ -222,7 +329,7 @@ where
     /// implementing unsupported features.
     pub async fn call_raw<O, T, E, Retry>(
         &self,
-        input: Operation<O, Retry>,
+        mut input: Operation<O, Retry>,
     ) -> Result<SdkSuccess<T>, SdkError<E>>
     where
         O: Send + Sync,
@@ -245,6 +352,37 @@ where
                 MISSING_SLEEP_IMPL_RECOMMENDATION
             );
         }
+        {
+            let metadata = input.metadata().cloned();
+            let mut properties = input.properties_mut();
+            if let Some(metadata) = metadata {
+                properties.insert(metadata);
+            }
+            if properties.get::<SharedTimeSource>().is_none() {
+                properties.insert(self.time_source.clone());
+            }
+            if properties.get::<ClockSkew>().is_none() {
+                properties.insert(self.clock_skew.clone());
+            }
+            if properties.get::<SharedMetrics>().is_none() {
+                properties.insert(self.metrics.clone());
+            }
+            if !self.interceptors.is_empty() {
+                let mut interceptors = self.interceptors.clone();
+                if let Some(operation_interceptors) = properties.remove::<Interceptors>() {
+                    interceptors.extend(&operation_interceptors);
+                }
+                properties.insert(interceptors);
+            }
+        }
+        let metrics = OperationMetrics::from_properties(&input.properties());
+        let interceptors = Interceptors::from_properties(&input.properties());
+        // Keep a handle on the property bag so that interceptors can read it once the operation
+        // completed, even if it completed without a response
+        let (request, parts) = input.into_request_response();
+        let (request, properties) = request.into_parts();
+        let serialized = interceptors.read_after_serialization(&request, &properties.acquire());
+        let input = Operation::from_parts(Request::from_parts(request, properties.clone()), parts);
         let connector = self.connector.clone();
 
         let timeout_service_params = generate_timeout_service_params_from_timeout_config(
@@ -259,6 +397,7 @@ where
                     .new_request_policy(self.sleep_impl.clone().into()),
             )
             .layer(TimeoutLayer::new(timeout_service_params.api_call_attempt))
+            .layer(InterceptorLayer::new())
             .layer(ParseResponseLayer::<O, Retry>::new())
             // These layers can be considered as occurring in order. That is, first invoke the
             // customer-provided middleware, then dispatch dispatch over the wire.
@@ -266,7 +405,22 @@ where
             .layer(DispatchLayer::new())
             .service(connector);
 
-        check_send_sync(svc).ready().await?.call(input).await
+        let start = Instant::now();
+        let result = match serialized {
+            Ok(()) => match check_send_sync(svc).ready().await {
+                Ok(svc) => svc.call(input).await,
+                Err(err) => Err(err),
+            },
+            Err(err) => Err(SdkError::ConstructionFailure(err)),
+        };
+        interceptors
+            .read_after_execution(interceptor::raw_response(&result), &properties.acquire());
+        if let Some(metrics) = metrics {
+            let tags = call_tags(metrics.tags().clone(), &result);
+            metrics.increment(names::CALLS, &tags);
+            metrics.record_duration(names::CALL_DURATION, start.elapsed(), &tags);
+        }
+        result
     }
 
     /// Statically check the validity of a `Client` without a request to send.
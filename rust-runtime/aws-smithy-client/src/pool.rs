@@ -0,0 +1,149 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Configuration and metrics for HTTP connection pools
+//!
+//! Connectors keep connections open after a request completes so that later requests can reuse
+//! them. [`PoolConfig`] controls how long and how many idle connections are kept, and
+//! [`PoolMetrics`] counts how connections are used. Both are applied to hyper connectors by
+//! [`hyper_ext::Builder::pool_config`](crate::hyper_ext::Builder::pool_config).
+//!
+//! # Examples
+//! Close idle connections after 20 seconds, e.g. in an environment like AWS Lambda where
+//! connections are frequently closed by the server while the process is suspended:
+//! ```no_run
+//! use std::time::Duration;
+//! use aws_smithy_client::pool::{PoolConfig, PoolMetrics};
+//! use aws_smithy_types::tristate::TriState;
+//!
+//! let metrics = PoolMetrics::new();
+//! let pool_config = PoolConfig::new()
+//!     .with_idle_timeout(TriState::Set(Duration::from_secs(20)))
+//!     .with_metrics(metrics.clone());
+//! // ... send requests ...
+//! println!("{} of {} connections were closed by the server", metrics.connections_closed_by_peer(), metrics.connections_opened());
+//! ```
+
+use aws_smithy_types::tristate::TriState;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Configuration for the pool of idle connections kept by a connector
+#[non_exhaustive]
+#[derive(Clone, Debug, Default)]
+pub struct PoolConfig {
+    idle_timeout: TriState<Duration>,
+    max_idle_per_host: Option<usize>,
+    metrics: Option<PoolMetrics>,
+}
+
+impl PoolConfig {
+    /// Create a new `PoolConfig` that uses the connector's defaults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How long a connection may stay idle in the pool before it is closed
+    ///
+    /// When unset, the connector's default is used (90 seconds for hyper). When disabled, idle
+    /// connections are kept until the server closes them.
+    pub fn idle_timeout(&self) -> TriState<Duration> {
+        self.idle_timeout.clone()
+    }
+
+    /// The maximum number of idle connections kept per host
+    ///
+    /// When `None`, the connector's default is used (no limit for hyper). Setting this to zero
+    /// disables connection reuse.
+    pub fn max_idle_per_host(&self) -> Option<usize> {
+        self.max_idle_per_host
+    }
+
+    /// The metrics updated by connectors that use this configuration
+    pub fn metrics(&self) -> Option<&PoolMetrics> {
+        self.metrics.as_ref()
+    }
+
+    /// Set how long a connection may stay idle in the pool before it is closed
+    pub fn with_idle_timeout(mut self, idle_timeout: TriState<Duration>) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Set the maximum number of idle connections kept per host
+    pub fn with_max_idle_per_host(mut self, max_idle_per_host: Option<usize>) -> Self {
+        self.max_idle_per_host = max_idle_per_host;
+        self
+    }
+
+    /// Record the usage of the connection pool in `metrics`
+    ///
+    /// `metrics` can be shared between several connectors to get aggregate counts.
+    pub fn with_metrics(mut self, metrics: PoolMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    opened: AtomicUsize,
+    reused: AtomicUsize,
+    closed: AtomicUsize,
+    closed_by_peer: AtomicUsize,
+}
+
+/// Counters describing how the connections of a connection pool were used
+///
+/// `PoolMetrics` is cheap to clone, and all clones share the same counters.
+#[derive(Clone, Debug, Default)]
+pub struct PoolMetrics(Arc<Counters>);
+
+impl PoolMetrics {
+    /// Create a new set of counters, all starting at zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of connections that were opened
+    pub fn connections_opened(&self) -> usize {
+        self.0.opened.load(Ordering::Relaxed)
+    }
+
+    /// The number of responses received over a connection that had been used before
+    pub fn connections_reused(&self) -> usize {
+        self.0.reused.load(Ordering::Relaxed)
+    }
+
+    /// The number of connections that were closed, for any reason
+    pub fn connections_closed(&self) -> usize {
+        self.0.closed.load(Ordering::Relaxed)
+    }
+
+    /// The number of closed connections that had been closed by the server first
+    ///
+    /// A high number of these compared to [`connections_opened`](PoolMetrics::connections_opened)
+    /// suggests that the [idle timeout](PoolConfig::with_idle_timeout) is longer than the
+    /// server keeps connections open for.
+    pub fn connections_closed_by_peer(&self) -> usize {
+        self.0.closed_by_peer.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_opened(&self) {
+        self.0.opened.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_reused(&self) {
+        self.0.reused.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_closed(&self, by_peer: bool) {
+        self.0.closed.fetch_add(1, Ordering::Relaxed);
+        if by_peer {
+            self.0.closed_by_peer.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
@@ -15,6 +15,9 @@
 //! - [`RetryHandler`]: A request-scoped retry policy, backed by request-local state and shared
 //!   state contained within [`Standard`].
 //! - [`Config`]: Static configuration (max attempts, max backoff etc.)
+//!
+//! Every retry is recorded in the [`RETRIES`](aws_smithy_http::metrics::names::RETRIES) metric,
+//! tagged with the kind of error that caused it.
 
 use std::future::Future;
 use std::pin::Pin;
@@ -23,6 +26,7 @@ use std::time::Duration;
 
 use crate::{SdkError, SdkSuccess};
 use aws_smithy_async::rt::sleep::AsyncSleep;
+use aws_smithy_http::metrics::{names, OperationMetrics};
 use aws_smithy_http::operation;
 use aws_smithy_http::operation::Operation;
 use aws_smithy_http::retry::ClassifyResponse;
@@ -346,7 +350,13 @@ where
     ) -> Option<Self::Future> {
         let policy = req.retry_policy();
         let retry_kind = policy.classify(result);
-        self.retry_for(retry_kind)
+        let retry_kind_tag = retry_kind_tag(&retry_kind);
+        let retry = self.retry_for(retry_kind)?;
+        if let Some(metrics) = OperationMetrics::from_properties(&req.properties()) {
+            let tags = metrics.tags().clone().with_error_kind(retry_kind_tag);
+            metrics.increment(names::RETRIES, &tags);
+        }
+        Some(retry)
     }
 
     fn clone_request(&self, req: &Operation<Handler, R>) -> Option<Operation<Handler, R>> {
@@ -354,6 +364,18 @@ where
     }
 }
 
+/// The `error_kind` tag of the [`RETRIES`](names::RETRIES) metric
+fn retry_kind_tag(retry_kind: &RetryKind) -> &'static str {
+    match retry_kind {
+        RetryKind::Error(ErrorKind::TransientError) => "transient",
+        RetryKind::Error(ErrorKind::ThrottlingError) => "throttling",
+        RetryKind::Error(ErrorKind::ServerError) => "server",
+        RetryKind::Error(ErrorKind::ClientError) => "client",
+        RetryKind::Explicit(_) => "explicit",
+        _ => "other",
+    }
+}
+
 fn check_send<T: Send>(t: T) -> T {
     t
 }
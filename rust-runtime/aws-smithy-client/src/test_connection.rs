@@ -48,6 +48,8 @@ impl CaptureRequestReceiver {
 #[doc(inline)]
 pub use crate::never;
 
+pub mod mock;
+
 impl tower::Service<http::Request<SdkBody>> for CaptureRequestHandler {
     type Response = http::Response<SdkBody>;
     type Error = ConnectorError;
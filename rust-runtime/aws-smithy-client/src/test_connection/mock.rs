@@ -0,0 +1,732 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Rule-based mock connector for unit tests
+//!
+//! Unlike [`TestConnection`](super::TestConnection), which replays a fixed list of responses in
+//! order, a [`MockConnection`] picks the response for each request from a list of [`Rule`]s. A rule
+//! matches requests on their method, path, headers, operation name, or an arbitrary predicate, and
+//! responds with either a single repeated [`MockResponse`], a sequence of responses (useful for
+//! exercising retries), or a response computed from the request. Responses can be injected
+//! connector errors, and can be delayed with an [`AsyncSleep`] implementation to simulate latency.
+//!
+//! Every request is recorded so that tests can assert on how many calls were made, and which rules
+//! they matched.
+//!
+//! # Examples
+//! ```rust
+//! use aws_smithy_client::test_connection::mock::{MockConnection, MockResponse, Rule};
+//!
+//! let conn = MockConnection::builder()
+//!     .rule(
+//!         Rule::new()
+//!             .match_operation("GetObject")
+//!             .respond_with_sequence(vec![
+//!                 MockResponse::status(503),
+//!                 MockResponse::io_error("connection reset"),
+//!                 MockResponse::status(200).with_body("hello"),
+//!             ])
+//!             .expect_calls(3),
+//!     )
+//!     .rule(Rule::new().match_method("PUT").respond(MockResponse::status(200)))
+//!     .build();
+//! // ... make requests with a client that uses `conn` ...
+//! # let _ = &conn;
+//! // conn.assert_expectations();
+//! ```
+
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use aws_smithy_async::future::never::Never;
+use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep};
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::operation;
+use aws_smithy_http::result::ConnectorError;
+use aws_smithy_types::retry::ErrorKind;
+use bytes::Bytes;
+use http::{HeaderMap, HeaderValue, Method};
+
+type Matcher = Box<dyn Fn(&http::Request<SdkBody>) -> bool + Send + Sync>;
+type ResponseFn = Box<dyn Fn(&http::Request<SdkBody>) -> MockResponse + Send + Sync>;
+type MakeError = Arc<dyn Fn() -> ConnectorError + Send + Sync>;
+
+/// Response, or failure, returned by a [`MockConnection`]
+#[derive(Clone)]
+pub struct MockResponse {
+    kind: ResponseKind,
+    delay: Duration,
+}
+
+#[derive(Clone)]
+enum ResponseKind {
+    Http {
+        status: u16,
+        headers: HeaderMap,
+        body: Bytes,
+    },
+    Error(MakeError),
+    Never,
+}
+
+impl Debug for MockResponse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut formatter = f.debug_struct("MockResponse");
+        match &self.kind {
+            ResponseKind::Http {
+                status,
+                headers,
+                body,
+            } => formatter
+                .field("status", status)
+                .field("headers", headers)
+                .field("body", body),
+            ResponseKind::Error(make_error) => formatter.field("error", &make_error()),
+            ResponseKind::Never => formatter.field("never", &true),
+        };
+        formatter.field("delay", &self.delay).finish()
+    }
+}
+
+impl MockResponse {
+    fn new(kind: ResponseKind) -> Self {
+        Self {
+            kind,
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// Responds with `status` and an empty body
+    pub fn status(status: u16) -> Self {
+        Self::new(ResponseKind::Http {
+            status,
+            headers: HeaderMap::new(),
+            body: Bytes::new(),
+        })
+    }
+
+    /// Responds with a copy of `response`
+    pub fn http(response: http::Response<impl Into<Bytes>>) -> Self {
+        let (parts, body) = response.into_parts();
+        Self::new(ResponseKind::Http {
+            status: parts.status.as_u16(),
+            headers: parts.headers,
+            body: body.into(),
+        })
+    }
+
+    /// Adds a header to the response
+    ///
+    /// # Panics
+    /// Panics if this isn't an HTTP response, or if `name` or `value` isn't a valid header.
+    pub fn with_header(mut self, name: &'static str, value: &str) -> Self {
+        match &mut self.kind {
+            ResponseKind::Http { headers, .. } => {
+                headers.append(
+                    name,
+                    HeaderValue::from_str(value).expect("valid header value"),
+                );
+            }
+            _ => panic!("only HTTP responses have headers"),
+        }
+        self
+    }
+
+    /// Sets the body of the response
+    ///
+    /// # Panics
+    /// Panics if this isn't an HTTP response.
+    pub fn with_body(mut self, body: impl Into<Bytes>) -> Self {
+        match &mut self.kind {
+            ResponseKind::Http { body: current, .. } => *current = body.into(),
+            _ => panic!("only HTTP responses have a body"),
+        }
+        self
+    }
+
+    /// Fails with the connector error returned by `make_error`
+    ///
+    /// `make_error` is called every time this response is returned.
+    pub fn error(make_error: impl Fn() -> ConnectorError + Send + Sync + 'static) -> Self {
+        Self::new(ResponseKind::Error(Arc::new(make_error)))
+    }
+
+    /// Fails with an IO connector error, as if the connection had been dropped
+    pub fn io_error(message: &'static str) -> Self {
+        Self::error(move || {
+            ConnectorError::io(
+                std::io::Error::new(std::io::ErrorKind::ConnectionReset, message).into(),
+            )
+        })
+    }
+
+    /// Fails with a timeout connector error
+    pub fn timeout() -> Self {
+        Self::error(|| ConnectorError::timeout("mock connection timed out".into()))
+    }
+
+    /// Fails with a connector error of another kind
+    pub fn other_error(message: &'static str, kind: Option<ErrorKind>) -> Self {
+        Self::error(move || ConnectorError::other(message.into(), kind))
+    }
+
+    /// Never responds, so that the client's own timeouts fire
+    pub fn never() -> Self {
+        Self::new(ResponseKind::Never)
+    }
+
+    /// Delays the response by `delay`
+    ///
+    /// The delay is measured by the connection's sleep implementation.
+    /// See [`MockConnectionBuilder::sleep_impl`].
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+enum Responder {
+    Repeat(MockResponse),
+    Sequence(Vec<MockResponse>),
+    Fn(ResponseFn),
+}
+
+/// Rule that decides how a [`MockConnection`] responds to matching requests
+///
+/// A rule without matchers matches every request. A rule without a responder responds with an
+/// empty `200 OK`.
+pub struct Rule {
+    matchers: Vec<(String, Matcher)>,
+    responder: Responder,
+    expected_calls: Option<usize>,
+}
+
+impl Debug for Rule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let matchers: Vec<_> = self.matchers.iter().map(|(name, _)| name).collect();
+        f.debug_struct("Rule")
+            .field("matchers", &matchers)
+            .field("expected_calls", &self.expected_calls)
+            .finish()
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Rule {
+    /// Creates a rule that matches every request
+    pub fn new() -> Self {
+        Self {
+            matchers: vec![],
+            responder: Responder::Repeat(MockResponse::status(200)),
+            expected_calls: None,
+        }
+    }
+
+    fn matcher(
+        mut self,
+        description: String,
+        matcher: impl Fn(&http::Request<SdkBody>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.matchers.push((description, Box::new(matcher)));
+        self
+    }
+
+    /// Only matches requests with the given HTTP method
+    ///
+    /// # Panics
+    /// Panics if `method` isn't a valid HTTP method.
+    pub fn match_method(self, method: &str) -> Self {
+        let method = Method::from_bytes(method.as_bytes()).expect("valid HTTP method");
+        self.matcher(format!("method == {}", method), move |req| {
+            req.method() == method
+        })
+    }
+
+    /// Only matches requests to the given URI path, which excludes the query string
+    pub fn match_path(self, path: impl Into<String>) -> Self {
+        let path = path.into();
+        self.matcher(format!("path == {}", path), move |req| {
+            req.uri().path() == path
+        })
+    }
+
+    /// Only matches requests with a header named `name` that is equal to `value`
+    pub fn match_header(self, name: &'static str, value: impl Into<String>) -> Self {
+        let value = value.into();
+        self.matcher(format!("header {} == {}", name, value), move |req| {
+            req.headers()
+                .get_all(name)
+                .iter()
+                .any(|actual| actual.as_bytes() == value.as_bytes())
+        })
+    }
+
+    /// Only matches requests made by the given operation
+    ///
+    /// The operation name is read from the [`Metadata`](operation::Metadata) that the client
+    /// attaches to each request, so requests sent without metadata never match.
+    pub fn match_operation(self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        self.matcher(format!("operation == {}", name), move |req| {
+            req.extensions()
+                .get::<operation::Metadata>()
+                .map(|metadata| metadata.name() == name)
+                .unwrap_or(false)
+        })
+    }
+
+    /// Only matches requests for which `predicate` returns true
+    pub fn match_fn(
+        self,
+        predicate: impl Fn(&http::Request<SdkBody>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.matcher("custom predicate".into(), predicate)
+    }
+
+    /// Responds to every matching request with `response`
+    pub fn respond(mut self, response: MockResponse) -> Self {
+        self.responder = Responder::Repeat(response);
+        self
+    }
+
+    /// Responds to matching requests with `responses`, in order
+    ///
+    /// Once every response has been returned, this rule stops matching, and later requests fall
+    /// through to the next matching rule.
+    pub fn respond_with_sequence(mut self, responses: Vec<MockResponse>) -> Self {
+        self.responder = Responder::Sequence(responses.into_iter().rev().collect());
+        self
+    }
+
+    /// Responds to matching requests with the response computed by `respond`
+    pub fn respond_with(
+        mut self,
+        respond: impl Fn(&http::Request<SdkBody>) -> MockResponse + Send + Sync + 'static,
+    ) -> Self {
+        self.responder = Responder::Fn(Box::new(respond));
+        self
+    }
+
+    /// Expects this rule to be matched exactly `calls` times
+    ///
+    /// This is checked by [`MockConnection::assert_expectations`].
+    pub fn expect_calls(mut self, calls: usize) -> Self {
+        self.expected_calls = Some(calls);
+        self
+    }
+
+    fn matches(&self, request: &http::Request<SdkBody>) -> bool {
+        !matches!(&self.responder, Responder::Sequence(responses) if responses.is_empty())
+            && self.matchers.iter().all(|(_, matcher)| matcher(request))
+    }
+
+    fn next_response(&mut self, request: &http::Request<SdkBody>) -> MockResponse {
+        match &mut self.responder {
+            Responder::Repeat(response) => response.clone(),
+            Responder::Sequence(responses) => responses.pop().expect("checked by `matches`"),
+            Responder::Fn(respond) => respond(request),
+        }
+    }
+}
+
+/// Request received by a [`MockConnection`]
+#[derive(Debug)]
+pub struct RecordedRequest {
+    request: http::Request<SdkBody>,
+    rule: Option<usize>,
+}
+
+impl RecordedRequest {
+    /// Returns the request
+    pub fn request(&self) -> &http::Request<SdkBody> {
+        &self.request
+    }
+
+    /// Returns the index of the rule that matched the request, or `None` if no rule matched
+    pub fn rule(&self) -> Option<usize> {
+        self.rule
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    rules: Vec<Rule>,
+    calls: Vec<usize>,
+    requests: Vec<RecordedRequest>,
+}
+
+/// Builder for [`MockConnection`]
+#[derive(Debug, Default)]
+pub struct MockConnectionBuilder {
+    rules: Vec<Rule>,
+    fallback: Option<MockResponse>,
+    sleep_impl: Option<Arc<dyn AsyncSleep>>,
+}
+
+impl MockConnectionBuilder {
+    /// Adds a rule
+    ///
+    /// Rules are tried in the order they were added, and the first matching rule responds.
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Sets the response to requests that no rule matches
+    ///
+    /// By default, unmatched requests fail with a connector error describing the request.
+    pub fn fallback(mut self, response: MockResponse) -> Self {
+        self.fallback = Some(response);
+        self
+    }
+
+    /// Sets the sleep implementation used to delay responses
+    ///
+    /// When unset, [`default_async_sleep`] is used.
+    pub fn sleep_impl(mut self, sleep_impl: impl AsyncSleep + 'static) -> Self {
+        self.sleep_impl = Some(Arc::new(sleep_impl));
+        self
+    }
+
+    /// Builds the connection
+    pub fn build(self) -> MockConnection {
+        let calls = vec![0; self.rules.len()];
+        MockConnection {
+            state: Arc::new(Mutex::new(State {
+                rules: self.rules,
+                calls,
+                requests: vec![],
+            })),
+            fallback: self.fallback,
+            sleep_impl: self.sleep_impl.or_else(default_async_sleep),
+        }
+    }
+}
+
+/// Mock connector that responds according to a list of [`Rule`]s
+///
+/// Clones share the same rules and recorded requests. See [the module documentation](self).
+#[derive(Clone, Debug)]
+pub struct MockConnection {
+    state: Arc<Mutex<State>>,
+    fallback: Option<MockResponse>,
+    sleep_impl: Option<Arc<dyn AsyncSleep>>,
+}
+
+impl MockConnection {
+    /// Returns a builder for a `MockConnection`
+    pub fn builder() -> MockConnectionBuilder {
+        MockConnectionBuilder::default()
+    }
+
+    /// Returns the total number of requests received
+    pub fn call_count(&self) -> usize {
+        self.state.lock().unwrap().requests.len()
+    }
+
+    /// Returns the number of requests matched by the rule at `index`
+    ///
+    /// # Panics
+    /// Panics if there is no rule at `index`.
+    pub fn rule_call_count(&self, index: usize) -> usize {
+        self.state.lock().unwrap().calls[index]
+    }
+
+    /// Returns the number of requests that no rule matched
+    pub fn unmatched_count(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state
+            .requests
+            .iter()
+            .filter(|req| req.rule.is_none())
+            .count()
+    }
+
+    /// Returns every request received so far, in the order they were received
+    pub fn requests(&self) -> impl std::ops::Deref<Target = Vec<RecordedRequest>> + '_ {
+        MappedGuard(self.state.lock().unwrap())
+    }
+
+    /// Asserts that every request matched a rule, and that each rule with an expected call count
+    /// was matched exactly that many times
+    pub fn assert_expectations(&self) {
+        let state = self.state.lock().unwrap();
+        let unmatched: Vec<_> = state
+            .requests
+            .iter()
+            .filter(|req| req.rule.is_none())
+            .map(|req| format!("{} {}", req.request.method(), req.request.uri()))
+            .collect();
+        assert!(
+            unmatched.is_empty(),
+            "no rule matched these requests: {:?}",
+            unmatched
+        );
+        for (index, rule) in state.rules.iter().enumerate() {
+            if let Some(expected) = rule.expected_calls {
+                assert_eq!(
+                    expected, state.calls[index],
+                    "expected rule {} ({:?}) to be called {} times, but it was called {} times",
+                    index, rule, expected, state.calls[index]
+                );
+            }
+        }
+    }
+
+    fn respond(&self, request: http::Request<SdkBody>) -> MockResponse {
+        let mut state = self.state.lock().unwrap();
+        let index = state.rules.iter().position(|rule| rule.matches(&request));
+        let response = match index {
+            Some(index) => {
+                state.calls[index] += 1;
+                Some(state.rules[index].next_response(&request))
+            }
+            None => self.fallback.clone(),
+        };
+        let response = response.unwrap_or_else(|| {
+            let message = format!(
+                "no rule matched request: {} {}",
+                request.method(),
+                request.uri()
+            );
+            MockResponse::error(move || ConnectorError::other(message.clone().into(), None))
+        });
+        state.requests.push(RecordedRequest {
+            request,
+            rule: index,
+        });
+        response
+    }
+}
+
+struct MappedGuard<'a>(std::sync::MutexGuard<'a, State>);
+
+impl std::ops::Deref for MappedGuard<'_> {
+    type Target = Vec<RecordedRequest>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0.requests
+    }
+}
+
+impl tower::Service<http::Request<SdkBody>> for MockConnection {
+    type Response = http::Response<SdkBody>;
+    type Error = ConnectorError;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<http::Response<SdkBody>, ConnectorError>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<SdkBody>) -> Self::Future {
+        let response = self.respond(request);
+        let delay = match response.delay {
+            delay if delay.is_zero() => None,
+            delay => Some(
+                self.sleep_impl
+                    .as_ref()
+                    .expect("a sleep impl must be provided to delay mock responses")
+                    .sleep(delay),
+            ),
+        };
+        Box::pin(async move {
+            if let Some(delay) = delay {
+                delay.await;
+            }
+            match response.kind {
+                ResponseKind::Http {
+                    status,
+                    headers,
+                    body,
+                } => {
+                    let mut response = http::Response::builder()
+                        .status(status)
+                        .body(SdkBody::from(body))
+                        .expect("valid status code");
+                    *response.headers_mut() = headers;
+                    Ok(response)
+                }
+                ResponseKind::Error(make_error) => Err(make_error()),
+                ResponseKind::Never => {
+                    Never::new().await;
+                    unreachable!()
+                }
+            }
+        })
+    }
+}
+
+impl From<MockConnection> for crate::Client<MockConnection, tower::layer::util::Identity> {
+    fn from(conn: MockConnection) -> Self {
+        crate::Builder::new()
+            .middleware(tower::layer::util::Identity::new())
+            .connector(conn)
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MockConnection, MockResponse, Rule};
+    use aws_smithy_async::test_util::manual_time_and_sleep;
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_http::operation::Metadata;
+    use aws_smithy_types::retry::ErrorKind;
+    use futures_util::FutureExt;
+    use std::time::{Duration, UNIX_EPOCH};
+    use tower::Service;
+
+    fn request(method: &str, uri: &str) -> http::Request<SdkBody> {
+        http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("x-amz-target", "Service.Operation")
+            .body(SdkBody::empty())
+            .unwrap()
+    }
+
+    fn status(response: Result<http::Response<SdkBody>, impl std::fmt::Debug>) -> u16 {
+        response.unwrap().status().as_u16()
+    }
+
+    #[tokio::test]
+    async fn rules_match_in_order() {
+        let mut conn = MockConnection::builder()
+            .rule(
+                Rule::new()
+                    .match_method("GET")
+                    .match_path("/object")
+                    .respond(
+                        MockResponse::status(200)
+                            .with_header("etag", "abc")
+                            .with_body("hello"),
+                    ),
+            )
+            .rule(
+                Rule::new()
+                    .match_header("x-amz-target", "Service.Operation")
+                    .respond(MockResponse::status(201)),
+            )
+            .build();
+
+        let response = conn.call(request("GET", "/object?x=1")).await.unwrap();
+        assert_eq!(200, response.status().as_u16());
+        assert_eq!("abc", response.headers()["etag"]);
+        assert_eq!(b"hello", response.body().bytes().unwrap());
+        assert_eq!(201, status(conn.call(request("PUT", "/object")).await));
+        assert_eq!(200, status(conn.call(request("GET", "/object")).await));
+
+        let err = conn
+            .call(
+                http::Request::builder()
+                    .uri("/unmatched")
+                    .body(SdkBody::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect_err("no rule matches");
+        assert!(err
+            .to_string()
+            .contains("no rule matched request: GET /unmatched"));
+
+        assert_eq!(4, conn.call_count());
+        assert_eq!(2, conn.rule_call_count(0));
+        assert_eq!(1, conn.rule_call_count(1));
+        assert_eq!(1, conn.unmatched_count());
+        let rules: Vec<_> = conn.requests().iter().map(|req| req.rule()).collect();
+        assert_eq!(vec![Some(0), Some(1), Some(0), None], rules);
+    }
+
+    #[tokio::test]
+    async fn sequences_fall_through_when_exhausted() {
+        let mut conn = MockConnection::builder()
+            .rule(
+                Rule::new()
+                    .respond_with_sequence(vec![
+                        MockResponse::status(503),
+                        MockResponse::io_error("connection reset"),
+                        MockResponse::timeout(),
+                        MockResponse::other_error("boom", Some(ErrorKind::ThrottlingError)),
+                    ])
+                    .expect_calls(4),
+            )
+            .fallback(MockResponse::status(200))
+            .build();
+
+        assert_eq!(503, status(conn.call(request("GET", "/")).await));
+        assert!(conn.call(request("GET", "/")).await.unwrap_err().is_io());
+        assert!(conn
+            .call(request("GET", "/"))
+            .await
+            .unwrap_err()
+            .is_timeout());
+        assert_eq!(
+            Some(ErrorKind::ThrottlingError),
+            conn.call(request("GET", "/")).await.unwrap_err().is_other()
+        );
+        assert_eq!(200, status(conn.call(request("GET", "/")).await));
+        assert_eq!(4, conn.rule_call_count(0));
+        assert_eq!(1, conn.unmatched_count());
+    }
+
+    #[tokio::test]
+    async fn respond_with_and_operation_matching() {
+        let mut conn = MockConnection::builder()
+            .rule(
+                Rule::new()
+                    .match_operation("GetObject")
+                    .respond_with(|req| MockResponse::status(200).with_body(req.uri().to_string())),
+            )
+            .build();
+        let mut req = request("GET", "/key");
+        req.extensions_mut()
+            .insert(Metadata::new("GetObject", "s3"));
+        let response = conn.call(req).await.unwrap();
+        assert_eq!(b"/key", response.body().bytes().unwrap());
+        assert!(conn.call(request("GET", "/key")).await.is_err());
+    }
+
+    #[test]
+    fn delays_use_the_sleep_impl() {
+        let (time, sleep) = manual_time_and_sleep(UNIX_EPOCH);
+        let mut conn = MockConnection::builder()
+            .rule(Rule::new().respond(MockResponse::status(200).with_delay(Duration::from_secs(2))))
+            .sleep_impl(sleep.clone())
+            .build();
+        let mut response = conn.call(request("GET", "/"));
+        assert!((&mut response).now_or_never().is_none());
+        time.advance(Duration::from_secs(1));
+        assert!((&mut response).now_or_never().is_none());
+        time.advance(Duration::from_secs(1));
+        assert_eq!(200, status(response.now_or_never().unwrap()));
+
+        let mut conn = MockConnection::builder()
+            .rule(Rule::new().respond(MockResponse::never()))
+            .build();
+        let mut response = conn.call(request("GET", "/"));
+        time.advance(Duration::from_secs(60));
+        assert!((&mut response).now_or_never().is_none());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "expected rule 0")]
+    async fn assert_expectations_checks_call_counts() {
+        let mut conn = MockConnection::builder()
+            .rule(Rule::new().expect_calls(2))
+            .build();
+        conn.call(request("GET", "/")).await.unwrap();
+        conn.assert_expectations();
+    }
+}
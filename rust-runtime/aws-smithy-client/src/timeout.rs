@@ -18,6 +18,7 @@ use std::time::Duration;
 use crate::SdkError;
 use aws_smithy_async::future::timeout::Timeout;
 use aws_smithy_async::rt::sleep::{AsyncSleep, Sleep};
+use aws_smithy_http::metrics::{names, OperationMetrics};
 use aws_smithy_http::operation::Operation;
 use pin_project_lite::pin_project;
 use tower::Layer;
@@ -53,6 +54,8 @@ pub struct TimeoutServiceParams {
     duration: Duration,
     /// The kind of timeouts created from these params
     kind: &'static str,
+    /// The `error_kind` tag of the timeouts recorded in the [`TIMEOUTS`](names::TIMEOUTS) metric
+    metric_tag: &'static str,
     /// The AsyncSleep impl that will be used to create time-limited futures
     async_sleep: Arc<dyn AsyncSleep>,
 }
@@ -79,6 +82,7 @@ pub fn generate_timeout_service_params_from_timeout_config(
                 .map(|duration| TimeoutServiceParams {
                     duration,
                     kind: "API call (all attempts including retries)",
+                    metric_tag: "call_timeout",
                     async_sleep: async_sleep.clone(),
                 })
                 .into(),
@@ -87,6 +91,7 @@ pub fn generate_timeout_service_params_from_timeout_config(
                 .map(|duration| TimeoutServiceParams {
                     duration,
                     kind: "API call (single attempt)",
+                    metric_tag: "attempt_timeout",
                     async_sleep: async_sleep.clone(),
                 })
                 .into(),
@@ -98,6 +103,8 @@ pub fn generate_timeout_service_params_from_timeout_config(
 
 /// A service that wraps another service, adding the ability to set a timeout for requests
 /// handled by the inner service.
+///
+/// Timeouts are recorded in the [`TIMEOUTS`](names::TIMEOUTS) metric.
 #[derive(Clone, Debug)]
 pub struct TimeoutService<S> {
     inner: S,
@@ -161,6 +168,8 @@ pin_project! {
             future: Timeout<F, Sleep>,
             kind: &'static str,
             duration: Duration,
+            metric_tag: &'static str,
+            metrics: Option<OperationMetrics>,
         },
         /// A thin wrapper around an inner future that will never time out
         NoTimeout {
@@ -175,10 +184,20 @@ impl<F> TimeoutServiceFuture<F> {
     /// wrap the `future` inside a [`Timeout`] future and create a new [`TimeoutServiceFuture`] that
     /// will output an [`SdkError`] if `future` doesn't complete before `duration` has elapsed.
     pub fn new(future: F, params: &TimeoutServiceParams) -> Self {
+        Self::with_metrics(future, params, None)
+    }
+
+    fn with_metrics(
+        future: F,
+        params: &TimeoutServiceParams,
+        metrics: Option<OperationMetrics>,
+    ) -> Self {
         Self::Timeout {
             future: Timeout::new(future, params.async_sleep.sleep(params.duration)),
             kind: params.kind,
             duration: params.duration,
+            metric_tag: params.metric_tag,
+            metrics,
         }
     }
 
@@ -195,19 +214,27 @@ where
     type Output = Result<T, SdkError<E>>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let (future, kind, duration) = match self.project() {
+        let (future, kind, duration, metric_tag, metrics) = match self.project() {
             TimeoutServiceFutureProj::NoTimeout { future } => return future.poll(cx),
             TimeoutServiceFutureProj::Timeout {
                 future,
                 kind,
                 duration,
-            } => (future, kind, duration),
+                metric_tag,
+                metrics,
+            } => (future, kind, duration, metric_tag, metrics),
         };
         match future.poll(cx) {
             Poll::Ready(Ok(response)) => Poll::Ready(response),
-            Poll::Ready(Err(_timeout)) => Poll::Ready(Err(SdkError::TimeoutError(
-                RequestTimeoutError::new_boxed(kind, *duration),
-            ))),
+            Poll::Ready(Err(_timeout)) => {
+                if let Some(metrics) = metrics {
+                    let tags = metrics.tags().clone().with_error_kind(*metric_tag);
+                    metrics.increment(names::TIMEOUTS, &tags);
+                }
+                Poll::Ready(Err(SdkError::TimeoutError(RequestTimeoutError::new_boxed(
+                    kind, *duration,
+                ))))
+            }
             Poll::Pending => Poll::Pending,
         }
     }
@@ -226,12 +253,12 @@ where
     }
 
     fn call(&mut self, req: Operation<H, R>) -> Self::Future {
-        let future = self.inner.call(req);
-
-        if let Some(params) = &self.params {
-            Self::Future::new(future, params)
-        } else {
-            Self::Future::no_timeout(future)
+        match &self.params {
+            Some(params) => {
+                let metrics = OperationMetrics::from_properties(&req.properties());
+                Self::Future::with_metrics(self.inner.call(req), params, metrics)
+            }
+            None => Self::Future::no_timeout(self.inner.call(req)),
         }
     }
 }
@@ -239,7 +266,7 @@ where
 #[cfg(test)]
 mod test {
     use std::sync::Arc;
-    use std::time::Duration;
+    use std::time::{Duration, UNIX_EPOCH};
 
     use crate::never::NeverService;
     use crate::timeout::generate_timeout_service_params_from_timeout_config;
@@ -247,10 +274,12 @@ mod test {
 
     use aws_smithy_async::assert_elapsed;
     use aws_smithy_async::rt::sleep::{AsyncSleep, TokioSleep};
+    use aws_smithy_async::test_util::manual_time_and_sleep;
     use aws_smithy_http::body::SdkBody;
     use aws_smithy_http::operation::{Operation, Request};
     use aws_smithy_types::tristate::TriState;
 
+    use futures_util::FutureExt;
     use tower::{Service, ServiceBuilder, ServiceExt};
 
     #[tokio::test]
@@ -276,4 +305,31 @@ mod test {
         assert_eq!(format!("{:?}", err), "TimeoutError(RequestTimeoutError { kind: \"API call (all attempts including retries)\", duration: 250ms })");
         assert_elapsed!(now, Duration::from_secs_f32(0.25));
     }
+
+    #[tokio::test]
+    async fn test_timeout_service_in_virtual_time() {
+        let req = Request::new(http::Request::new(SdkBody::empty()));
+        let op = Operation::new(req, ());
+        let never_service: NeverService<_, (), _> = NeverService::new();
+        let timeout_config = aws_smithy_types::timeout::Api::new()
+            .with_call_timeout(TriState::Set(Duration::from_secs(30)));
+        let (time, sleep) = manual_time_and_sleep(UNIX_EPOCH);
+        let sleep_impl: Option<Arc<dyn AsyncSleep>> = Some(Arc::new(sleep));
+        let timeout_service_params =
+            generate_timeout_service_params_from_timeout_config(&timeout_config, sleep_impl);
+        let mut svc = ServiceBuilder::new()
+            .layer(TimeoutLayer::new(timeout_service_params.api_call))
+            .service(never_service);
+
+        let call = svc.ready().await.unwrap().call(op);
+        tokio::pin!(call);
+        assert!((&mut call).now_or_never().is_none());
+        assert_eq!(vec![Duration::from_secs(30)], time.sleeps());
+
+        time.advance(Duration::from_secs(29));
+        assert!((&mut call).now_or_never().is_none());
+        time.advance(Duration::from_secs(1));
+        let err: SdkError<Box<dyn std::error::Error + 'static>> = call.await.unwrap_err();
+        assert!(matches!(err, SdkError::TimeoutError(_)), "{:?}", err);
+    }
 }
@@ -0,0 +1,530 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! TLS configuration for the HTTPS connectors
+//!
+//! [`TlsConfig`] adds root certificates for services behind a private PKI, presents a client
+//! certificate for mutual TLS, and sets the minimum TLS version. It is applied by
+//! [`conns::https_with_tls_config`](crate::conns::https_with_tls_config) (rustls) and
+//! [`conns::native_tls_with_tls_config`](crate::conns::native_tls_with_tls_config) (native-tls)
+//! and `hyper_rt::HttpsConnector::with_tls_config` (async-std and smol), and can be passed to the default connectors through
+//! [`HttpSettings`](crate::http_connector::HttpSettings).
+//!
+//! # Examples
+//! ```no_run
+//! use aws_smithy_client::tls::{Certificate, ClientIdentity, TlsConfig, TlsVersion};
+//!
+//! let tls_config = TlsConfig::new()
+//!     .with_root_certificate(Certificate::from_pem(std::fs::read("internal-ca.pem").unwrap()))
+//!     .with_client_identity(ClientIdentity::from_pem(
+//!         std::fs::read("client.pem").unwrap(),
+//!         std::fs::read("client.key").unwrap(),
+//!     ))
+//!     .with_min_tls_version(TlsVersion::Tls13);
+//! ```
+
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+
+#[cfg(all(feature = "futures-rustls", not(feature = "rustls")))]
+use futures_rustls::rustls;
+#[cfg(feature = "rustls")]
+use tokio_rustls::rustls;
+
+/// TLS configuration for the HTTPS connectors
+///
+/// The default configuration trusts the platform's root certificates, presents no client
+/// certificate, and uses the TLS versions supported by the TLS implementation.
+#[derive(Clone, Debug)]
+pub struct TlsConfig {
+    root_certificates: Vec<Certificate>,
+    native_roots: bool,
+    client_identity: Option<ClientIdentity>,
+    min_tls_version: Option<TlsVersion>,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self {
+            root_certificates: Vec::new(),
+            native_roots: true,
+            client_identity: None,
+            min_tls_version: None,
+        }
+    }
+}
+
+impl TlsConfig {
+    /// Create a TLS configuration that matches the defaults of the HTTPS connectors
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust `certificate` in addition to the platform's root certificates
+    pub fn with_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Trust `certificates` in addition to the platform's root certificates
+    pub fn with_root_certificates(
+        mut self,
+        certificates: impl IntoIterator<Item = Certificate>,
+    ) -> Self {
+        self.root_certificates.extend(certificates);
+        self
+    }
+
+    /// Set whether the platform's root certificates are trusted
+    ///
+    /// Disabling the platform's root certificates replaces the root store with the certificates
+    /// added with [`with_root_certificate`](TlsConfig::with_root_certificate).
+    pub fn with_native_roots(mut self, enabled: bool) -> Self {
+        self.native_roots = enabled;
+        self
+    }
+
+    /// Present `identity` to servers that request a client certificate (mutual TLS)
+    pub fn with_client_identity(mut self, identity: ClientIdentity) -> Self {
+        self.client_identity = Some(identity);
+        self
+    }
+
+    /// Refuse to connect with a TLS version older than `version`
+    pub fn with_min_tls_version(mut self, version: TlsVersion) -> Self {
+        self.min_tls_version = Some(version);
+        self
+    }
+
+    /// Additional root certificates
+    pub fn root_certificates(&self) -> &[Certificate] {
+        &self.root_certificates
+    }
+
+    /// Whether the platform's root certificates are trusted
+    pub fn native_roots(&self) -> bool {
+        self.native_roots
+    }
+
+    /// The client certificate used for mutual TLS, if any
+    pub fn client_identity(&self) -> Option<&ClientIdentity> {
+        self.client_identity.as_ref()
+    }
+
+    /// The minimum TLS version, if any
+    pub fn min_tls_version(&self) -> Option<TlsVersion> {
+        self.min_tls_version
+    }
+
+    /// Returns true if this configuration doesn't change anything from the defaults
+    ///
+    /// The default connectors are cached, so they are reused for default configurations.
+    pub fn is_default(&self) -> bool {
+        self.root_certificates.is_empty()
+            && self.native_roots
+            && self.client_identity.is_none()
+            && self.min_tls_version.is_none()
+    }
+}
+
+#[derive(Clone)]
+enum Encoding {
+    Pem,
+    Der,
+}
+
+/// An X.509 certificate
+#[derive(Clone)]
+pub struct Certificate {
+    encoding: Encoding,
+    bytes: Vec<u8>,
+}
+
+impl Certificate {
+    /// Load PEM encoded certificates
+    ///
+    /// `pem` may contain several certificates, such as a CA bundle. All of them are trusted.
+    pub fn from_pem(pem: impl Into<Vec<u8>>) -> Self {
+        Self {
+            encoding: Encoding::Pem,
+            bytes: pem.into(),
+        }
+    }
+
+    /// Load a single DER encoded certificate
+    pub fn from_der(der: impl Into<Vec<u8>>) -> Self {
+        Self {
+            encoding: Encoding::Der,
+            bytes: der.into(),
+        }
+    }
+}
+
+impl Debug for Certificate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let encoding = match self.encoding {
+            Encoding::Pem => "pem",
+            Encoding::Der => "der",
+        };
+        f.debug_struct("Certificate")
+            .field("encoding", &encoding)
+            .field("len", &self.bytes.len())
+            .finish()
+    }
+}
+
+/// A client certificate chain and private key, used for mutual TLS
+#[derive(Clone)]
+pub struct ClientIdentity {
+    certificate_chain: Vec<u8>,
+    #[allow(dead_code)] // unused when no TLS feature is enabled
+    private_key: Vec<u8>,
+}
+
+impl ClientIdentity {
+    /// Load a client identity from a PEM encoded certificate chain and PKCS #8 private key
+    ///
+    /// With rustls, PKCS #1 (`BEGIN RSA PRIVATE KEY`) private keys are also supported.
+    pub fn from_pem(
+        certificate_chain: impl Into<Vec<u8>>,
+        private_key: impl Into<Vec<u8>>,
+    ) -> Self {
+        Self {
+            certificate_chain: certificate_chain.into(),
+            private_key: private_key.into(),
+        }
+    }
+}
+
+impl Debug for ClientIdentity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientIdentity")
+            .field("certificate_chain", &self.certificate_chain.len())
+            .field("private_key", &"** redacted **")
+            .finish()
+    }
+}
+
+/// A version of the TLS protocol
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TlsVersion {
+    /// TLS 1.2
+    Tls12,
+    /// TLS 1.3
+    ///
+    /// Not supported as a minimum version by native-tls.
+    Tls13,
+}
+
+// Which variants are constructed depends on the TLS features that are enabled
+#[allow(dead_code)]
+#[derive(Debug)]
+enum TlsConfigErrorKind {
+    InvalidCertificate(String),
+    InvalidClientIdentity(String),
+    NoRootCertificates,
+    UnsupportedMinVersion(TlsVersion),
+    NativeRoots(std::io::Error),
+    Other(Box<dyn Error + Send + Sync>),
+}
+
+/// Error returned when a [`TlsConfig`] can't be applied to a connector
+#[derive(Debug)]
+pub struct TlsConfigError {
+    kind: TlsConfigErrorKind,
+}
+
+impl TlsConfigError {
+    #[allow(dead_code)] // unused when no TLS feature is enabled
+    fn new(kind: TlsConfigErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+impl Display for TlsConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        use TlsConfigErrorKind::*;
+        match &self.kind {
+            InvalidCertificate(reason) => write!(f, "invalid root certificate: {}", reason),
+            InvalidClientIdentity(reason) => write!(f, "invalid client identity: {}", reason),
+            NoRootCertificates => write!(
+                f,
+                "no root certificates: native roots were disabled and no root certificate was added"
+            ),
+            UnsupportedMinVersion(version) => write!(
+                f,
+                "{:?} is not supported as the minimum TLS version by this TLS implementation",
+                version
+            ),
+            NativeRoots(_) => write!(f, "failed to load the platform's root certificates"),
+            Other(_) => write!(f, "failed to build the TLS connector"),
+        }
+    }
+}
+
+impl Error for TlsConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            TlsConfigErrorKind::NativeRoots(err) => Some(err),
+            TlsConfigErrorKind::Other(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+// `tokio-rustls` and `futures-rustls` re-export the same version of `rustls`
+#[cfg(any(feature = "rustls", feature = "futures-rustls"))]
+impl TlsConfig {
+    pub(crate) fn rustls_client_config(&self) -> Result<rustls::ClientConfig, TlsConfigError> {
+        use rustls::internal::pemfile;
+        use rustls::{ClientConfig, ProtocolVersion};
+        use TlsConfigErrorKind::*;
+
+        let mut config = ClientConfig::new();
+        if self.native_roots {
+            config.root_store = match rustls_native_certs::load_native_certs() {
+                Ok(store) => store,
+                Err((Some(store), err)) => {
+                    tracing::warn!(err = %err, "could not load all native root certificates");
+                    store
+                }
+                Err((None, err)) => return Err(TlsConfigError::new(NativeRoots(err))),
+            };
+        }
+        for certificate in &self.root_certificates {
+            let certificates = match certificate.encoding {
+                Encoding::Pem => pemfile::certs(&mut certificate.bytes.as_slice())
+                    .map_err(|_| TlsConfigError::new(InvalidCertificate("malformed PEM".into())))?,
+                Encoding::Der => vec![rustls::Certificate(certificate.bytes.clone())],
+            };
+            if certificates.is_empty() {
+                return Err(TlsConfigError::new(InvalidCertificate(
+                    "no certificates found in PEM".into(),
+                )));
+            }
+            for certificate in &certificates {
+                config
+                    .root_store
+                    .add(certificate)
+                    .map_err(|err| TlsConfigError::new(InvalidCertificate(err.to_string())))?;
+            }
+        }
+        if config.root_store.is_empty() {
+            return Err(TlsConfigError::new(NoRootCertificates));
+        }
+        if let Some(identity) = &self.client_identity {
+            let invalid = |reason: &str| TlsConfigError::new(InvalidClientIdentity(reason.into()));
+            let chain = pemfile::certs(&mut identity.certificate_chain.as_slice())
+                .map_err(|_| invalid("malformed certificate chain PEM"))?;
+            if chain.is_empty() {
+                return Err(invalid("no certificates found in certificate chain PEM"));
+            }
+            let mut keys = pemfile::pkcs8_private_keys(&mut identity.private_key.as_slice())
+                .map_err(|_| invalid("malformed private key PEM"))?;
+            if keys.is_empty() {
+                keys = pemfile::rsa_private_keys(&mut identity.private_key.as_slice())
+                    .map_err(|_| invalid("malformed private key PEM"))?;
+            }
+            let key = keys
+                .into_iter()
+                .next()
+                .ok_or_else(|| invalid("no private key found in PEM"))?;
+            config
+                .set_single_client_cert(chain, key)
+                .map_err(|err| invalid(&err.to_string()))?;
+        }
+        match self.min_tls_version {
+            // rustls doesn't support TLS versions older than 1.2
+            None | Some(TlsVersion::Tls12) => {}
+            Some(TlsVersion::Tls13) => config.versions = vec![ProtocolVersion::TLSv1_3],
+        }
+        // match the ALPN settings of `hyper_rustls::HttpsConnector::with_native_roots`
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(config)
+    }
+}
+
+#[cfg(feature = "native-tls")]
+impl TlsConfig {
+    pub(crate) fn native_tls_connector(
+        &self,
+    ) -> Result<tokio_native_tls::native_tls::TlsConnector, TlsConfigError> {
+        use tokio_native_tls::native_tls;
+        use TlsConfigErrorKind::*;
+
+        let mut builder = native_tls::TlsConnector::builder();
+        builder.disable_built_in_roots(!self.native_roots);
+        if !self.native_roots && self.root_certificates.is_empty() {
+            return Err(TlsConfigError::new(NoRootCertificates));
+        }
+        for certificate in &self.root_certificates {
+            let certificates = match certificate.encoding {
+                Encoding::Pem => native_tls::Certificate::stack_from_pem(&certificate.bytes),
+                Encoding::Der => native_tls::Certificate::from_der(&certificate.bytes)
+                    .map(|certificate| vec![certificate]),
+            }
+            .map_err(|err| TlsConfigError::new(InvalidCertificate(err.to_string())))?;
+            if certificates.is_empty() {
+                return Err(TlsConfigError::new(InvalidCertificate(
+                    "no certificates found in PEM".into(),
+                )));
+            }
+            for certificate in certificates {
+                builder.add_root_certificate(certificate);
+            }
+        }
+        if let Some(identity) = &self.client_identity {
+            let identity = native_tls::Identity::from_pkcs8(
+                &identity.certificate_chain,
+                &identity.private_key,
+            )
+            .map_err(|err| TlsConfigError::new(InvalidClientIdentity(err.to_string())))?;
+            builder.identity(identity);
+        }
+        match self.min_tls_version {
+            None => {}
+            Some(TlsVersion::Tls12) => {
+                builder.min_protocol_version(Some(native_tls::Protocol::Tlsv12));
+            }
+            Some(version @ TlsVersion::Tls13) => {
+                return Err(TlsConfigError::new(UnsupportedMinVersion(version)))
+            }
+        }
+        builder
+            .build()
+            .map_err(|err| TlsConfigError::new(Other(err.into())))
+    }
+}
+
+#[cfg(all(test, feature = "rustls"))]
+mod test {
+    use super::{Certificate, ClientIdentity, TlsConfig, TlsConfigError, TlsVersion};
+    use tokio_rustls::rustls::ProtocolVersion;
+
+    const CERT_PEM: &[u8] = include_bytes!("../test-data/tls-test-cert.pem");
+    const CERT_DER: &[u8] = include_bytes!("../test-data/tls-test-cert.der");
+    const KEY_PEM: &[u8] = include_bytes!("../test-data/tls-test-key.pem");
+
+    fn rustls_error(tls_config: TlsConfig) -> TlsConfigError {
+        match tls_config.rustls_client_config() {
+            Ok(_) => panic!("expected the TLS config to be rejected"),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn default_config_is_default() {
+        assert!(TlsConfig::new().is_default());
+        assert!(!TlsConfig::new()
+            .with_min_tls_version(TlsVersion::Tls12)
+            .is_default());
+    }
+
+    #[test]
+    fn replace_root_certificates() {
+        for certificate in [
+            Certificate::from_pem(CERT_PEM),
+            Certificate::from_der(CERT_DER),
+        ] {
+            let config = TlsConfig::new()
+                .with_native_roots(false)
+                .with_root_certificate(certificate)
+                .rustls_client_config()
+                .expect("valid config");
+            assert_eq!(1, config.root_store.len());
+        }
+    }
+
+    #[test]
+    fn mtls_and_min_version() {
+        let config = TlsConfig::new()
+            .with_native_roots(false)
+            .with_root_certificate(Certificate::from_pem(CERT_PEM))
+            .with_client_identity(ClientIdentity::from_pem(CERT_PEM, KEY_PEM))
+            .with_min_tls_version(TlsVersion::Tls13)
+            .rustls_client_config()
+            .expect("valid config");
+        assert_eq!(vec![ProtocolVersion::TLSv1_3], config.versions);
+        assert!(config.client_auth_cert_resolver.has_certs());
+    }
+
+    #[test]
+    fn replacing_roots_requires_a_certificate() {
+        let err = rustls_error(TlsConfig::new().with_native_roots(false));
+        assert!(err.to_string().contains("no root certificates"), "{}", err);
+    }
+
+    #[test]
+    fn invalid_certificates_are_rejected() {
+        let err = rustls_error(
+            TlsConfig::new()
+                .with_native_roots(false)
+                .with_root_certificate(Certificate::from_pem("not a certificate")),
+        );
+        assert_eq!(
+            "invalid root certificate: no certificates found in PEM",
+            err.to_string()
+        );
+
+        let err = rustls_error(
+            TlsConfig::new()
+                .with_native_roots(false)
+                .with_root_certificate(Certificate::from_der(vec![1, 2, 3])),
+        );
+        assert!(err.to_string().starts_with("invalid root certificate"));
+    }
+
+    #[test]
+    fn client_identity_requires_a_key() {
+        let err = rustls_error(
+            TlsConfig::new()
+                .with_native_roots(false)
+                .with_root_certificate(Certificate::from_pem(CERT_PEM))
+                .with_client_identity(ClientIdentity::from_pem(CERT_PEM, "")),
+        );
+        assert_eq!(
+            "invalid client identity: no private key found in PEM",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn client_identity_is_redacted() {
+        let identity = ClientIdentity::from_pem("cert", "secret key");
+        assert!(!format!("{:?}", identity).contains("secret"));
+    }
+}
+
+#[cfg(all(test, feature = "native-tls"))]
+mod native_tls_test {
+    use super::{Certificate, ClientIdentity, TlsConfig, TlsVersion};
+
+    const CERT_PEM: &[u8] = include_bytes!("../test-data/tls-test-cert.pem");
+    const KEY_PEM: &[u8] = include_bytes!("../test-data/tls-test-key.pem");
+
+    #[test]
+    fn native_tls_connector() {
+        TlsConfig::new()
+            .with_native_roots(false)
+            .with_root_certificate(Certificate::from_pem(CERT_PEM))
+            .with_client_identity(ClientIdentity::from_pem(CERT_PEM, KEY_PEM))
+            .with_min_tls_version(TlsVersion::Tls12)
+            .native_tls_connector()
+            .expect("valid config");
+    }
+
+    #[test]
+    fn native_tls_min_tls13_is_unsupported() {
+        let err = TlsConfig::new()
+            .with_min_tls_version(TlsVersion::Tls13)
+            .native_tls_connector()
+            .expect_err("unsupported");
+        assert_eq!(
+            "Tls13 is not supported as the minimum TLS version by this TLS implementation",
+            err.to_string()
+        );
+    }
+}
@@ -5,15 +5,24 @@
 
 use crate::test_operation::TestPolicy;
 use aws_smithy_async::rt::sleep::TokioSleep;
+use aws_smithy_async::test_util::instant_time_and_sleep;
+use aws_smithy_async::time::{ClockSkew, SharedTimeSource, TimeSource};
 
+use aws_smithy_client::test_connection::mock::{MockConnection, MockResponse, Rule};
 use aws_smithy_client::test_connection::TestConnection;
-use aws_smithy_client::Client;
+use aws_smithy_client::{Builder, Client};
 use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::interceptor::Interceptor;
+use aws_smithy_http::metrics::{names, InMemoryMetrics, MetricTags};
 use aws_smithy_http::operation;
 use aws_smithy_http::operation::Operation;
+use aws_smithy_http::property_bag::PropertyBag;
 use aws_smithy_http::result::SdkError;
-use std::sync::Arc;
-use std::time::Duration;
+use aws_smithy_types::timeout;
+use aws_smithy_types::tristate::TriState;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, UNIX_EPOCH};
 use tokio::time::Instant;
 use tower::layer::util::Identity;
 
@@ -78,6 +87,11 @@ mod test_operation {
         fn classify(&self, err: Result<&T, &SdkError<E>>) -> RetryKind {
             let kind = match err {
                 Err(SdkError::ServiceError { err, .. }) => err.retryable_error_kind(),
+                Err(SdkError::DispatchFailure(err)) if err.is_timeout() || err.is_io() => {
+                    Some(ErrorKind::TransientError)
+                }
+                Err(SdkError::DispatchFailure(_)) | Err(SdkError::ConstructionFailure(_)) => None,
+                Err(SdkError::TimeoutError(_)) => Some(ErrorKind::TransientError),
                 Ok(_) => return RetryKind::Unnecessary,
                 _ => panic!("test handler only handles modeled errors got: {:?}", err),
             };
@@ -171,6 +185,407 @@ async fn end_to_end_retry_test() {
     assert_time_passed(initial, Duration::from_secs(7));
 }
 
+#[tokio::test]
+async fn retry_backoff_in_virtual_time() {
+    fn req() -> http::Request<SdkBody> {
+        http::Request::builder()
+            .body(SdkBody::from("request body"))
+            .unwrap()
+    }
+
+    fn response(status: u16) -> http::Response<&'static str> {
+        http::Response::builder()
+            .status(status)
+            .body("response body")
+            .unwrap()
+    }
+
+    let conn = TestConnection::new(vec![
+        (req(), response(500)),
+        (req(), response(500)),
+        (req(), response(200)),
+    ]);
+    let retry_config = aws_smithy_client::retry::Config::default()
+        .with_max_attempts(3)
+        .with_base(|| 1_f64);
+    let (time, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+    let client = Client::<TestConnection<_>, Identity>::new(conn.clone())
+        .with_retry_config(retry_config)
+        .with_sleep_impl(Arc::new(sleep));
+
+    let resp = client
+        .call(test_operation())
+        .await
+        .expect("successful operation");
+    assert_eq!(resp, "Hello!");
+    assert_eq!(conn.requests().len(), 3);
+    assert_eq!(
+        time.sleeps(),
+        vec![Duration::from_secs(1), Duration::from_secs(2)]
+    );
+    assert_eq!(time.now(), UNIX_EPOCH + Duration::from_secs(3));
+}
+
+#[tokio::test]
+async fn time_source_and_clock_skew_are_added_to_property_bag() {
+    let conn = TestConnection::new(vec![(
+        http::Request::builder()
+            .body(SdkBody::from("request body"))
+            .unwrap(),
+        http::Response::builder()
+            .status(200)
+            .body("response body")
+            .unwrap(),
+    )]);
+    let start = UNIX_EPOCH + Duration::from_secs(1234);
+    let (time, _) = instant_time_and_sleep(start);
+    let observed = Arc::new(Mutex::new(None));
+    let client = Builder::new()
+        .connector(conn)
+        .middleware_fn({
+            let observed = observed.clone();
+            move |req: operation::Request| {
+                let properties = req.properties();
+                let time = properties.get::<SharedTimeSource>().map(|time| time.now());
+                let skew = properties
+                    .get::<ClockSkew>()
+                    .map(|skew| skew.offset_millis());
+                *observed.lock().unwrap() = Some((time, skew));
+                drop(properties);
+                req
+            }
+        })
+        .time_source(time)
+        .build();
+    client
+        .clock_skew()
+        .update(start, start + Duration::from_secs(60));
+
+    client
+        .call(test_operation())
+        .await
+        .expect("successful operation");
+    assert_eq!(
+        Some((Some(start), Some(60_000))),
+        observed.lock().unwrap().take()
+    );
+}
+
+#[tokio::test]
+async fn mock_connection_retries_by_operation() {
+    let (time, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+    let conn = MockConnection::builder()
+        .rule(
+            Rule::new()
+                .match_operation("TestOperation")
+                .respond_with_sequence(vec![
+                    MockResponse::status(500),
+                    MockResponse::timeout(),
+                    MockResponse::status(200).with_delay(Duration::from_secs(5)),
+                ])
+                .expect_calls(3),
+        )
+        .sleep_impl(sleep.clone())
+        .build();
+    let retry_config = aws_smithy_client::retry::Config::default()
+        .with_max_attempts(3)
+        .with_base(|| 1_f64);
+    let client = Client::<MockConnection, Identity>::new(conn.clone())
+        .with_retry_config(retry_config)
+        .with_sleep_impl(Arc::new(sleep));
+
+    let resp = client
+        .call(
+            test_operation()
+                .with_metadata(operation::Metadata::new("TestOperation", "test-service")),
+        )
+        .await
+        .expect("successful operation");
+    assert_eq!(resp, "Hello!");
+    conn.assert_expectations();
+    assert_eq!(
+        time.sleeps(),
+        vec![
+            Duration::from_secs(1),
+            Duration::from_secs(2),
+            Duration::from_secs(5)
+        ]
+    );
+
+    // requests without the operation's metadata don't match the rule
+    client
+        .call(test_operation())
+        .await
+        .expect_err("no rule matches");
+    assert_eq!(1, conn.unmatched_count());
+}
+
+#[tokio::test]
+async fn metrics_are_recorded_per_operation() {
+    let (_, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+    let conn = MockConnection::builder()
+        .rule(Rule::new().respond_with_sequence(vec![
+            MockResponse::status(500),
+            MockResponse::timeout(),
+            MockResponse::status(200),
+        ]))
+        .build();
+    let metrics = InMemoryMetrics::new();
+    let retry_config = aws_smithy_client::retry::Config::default().with_max_attempts(3);
+    let client = Client::<MockConnection, Identity>::new(conn)
+        .with_retry_config(retry_config)
+        .with_sleep_impl(Arc::new(sleep))
+        .with_metrics(metrics.clone());
+
+    client
+        .call(
+            test_operation()
+                .with_metadata(operation::Metadata::new("TestOperation", "test-service")),
+        )
+        .await
+        .expect("successful operation");
+
+    let operation_tags =
+        MetricTags::for_operation(&operation::Metadata::new("TestOperation", "test-service"));
+    assert_eq!(
+        1,
+        metrics.counter_matching(names::CALLS, |tags| *tags
+            == operation_tags.clone().with_status(200))
+    );
+    assert_eq!(1, metrics.histogram(names::CALL_DURATION).len());
+    assert_eq!(3, metrics.counter(names::ATTEMPTS));
+    assert_eq!(
+        1,
+        metrics.counter_matching(names::ATTEMPTS, |tags| tags.status() == Some(500))
+    );
+    assert_eq!(
+        1,
+        metrics.counter_matching(names::ATTEMPTS, |tags| tags.error_kind() == Some("timeout"))
+    );
+    assert_eq!(3, metrics.histogram(names::ATTEMPT_DURATION).len());
+    assert_eq!(
+        vec![(12.0, operation_tags.clone()); 3],
+        metrics.histogram(names::REQUEST_BYTES)
+    );
+    assert_eq!(2, metrics.counter(names::RETRIES));
+    assert_eq!(
+        1,
+        metrics.counter_matching(names::RETRIES, |tags| tags.error_kind()
+            == Some("throttling"))
+    );
+    assert_eq!(
+        1,
+        metrics.counter_matching(names::RETRIES, |tags| tags.error_kind()
+            == Some("transient"))
+    );
+}
+
+/// Records every hook it is called for, and marks requests with headers
+#[derive(Debug)]
+struct RecordingInterceptor {
+    name: &'static str,
+    events: Arc<Mutex<Vec<String>>>,
+    reject_before_signing: bool,
+}
+
+impl RecordingInterceptor {
+    fn new(name: &'static str, events: &Arc<Mutex<Vec<String>>>) -> Self {
+        Self {
+            name,
+            events: events.clone(),
+            reject_before_signing: false,
+        }
+    }
+
+    fn record(&self, event: impl std::fmt::Display) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("{}:{}", self.name, event));
+    }
+}
+
+fn status(response: Option<&http::Response<SdkBody>>) -> u16 {
+    response.map(|resp| resp.status().as_u16()).unwrap_or(0)
+}
+
+impl Interceptor for RecordingInterceptor {
+    fn read_after_serialization(
+        &self,
+        _request: &http::Request<SdkBody>,
+        _properties: &PropertyBag,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.record("read_after_serialization");
+        Ok(())
+    }
+
+    fn modify_before_signing(
+        &self,
+        request: &mut http::Request<SdkBody>,
+        _properties: &mut PropertyBag,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.record("modify_before_signing");
+        if self.reject_before_signing {
+            return Err("rejected".into());
+        }
+        request
+            .headers_mut()
+            .insert("x-before-signing", self.name.parse().unwrap());
+        Ok(())
+    }
+
+    fn modify_before_transmit(
+        &self,
+        request: &mut http::Request<SdkBody>,
+        _properties: &mut PropertyBag,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.record("modify_before_transmit");
+        request
+            .headers_mut()
+            .insert("x-before-transmit", self.name.parse().unwrap());
+        Ok(())
+    }
+
+    fn modify_before_deserialization(
+        &self,
+        response: &mut http::Response<SdkBody>,
+        _properties: &mut PropertyBag,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.record(format!(
+            "modify_before_deserialization {}",
+            response.status().as_u16()
+        ));
+        Ok(())
+    }
+
+    fn read_after_attempt(
+        &self,
+        response: Option<&http::Response<SdkBody>>,
+        _properties: &PropertyBag,
+    ) {
+        self.record(format!("read_after_attempt {}", status(response)));
+    }
+
+    fn read_after_execution(
+        &self,
+        response: Option<&http::Response<SdkBody>>,
+        _properties: &PropertyBag,
+    ) {
+        self.record(format!("read_after_execution {}", status(response)));
+    }
+}
+
+#[tokio::test]
+async fn interceptors_are_called_around_every_attempt() {
+    let (_, sleep) = instant_time_and_sleep(UNIX_EPOCH);
+    let conn = MockConnection::builder()
+        .rule(
+            Rule::new()
+                .match_header("x-before-signing", "operation")
+                .match_header("x-before-transmit", "operation")
+                .respond_with_sequence(vec![MockResponse::status(500), MockResponse::status(200)]),
+        )
+        .build();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let client = Client::<MockConnection, Identity>::new(conn.clone())
+        .with_sleep_impl(Arc::new(sleep))
+        .with_interceptor(RecordingInterceptor::new("client", &events));
+
+    client
+        .call(test_operation().with_interceptor(RecordingInterceptor::new("operation", &events)))
+        .await
+        .expect("successful operation");
+
+    assert_eq!(0, conn.unmatched_count());
+    let attempt = |status: u16| {
+        vec![
+            "client:modify_before_signing".to_string(),
+            "operation:modify_before_signing".to_string(),
+            "client:modify_before_transmit".to_string(),
+            "operation:modify_before_transmit".to_string(),
+            format!("client:modify_before_deserialization {}", status),
+            format!("operation:modify_before_deserialization {}", status),
+            format!("client:read_after_attempt {}", status),
+            format!("operation:read_after_attempt {}", status),
+        ]
+    };
+    let mut expected = vec![
+        "client:read_after_serialization".to_string(),
+        "operation:read_after_serialization".to_string(),
+    ];
+    expected.extend(attempt(500));
+    expected.extend(attempt(200));
+    expected.push("client:read_after_execution 200".to_string());
+    expected.push("operation:read_after_execution 200".to_string());
+    assert_eq!(expected, *events.lock().unwrap());
+}
+
+#[tokio::test]
+async fn interceptor_errors_fail_the_operation() {
+    let conn = MockConnection::builder()
+        .fallback(MockResponse::status(200))
+        .build();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let client = Client::<MockConnection, Identity>::new(conn.clone())
+        .with_sleep_impl(Arc::new(TokioSleep::new()))
+        .with_interceptor(RecordingInterceptor {
+            reject_before_signing: true,
+            ..RecordingInterceptor::new("client", &events)
+        });
+
+    let err = client
+        .call(test_operation())
+        .await
+        .expect_err("interceptor rejected the request");
+
+    assert!(matches!(err, SdkError::ConstructionFailure(_)), "{:?}", err);
+    assert_eq!(0, conn.call_count());
+    assert_eq!(
+        vec![
+            "client:read_after_serialization",
+            "client:modify_before_signing",
+            "client:read_after_attempt 0",
+            "client:read_after_execution 0",
+        ],
+        *events.lock().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn timeouts_are_recorded_in_metrics() {
+    tokio::time::pause();
+    let conn = MockConnection::builder()
+        .rule(Rule::new().respond_with_sequence(vec![
+            MockResponse::status(200).with_delay(Duration::from_secs(5)),
+        ]))
+        .sleep_impl(TokioSleep::new())
+        .build();
+    let metrics = InMemoryMetrics::new();
+    let timeout_config = timeout::Config::new().with_api_timeouts(
+        timeout::Api::new().with_call_attempt_timeout(TriState::Set(Duration::from_secs(1))),
+    );
+    let client = Client::<MockConnection, Identity>::new(conn)
+        .with_retry_config(aws_smithy_client::retry::Config::default().with_max_attempts(1))
+        .with_timeout_config(timeout_config)
+        .with_sleep_impl(Arc::new(TokioSleep::new()))
+        .with_metrics(metrics.clone());
+
+    let err = client
+        .call(test_operation())
+        .await
+        .expect_err("the attempt timed out");
+    assert!(matches!(err, SdkError::TimeoutError(_)), "{:?}", err);
+    assert_eq!(
+        1,
+        metrics.counter_matching(names::TIMEOUTS, |tags| tags.error_kind()
+            == Some("attempt_timeout"))
+    );
+    assert_eq!(
+        1,
+        metrics.counter_matching(names::CALLS, |tags| tags.error_kind() == Some("timeout"))
+    );
+}
+
 /// Validate that time has passed with a 5ms tolerance
 ///
 /// This is to account for some non-determinism in the Tokio timer
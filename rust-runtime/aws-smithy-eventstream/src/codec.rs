@@ -0,0 +1,233 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Tokio codec and stream adapters for Event Stream messages.
+//!
+//! [`MessageCodec`] implements tokio-util's [`Decoder`] and [`Encoder`], so it can be used with
+//! [`FramedRead`], [`FramedWrite`](tokio_util::codec::FramedWrite) and
+//! [`Framed`](tokio_util::codec::Framed) to read and write messages over any transport.
+//! [`MessageStream`] is a [`Stream`] of the messages read from an [`AsyncRead`].
+//!
+//! # Examples
+//! ```no_run
+//! use aws_smithy_eventstream::codec::MessageStream;
+//! use futures_util::StreamExt;
+//!
+//! async fn replay(path: &str) -> Result<(), aws_smithy_eventstream::error::Error> {
+//!     let file = tokio::fs::File::open(path).await?;
+//!     let mut messages = MessageStream::new(file);
+//!     while let Some(message) = messages.next().await {
+//!         println!("{:?}", message?);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use crate::error::Error;
+use crate::frame::{DecodedFrame, Message, MessageFrameDecoder};
+use bytes::BytesMut;
+use futures_core::Stream;
+use pin_project_lite::pin_project;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::AsyncRead;
+use tokio_util::codec::{Decoder, Encoder, FramedRead};
+
+/// Encodes and decodes Event Stream [`Message`]s
+#[non_exhaustive]
+#[derive(Debug, Default)]
+pub struct MessageCodec {
+    decoder: MessageFrameDecoder,
+}
+
+impl MessageCodec {
+    /// Returns a new `MessageCodec` without a maximum message size.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Rejects messages larger than `max_message_size` bytes, both when encoding and decoding.
+    pub fn with_max_message_size(mut self, max_message_size: u32) -> Self {
+        self.decoder = self.decoder.with_max_message_size(max_message_size);
+        self
+    }
+
+    /// Returns the maximum message size, if any
+    pub fn max_message_size(&self) -> Option<u32> {
+        self.decoder.max_message_size()
+    }
+}
+
+impl Decoder for MessageCodec {
+    type Item = Message;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self.decoder.decode_frame(src)? {
+            DecodedFrame::Complete(message) => Ok(Some(message)),
+            DecodedFrame::Incomplete => Ok(None),
+        }
+    }
+
+    fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self.decode(buf)? {
+            Some(message) => Ok(Some(message)),
+            None if buf.is_empty() && !self.decoder.has_partial_frame() => Ok(None),
+            None => Err(Error::Io(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "stream ended in the middle of a message",
+            ))),
+        }
+    }
+}
+
+impl Encoder<Message> for MessageCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: Message, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        self.encode(&item, dst)
+    }
+}
+
+impl Encoder<&Message> for MessageCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: &Message, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let start = dst.len();
+        item.write_to(dst)?;
+        let message_len = dst.len() - start;
+        match self.max_message_size() {
+            Some(max) if message_len > max as usize => {
+                dst.truncate(start);
+                // `write_to` fails for messages longer than `u32::MAX`
+                Err(Error::MessageExceedsMaxSize(message_len as u32, max))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+pin_project! {
+    /// A [`Stream`] of the Event Stream [`Message`]s read from an [`AsyncRead`]
+    ///
+    /// The stream ends when the reader reaches EOF between two messages. Reaching EOF in the
+    /// middle of a message is an error.
+    #[derive(Debug)]
+    pub struct MessageStream<R> {
+        #[pin]
+        inner: FramedRead<R, MessageCodec>,
+    }
+}
+
+impl<R: AsyncRead> MessageStream<R> {
+    /// Returns a stream of the messages read from `reader`.
+    pub fn new(reader: R) -> Self {
+        Self::with_codec(reader, MessageCodec::new())
+    }
+
+    /// Returns a stream of the messages read from `reader` with `codec`, e.g. to limit the
+    /// message size.
+    pub fn with_codec(reader: R, codec: MessageCodec) -> Self {
+        Self {
+            inner: FramedRead::new(reader, codec),
+        }
+    }
+
+    /// Returns the underlying reader.
+    ///
+    /// Data that was already read from the reader but not yet decoded is lost.
+    pub fn into_inner(self) -> R {
+        self.inner.into_inner()
+    }
+}
+
+impl<R: AsyncRead> Stream for MessageStream<R> {
+    type Item = Result<Message, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().inner.poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MessageCodec, MessageStream};
+    use crate::error::Error;
+    use crate::frame::{Header, HeaderValue, Message};
+    use bytes::BytesMut;
+    use futures_util::StreamExt;
+    use tokio_util::codec::{Decoder, Encoder};
+
+    fn message(payload: &'static str) -> Message {
+        Message::new(payload).add_header(Header::new(
+            ":event-type",
+            HeaderValue::String("test".into()),
+        ))
+    }
+
+    fn encode(messages: &[Message]) -> Vec<u8> {
+        let mut buffer = BytesMut::new();
+        let mut codec = MessageCodec::new();
+        for message in messages {
+            codec.encode(message, &mut buffer).unwrap();
+        }
+        buffer.to_vec()
+    }
+
+    #[test]
+    fn codec_round_trip() {
+        let mut buffer = BytesMut::from(&encode(&[message("one"), message("two")])[..]);
+        let mut codec = MessageCodec::new();
+        assert_eq!(Some(message("one")), codec.decode(&mut buffer).unwrap());
+        assert_eq!(Some(message("two")), codec.decode(&mut buffer).unwrap());
+        assert_eq!(None, codec.decode(&mut buffer).unwrap());
+    }
+
+    #[test]
+    fn codec_enforces_max_message_size() {
+        let len = encode(&[message("one")]).len() as u32;
+        let mut codec = MessageCodec::new().with_max_message_size(len - 1);
+        let mut buffer = BytesMut::new();
+        let err = codec
+            .encode(message("one"), &mut buffer)
+            .expect_err("message is too large");
+        assert!(matches!(err, Error::MessageExceedsMaxSize(actual, _) if actual == len));
+        assert!(buffer.is_empty());
+
+        let mut buffer = BytesMut::from(&encode(&[message("one")])[..]);
+        assert!(matches!(
+            codec.decode(&mut buffer),
+            Err(Error::MessageExceedsMaxSize(_, _))
+        ));
+    }
+
+    #[tokio::test]
+    async fn message_stream() {
+        let data = encode(&[message("one"), message("two")]);
+        // Read the messages a few bytes at a time
+        let (mut writer, reader) = tokio::io::duplex(7);
+        tokio::spawn(async move {
+            tokio::io::AsyncWriteExt::write_all(&mut writer, &data)
+                .await
+                .unwrap();
+        });
+        let messages: Vec<_> = MessageStream::new(reader).collect().await;
+        let messages: Vec<_> = messages.into_iter().map(Result::unwrap).collect();
+        assert_eq!(vec![message("one"), message("two")], messages);
+    }
+
+    #[tokio::test]
+    async fn message_stream_fails_on_truncated_message() {
+        let data = encode(&[message("one")]);
+        let mut messages = MessageStream::new(&data[..data.len() - 1]);
+        let err = messages
+            .next()
+            .await
+            .expect("stream has an item")
+            .expect_err("message was truncated");
+        assert!(matches!(err, Error::Io(_)), "{:?}", err);
+    }
+}
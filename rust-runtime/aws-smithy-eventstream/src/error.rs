@@ -20,14 +20,34 @@ pub enum Error {
     InvalidUtf8String,
     MessageChecksumMismatch(u32, u32),
     MessageTooLong,
+    /// The message's length (first value) exceeds the configured maximum (second value)
+    MessageExceedsMaxSize(u32, u32),
     PayloadTooLong,
     PreludeChecksumMismatch(u32, u32),
     TimestampValueTooLarge(DateTime),
     Marshalling(String),
     Unmarshalling(String),
+    /// Reading or writing messages failed
+    #[cfg(feature = "tokio-codec")]
+    Io(std::io::Error),
 }
 
-impl StdError for Error {}
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            #[cfg(feature = "tokio-codec")]
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "tokio-codec")]
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -47,6 +67,11 @@ impl fmt::Display for Error {
                 actual, expected
             ),
             MessageTooLong => write!(f, "message too long to fit in event stream frame"),
+            MessageExceedsMaxSize(len, max) => write!(
+                f,
+                "message of {} bytes exceeds the maximum message size of {} bytes",
+                len, max
+            ),
             PayloadTooLong => write!(f, "message payload too long to fit in event stream frame"),
             PreludeChecksumMismatch(expected, actual) => write!(
                 f,
@@ -60,6 +85,8 @@ impl fmt::Display for Error {
             ),
             Marshalling(error) => write!(f, "failed to marshall message: {}", error),
             Unmarshalling(error) => write!(f, "failed to unmarshall message: {}", error),
+            #[cfg(feature = "tokio-codec")]
+            Io(error) => write!(f, "failed to read or write message: {}", error),
         }
     }
 }
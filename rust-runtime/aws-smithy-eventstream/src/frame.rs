@@ -699,6 +699,7 @@ pub enum DecodedFrame {
 pub struct MessageFrameDecoder {
     prelude: [u8; PRELUDE_LENGTH_BYTES_USIZE],
     prelude_read: bool,
+    max_message_size: Option<u32>,
 }
 
 impl MessageFrameDecoder {
@@ -707,6 +708,20 @@ impl MessageFrameDecoder {
         Default::default()
     }
 
+    /// Rejects messages larger than `max_message_size` bytes
+    ///
+    /// The limit is checked as soon as the prelude of a message was read, so that the rest of
+    /// an oversized message is never buffered.
+    pub fn with_max_message_size(mut self, max_message_size: u32) -> Self {
+        self.max_message_size = Some(max_message_size);
+        self
+    }
+
+    /// Returns the maximum message size, if any
+    pub fn max_message_size(&self) -> Option<u32> {
+        self.max_message_size
+    }
+
     /// Determines if the `buffer` has enough data in it to read a full frame.
     /// Returns `Ok(None)` if there's not enough data, or `Some(remaining)` where
     /// `remaining` is the number of bytes after the prelude that belong to the
@@ -727,6 +742,12 @@ impl MessageFrameDecoder {
         Ok(None)
     }
 
+    /// Returns true if part of a message was consumed from the buffer, but not the full message.
+    #[cfg(feature = "tokio-codec")]
+    pub(crate) fn has_partial_frame(&self) -> bool {
+        self.prelude_read
+    }
+
     /// Resets the decoder.
     fn reset(&mut self) {
         self.prelude_read = false;
@@ -745,6 +766,13 @@ impl MessageFrameDecoder {
         if !self.prelude_read && buffer.remaining() >= PRELUDE_LENGTH_BYTES_USIZE {
             buffer.copy_to_slice(&mut self.prelude);
             self.prelude_read = true;
+            if let Some(max_message_size) = self.max_message_size {
+                let message_len = (&self.prelude[..]).get_u32();
+                if message_len > max_message_size {
+                    self.reset();
+                    return Err(Error::MessageExceedsMaxSize(message_len, max_message_size));
+                }
+            }
         }
 
         if let Some(remaining_len) = self.remaining_bytes_if_frame_available(&buffer)? {
@@ -761,6 +789,7 @@ impl MessageFrameDecoder {
 #[cfg(test)]
 mod message_frame_decoder_tests {
     use super::{DecodedFrame, MessageFrameDecoder};
+    use crate::error::Error;
     use crate::frame::Message;
     use bytes::Bytes;
     use bytes_utils::SegmentedBuf;
@@ -818,6 +847,27 @@ mod message_frame_decoder_tests {
         assert_eq!(expected3, decoded[2]);
     }
 
+    #[test]
+    fn max_message_size() {
+        let message = include_bytes!("../test_data/valid_with_all_headers_and_payload");
+        let mut decoder = MessageFrameDecoder::new().with_max_message_size(message.len() as u32);
+        assert!(matches!(
+            decoder.decode_frame(&mut Bytes::from_static(message)),
+            Ok(DecodedFrame::Complete(_))
+        ));
+
+        let mut decoder =
+            MessageFrameDecoder::new().with_max_message_size(message.len() as u32 - 1);
+        let err = decoder
+            .decode_frame(&mut Bytes::from_static(message))
+            .expect_err("message is too large");
+        assert!(
+            matches!(err, Error::MessageExceedsMaxSize(len, _) if len as usize == message.len()),
+            "{:?}",
+            err
+        );
+    }
+
     #[test]
     fn multiple_streaming_messages() {
         for chunk_size in 1..=11 {
@@ -0,0 +1,196 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! JSON representation of Event Stream messages, for debugging and logging.
+//!
+//! A message is represented as an object with a `headers` array and a base64 encoded `payload`.
+//! Every header is an object with its `name`, its `type`, and its `value`:
+//!
+//! ```json
+//! {
+//!   "headers": [
+//!     { "name": ":event-type", "type": "string", "value": "Records" },
+//!     { "name": ":date", "type": "timestamp", "value": "2022-01-01T00:00:00Z" }
+//!   ],
+//!   "payload": "aGVsbG8="
+//! }
+//! ```
+//!
+//! Byte arrays are base64 encoded, timestamps are RFC-3339 date-times, and UUIDs are
+//! hex-encoded. Everything else is a JSON boolean, number or string.
+
+use crate::error::Error;
+use crate::frame::{Header, HeaderValue, Message};
+use crate::str_bytes::StrBytes;
+use aws_smithy_types::base64;
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::DateTime;
+use serde_json::{json, Map, Value};
+use std::convert::TryFrom;
+
+impl Message {
+    /// Returns the JSON representation of this message.
+    ///
+    /// Fails if the message contains a timestamp that can't be formatted.
+    pub fn to_json(&self) -> Result<Value, Error> {
+        let headers = self
+            .headers()
+            .iter()
+            .map(header_to_json)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(json!({
+            "headers": headers,
+            "payload": base64::encode(self.payload()),
+        }))
+    }
+
+    /// Parses a message from its JSON representation, see [`to_json`](Message::to_json).
+    pub fn from_json(value: &Value) -> Result<Message, Error> {
+        let object = value.as_object().ok_or_else(|| invalid("message", value))?;
+        let headers = match object.get("headers") {
+            Some(Value::Array(headers)) => headers
+                .iter()
+                .map(header_from_json)
+                .collect::<Result<Vec<_>, _>>()?,
+            Some(other) => return Err(invalid("headers", other)),
+            None => Vec::new(),
+        };
+        let payload = match object.get("payload") {
+            Some(Value::String(payload)) => base64::decode(payload)
+                .map_err(|err| Error::Unmarshalling(format!("invalid payload: {}", err)))?,
+            Some(other) => return Err(invalid("payload", other)),
+            None => Vec::new(),
+        };
+        Ok(Message::new_from_parts(headers, payload))
+    }
+}
+
+fn invalid(what: &str, value: &Value) -> Error {
+    Error::Unmarshalling(format!("invalid {} in JSON message: {}", what, value))
+}
+
+fn header_to_json(header: &Header) -> Result<Value, Error> {
+    let (typ, value) = match header.value() {
+        HeaderValue::Bool(value) => ("bool", json!(value)),
+        HeaderValue::Byte(value) => ("byte", json!(value)),
+        HeaderValue::Int16(value) => ("int16", json!(value)),
+        HeaderValue::Int32(value) => ("int32", json!(value)),
+        HeaderValue::Int64(value) => ("int64", json!(value)),
+        HeaderValue::ByteArray(value) => ("byte_array", json!(base64::encode(value))),
+        HeaderValue::String(value) => ("string", json!(value.as_str())),
+        HeaderValue::Timestamp(value) => (
+            "timestamp",
+            json!(value
+                .fmt(Format::DateTime)
+                .map_err(|err| Error::Marshalling(format!("invalid timestamp: {}", err)))?),
+        ),
+        HeaderValue::Uuid(value) => ("uuid", json!(format!("{:032x}", value))),
+    };
+    let mut object = Map::new();
+    object.insert("name".into(), json!(header.name().as_str()));
+    object.insert("type".into(), json!(typ));
+    object.insert("value".into(), value);
+    Ok(Value::Object(object))
+}
+
+fn header_from_json(value: &Value) -> Result<Header, Error> {
+    let field = |name: &str| value.get(name).ok_or_else(|| invalid("header", value));
+    let name = field("name")?
+        .as_str()
+        .ok_or_else(|| invalid("header name", value))?;
+    let typ = field("type")?
+        .as_str()
+        .ok_or_else(|| invalid("header type", value))?;
+    let raw = field("value")?;
+    let integer = || raw.as_i64().ok_or_else(|| invalid("header value", value));
+    let string = || raw.as_str().ok_or_else(|| invalid("header value", value));
+    let out_of_range = |_| invalid("header value", value);
+    let header_value = match typ {
+        "bool" => HeaderValue::Bool(
+            raw.as_bool()
+                .ok_or_else(|| invalid("header value", value))?,
+        ),
+        "byte" => HeaderValue::Byte(i8::try_from(integer()?).map_err(out_of_range)?),
+        "int16" => HeaderValue::Int16(i16::try_from(integer()?).map_err(out_of_range)?),
+        "int32" => HeaderValue::Int32(i32::try_from(integer()?).map_err(out_of_range)?),
+        "int64" => HeaderValue::Int64(integer()?),
+        "byte_array" => HeaderValue::ByteArray(
+            base64::decode(string()?)
+                .map_err(|_| invalid("header value", value))?
+                .into(),
+        ),
+        "string" => HeaderValue::String(StrBytes::copy_from_str(string()?)),
+        "timestamp" => HeaderValue::Timestamp(
+            DateTime::from_str(string()?, Format::DateTime)
+                .map_err(|_| invalid("header value", value))?,
+        ),
+        "uuid" => HeaderValue::Uuid(
+            u128::from_str_radix(string()?, 16).map_err(|_| invalid("header value", value))?,
+        ),
+        _ => return Err(invalid("header type", value)),
+    };
+    Ok(Header::new(StrBytes::copy_from_str(name), header_value))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frame::{Header, HeaderValue, Message};
+    use aws_smithy_types::DateTime;
+    use bytes::Bytes;
+    use serde_json::json;
+
+    #[test]
+    fn json_round_trip() {
+        let message = Message::new(&b"hello"[..])
+            .add_header(Header::new("bool", HeaderValue::Bool(true)))
+            .add_header(Header::new("byte", HeaderValue::Byte(-1)))
+            .add_header(Header::new("int16", HeaderValue::Int16(256)))
+            .add_header(Header::new("int32", HeaderValue::Int32(-65536)))
+            .add_header(Header::new("int64", HeaderValue::Int64(1 << 40)))
+            .add_header(Header::new(
+                "bytes",
+                HeaderValue::ByteArray(Bytes::from_static(&[0, 1, 2])),
+            ))
+            .add_header(Header::new("string", HeaderValue::String("value".into())))
+            .add_header(Header::new(
+                "timestamp",
+                HeaderValue::Timestamp(DateTime::from_millis(1_640_995_200_123)),
+            ))
+            .add_header(Header::new("uuid", HeaderValue::Uuid(0x1234)));
+
+        let value = message.to_json().unwrap();
+        assert_eq!(
+            json!({
+                "headers": [
+                    { "name": "bool", "type": "bool", "value": true },
+                    { "name": "byte", "type": "byte", "value": -1 },
+                    { "name": "int16", "type": "int16", "value": 256 },
+                    { "name": "int32", "type": "int32", "value": -65536 },
+                    { "name": "int64", "type": "int64", "value": 1_099_511_627_776_i64 },
+                    { "name": "bytes", "type": "byte_array", "value": "AAEC" },
+                    { "name": "string", "type": "string", "value": "value" },
+                    { "name": "timestamp", "type": "timestamp", "value": "2022-01-01T00:00:00.123Z" },
+                    { "name": "uuid", "type": "uuid", "value": "00000000000000000000000000001234" },
+                ],
+                "payload": "aGVsbG8=",
+            }),
+            value
+        );
+        assert_eq!(message, Message::from_json(&value).unwrap());
+    }
+
+    #[test]
+    fn invalid_json() {
+        for value in [
+            json!([]),
+            json!({ "payload": 5 }),
+            json!({ "headers": [{ "name": "a", "type": "byte", "value": 300 }] }),
+            json!({ "headers": [{ "name": "a", "type": "unknown", "value": 1 }] }),
+        ] {
+            let err = Message::from_json(&value).expect_err("invalid message");
+            assert!(format!("{}", err).contains("invalid"), "{}", err);
+        }
+    }
+}
@@ -13,7 +13,11 @@
 //! AWS Event Stream frame serialization/deserialization implementation.
 
 mod buf;
+#[cfg(feature = "tokio-codec")]
+pub mod codec;
 pub mod error;
 pub mod frame;
+#[cfg(feature = "json")]
+pub mod json;
 pub mod smithy;
 pub mod str_bytes;
@@ -49,6 +49,11 @@ impl Error {
     pub(crate) fn new(error: impl Into<BoxError>) -> Self {
         Self { inner: error.into() }
     }
+
+    /// Returns the underlying error if it's of type `T`.
+    pub(crate) fn downcast_ref<T: StdError + 'static>(&self) -> Option<&T> {
+        self.inner.downcast_ref()
+    }
 }
 
 impl fmt::Display for Error {
@@ -15,6 +15,7 @@ pub enum Protocol {
     RestXml,
     AwsJson10,
     AwsJson11,
+    RpcV2Cbor,
 }
 
 /// Implement the content-type header validation for a request.
@@ -70,6 +71,13 @@ impl_content_type_validation!(
     RequestRejection::MissingAwsJson11ContentType
 );
 
+impl_content_type_validation!(
+    "rpc_v2_cbor",
+    "application",
+    "cbor",
+    RequestRejection::MissingRpcV2CborContentType
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,4 +216,30 @@ mod tests {
         // Check request with not parsable content-type header.
         validate_rejection_type!(check_aws_json_11_content_type(&req("123")), RequestRejection::MimeParse);
     }
+
+    #[test]
+    fn validate_rpc_v2_cbor_content_type() {
+        // Check valid content-type header.
+        let request = req("application/cbor");
+        assert!(check_rpc_v2_cbor_content_type(&request).is_ok());
+
+        // Check invalid content-type header.
+        let invalid = vec![
+            req("application/cbo"),
+            req("application/cbor1"),
+            req("applicatio/cbor"),
+            req("application/json"),
+            req("application/x-amz-json-1.0"),
+            RequestParts::new(Request::builder().body("").unwrap()),
+        ];
+        for request in &invalid {
+            validate_rejection_type!(
+                check_rpc_v2_cbor_content_type(request),
+                RequestRejection::MissingRpcV2CborContentType
+            );
+        }
+
+        // Check request with not parsable content-type header.
+        validate_rejection_type!(check_rpc_v2_cbor_content_type(&req("123")), RequestRejection::MimeParse);
+    }
 }
@@ -138,6 +138,7 @@ pub enum RequestRejection {
     MissingAwsJson10ContentType,
     MissingAwsJson11ContentType,
     MissingRestXmlContentType,
+    MissingRpcV2CborContentType,
     MimeParse,
 
     /// Used when failing to deserialize the HTTP body's bytes into a JSON document conforming to
@@ -146,6 +147,9 @@ pub enum RequestRejection {
     /// Used when failing to deserialize the HTTP body's bytes into a XML conforming to the modeled
     /// input it should represent.
     XmlDeserialize(crate::Error),
+    /// Used when failing to deserialize the HTTP body's bytes into a CBOR data item conforming to
+    /// the modeled input it should represent.
+    CborDeserialize(crate::Error),
 
     /// Used when attempting to take the request's headers, and they have already been taken (presumably
     /// by an outer `Service` that handled the request before us).
@@ -189,6 +193,20 @@ pub enum RequestRejection {
 
 impl std::error::Error for RequestRejection {}
 
+impl RequestRejection {
+    /// Returns the path of the value in the request body that failed to deserialize (for
+    /// example, `$.Items[3].attrs.created`) when this is a [`RequestRejection::JsonDeserialize`]
+    /// and the path could be determined.
+    pub fn json_path(&self) -> Option<&aws_smithy_json::deserialize::JsonPath> {
+        match self {
+            RequestRejection::JsonDeserialize(err) => err
+                .downcast_ref::<aws_smithy_json::deserialize::Error>()
+                .and_then(|err| err.path()),
+            _ => None,
+        }
+    }
+}
+
 // These converters are solely to make code-generation simpler. They convert from a specific error
 // type (from a runtime/third-party crate or the standard library) into a variant of the
 // [`crate::rejection::RequestRejection`] enum holding the type-erased boxed [`crate::Error`]
@@ -197,6 +215,7 @@ impl std::error::Error for RequestRejection {}
 
 convert_to_request_rejection!(aws_smithy_json::deserialize::Error, JsonDeserialize);
 convert_to_request_rejection!(aws_smithy_xml::decode::XmlError, XmlDeserialize);
+convert_to_request_rejection!(aws_smithy_cbor::decode::Error, CborDeserialize);
 convert_to_request_rejection!(aws_smithy_http::operation::BuildError, Build);
 convert_to_request_rejection!(aws_smithy_http::header::ParseError, HeaderParse);
 convert_to_request_rejection!(aws_smithy_types::date_time::DateTimeParseError, DateTimeParse);
@@ -224,3 +243,20 @@ convert_to_request_rejection!(std::str::Utf8Error, InvalidUtf8);
 // tests use `[crate::body::Body]` as their body type when constructing requests (and almost
 // everyone will run a Hyper-based server in their services).
 convert_to_request_rejection!(hyper::Error, HttpBody);
+
+#[cfg(test)]
+mod tests {
+    use super::RequestRejection;
+    use aws_smithy_json::deserialize::{Error, ErrorReason};
+
+    #[test]
+    fn json_path() {
+        let input = br#"{"Items": [{"created": 5}]}"#;
+        let err = Error::new(ErrorReason::Custom("expected ValueString".into()), Some(23)).with_path_from(input);
+        let rejection = RequestRejection::from(err);
+        assert_eq!("$.Items[0].created", rejection.json_path().unwrap().to_string());
+
+        assert!(RequestRejection::from(Error::custom("no offset")).json_path().is_none());
+        assert!(RequestRejection::MimeParse.json_path().is_none());
+    }
+}
@@ -41,7 +41,7 @@ pub use self::{into_make_service::IntoMakeService, route::Route};
 /// the [AwsJson specification].
 ///
 /// The router is also [Protocol] aware and currently supports REST based protocols like [restJson1] or [restXml]
-/// and RPC based protocols like [awsJson1.0] or [awsJson1.1].
+/// and RPC based protocols like [awsJson1.0], [awsJson1.1] or [rpcv2Cbor].
 /// It currently does not support Smithy's [endpoint trait].
 ///
 /// You should not **instantiate** this router directly; it will be created for you from the
@@ -54,6 +54,7 @@ pub use self::{into_make_service::IntoMakeService, route::Route};
 /// [restXml]: https://awslabs.github.io/smithy/1.0/spec/aws/aws-restxml-protocol.html
 /// [awsJson1.0]: https://awslabs.github.io/smithy/1.0/spec/aws/aws-json-1_0-protocol.html
 /// [awsJson1.1]: https://awslabs.github.io/smithy/1.0/spec/aws/aws-json-1_1-protocol.html
+/// [rpcv2Cbor]: https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html
 /// [endpoint trait]: https://awslabs.github.io/smithy/1.0/spec/core/endpoint-traits.html#endpoint-trait
 #[derive(Debug)]
 pub struct Router<B = Body> {
@@ -67,12 +68,16 @@ pub struct Router<B = Body> {
 ///
 /// AwsJson 1.0 and 1.1 routes can be stored in a `HashMap` since the requested operation can be
 /// directly found in the `X-Amz-Target` HTTP header.
+///
+/// RpcV2Cbor routes are stored in a `HashMap` keyed by `ServiceName.OperationName`, since both
+/// names can be directly found in the request URI.
 #[derive(Debug)]
 enum Routes<B = Body> {
     RestXml(Vec<(Route<B>, RequestSpec)>),
     RestJson1(Vec<(Route<B>, RequestSpec)>),
     AwsJson10(HashMap<String, Route<B>>),
     AwsJson11(HashMap<String, Route<B>>),
+    RpcV2Cbor(HashMap<String, Route<B>>),
 }
 
 impl<B> Clone for Router<B> {
@@ -90,6 +95,9 @@ impl<B> Clone for Router<B> {
             Routes::AwsJson11(routes) => Router {
                 routes: Routes::AwsJson11(routes.clone()),
             },
+            Routes::RpcV2Cbor(routes) => Router {
+                routes: Routes::RpcV2Cbor(routes.clone()),
+            },
         }
     }
 }
@@ -105,6 +113,7 @@ where
             Routes::RestXml(_) => Protocol::RestXml,
             Routes::AwsJson10(_) => Protocol::AwsJson10,
             Routes::AwsJson11(_) => Protocol::AwsJson11,
+            Routes::RpcV2Cbor(_) => Protocol::RpcV2Cbor,
         };
         let error = RuntimeError {
             protocol,
@@ -189,6 +198,15 @@ where
                     routes: Routes::AwsJson11(routes),
                 }
             }
+            Routes::RpcV2Cbor(routes) => {
+                let routes = routes
+                    .into_iter()
+                    .map(|(operation, route)| (operation, Layer::layer(&layer, route)))
+                    .collect();
+                Router {
+                    routes: Routes::RpcV2Cbor(routes),
+                }
+            }
         }
     }
 
@@ -293,6 +311,44 @@ where
             routes: Routes::AwsJson11(routes),
         }
     }
+
+    /// Create a new RpcV2Cbor `Router` from an iterator over pairs of `ServiceName.OperationName`
+    /// strings and services.
+    ///
+    /// If the iterator is empty the router will respond `404 Not Found` to all requests.
+    #[doc(hidden)]
+    pub fn new_rpc_v2_cbor_router<T>(routes: T) -> Self
+    where
+        T: IntoIterator<
+            Item = (
+                tower::util::BoxCloneService<Request<B>, Response<BoxBody>, Infallible>,
+                String,
+            ),
+        >,
+    {
+        let routes = routes
+            .into_iter()
+            .map(|(svc, operation)| (operation, Route::from_box_clone_service(svc)))
+            .collect();
+
+        Self {
+            routes: Routes::RpcV2Cbor(routes),
+        }
+    }
+}
+
+/// Extracts `ServiceName.OperationName` from an RpcV2Cbor request path, which has the form
+/// `{prefix}/service/{ServiceName}/operation/{OperationName}`.
+fn rpc_v2_operation_name(path: &str) -> Option<String> {
+    let mut segments = path.rsplit('/');
+    let operation = segments.next()?;
+    let operation_literal = segments.next()?;
+    let service = segments.next()?;
+    let service_literal = segments.next()?;
+    if operation_literal != "operation" || service_literal != "service" || operation.is_empty() || service.is_empty() {
+        return None;
+    }
+    Some(format!("{}.{}", service, operation))
 }
 
 impl<B> Service<Request<B>> for Router<B>
@@ -358,6 +414,29 @@ where
                 // In any other case return the `RuntimeError::UnknownOperation`.
                 self.unknown_operation()
             }
+            // RpcV2Cbor routes.
+            Routes::RpcV2Cbor(routes) => {
+                if let Some(operation) = rpc_v2_operation_name(req.uri().path()) {
+                    // Check the request method for POST.
+                    if req.method() != http::Method::POST {
+                        return self.method_not_allowed();
+                    }
+                    // The `smithy-protocol` header is required to identify the protocol.
+                    let is_rpc_v2_cbor = req
+                        .headers()
+                        .get("smithy-protocol")
+                        .map(|value| value == "rpc-v2-cbor")
+                        .unwrap_or(false);
+                    if is_rpc_v2_cbor {
+                        // Lookup in the `HashMap` for a route for the operation.
+                        if let Some(route) = routes.get(&operation) {
+                            return RouterFuture::from_oneshot(route.clone().oneshot(req));
+                        }
+                    }
+                }
+                // In any other case return the `RuntimeError::UnknownOperation`.
+                self.unknown_operation()
+            }
         }
     }
 }
@@ -674,3 +753,95 @@ mod awsjson_tests {
         }
     }
 }
+
+#[cfg(test)]
+mod rpc_v2_cbor_tests {
+    use super::rest_tests::{get_body_as_string, req};
+    use super::*;
+    use crate::body::boxed;
+    use futures_util::Future;
+    use http::{HeaderMap, HeaderValue, Method};
+    use pretty_assertions::assert_eq;
+    use std::pin::Pin;
+
+    /// A service that returns its name and the request's URI path in the response body.
+    #[derive(Clone)]
+    struct NamedEchoPathService(String);
+
+    impl<B> Service<Request<B>> for NamedEchoPathService {
+        type Response = Response<BoxBody>;
+        type Error = Infallible;
+        type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+        #[inline]
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        #[inline]
+        fn call(&mut self, req: Request<B>) -> Self::Future {
+            let body = boxed(Body::from(format!("{} :: {}", self.0, req.uri().path())));
+            let fut = async { Ok(Response::builder().status(&http::StatusCode::OK).body(body).unwrap()) };
+            Box::pin(fut)
+        }
+    }
+
+    #[test]
+    fn operation_name_from_path() {
+        assert_eq!(
+            Some("Service.Operation".to_string()),
+            rpc_v2_operation_name("/service/Service/operation/Operation")
+        );
+        assert_eq!(
+            Some("Service.Operation".to_string()),
+            rpc_v2_operation_name("/some/prefix/service/Service/operation/Operation")
+        );
+        assert_eq!(None, rpc_v2_operation_name("/service/Service/operation/"));
+        assert_eq!(None, rpc_v2_operation_name("/service//operation/Operation"));
+        assert_eq!(None, rpc_v2_operation_name("/services/Service/operation/Operation"));
+        assert_eq!(None, rpc_v2_operation_name("/operation/Operation"));
+        assert_eq!(None, rpc_v2_operation_name("/"));
+    }
+
+    #[tokio::test]
+    async fn simple_routing() {
+        let routes = vec![("Service.Operation", "A")];
+        let mut router = Router::new_rpc_v2_cbor_router(routes.into_iter().map(|(operation, svc_name)| {
+            (
+                tower::util::BoxCloneService::new(NamedEchoPathService(String::from(svc_name))),
+                operation.to_string(),
+            )
+        }));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("smithy-protocol", HeaderValue::from_static("rpc-v2-cbor"));
+        let uri = "/service/Service/operation/Operation";
+
+        // Valid request, should return a valid body.
+        let mut res = router
+            .call(req(&Method::POST, uri, Some(headers.clone())))
+            .await
+            .unwrap();
+        let actual_body = get_body_as_string(&mut res).await;
+        assert_eq!(format!("{} :: {}", "A", uri), actual_body);
+
+        // No `smithy-protocol` header, should return NOT_FOUND.
+        let res = router.call(req(&Method::POST, uri, None)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_eq!(res.headers().get("content-type").unwrap(), "application/cbor");
+
+        // Wrong HTTP method, should return METHOD_NOT_ALLOWED.
+        let res = router
+            .call(req(&Method::GET, uri, Some(headers.clone())))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        // Unknown operation, should return NOT_FOUND.
+        let res = router
+            .call(req(&Method::POST, "/service/Service/operation/Other", Some(headers)))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -50,6 +50,16 @@ impl RuntimeErrorKind {
             RuntimeErrorKind::UnknownOperation => "UnknownOperation",
         }
     }
+
+    /// Returns the path of the value in a JSON request body that failed to deserialize, if known.
+    fn json_path(&self) -> Option<&aws_smithy_json::deserialize::JsonPath> {
+        match self {
+            RuntimeErrorKind::Serialization(err) => err
+                .downcast_ref::<crate::rejection::RequestRejection>()
+                .and_then(|rejection| rejection.json_path()),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -66,14 +76,33 @@ impl IntoResponse for RuntimeError {
             RuntimeErrorKind::UnknownOperation => http::StatusCode::NOT_FOUND,
         };
 
-        let body = crate::body::to_boxed(match self.protocol {
-            Protocol::RestJson1 => "{}",
-            Protocol::RestXml => "",
+        let body = match (self.protocol, self.kind.json_path()) {
+            // Point clients at the value in the request body that failed to deserialize
+            (Protocol::RestJson1 | Protocol::AwsJson10 | Protocol::AwsJson11, Some(path)) => {
+                let mut body = String::new();
+                let mut object = aws_smithy_json::serialize::JsonObjectWriter::new(&mut body);
+                object
+                    .key("message")
+                    .string(&format!("failed to deserialize the request body at `{}`", path));
+                object.finish();
+                crate::body::to_boxed(body)
+            }
+            (Protocol::RestJson1, None) => crate::body::to_boxed("{}"),
+            (Protocol::RestXml, _) => crate::body::to_boxed(""),
             // See https://awslabs.github.io/smithy/1.0/spec/aws/aws-json-1_0-protocol.html#empty-body-serialization
-            Protocol::AwsJson10 => "",
+            (Protocol::AwsJson10, None) => crate::body::to_boxed(""),
             // See https://awslabs.github.io/smithy/1.0/spec/aws/aws-json-1_1-protocol.html#empty-body-serialization
-            Protocol::AwsJson11 => "",
-        });
+            (Protocol::AwsJson11, None) => crate::body::to_boxed(""),
+            // RpcV2Cbor has no error type header, so the error type is carried in the `__type` field.
+            // See https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html#operation-error-serialization
+            (Protocol::RpcV2Cbor, _) => {
+                let mut body = Vec::new();
+                let mut object = aws_smithy_cbor::encode::CborObjectWriter::new(&mut body);
+                object.key("__type").string(self.kind.name());
+                object.finish();
+                crate::body::to_boxed(body)
+            }
+        };
 
         let mut builder = http::Response::builder();
         builder = builder.status(status_code);
@@ -87,6 +116,11 @@ impl IntoResponse for RuntimeError {
             Protocol::RestXml => builder = builder.header("Content-Type", "application/xml"),
             Protocol::AwsJson10 => builder = builder.header("Content-Type", "application/x-amz-json-1.0"),
             Protocol::AwsJson11 => builder = builder.header("Content-Type", "application/x-amz-json-1.1"),
+            Protocol::RpcV2Cbor => {
+                builder = builder
+                    .header("Content-Type", "application/cbor")
+                    .header("smithy-protocol", "rpc-v2-cbor");
+            }
         }
 
         builder = builder.extension(crate::extension::RuntimeErrorExtension::new(String::from(
@@ -114,3 +148,34 @@ impl From<crate::rejection::RequestRejection> for RuntimeErrorKind {
         RuntimeErrorKind::Serialization(crate::Error::new(err))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{RuntimeError, RuntimeErrorKind};
+    use crate::protocols::Protocol;
+    use crate::rejection::RequestRejection;
+    use crate::response::IntoResponse;
+    use aws_smithy_json::deserialize::{Error, ErrorReason};
+
+    async fn body(protocol: Protocol, kind: RuntimeErrorKind) -> String {
+        let response = RuntimeError { protocol, kind }.into_response();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn json_path_in_body() {
+        let input = br#"{"Items": [{"created": 5}]}"#;
+        let rejection = || {
+            let err = Error::new(ErrorReason::Custom("expected ValueString".into()), Some(23)).with_path_from(input);
+            RuntimeErrorKind::from(RequestRejection::from(err))
+        };
+        let expected = r#"{"message":"failed to deserialize the request body at `$.Items[0].created`"}"#;
+        assert_eq!(expected, body(Protocol::RestJson1, rejection()).await);
+        assert_eq!(expected, body(Protocol::AwsJson11, rejection()).await);
+        assert_eq!("", body(Protocol::RestXml, rejection()).await);
+
+        let without_path = RuntimeErrorKind::from(RequestRejection::MimeParse);
+        assert_eq!("{}", body(Protocol::RestJson1, without_path).await);
+    }
+}
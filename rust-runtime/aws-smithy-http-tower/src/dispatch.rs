@@ -5,18 +5,34 @@
 
 use crate::SendOperationError;
 use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::http_versions::HttpVersionList;
+use aws_smithy_http::interceptor::{Interceptor, Interceptors};
+use aws_smithy_http::metrics::{names, OperationMetrics};
 use aws_smithy_http::operation;
 use aws_smithy_http::result::ConnectorError;
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Instant;
 use tower::{Layer, Service};
 use tracing::trace;
 
 /// Connects Operation driven middleware to an HTTP implementation.
 ///
 /// It will also wrap the error type in OperationError to enable operation middleware
-/// reporting specific errors
+/// reporting specific errors. If the property bag contains the operation's
+/// [`Metadata`](operation::Metadata) or its desired [`HttpVersionList`], they are copied into the
+/// HTTP request's extensions so that connectors can tell which operation a request belongs to and
+/// which HTTP versions it may be sent with.
+///
+/// When the property bag contains a [`SharedMetrics`](aws_smithy_http::metrics::SharedMetrics)
+/// recorder, every request is recorded in the [`ATTEMPTS`](names::ATTEMPTS) and
+/// [`ATTEMPT_DURATION`](names::ATTEMPT_DURATION) metrics, along with the size of its body and the
+/// size of the response body when they are known up front.
+///
+/// [`Interceptors`] registered in the property bag are called right before the request is
+/// transmitted and right after a response was received. Errors returned by the former become
+/// construction errors, errors returned by the latter become dispatch errors.
 #[derive(Clone)]
 pub struct DispatchService<S> {
     inner: S,
@@ -41,20 +57,80 @@ where
     }
 
     fn call(&mut self, req: operation::Request) -> Self::Future {
-        let (req, property_bag) = req.into_parts();
+        let (mut req, property_bag) = req.into_parts();
+        let interceptors = Interceptors::from_properties(&property_bag.acquire());
+        if let Err(err) =
+            interceptors.modify_before_transmit(&mut req, &mut property_bag.acquire_mut())
+        {
+            return Box::pin(async move { Err(SendOperationError::RequestConstructionError(err)) });
+        }
+        let metrics = {
+            let properties = property_bag.acquire();
+            if let Some(metadata) = properties.get::<operation::Metadata>() {
+                req.extensions_mut().insert(metadata.clone());
+            }
+            if let Some(versions) = properties.get::<HttpVersionList>() {
+                req.extensions_mut().insert(versions.clone());
+            }
+            OperationMetrics::from_properties(&properties)
+        };
+        if let (Some(metrics), Some(len)) = (&metrics, req.body().content_length()) {
+            metrics.record(names::REQUEST_BYTES, len as f64, metrics.tags());
+        }
         let mut inner = self.inner.clone();
         let future = async move {
             trace!(request = ?req);
-            inner
-                .call(req)
-                .await
-                .map(|resp| operation::Response::from_parts(resp, property_bag))
-                .map_err(|e| SendOperationError::RequestDispatchError(e.into()))
+            let start = Instant::now();
+            let result = inner.call(req).await.map_err(|e| e.into());
+            if let Some(metrics) = &metrics {
+                record_attempt(metrics, &result, start);
+            }
+            let mut resp = result.map_err(SendOperationError::RequestDispatchError)?;
+            interceptors
+                .modify_before_deserialization(&mut resp, &mut property_bag.acquire_mut())
+                .map_err(|err| {
+                    SendOperationError::RequestDispatchError(ConnectorError::other(err, None))
+                })?;
+            Ok(operation::Response::from_parts(resp, property_bag))
         };
         Box::pin(future)
     }
 }
 
+fn record_attempt(
+    metrics: &OperationMetrics,
+    result: &Result<http::Response<SdkBody>, ConnectorError>,
+    start: Instant,
+) {
+    let tags = match result {
+        Ok(resp) => {
+            let tags = metrics.tags().clone().with_status(resp.status().as_u16());
+            if let Some(len) = resp.body().content_length() {
+                metrics.record(names::RESPONSE_BYTES, len as f64, &tags);
+            }
+            tags
+        }
+        Err(err) => metrics
+            .tags()
+            .clone()
+            .with_error_kind(connector_error_kind(err)),
+    };
+    metrics.increment(names::ATTEMPTS, &tags);
+    metrics.record_duration(names::ATTEMPT_DURATION, start.elapsed(), &tags);
+}
+
+fn connector_error_kind(err: &ConnectorError) -> &'static str {
+    if err.is_timeout() {
+        "timeout"
+    } else if err.is_io() {
+        "io"
+    } else if err.is_user() {
+        "user"
+    } else {
+        "other"
+    }
+}
+
 #[derive(Clone, Default)]
 #[non_exhaustive]
 pub struct DispatchLayer;
@@ -4,6 +4,7 @@
  */
 
 use bytes::Bytes;
+use futures_core::ready;
 use http::{HeaderMap, HeaderValue};
 use http_body::{Body, SizeHint};
 use pin_project::pin_project;
@@ -16,6 +17,8 @@ use std::task::{Context, Poll};
 use crate::callback::BodyCallback;
 use crate::header::append_merge_header_maps;
 
+mod replay;
+
 pub type Error = Box<dyn StdError + Send + Sync>;
 
 /// SdkBody type
@@ -101,6 +104,27 @@ impl SdkBody {
         }
     }
 
+    /// Make this body retryable by buffering up to `max_buffer_size` bytes of it in memory
+    ///
+    /// Bodies that are already retryable are returned unchanged. Otherwise, the data read from this
+    /// body is recorded so that a retry can replay it. If more than `max_buffer_size` bytes are read,
+    /// the recorded data is discarded and a retry fails with an error instead of sending a truncated
+    /// body. This allows small streams, such as request bodies received from another service, to be
+    /// retried without reading them into memory up front.
+    ///
+    /// For data read from the filesystem, prefer [`ByteStream::read_from`](crate::byte_stream::ByteStream::read_from),
+    /// which retries by reading the file again.
+    pub fn with_replay_buffer(mut self, max_buffer_size: usize) -> Self {
+        if self.rebuild.is_some() {
+            return self;
+        }
+        // Callbacks move to the replayable body so that each retry gets a fresh set of them
+        let callbacks = std::mem::take(&mut self.callbacks);
+        let mut body = replay::ReplayBody::replayable(self, max_buffer_size);
+        body.callbacks = callbacks;
+        body
+    }
+
     pub fn taken() -> Self {
         Self {
             inner: Inner::Taken,
@@ -253,11 +277,17 @@ impl http_body::Body for SdkBody {
 
     fn poll_trailers(
         self: Pin<&mut Self>,
-        _cx: &mut Context<'_>,
+        cx: &mut Context<'_>,
     ) -> Poll<Result<Option<HeaderMap<HeaderValue>>, Self::Error>> {
-        let mut header_map = None;
+        let this = self.project();
+        // Trailers of the inner body come first, followed by the trailers of each callback
+        let mut header_map = match this.inner.project() {
+            InnerProj::Streaming(body) => ready!(body.poll_trailers(cx))?,
+            InnerProj::Dyn(box_body) => ready!(box_body.poll_trailers(cx))?,
+            InnerProj::Once(_) | InnerProj::Taken => None,
+        };
         // Iterate over all callbacks, checking each for any `HeaderMap`s
-        for callback in &self.callbacks {
+        for callback in this.callbacks.iter() {
             match callback.trailers() {
                 // If this is the first `HeaderMap` we've encountered, save it
                 Ok(Some(right_header_map)) if header_map.is_none() => {
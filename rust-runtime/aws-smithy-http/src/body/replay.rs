@@ -0,0 +1,219 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Buffered replay of streaming bodies. See [`SdkBody::with_replay_buffer`].
+
+use bytes::Bytes;
+use futures_core::ready;
+use http::HeaderMap;
+use http_body::{Body, SizeHint};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use super::{Error, SdkBody};
+
+/// State shared by every replay of the same body
+struct Shared {
+    source: SdkBody,
+    /// Size hint of `source` before anything was read from it
+    size_hint: SizeHint,
+    /// Chunks read from `source` so far, as long as they fit into `max_buffer_size`
+    buffer: Vec<Bytes>,
+    buffered_bytes: usize,
+    max_buffer_size: usize,
+    /// Number of chunks read from `source`. Once the buffer has overflowed, this is larger than
+    /// `buffer.len()`.
+    chunks_read: usize,
+    overflowed: bool,
+    complete: bool,
+    /// Trailers of `source`, once they were read
+    trailers: Option<Option<HeaderMap>>,
+}
+
+/// A body that records the chunks it reads from a shared source so that it can be replayed
+///
+/// Each replay yields the recorded chunks first and then continues reading from the source.
+pub(super) struct ReplayBody {
+    shared: Arc<Mutex<Shared>>,
+    /// Index of the next chunk this replay yields
+    position: usize,
+}
+
+impl ReplayBody {
+    pub(super) fn replayable(source: SdkBody, max_buffer_size: usize) -> SdkBody {
+        let shared = Arc::new(Mutex::new(Shared {
+            size_hint: source.size_hint(),
+            source,
+            buffer: Vec::new(),
+            buffered_bytes: 0,
+            max_buffer_size,
+            chunks_read: 0,
+            overflowed: false,
+            complete: false,
+            trailers: None,
+        }));
+        SdkBody::retryable(move || {
+            SdkBody::from_dyn(http_body::combinators::BoxBody::new(ReplayBody {
+                shared: shared.clone(),
+                position: 0,
+            }))
+        })
+    }
+}
+
+impl Body for ReplayBody {
+    type Data = Bytes;
+    type Error = Error;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let shared = self.shared.clone();
+        let mut shared = shared.lock().unwrap();
+        if let Some(chunk) = shared.buffer.get(self.position) {
+            let chunk = chunk.clone();
+            self.position += 1;
+            return Poll::Ready(Some(Ok(chunk)));
+        }
+        if self.position < shared.chunks_read {
+            return Poll::Ready(Some(Err(format!(
+                "this body can't be replayed because more than {} bytes were read from it",
+                shared.max_buffer_size
+            )
+            .into())));
+        }
+        if shared.complete {
+            return Poll::Ready(None);
+        }
+        match Pin::new(&mut shared.source).poll_data(cx) {
+            Poll::Ready(Some(Ok(chunk))) => {
+                shared.chunks_read += 1;
+                self.position += 1;
+                if !shared.overflowed {
+                    if shared.buffered_bytes + chunk.len() <= shared.max_buffer_size {
+                        shared.buffered_bytes += chunk.len();
+                        shared.buffer.push(chunk.clone());
+                    } else {
+                        // The body can no longer be replayed, so there's no point holding on to
+                        // the chunks read so far
+                        shared.overflowed = true;
+                        shared.buffer = Vec::new();
+                        shared.buffered_bytes = 0;
+                    }
+                }
+                Poll::Ready(Some(Ok(chunk)))
+            }
+            Poll::Ready(None) => {
+                shared.complete = true;
+                Poll::Ready(None)
+            }
+            other => other,
+        }
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        let mut shared = self.shared.lock().unwrap();
+        if let Some(trailers) = &shared.trailers {
+            return Poll::Ready(Ok(trailers.clone()));
+        }
+        let trailers = ready!(Pin::new(&mut shared.source).poll_trailers(cx))?;
+        shared.trailers = Some(trailers.clone());
+        Poll::Ready(Ok(trailers))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        let shared = self.shared.lock().unwrap();
+        shared.complete && self.position == shared.chunks_read
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.shared.lock().unwrap().size_hint.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::body::SdkBody;
+    use crate::byte_stream::ByteStream;
+    use bytes::Bytes;
+    use http_body::Body;
+
+    fn streaming_body(chunks: &[&'static str]) -> SdkBody {
+        let chunks: Vec<Result<_, std::io::Error>> = chunks
+            .iter()
+            .map(|chunk| Ok(Bytes::from_static(chunk.as_bytes())))
+            .collect();
+        SdkBody::from(hyper::Body::wrap_stream(futures_util::stream::iter(chunks)))
+    }
+
+    async fn read_all(body: SdkBody) -> Result<String, crate::byte_stream::Error> {
+        let data = ByteStream::new(body).collect().await?.into_bytes();
+        Ok(String::from_utf8(data.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn small_streams_can_be_replayed() {
+        let body = streaming_body(&["hello", " ", "world"]).with_replay_buffer(64);
+        let first = body.try_clone().expect("replayable");
+        let second = body.try_clone().expect("replayable");
+        assert_eq!("hello world", read_all(first).await.unwrap());
+        assert_eq!("hello world", read_all(second).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn partially_read_streams_can_be_replayed() {
+        let body = streaming_body(&["hello", " ", "world"]).with_replay_buffer(64);
+        let mut first = body.try_clone().expect("replayable");
+        let chunk = first.data().await.unwrap().unwrap();
+        assert_eq!("hello", chunk);
+        drop(first);
+        // the retry replays the first chunk and then reads the rest from the source
+        assert_eq!("hello world", read_all(body).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn replay_fails_when_the_buffer_overflows() {
+        let body = streaming_body(&["hello", " ", "world"]).with_replay_buffer(8);
+        let first = body.try_clone().expect("replayable");
+        // the first attempt is unaffected by the buffer size
+        assert_eq!("hello world", read_all(first).await.unwrap());
+        let err = read_all(body).await.expect_err("buffer overflowed");
+        assert!(
+            format!("{:?}", err).contains("more than 8 bytes"),
+            "{:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn trailers_are_replayed() {
+        let mut trailers = http::HeaderMap::new();
+        trailers.insert("x-amz-checksum-crc32", "abcd".parse().unwrap());
+        let (mut sender, source) = hyper::Body::channel();
+        sender
+            .try_send_data(Bytes::from_static(b"hello"))
+            .expect("channel has capacity");
+        sender.send_trailers(trailers.clone()).await.unwrap();
+        drop(sender);
+        let body = SdkBody::from(source).with_replay_buffer(64);
+        for _ in 0..2 {
+            let mut attempt = body.try_clone().expect("replayable");
+            assert_eq!("hello", attempt.data().await.unwrap().unwrap());
+            assert!(attempt.data().await.is_none());
+            assert_eq!(Some(&trailers), attempt.trailers().await.unwrap().as_ref());
+        }
+    }
+
+    #[test]
+    fn retryable_bodies_are_unchanged() {
+        let body = SdkBody::from("hello").with_replay_buffer(1);
+        assert_eq!(Some(&b"hello"[..]), body.bytes());
+    }
+}
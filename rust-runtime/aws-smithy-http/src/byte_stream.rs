@@ -74,6 +74,9 @@
 //! }
 //! ```
 //!
+//! With `rt-tokio` enabled, [`ByteStream::write_to_file`] does the same in a single call, and
+//! [`ByteStream::into_async_read`] adapts a ByteStream to `tokio::io::AsyncRead`.
+//!
 //! ### Create a ByteStream from a file
 //!
 //! _Note: This is only available with `rt-tokio` enabled._
@@ -131,8 +134,11 @@ use std::error::Error as StdError;
 use std::fmt::{Debug, Formatter};
 use std::io::IoSlice;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
+#[cfg(feature = "rt-tokio")]
+mod async_io;
 #[cfg(feature = "rt-tokio")]
 mod bytestream_util;
 #[cfg(feature = "rt-tokio")]
@@ -328,8 +334,8 @@ impl ByteStream {
 
     /// Create a ByteStream from a file
     ///
-    /// NOTE: This will NOT result in a retryable ByteStream. For a ByteStream that can be retried in the case of
-    /// upstream failures, use [`ByteStream::from_path`](ByteStream::from_path)
+    /// The returned ByteStream is retryable: if the request fails, the file is seeked back to the
+    /// position it was at when this function was called and read again.
     #[deprecated(
         since = "0.40.0",
         note = "Prefer the more extensible ByteStream::read_from() API"
@@ -340,6 +346,44 @@ impl ByteStream {
         FsBuilder::new().file(file).build().await
     }
 
+    /// Make this `ByteStream` retryable by buffering up to `max_buffer_size` bytes of it in memory
+    ///
+    /// See [`SdkBody::with_replay_buffer`] for details.
+    pub fn with_replay_buffer(self, max_buffer_size: usize) -> Self {
+        Self::new(self.into_inner().with_replay_buffer(max_buffer_size))
+    }
+
+    /// Call `progress` each time a chunk of data is read from this `ByteStream`
+    ///
+    /// This works for every way of reading the data, including [`collect`](ByteStream::collect),
+    /// `write_to_file` and `into_async_read`. If this `ByteStream` is used as a request body, the
+    /// count restarts from zero when the request is retried.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use aws_smithy_http::byte_stream::ByteStream;
+    /// async fn download(mut stream: ByteStream) {
+    ///     stream.on_progress(|progress| match progress.total_bytes() {
+    ///         Some(total) => println!("read {} of {} bytes", progress.bytes_read(), total),
+    ///         None => println!("read {} bytes", progress.bytes_read()),
+    ///     });
+    ///     let _data = stream.collect().await;
+    /// }
+    /// ```
+    pub fn on_progress(
+        &mut self,
+        progress: impl Fn(&Progress) + Send + Sync + 'static,
+    ) -> &mut Self {
+        let total_bytes = self.0.body.size_hint().exact();
+        self.with_body_callback(Box::new(ProgressCallback {
+            progress: Progress {
+                bytes_read: 0,
+                total_bytes,
+            },
+            callback: Arc::new(progress),
+        }))
+    }
+
     /// Set a callback on this `ByteStream`. The callback's methods will be called at various points
     /// throughout this `ByteStream`'s life cycle. See the [`BodyCallback`](BodyCallback) trait for
     /// more information.
@@ -388,6 +432,48 @@ impl From<hyper::Body> for ByteStream {
     }
 }
 
+/// Progress of reading a [`ByteStream`], passed to the callback set with [`ByteStream::on_progress`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    bytes_read: u64,
+    total_bytes: Option<u64>,
+}
+
+impl Progress {
+    /// Number of bytes read so far
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    /// Total number of bytes in the stream, if it is known up front
+    pub fn total_bytes(&self) -> Option<u64> {
+        self.total_bytes
+    }
+}
+
+struct ProgressCallback {
+    progress: Progress,
+    callback: Arc<dyn Fn(&Progress) + Send + Sync>,
+}
+
+impl BodyCallback for ProgressCallback {
+    fn update(&mut self, bytes: &[u8]) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        self.progress.bytes_read += bytes.len() as u64;
+        (self.callback)(&self.progress);
+        Ok(())
+    }
+
+    fn make_new(&self) -> Box<dyn BodyCallback> {
+        Box::new(ProgressCallback {
+            progress: Progress {
+                bytes_read: 0,
+                total_bytes: self.progress.total_bytes,
+            },
+            callback: self.callback.clone(),
+        })
+    }
+}
+
 #[derive(Debug)]
 pub struct Error(Box<dyn StdError + Send + Sync + 'static>);
 
@@ -0,0 +1,316 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Interop between [`ByteStream`] and Tokio's [`AsyncRead`]/[`AsyncWrite`] traits
+
+use bytes::Bytes;
+use futures_core::{ready, Stream};
+use http::HeaderMap;
+use http_body::{Body, SizeHint};
+use pin_project::pin_project;
+use std::io;
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio_util::io::{ReaderStream, StreamReader};
+
+use crate::body::SdkBody;
+
+use super::{ByteStream, Error};
+
+impl ByteStream {
+    /// Create a ByteStream that reads data from `reader`
+    ///
+    /// The returned ByteStream is NOT retryable and provides no size hint. If the length of the data
+    /// is known, use [`from_async_read_with_length`](ByteStream::from_async_read_with_length) instead.
+    /// To retry small streams, see [`with_replay_buffer`](ByteStream::with_replay_buffer).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use aws_smithy_http::byte_stream::ByteStream;
+    /// async fn from_pipe() -> std::io::Result<ByteStream> {
+    ///     let pipe = tokio::fs::File::open("/tmp/some-named-pipe").await?;
+    ///     Ok(ByteStream::from_async_read(pipe))
+    /// }
+    /// ```
+    pub fn from_async_read(reader: impl AsyncRead + Send + Sync + 'static) -> Self {
+        Self::new(SdkBody::from_dyn(http_body::combinators::BoxBody::new(
+            AsyncReadBody::new(reader, None),
+        )))
+    }
+
+    /// Create a ByteStream that reads exactly `length` bytes from `reader`
+    ///
+    /// The length is used as the size hint of the body, so requests can be sent with a
+    /// `Content-Length`. Reading fails if `reader` ends before `length` bytes were read.
+    pub fn from_async_read_with_length(
+        reader: impl AsyncRead + Send + Sync + 'static,
+        length: u64,
+    ) -> Self {
+        Self::new(SdkBody::from_dyn(http_body::combinators::BoxBody::new(
+            AsyncReadBody::new(reader, Some(length)),
+        )))
+    }
+
+    /// Convert this ByteStream into a type that implements [`AsyncRead`] and [`AsyncBufRead`]
+    ///
+    /// Errors in the underlying stream are returned as [`io::Error`]s.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use aws_smithy_http::byte_stream::ByteStream;
+    /// use tokio::io::AsyncBufReadExt;
+    /// async fn count_lines(stream: ByteStream) -> std::io::Result<usize> {
+    ///     let mut lines = stream.into_async_read().lines();
+    ///     let mut count = 0;
+    ///     while lines.next_line().await?.is_some() {
+    ///         count += 1;
+    ///     }
+    ///     Ok(count)
+    /// }
+    /// ```
+    pub fn into_async_read(self) -> impl AsyncBufRead + Send + Sync {
+        StreamReader::new(IoStream(self))
+    }
+
+    /// Write all the data from this ByteStream to `writer`, returning the number of bytes written
+    ///
+    /// `writer` is flushed once all data has been written. To report progress while writing, see
+    /// [`on_progress`](ByteStream::on_progress).
+    pub async fn write_to<W>(self, writer: &mut W) -> Result<u64, Error>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let mut written = 0;
+        let body = self.into_inner();
+        crate::pin_mut!(body);
+        while let Some(bytes) = body.data().await {
+            let bytes = bytes.map_err(Error)?;
+            writer
+                .write_all(&bytes)
+                .await
+                .map_err(|err| Error(err.into()))?;
+            written += bytes.len() as u64;
+        }
+        writer.flush().await.map_err(|err| Error(err.into()))?;
+        Ok(written)
+    }
+
+    /// Write all the data from this ByteStream to the file at `path`, returning the number of bytes written
+    ///
+    /// The file is created if it doesn't exist, and truncated if it does. Data is streamed to the
+    /// file chunk by chunk rather than read into memory first.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use aws_smithy_http::byte_stream::ByteStream;
+    /// async fn download(stream: ByteStream) -> Result<(), aws_smithy_http::byte_stream::Error> {
+    ///     let mut stream = stream;
+    ///     stream.on_progress(|progress| {
+    ///         println!("{} of {:?} bytes", progress.bytes_read(), progress.total_bytes())
+    ///     });
+    ///     stream.write_to_file("download.bin").await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn write_to_file(self, path: impl AsRef<Path>) -> Result<u64, Error> {
+        let mut file = tokio::fs::File::create(path)
+            .await
+            .map_err(|err| Error(err.into()))?;
+        self.write_to(&mut file).await
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        match err.0.downcast::<io::Error>() {
+            Ok(err) => *err,
+            Err(err) => io::Error::new(io::ErrorKind::Other, err),
+        }
+    }
+}
+
+/// Adapts a [`ByteStream`] to the item type required by [`StreamReader`]
+struct IoStream(ByteStream);
+
+impl Stream for IoStream {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.0)
+            .poll_next(cx)
+            .map(|item| item.map(|item| item.map_err(io::Error::from)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// An HTTP Body that reads from an [`AsyncRead`]
+#[pin_project]
+struct AsyncReadBody<R> {
+    #[pin]
+    stream: ReaderStream<tokio::io::Take<R>>,
+    length: Option<u64>,
+    remaining: u64,
+}
+
+impl<R: AsyncRead> AsyncReadBody<R> {
+    fn new(reader: R, length: Option<u64>) -> Self {
+        Self {
+            stream: ReaderStream::new(reader.take(length.unwrap_or(u64::MAX))),
+            length,
+            remaining: length.unwrap_or(u64::MAX),
+        }
+    }
+}
+
+impl<R: AsyncRead> Body for AsyncReadBody<R> {
+    type Data = Bytes;
+    type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let this = self.project();
+        match ready!(this.stream.poll_next(cx)) {
+            Some(Ok(bytes)) => {
+                *this.remaining -= bytes.len() as u64;
+                Poll::Ready(Some(Ok(bytes)))
+            }
+            Some(Err(err)) => Poll::Ready(Some(Err(err.into()))),
+            None if this.length.is_some() && *this.remaining > 0 => {
+                Poll::Ready(Some(Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "reader ended {} bytes before the expected length",
+                        this.remaining
+                    ),
+                )
+                .into())))
+            }
+            None => Poll::Ready(None),
+        }
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        Poll::Ready(Ok(None))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.length == Some(0)
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        match self.length {
+            Some(length) => SizeHint::with_exact(length),
+            None => SizeHint::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::body::SdkBody;
+    use crate::byte_stream::ByteStream;
+    use http_body::Body;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+
+    fn streaming(chunks: &[&'static str]) -> ByteStream {
+        let chunks: Vec<Result<_, std::io::Error>> = chunks
+            .iter()
+            .map(|chunk| Ok(bytes::Bytes::from_static(chunk.as_bytes())))
+            .collect();
+        ByteStream::new(SdkBody::from(hyper::Body::wrap_stream(
+            futures_util::stream::iter(chunks),
+        )))
+    }
+
+    #[tokio::test]
+    async fn into_async_read() {
+        let mut lines = streaming(&["line 1\nli", "ne 2\n"])
+            .into_async_read()
+            .lines();
+        assert_eq!(Some("line 1".to_string()), lines.next_line().await.unwrap());
+        assert_eq!(Some("line 2".to_string()), lines.next_line().await.unwrap());
+        assert_eq!(None, lines.next_line().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn into_async_read_surfaces_io_errors() {
+        let chunks: Vec<Result<bytes::Bytes, std::io::Error>> = vec![Err(std::io::Error::new(
+            std::io::ErrorKind::ConnectionReset,
+            "reset",
+        ))];
+        let stream = ByteStream::from(hyper::Body::wrap_stream(futures_util::stream::iter(chunks)));
+        let mut buf = Vec::new();
+        let err = stream
+            .into_async_read()
+            .read_to_end(&mut buf)
+            .await
+            .expect_err("stream failed");
+        assert_eq!(std::io::ErrorKind::ConnectionReset, err.kind());
+    }
+
+    #[tokio::test]
+    async fn write_to_file_reports_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("output");
+        let progress = Arc::new(Mutex::new(Vec::new()));
+
+        let mut stream = ByteStream::from_static(b"hello world");
+        stream.on_progress({
+            let progress = progress.clone();
+            move |p| {
+                progress
+                    .lock()
+                    .unwrap()
+                    .push((p.bytes_read(), p.total_bytes()))
+            }
+        });
+        let written = stream.write_to_file(&path).await.unwrap();
+
+        assert_eq!(11, written);
+        assert_eq!(b"hello world".to_vec(), std::fs::read(&path).unwrap());
+        assert_eq!(vec![(11, Some(11))], *progress.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn write_to() {
+        let mut output = Vec::new();
+        let written = streaming(&["a", "bc"]).write_to(&mut output).await.unwrap();
+        assert_eq!(3, written);
+        assert_eq!(b"abc".to_vec(), output);
+    }
+
+    #[tokio::test]
+    async fn from_async_read() {
+        let stream = ByteStream::from_async_read(&b"some data"[..]);
+        assert_eq!(None, futures_core::Stream::size_hint(&stream).1);
+        let data = stream.collect().await.unwrap().into_bytes();
+        assert_eq!("some data", data);
+    }
+
+    #[tokio::test]
+    async fn from_async_read_with_length() {
+        let body = ByteStream::from_async_read_with_length(&b"some data"[..], 4).into_inner();
+        assert_eq!(Some(4), body.size_hint().exact());
+        let data = ByteStream::new(body).collect().await.unwrap().into_bytes();
+        assert_eq!("some", data);
+
+        let err = ByteStream::from_async_read_with_length(&b"short"[..], 10)
+            .collect()
+            .await
+            .expect_err("reader is too short");
+        assert!(format!("{}", err).contains("5 bytes before the expected length"));
+    }
+}
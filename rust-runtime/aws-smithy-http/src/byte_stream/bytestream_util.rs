@@ -10,6 +10,7 @@ use http_body::{Body, SizeHint};
 use std::future::Future;
 use std::path::PathBuf;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use tokio::fs::File;
 use tokio::io::{self, AsyncReadExt, AsyncSeekExt};
@@ -28,7 +29,8 @@ const DEFAULT_OFFSET: u64 = 0;
 ///
 /// PathBody is a three-phase HTTP body designed to wrap files with three specific features:
 /// 1. The underlying file is wrapped with StreamReader to implement HTTP body
-/// 2. It can be constructed directly from a path so it's easy to use during retries
+/// 2. It can be constructed directly from a path or a shared file handle so it's easy to use
+///    during retries
 /// 3. Provide size hint
 struct PathBody {
     state: State,
@@ -49,13 +51,20 @@ impl PathBody {
         }
     }
 
-    fn from_file(file: File, length: u64, buffer_size: usize) -> Self {
+    fn from_file(file: Arc<std::fs::File>, length: u64, buffer_size: usize, offset: u64) -> Self {
         PathBody {
-            state: State::Loaded(ReaderStream::with_capacity(file.take(length), buffer_size)),
+            // The file handle is shared between attempts, so each attempt reads at an explicit
+            // position, starting where the first attempt started reading, rather than relying on
+            // wherever a previous attempt left the file's cursor
+            state: State::ReadingAt {
+                file,
+                position: offset,
+                remaining: length,
+                read: None,
+            },
             length,
             buffer_size,
-            /// The file used to create this `PathBody` should have already had an offset applied
-            offset: None,
+            offset: Some(offset),
         }
     }
 }
@@ -135,8 +144,9 @@ impl FsBuilder {
 
     /// Sets the file to read from.
     ///
-    /// NOTE: The resulting ByteStream (after calling [build](FsBuilder::build)) will not be a retryable ByteStream.
-    /// For a ByteStream that can be retried in the case of upstream failures, use [`FsBuilder::path`](FsBuilder::path).
+    /// NOTE: The resulting ByteStream (after calling [build](FsBuilder::build)) will be retryable.
+    /// Reading starts at [`offset`](FsBuilder::offset) if one is set, and at the current position of
+    /// `file` otherwise. If the request fails, the file is read again from that position.
     pub fn file(mut self, file: tokio::fs::File) -> Self {
         self.file = Some(file);
         self
@@ -170,13 +180,22 @@ impl FsBuilder {
     }
 
     /// Returns a [`ByteStream`](crate::byte_stream::ByteStream) from this builder.
-    pub async fn build(self) -> Result<ByteStream, Error> {
+    pub async fn build(mut self) -> Result<ByteStream, Error> {
         if self.path.is_some() && self.file.is_some() {
             panic!("The 'file' and 'path' options on an FsBuilder are mutually exclusive but both were set. Please set only one")
         };
 
         let buffer_size = self.buffer_size;
-        let offset = self.offset.unwrap_or(DEFAULT_OFFSET);
+        let offset = match (self.offset, self.file.as_mut()) {
+            (Some(offset), _) => offset,
+            // Without an explicit offset, a file is read from its current position. Record it so
+            // that retries can seek back to it.
+            (None, Some(file)) => file
+                .seek(std::io::SeekFrom::Current(0))
+                .await
+                .map_err(|err| Error(err.into()))?,
+            (None, None) => DEFAULT_OFFSET,
+        };
         // Checking the file length like this does have a cost, but the benefit is that we can
         // notify users when file/chunk is smaller than expected.
         let file_length = self.get_file_size().await?;
@@ -214,20 +233,21 @@ impl FsBuilder {
             };
 
             Ok(ByteStream::new(SdkBody::retryable(body_loader)))
-        } else if let Some(mut file) = self.file {
-            // When starting from a `File`, we need to do our own seeking
-            if offset != 0 {
-                let _s = file
-                    .seek(std::io::SeekFrom::Start(offset))
-                    .await
-                    .map_err(|err| Error(err.into()))?;
-            }
-
-            let body = SdkBody::from_dyn(http_body::combinators::BoxBody::new(
-                PathBody::from_file(file, length, buffer_size),
-            ));
+        } else if let Some(file) = self.file {
+            // Attempts share the file handle and read it with positional reads, so attempts that
+            // overlap (such as a retry while the body of a previous attempt is still alive) don't
+            // move each other's position in the file.
+            let file = Arc::new(file.into_std().await);
+            let body_loader = move || {
+                SdkBody::from_dyn(http_body::combinators::BoxBody::new(PathBody::from_file(
+                    file.clone(),
+                    length,
+                    buffer_size,
+                    offset,
+                )))
+            };
 
-            Ok(ByteStream::new(body))
+            Ok(ByteStream::new(SdkBody::retryable(body_loader)))
         } else {
             panic!("FsBuilder constructed without a file or a path")
         }
@@ -246,10 +266,45 @@ impl FsBuilder {
 
 enum State {
     Unloaded(PathBuf),
+    /// Reading a shared file handle at `position`, with `read` in progress if it's `Some`
+    ReadingAt {
+        file: Arc<std::fs::File>,
+        position: u64,
+        remaining: u64,
+        read: Option<tokio::task::JoinHandle<io::Result<Bytes>>>,
+    },
     Loading(Pin<Box<dyn Future<Output = io::Result<File>> + Send + Sync + 'static>>),
     Loaded(tokio_util::io::ReaderStream<io::Take<File>>),
 }
 
+/// Reads up to `len` bytes of `file` starting at `position`.
+///
+/// The read doesn't depend on the file's cursor, but only Unix leaves it untouched: on Windows,
+/// and on other platforms where this falls back to seeking before reading, the cursor is moved
+/// to the end of the read.
+fn read_at(file: &std::fs::File, position: u64, len: usize) -> io::Result<Bytes> {
+    let mut buffer = vec![0; len];
+    let read = loop {
+        #[cfg(unix)]
+        let result = std::os::unix::fs::FileExt::read_at(file, &mut buffer, position);
+        #[cfg(windows)]
+        let result = std::os::windows::fs::FileExt::seek_read(file, &mut buffer, position);
+        #[cfg(not(any(unix, windows)))]
+        let result = {
+            use std::io::{Read, Seek};
+            let mut file = file;
+            file.seek(io::SeekFrom::Start(position))
+                .and_then(|_| file.read(&mut buffer))
+        };
+        match result {
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            result => break result?,
+        }
+    };
+    buffer.truncate(read);
+    Ok(buffer.into())
+}
+
 impl Body for PathBody {
     type Data = Bytes;
     type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
@@ -259,6 +314,7 @@ impl Body for PathBody {
         cx: &mut Context<'_>,
     ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
         let offset = self.offset.unwrap_or(DEFAULT_OFFSET);
+        let buffer_size = self.buffer_size;
         loop {
             match self.state {
                 State::Unloaded(ref path_buf) => {
@@ -273,6 +329,37 @@ impl Body for PathBody {
                         Ok(file)
                     }));
                 }
+                State::ReadingAt {
+                    ref file,
+                    ref mut position,
+                    ref mut remaining,
+                    ref mut read,
+                } => {
+                    if *remaining == 0 {
+                        return Poll::Ready(None);
+                    }
+                    let read_handle = read.get_or_insert_with(|| {
+                        let (file, read_position) = (file.clone(), *position);
+                        let len = (*remaining).min(buffer_size as u64) as usize;
+                        tokio::task::spawn_blocking(move || read_at(&file, read_position, len))
+                    });
+                    let result = ready!(Pin::new(read_handle).poll(cx));
+                    *read = None;
+                    return match result {
+                        Ok(Ok(bytes)) if bytes.is_empty() => {
+                            // The file is shorter than expected
+                            *remaining = 0;
+                            Poll::Ready(None)
+                        }
+                        Ok(Ok(bytes)) => {
+                            *position += bytes.len() as u64;
+                            *remaining -= bytes.len() as u64;
+                            Poll::Ready(Some(Ok(bytes)))
+                        }
+                        Ok(Err(err)) => Poll::Ready(Some(Err(err.into()))),
+                        Err(err) => Poll::Ready(Some(Err(err.into()))),
+                    };
+                }
                 State::Loading(ref mut future) => {
                     match ready!(Pin::new(future).poll(cx)) {
                         Ok(file) => {
@@ -363,6 +450,66 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn file_based_bytestreams_are_retryable() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "Line 0\nLine 1\n").unwrap();
+        file.flush().expect("flushing is OK");
+
+        let mut tokio_file = tokio::fs::File::from_std(file.reopen().unwrap());
+        // Without an offset, the file is read from its current position
+        tokio::io::AsyncSeekExt::seek(&mut tokio_file, std::io::SeekFrom::Start(7))
+            .await
+            .unwrap();
+        let body = FsBuilder::new()
+            .file(tokio_file)
+            .build()
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(body.size_hint().exact(), Some(7));
+
+        let body1 = body.try_clone().expect("retryable bodies are cloneable");
+        let data = ByteStream::new(body1).collect().await.unwrap().into_bytes();
+        assert_eq!(&data[..], b"Line 1\n");
+        // A retry seeks back to the original position
+        let data = ByteStream::new(body).collect().await.unwrap().into_bytes();
+        assert_eq!(&data[..], b"Line 1\n");
+    }
+
+    #[tokio::test]
+    async fn overlapping_attempts_on_a_file_do_not_interfere() {
+        let mut file = NamedTempFile::new().unwrap();
+        for i in 0..1000 {
+            writeln!(file, "Line {}", i).unwrap();
+        }
+        file.flush().expect("flushing is OK");
+        let expected = std::fs::read(file.path()).unwrap();
+
+        let body = FsBuilder::new()
+            .file(tokio::fs::File::from_std(file.reopen().unwrap()))
+            .buffer_size(16)
+            .build()
+            .await
+            .unwrap()
+            .into_inner();
+        let mut first = body.try_clone().expect("retryable bodies are cloneable");
+        let mut second = body.try_clone().expect("retryable bodies are cloneable");
+        let (mut first_data, mut second_data) = (Vec::<u8>::new(), Vec::<u8>::new());
+        // Interleave the reads of both attempts
+        loop {
+            let first_chunk = first.data().await.transpose().unwrap();
+            let second_chunk = second.data().await.transpose().unwrap();
+            if first_chunk.is_none() && second_chunk.is_none() {
+                break;
+            }
+            first_data.extend(first_chunk.iter().flat_map(|chunk| chunk.iter()));
+            second_data.extend(second_chunk.iter().flat_map(|chunk| chunk.iter()));
+        }
+        assert_eq!(expected, first_data);
+        assert_eq!(expected, second_data);
+    }
+
     #[tokio::test]
     async fn fsbuilder_length_is_used_as_size_hint() {
         let mut file = NamedTempFile::new().unwrap();
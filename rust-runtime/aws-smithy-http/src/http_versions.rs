@@ -8,10 +8,38 @@
 use http::Version as HttpVersion;
 use once_cell::sync::Lazy;
 
-/// A list of supported or desired HttpVersions. Typically use when requesting an HTTP Client from a
-/// client cache.
+/// A list of supported or desired HttpVersions, in order of preference
+///
+/// Generated operations insert the list they require into the operation's property bag. The
+/// dispatch layer copies it into the extensions of the outgoing HTTP request so that connectors
+/// can pick a suitably configured HTTP client, e.g. an HTTP/2-only client for bidirectional
+/// event streams.
 pub type HttpVersionList = Vec<HttpVersion>;
 
 /// The default list of desired HTTP protocol versions to use when making requests
 pub static DEFAULT_HTTP_VERSION_LIST: Lazy<HttpVersionList> =
     Lazy::new(|| vec![HttpVersion::HTTP_11]);
+
+/// Returns true if a request that wants `versions` must be sent over HTTP/2
+///
+/// This is the case when HTTP/2 is the only version in the list.
+pub fn requires_http2(versions: &[HttpVersion]) -> bool {
+    !versions.is_empty()
+        && versions
+            .iter()
+            .all(|version| *version == HttpVersion::HTTP_2)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{requires_http2, DEFAULT_HTTP_VERSION_LIST};
+    use http::Version;
+
+    #[test]
+    fn http2_is_only_required_when_nothing_else_is_acceptable() {
+        assert!(!requires_http2(&DEFAULT_HTTP_VERSION_LIST));
+        assert!(!requires_http2(&[]));
+        assert!(!requires_http2(&[Version::HTTP_11, Version::HTTP_2]));
+        assert!(requires_http2(&[Version::HTTP_2]));
+    }
+}
@@ -0,0 +1,295 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Hooks into the lifecycle of an operation
+//!
+//! An [`Interceptor`] is called at fixed points while an operation is sent, and can read or
+//! modify the HTTP request and response as well as the operation's [`PropertyBag`]. Unlike a
+//! [`MapRequest`](crate::middleware::MapRequest), interceptors don't change the type of the
+//! client's middleware, so they can be registered at runtime.
+//!
+//! Interceptors are registered in the property bag of an operation as [`Interceptors`], either
+//! directly with [`Operation::with_interceptor`](crate::operation::Operation::with_interceptor)
+//! or by a client that adds its own interceptors to every operation it sends. For every
+//! operation, the hooks are called in this order:
+//!
+//! 1. [`read_after_serialization`](Interceptor::read_after_serialization), once
+//! 2. For every attempt:
+//!     1. [`modify_before_signing`](Interceptor::modify_before_signing), before the client's
+//!        middleware (which signs the request) runs
+//!     2. [`modify_before_transmit`](Interceptor::modify_before_transmit), after the middleware
+//!        ran, right before the request is handed to the connector
+//!     3. [`modify_before_deserialization`](Interceptor::modify_before_deserialization), if a
+//!        response was received
+//!     4. [`read_after_attempt`](Interceptor::read_after_attempt)
+//! 3. [`read_after_execution`](Interceptor::read_after_execution), once
+//!
+//! # Examples
+//! ```
+//! use aws_smithy_http::body::SdkBody;
+//! use aws_smithy_http::interceptor::Interceptor;
+//! use aws_smithy_http::property_bag::PropertyBag;
+//! use std::error::Error;
+//!
+//! /// Adds a header to every attempt
+//! #[derive(Debug)]
+//! struct AddTraceId(String);
+//!
+//! impl Interceptor for AddTraceId {
+//!     fn modify_before_transmit(
+//!         &self,
+//!         request: &mut http::Request<SdkBody>,
+//!         _properties: &mut PropertyBag,
+//!     ) -> Result<(), Box<dyn Error + Send + Sync>> {
+//!         request.headers_mut().insert("x-trace-id", self.0.parse()?);
+//!         Ok(())
+//!     }
+//! }
+//! ```
+
+use crate::body::SdkBody;
+use crate::property_bag::PropertyBag;
+use std::error::Error;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+/// Hooks called while an operation is sent
+///
+/// All hooks do nothing by default. Hooks that return an error fail the operation: errors
+/// returned before the request was transmitted become construction failures, and errors returned
+/// by [`modify_before_deserialization`](Interceptor::modify_before_deserialization) become
+/// dispatch failures. Hooks are called from within the request future, so they must not block.
+pub trait Interceptor: Debug + Send + Sync {
+    /// Called once, after the operation's input was serialized into an HTTP request
+    fn read_after_serialization(
+        &self,
+        _request: &http::Request<SdkBody>,
+        _properties: &PropertyBag,
+    ) -> Result<(), BoxError> {
+        Ok(())
+    }
+
+    /// Called before every attempt, before the request is passed to the client's middleware
+    ///
+    /// Changes made here are visible to the middleware, so e.g. added headers are signed.
+    fn modify_before_signing(
+        &self,
+        _request: &mut http::Request<SdkBody>,
+        _properties: &mut PropertyBag,
+    ) -> Result<(), BoxError> {
+        Ok(())
+    }
+
+    /// Called before every attempt, after the client's middleware ran and right before the
+    /// request is transmitted
+    fn modify_before_transmit(
+        &self,
+        _request: &mut http::Request<SdkBody>,
+        _properties: &mut PropertyBag,
+    ) -> Result<(), BoxError> {
+        Ok(())
+    }
+
+    /// Called after every attempt that received a response, before the response is deserialized
+    fn modify_before_deserialization(
+        &self,
+        _response: &mut http::Response<SdkBody>,
+        _properties: &mut PropertyBag,
+    ) -> Result<(), BoxError> {
+        Ok(())
+    }
+
+    /// Called after every attempt, once its response was deserialized or the attempt failed
+    ///
+    /// `response` is `None` if no response was received, e.g. because of a timeout.
+    fn read_after_attempt(
+        &self,
+        _response: Option<&http::Response<SdkBody>>,
+        _properties: &PropertyBag,
+    ) {
+    }
+
+    /// Called once, after the last attempt
+    ///
+    /// `response` is the response of the last attempt, or `None` if no response was received.
+    fn read_after_execution(
+        &self,
+        _response: Option<&http::Response<SdkBody>>,
+        _properties: &PropertyBag,
+    ) {
+    }
+}
+
+/// An ordered list of [`Interceptor`]s
+///
+/// `Interceptors` is itself an [`Interceptor`] that calls every interceptor in the list in the
+/// order they were added. Hooks that can fail stop at the first interceptor that returns an
+/// error. `Interceptors` is cheap to clone.
+#[derive(Clone, Debug, Default)]
+pub struct Interceptors(Vec<Arc<dyn Interceptor>>);
+
+impl Interceptors {
+    /// Create an empty list of interceptors
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `interceptor` to the end of the list
+    pub fn push(&mut self, interceptor: impl Interceptor + 'static) {
+        self.0.push(Arc::new(interceptor));
+    }
+
+    /// Add `interceptor` to the end of the list
+    pub fn with(mut self, interceptor: impl Interceptor + 'static) -> Self {
+        self.push(interceptor);
+        self
+    }
+
+    /// Add all interceptors of `other` to the end of the list
+    pub fn extend(&mut self, other: &Interceptors) {
+        self.0.extend(other.0.iter().cloned());
+    }
+
+    /// Returns true if the list contains no interceptors
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The number of interceptors in the list
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Load the interceptors registered in an operation's property bag
+    pub fn from_properties(properties: &PropertyBag) -> Self {
+        properties
+            .get::<Interceptors>()
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+impl Interceptor for Interceptors {
+    fn read_after_serialization(
+        &self,
+        request: &http::Request<SdkBody>,
+        properties: &PropertyBag,
+    ) -> Result<(), BoxError> {
+        self.0
+            .iter()
+            .try_for_each(|i| i.read_after_serialization(request, properties))
+    }
+
+    fn modify_before_signing(
+        &self,
+        request: &mut http::Request<SdkBody>,
+        properties: &mut PropertyBag,
+    ) -> Result<(), BoxError> {
+        self.0
+            .iter()
+            .try_for_each(|i| i.modify_before_signing(request, properties))
+    }
+
+    fn modify_before_transmit(
+        &self,
+        request: &mut http::Request<SdkBody>,
+        properties: &mut PropertyBag,
+    ) -> Result<(), BoxError> {
+        self.0
+            .iter()
+            .try_for_each(|i| i.modify_before_transmit(request, properties))
+    }
+
+    fn modify_before_deserialization(
+        &self,
+        response: &mut http::Response<SdkBody>,
+        properties: &mut PropertyBag,
+    ) -> Result<(), BoxError> {
+        self.0
+            .iter()
+            .try_for_each(|i| i.modify_before_deserialization(response, properties))
+    }
+
+    fn read_after_attempt(
+        &self,
+        response: Option<&http::Response<SdkBody>>,
+        properties: &PropertyBag,
+    ) {
+        for interceptor in &self.0 {
+            interceptor.read_after_attempt(response, properties);
+        }
+    }
+
+    fn read_after_execution(
+        &self,
+        response: Option<&http::Response<SdkBody>>,
+        properties: &PropertyBag,
+    ) {
+        for interceptor in &self.0 {
+            interceptor.read_after_execution(response, properties);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Interceptor, Interceptors};
+    use crate::body::SdkBody;
+    use crate::property_bag::PropertyBag;
+    use std::error::Error;
+
+    #[derive(Debug)]
+    struct AppendHeader(&'static str);
+
+    impl Interceptor for AppendHeader {
+        fn modify_before_transmit(
+            &self,
+            request: &mut http::Request<SdkBody>,
+            _properties: &mut PropertyBag,
+        ) -> Result<(), Box<dyn Error + Send + Sync>> {
+            request
+                .headers_mut()
+                .append("x-interceptor", http::HeaderValue::from_static(self.0));
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct Fail;
+
+    impl Interceptor for Fail {
+        fn modify_before_transmit(
+            &self,
+            _request: &mut http::Request<SdkBody>,
+            _properties: &mut PropertyBag,
+        ) -> Result<(), Box<dyn Error + Send + Sync>> {
+            Err("rejected".into())
+        }
+    }
+
+    #[test]
+    fn interceptors_run_in_order_until_the_first_error() {
+        let mut interceptors = Interceptors::new()
+            .with(AppendHeader("first"))
+            .with(AppendHeader("second"));
+        let mut request = http::Request::new(SdkBody::empty());
+        interceptors
+            .modify_before_transmit(&mut request, &mut PropertyBag::new())
+            .unwrap();
+        let values: Vec<_> = request.headers().get_all("x-interceptor").iter().collect();
+        assert_eq!(vec!["first", "second"], values);
+
+        interceptors.extend(&Interceptors::new().with(Fail).with(AppendHeader("third")));
+        assert_eq!(4, interceptors.len());
+        let mut request = http::Request::new(SdkBody::empty());
+        let err = interceptors
+            .modify_before_transmit(&mut request, &mut PropertyBag::new())
+            .expect_err("interceptor failed");
+        assert_eq!("rejected", format!("{}", err));
+        assert_eq!(2, request.headers().get_all("x-interceptor").iter().count());
+    }
+}
@@ -0,0 +1,219 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! JSON tokenizer that reads from an HTTP body as it arrives.
+
+use aws_smithy_json::deserialize::{Error as JsonError, IncrementalTokenizer, Next, Token};
+use bytes::Buf;
+use http_body::Body;
+use std::error::Error as StdError;
+use std::fmt;
+
+type BoxError = Box<dyn StdError + Send + Sync + 'static>;
+
+/// Drives an [`IncrementalTokenizer`] with the chunks of an HTTP body, such as an
+/// [`SdkBody`](crate::body::SdkBody)
+///
+/// Chunks are only read from the body when the tokenizer needs more input to complete a token,
+/// so the body is never buffered as a whole.
+///
+/// # Examples
+/// ```rust
+/// use aws_smithy_http::body::SdkBody;
+/// use aws_smithy_http::json_body::JsonBodyTokenizer;
+/// use aws_smithy_json::deserialize::Token;
+///
+/// # async fn docs() -> Result<(), aws_smithy_http::json_body::Error> {
+/// let mut tokens = JsonBodyTokenizer::new(SdkBody::from(r#"{"Count": 5}"#));
+/// while let Some(token) = tokens.next_token().await? {
+///     if let Token::ValueNumber { value, .. } = token {
+///         println!("count: {:?}", value);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct JsonBodyTokenizer<B> {
+    body: B,
+    tokenizer: IncrementalTokenizer,
+}
+
+impl<B> JsonBodyTokenizer<B>
+where
+    B: Body + Unpin,
+    B::Error: Into<BoxError>,
+{
+    /// Creates a tokenizer that reads `body`, with no limit on how much of it can be buffered.
+    pub fn new(body: B) -> Self {
+        Self::with_tokenizer(body, IncrementalTokenizer::new())
+    }
+
+    /// Creates a tokenizer that reads `body` into `tokenizer`.
+    ///
+    /// Use this to limit how much of the body can be buffered with
+    /// [`IncrementalTokenizer::with_buffer_limit`].
+    pub fn with_tokenizer(body: B, tokenizer: IncrementalTokenizer) -> Self {
+        Self { body, tokenizer }
+    }
+
+    /// Returns the next token, reading from the body until it's complete, or `None` once the
+    /// body has been fully tokenized.
+    pub async fn next_token(&mut self) -> Result<Option<Token<'_>>, Error> {
+        while self.tokenizer.needs_input()? {
+            match self.body.data().await {
+                Some(Ok(mut chunk)) => {
+                    while chunk.has_remaining() {
+                        let bytes = chunk.chunk();
+                        let len = bytes.len();
+                        self.tokenizer.feed(bytes)?;
+                        chunk.advance(len);
+                    }
+                }
+                Some(Err(err)) => return Err(Error::Body(err.into())),
+                None => self.tokenizer.finish(),
+            }
+        }
+        match self.tokenizer.next_token()? {
+            Next::Token(token) => Ok(Some(token)),
+            Next::EndOfInput => Ok(None),
+            Next::NeedMoreInput => {
+                unreachable!("the tokenizer was given more input until it had a token")
+            }
+        }
+    }
+
+    /// Returns the body, along with whatever of it hasn't been read yet.
+    pub fn into_inner(self) -> B {
+        self.body
+    }
+}
+
+/// Error returned by [`JsonBodyTokenizer::next_token`]
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum Error {
+    /// The body failed to produce the next chunk.
+    Body(BoxError),
+    /// The body isn't valid JSON.
+    Json(JsonError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Body(_) => write!(f, "failed to read the body"),
+            Error::Json(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Body(err) => Some(err.as_ref() as _),
+            Error::Json(err) => Some(err as _),
+        }
+    }
+}
+
+impl From<JsonError> for Error {
+    fn from(err: JsonError) -> Self {
+        Error::Json(err)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Error, JsonBodyTokenizer};
+    use crate::body::SdkBody;
+    use aws_smithy_json::deserialize::{IncrementalTokenizer, Token};
+    use bytes::Bytes;
+
+    fn chunked_body(chunks: Vec<Result<&'static str, std::io::Error>>) -> SdkBody {
+        let chunks: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| chunk.map(|chunk| Bytes::from_static(chunk.as_bytes())))
+            .collect();
+        SdkBody::from(hyper::Body::wrap_stream(futures_util::stream::iter(chunks)))
+    }
+
+    /// Reads every token of `body` into a readable form
+    async fn tokens<B>(mut tokenizer: JsonBodyTokenizer<B>) -> Result<Vec<String>, Error>
+    where
+        B: http_body::Body + Unpin,
+        B::Error: Into<super::BoxError>,
+    {
+        let mut tokens = Vec::new();
+        while let Some(token) = tokenizer.next_token().await? {
+            tokens.push(match token {
+                Token::ObjectKey { key, .. } => format!("key {}", key.as_escaped_str()),
+                Token::ValueString { value, .. } => format!("string {}", value.as_escaped_str()),
+                Token::ValueNumber { value, .. } => format!("number {:?}", value),
+                other => format!("{:?}", other)
+                    .split(' ')
+                    .next()
+                    .unwrap()
+                    .to_string(),
+            });
+        }
+        Ok(tokens)
+    }
+
+    #[tokio::test]
+    async fn tokens_split_across_chunks() {
+        let body = chunked_body(vec![
+            Ok(r#"{"Na"#),
+            Ok(r#"me": "hel"#),
+            Ok(r#"lo", "Count": 1"#),
+            Ok("2}"),
+        ]);
+        assert_eq!(
+            vec![
+                "StartObject",
+                "key Name",
+                "string hello",
+                "key Count",
+                "number PosInt(12)",
+                "EndObject"
+            ],
+            tokens(JsonBodyTokenizer::new(body)).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_json() {
+        let body = chunked_body(vec![Ok(r#"{"Name": "hel"#), Ok(r#"lo" "Count"}"#)]);
+        let err = tokens(JsonBodyTokenizer::new(body)).await.unwrap_err();
+        assert!(matches!(err, Error::Json(_)), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn body_errors_are_returned() {
+        let body = chunked_body(vec![
+            Ok("[1, "),
+            Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "connection reset",
+            )),
+        ]);
+        let mut tokenizer = JsonBodyTokenizer::new(body);
+        assert!(tokenizer.next_token().await.unwrap().is_some());
+        assert!(tokenizer.next_token().await.unwrap().is_some());
+        let err = tokenizer.next_token().await.unwrap_err();
+        assert!(matches!(err, Error::Body(_)), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn buffer_limit() {
+        let body = chunked_body(vec![Ok(r#"["abc"#), Ok("defghij\"]")]);
+        let tokenizer = JsonBodyTokenizer::with_tokenizer(
+            body,
+            IncrementalTokenizer::new().with_buffer_limit(8),
+        );
+        let err = tokens(tokenizer).await.unwrap_err();
+        assert!(format!("{}", err).contains("8"), "{}", err);
+    }
+}
@@ -14,6 +14,7 @@
 //! |----------------|-------------|
 //! | `rt-tokio`     | Provides features that are dependent on `tokio` including the `ByteStream::from_path` util |
 //! | `event-stream` | Provides Sender/Receiver implementations for Event Stream codegen. |
+//! | `json`         | Provides a JSON tokenizer that reads from an HTTP body as it arrives. |
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
@@ -22,7 +23,9 @@ pub mod callback;
 pub mod endpoint;
 pub mod header;
 pub mod http_versions;
+pub mod interceptor;
 pub mod label;
+pub mod metrics;
 pub mod middleware;
 pub mod operation;
 pub mod property_bag;
@@ -34,6 +37,9 @@ pub mod retry;
 #[cfg(feature = "event-stream")]
 pub mod event_stream;
 
+#[cfg(feature = "json")]
+pub mod json_body;
+
 pub mod byte_stream;
 
 mod pin_util;
@@ -0,0 +1,359 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Pluggable metrics for operations
+//!
+//! Implement [`RecordMetrics`] to forward the metrics emitted while sending an operation to a
+//! metrics backend. Clients place the recorder in the property bag of every operation as a
+//! [`SharedMetrics`], and the layers that make up a client (the retry handler, timeouts and
+//! dispatch) read it from there through [`OperationMetrics`].
+//!
+//! Every metric is tagged with [`MetricTags`]. The names of the metrics emitted by the runtime
+//! crates are listed in [`names`].
+
+use crate::operation::Metadata;
+use crate::property_bag::PropertyBag;
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Names of the metrics emitted by the runtime crates
+pub mod names {
+    /// Counter incremented once per operation that was sent, including all of its attempts
+    pub const CALLS: &str = "smithy.client.calls";
+    /// Histogram of the duration of operations in seconds, including all of their attempts
+    pub const CALL_DURATION: &str = "smithy.client.call.duration";
+    /// Counter incremented once per HTTP request that was dispatched
+    pub const ATTEMPTS: &str = "smithy.client.attempts";
+    /// Histogram of the duration of HTTP requests in seconds, until the response headers arrived
+    pub const ATTEMPT_DURATION: &str = "smithy.client.attempt.duration";
+    /// Counter incremented every time a failed attempt is retried
+    pub const RETRIES: &str = "smithy.client.retries";
+    /// Counter incremented every time a call or attempt timed out
+    pub const TIMEOUTS: &str = "smithy.client.timeouts";
+    /// Histogram of the size of request bodies in bytes, when the size is known up front
+    pub const REQUEST_BYTES: &str = "smithy.client.request.bytes";
+    /// Histogram of the size of response bodies in bytes, when the size is known up front
+    pub const RESPONSE_BYTES: &str = "smithy.client.response.bytes";
+}
+
+/// Tags attached to every metric
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MetricTags {
+    service: Option<Cow<'static, str>>,
+    operation: Option<Cow<'static, str>>,
+    status: Option<u16>,
+    error_kind: Option<Cow<'static, str>>,
+}
+
+impl MetricTags {
+    /// Create tags for the operation described by `metadata`
+    ///
+    /// This doesn't allocate when the names in `metadata` are static, as they are in
+    /// generated clients.
+    pub fn for_operation(metadata: &Metadata) -> Self {
+        Self {
+            service: Some(metadata.service.clone()),
+            operation: Some(metadata.operation.clone()),
+            ..Default::default()
+        }
+    }
+
+    /// The name of the service the operation belongs to
+    pub fn service(&self) -> Option<&str> {
+        self.service.as_deref()
+    }
+
+    /// The name of the operation
+    pub fn operation(&self) -> Option<&str> {
+        self.operation.as_deref()
+    }
+
+    /// The HTTP status code of the response, if one was received
+    pub fn status(&self) -> Option<u16> {
+        self.status
+    }
+
+    /// The kind of error that occurred, if any
+    pub fn error_kind(&self) -> Option<&str> {
+        self.error_kind.as_deref()
+    }
+
+    /// Set the HTTP status code of the response
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Set the kind of error that occurred
+    pub fn with_error_kind(mut self, error_kind: impl Into<Cow<'static, str>>) -> Self {
+        self.error_kind = Some(error_kind.into());
+        self
+    }
+}
+
+/// A recorder for metrics, e.g. an adapter to a metrics backend
+///
+/// Recording must be cheap and must not block, since it happens while sending requests.
+pub trait RecordMetrics: Debug + Send + Sync {
+    /// Increment the counter `name` by `value`
+    fn increment_counter(&self, name: &'static str, value: u64, tags: &MetricTags);
+
+    /// Record `value` in the histogram `name`
+    fn record_histogram(&self, name: &'static str, value: f64, tags: &MetricTags);
+}
+
+impl<T> RecordMetrics for Arc<T>
+where
+    T: RecordMetrics + ?Sized,
+{
+    fn increment_counter(&self, name: &'static str, value: u64, tags: &MetricTags) {
+        T::increment_counter(self, name, value, tags)
+    }
+
+    fn record_histogram(&self, name: &'static str, value: f64, tags: &MetricTags) {
+        T::record_histogram(self, name, value, tags)
+    }
+}
+
+/// A [`RecordMetrics`] implementation that discards all metrics
+#[non_exhaustive]
+#[derive(Clone, Debug, Default)]
+pub struct NoopMetrics;
+
+impl NoopMetrics {
+    /// Create a new recorder that discards all metrics
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl RecordMetrics for NoopMetrics {
+    fn increment_counter(&self, _name: &'static str, _value: u64, _tags: &MetricTags) {}
+
+    fn record_histogram(&self, _name: &'static str, _value: f64, _tags: &MetricTags) {}
+}
+
+/// A metric recorded by [`InMemoryMetrics`]
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Metric {
+    /// A counter was incremented
+    Counter {
+        /// The name of the counter
+        name: &'static str,
+        /// The amount the counter was incremented by
+        value: u64,
+        /// The tags of the increment
+        tags: MetricTags,
+    },
+    /// A value was recorded in a histogram
+    Histogram {
+        /// The name of the histogram
+        name: &'static str,
+        /// The recorded value
+        value: f64,
+        /// The tags of the value
+        tags: MetricTags,
+    },
+}
+
+/// A [`RecordMetrics`] implementation that keeps all metrics in memory, for tests
+///
+/// Clones share the same recorded metrics.
+///
+/// # Examples
+/// ```
+/// use aws_smithy_http::metrics::{names, InMemoryMetrics, MetricTags, RecordMetrics};
+/// let metrics = InMemoryMetrics::new();
+/// metrics.increment_counter(names::RETRIES, 1, &MetricTags::default());
+/// assert_eq!(1, metrics.counter(names::RETRIES));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryMetrics {
+    metrics: Arc<Mutex<Vec<Metric>>>,
+}
+
+impl InMemoryMetrics {
+    /// Create a new recorder with no metrics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All the metrics recorded so far, in the order they were recorded
+    pub fn metrics(&self) -> Vec<Metric> {
+        self.metrics.lock().unwrap().clone()
+    }
+
+    /// The total of all increments of the counter `name`, regardless of their tags
+    pub fn counter(&self, name: &str) -> u64 {
+        self.counter_matching(name, |_| true)
+    }
+
+    /// The total of the increments of the counter `name` whose tags match `filter`
+    pub fn counter_matching(&self, name: &str, filter: impl Fn(&MetricTags) -> bool) -> u64 {
+        self.metrics
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|metric| match metric {
+                Metric::Counter {
+                    name: counter,
+                    value,
+                    tags,
+                } if *counter == name && filter(tags) => Some(*value),
+                _ => None,
+            })
+            .sum()
+    }
+
+    /// The values recorded in the histogram `name`, along with their tags
+    pub fn histogram(&self, name: &str) -> Vec<(f64, MetricTags)> {
+        self.metrics
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|metric| match metric {
+                Metric::Histogram {
+                    name: histogram,
+                    value,
+                    tags,
+                } if *histogram == name => Some((*value, tags.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl RecordMetrics for InMemoryMetrics {
+    fn increment_counter(&self, name: &'static str, value: u64, tags: &MetricTags) {
+        self.metrics.lock().unwrap().push(Metric::Counter {
+            name,
+            value,
+            tags: tags.clone(),
+        });
+    }
+
+    fn record_histogram(&self, name: &'static str, value: f64, tags: &MetricTags) {
+        self.metrics.lock().unwrap().push(Metric::Histogram {
+            name,
+            value,
+            tags: tags.clone(),
+        });
+    }
+}
+
+/// Cheaply cloneable [`RecordMetrics`], defaulting to [`NoopMetrics`]
+#[derive(Clone, Debug)]
+pub struct SharedMetrics(Arc<dyn RecordMetrics>);
+
+impl SharedMetrics {
+    /// Wraps `metrics` so that it can be shared
+    pub fn new(metrics: impl RecordMetrics + 'static) -> Self {
+        Self(Arc::new(metrics))
+    }
+}
+
+impl Default for SharedMetrics {
+    fn default() -> Self {
+        Self::new(NoopMetrics::new())
+    }
+}
+
+impl RecordMetrics for SharedMetrics {
+    fn increment_counter(&self, name: &'static str, value: u64, tags: &MetricTags) {
+        self.0.increment_counter(name, value, tags)
+    }
+
+    fn record_histogram(&self, name: &'static str, value: f64, tags: &MetricTags) {
+        self.0.record_histogram(name, value, tags)
+    }
+}
+
+/// The metrics recorder of an operation, along with the tags describing the operation
+#[derive(Clone, Debug)]
+pub struct OperationMetrics {
+    recorder: SharedMetrics,
+    tags: MetricTags,
+}
+
+impl OperationMetrics {
+    /// Load the recorder and tags from an operation's property bag
+    ///
+    /// Returns `None` if the property bag doesn't contain a [`SharedMetrics`]. The tags are
+    /// created from the operation's [`Metadata`], if present.
+    pub fn from_properties(properties: &PropertyBag) -> Option<Self> {
+        let recorder = properties.get::<SharedMetrics>()?.clone();
+        let tags = properties
+            .get::<Metadata>()
+            .map(MetricTags::for_operation)
+            .unwrap_or_default();
+        Some(Self { recorder, tags })
+    }
+
+    /// The tags describing the operation
+    pub fn tags(&self) -> &MetricTags {
+        &self.tags
+    }
+
+    /// Increment the counter `name` by one
+    pub fn increment(&self, name: &'static str, tags: &MetricTags) {
+        self.recorder.increment_counter(name, 1, tags)
+    }
+
+    /// Record `duration` in seconds in the histogram `name`
+    pub fn record_duration(&self, name: &'static str, duration: Duration, tags: &MetricTags) {
+        self.recorder
+            .record_histogram(name, duration.as_secs_f64(), tags)
+    }
+
+    /// Record `value` in the histogram `name`
+    pub fn record(&self, name: &'static str, value: f64, tags: &MetricTags) {
+        self.recorder.record_histogram(name, value, tags)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{names, InMemoryMetrics, MetricTags, OperationMetrics, SharedMetrics};
+    use crate::operation::Metadata;
+    use crate::property_bag::PropertyBag;
+    use std::borrow::Cow;
+
+    #[test]
+    fn operation_metrics_are_tagged_with_the_operation() {
+        let mut properties = PropertyBag::new();
+        assert!(OperationMetrics::from_properties(&properties).is_none());
+
+        let recorder = InMemoryMetrics::new();
+        properties.insert(SharedMetrics::new(recorder.clone()));
+        properties.insert(Metadata::new("GetObject", "s3"));
+        let metrics = OperationMetrics::from_properties(&properties).unwrap();
+        metrics.increment(names::ATTEMPTS, &metrics.tags().clone().with_status(200));
+        metrics.increment(names::ATTEMPTS, &metrics.tags().clone().with_status(503));
+        metrics.record(names::REQUEST_BYTES, 5.0, metrics.tags());
+
+        assert_eq!(2, recorder.counter(names::ATTEMPTS));
+        assert_eq!(
+            1,
+            recorder.counter_matching(names::ATTEMPTS, |tags| tags.status() == Some(503))
+        );
+        assert_eq!(
+            vec![(5.0, metrics.tags().clone())],
+            recorder.histogram(names::REQUEST_BYTES)
+        );
+        assert_eq!(Some("s3"), metrics.tags().service());
+        assert_eq!(Some("GetObject"), metrics.tags().operation());
+    }
+
+    #[test]
+    fn tags_borrow_static_names() {
+        let tags = MetricTags::for_operation(&Metadata::new("GetObject", "s3"));
+        assert!(matches!(tags.service, Some(Cow::Borrowed("s3"))));
+        assert!(matches!(tags.operation, Some(Cow::Borrowed("GetObject"))));
+    }
+}
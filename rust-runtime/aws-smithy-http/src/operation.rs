@@ -4,6 +4,7 @@
  */
 
 use crate::body::SdkBody;
+use crate::interceptor::{Interceptor, Interceptors};
 use crate::property_bag::{PropertyBag, SharedPropertyBag};
 use aws_smithy_types::date_time::DateTimeFormatError;
 use http::uri::InvalidUri;
@@ -14,8 +15,8 @@ use std::ops::{Deref, DerefMut};
 
 #[derive(Clone, Debug)]
 pub struct Metadata {
-    operation: Cow<'static, str>,
-    service: Cow<'static, str>,
+    pub(crate) operation: Cow<'static, str>,
+    pub(crate) service: Cow<'static, str>,
 }
 
 impl Metadata {
@@ -193,6 +194,25 @@ impl<H, R> Operation<H, R> {
         self
     }
 
+    pub fn metadata(&self) -> Option<&Metadata> {
+        self.parts.metadata.as_ref()
+    }
+
+    /// Register `interceptor` for this operation only
+    ///
+    /// Interceptors registered on the operation run after those registered on the client.
+    pub fn with_interceptor(mut self, interceptor: impl Interceptor + 'static) -> Self {
+        let mut properties = self.properties_mut();
+        match properties.get_mut::<Interceptors>() {
+            Some(interceptors) => interceptors.push(interceptor),
+            None => {
+                properties.insert(Interceptors::new().with(interceptor));
+            }
+        }
+        drop(properties);
+        self
+    }
+
     pub fn with_retry_policy<R2>(self, retry_policy: R2) -> Operation<H, R2> {
         Operation {
             request: self.request,
@@ -6,9 +6,13 @@
 use aws_smithy_types::Number;
 
 mod error;
+pub mod incremental;
+pub mod path;
 pub mod token;
 
 pub use error::{Error, ErrorReason};
+pub use incremental::{IncrementalTokenizer, Next};
+pub use path::{JsonPath, PathSegment};
 pub use token::{EscapeError, EscapedStr, Offset, Token};
 
 use ErrorReason::*;
@@ -30,7 +34,9 @@ pub fn json_token_iter(input: &[u8]) -> JsonTokenIterator {
     JsonTokenIterator {
         input,
         index: 0,
+        base_offset: 0,
         state_stack: vec![State::Initial],
+        last_token_offset: None,
     }
 }
 
@@ -56,10 +62,34 @@ enum State {
 pub struct JsonTokenIterator<'a> {
     input: &'a [u8],
     index: usize,
+    /// Offset of `input` within the overall stream, for when `input` is only part of it
+    base_offset: usize,
     state_stack: Vec<State>,
+    /// Offset of the most recent token returned
+    last_token_offset: Option<usize>,
 }
 
 impl<'a> JsonTokenIterator<'a> {
+    /// Resumes parsing `input`, which starts at `base_offset` in the overall stream, with a
+    /// `state_stack` saved from a previous iterator.
+    fn resume(input: &'a [u8], base_offset: usize, state_stack: Vec<State>) -> Self {
+        JsonTokenIterator {
+            input,
+            index: 0,
+            base_offset,
+            state_stack,
+            last_token_offset: None,
+        }
+    }
+
+    /// Returns the offset of the most recent token this iterator returned.
+    ///
+    /// Errors raised while interpreting the tokens, rather than while tokenizing, can use this
+    /// to point at the token that was being interpreted.
+    pub fn last_token_offset(&self) -> Option<usize> {
+        self.last_token_offset
+    }
+
     /// Previews the next byte.
     fn peek_byte(&self) -> Option<u8> {
         if self.index >= self.input.len() {
@@ -97,7 +127,7 @@ impl<'a> JsonTokenIterator<'a> {
 
     /// Creates an error at the given `offset` in the stream.
     fn error_at(&self, offset: usize, reason: ErrorReason) -> Error {
-        Error::new(reason, Some(offset))
+        Error::new(reason, Some(self.base_offset + offset))
     }
 
     /// Creates an error at the current offset in the stream.
@@ -130,7 +160,7 @@ impl<'a> JsonTokenIterator<'a> {
 
     /// Returns current offset
     fn offset(&self) -> Offset {
-        Offset(self.index)
+        Offset(self.base_offset + self.index)
     }
 
     /// Discards the '{' character and pushes the `ObjectFirstKeyOrEnd` state.
@@ -478,9 +508,11 @@ impl<'a> Iterator for JsonTokenIterator<'a> {
             State::ObjectNextKeyOrEnd => Some(self.state_object_next_key_or_end()),
             State::ObjectFieldValue => Some(self.state_object_field_value()),
         };
-        // Invalidate the stream if we encountered an error
-        if result.as_ref().map(|r| r.is_err()).unwrap_or(false) {
-            self.index = self.input.len();
+        match &result {
+            Some(Ok(token)) => self.last_token_offset = Some(token.offset().0),
+            // Invalidate the stream if we encountered an error
+            Some(Err(_)) => self.index = self.input.len(),
+            None => {}
         }
         result
     }
@@ -3,6 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::deserialize::path::JsonPath;
 use crate::escape::EscapeError;
 use std::borrow::Cow;
 use std::fmt;
@@ -19,6 +20,7 @@ pub enum ErrorReason {
     UnexpectedControlCharacter(u8),
     UnexpectedEos,
     UnexpectedToken(char, &'static str),
+    BufferLimitExceeded(usize),
 }
 use ErrorReason::*;
 
@@ -26,25 +28,73 @@ use ErrorReason::*;
 pub struct Error {
     reason: ErrorReason,
     offset: Option<usize>,
+    path: Option<JsonPath>,
 }
 
 impl Error {
     pub fn new(reason: ErrorReason, offset: Option<usize>) -> Self {
-        Error { reason, offset }
+        Error {
+            reason,
+            offset,
+            path: None,
+        }
     }
 
     /// Returns a custom error without an offset.
     pub fn custom(message: impl Into<Cow<'static, str>>) -> Error {
         Error::new(ErrorReason::Custom(message.into()), None)
     }
+
+    /// Sets the offset of this error, unless it already has one.
+    pub fn or_offset(mut self, offset: Option<usize>) -> Self {
+        self.offset = self.offset.or(offset);
+        self
+    }
+
+    pub(crate) fn reason(&self) -> &ErrorReason {
+        &self.reason
+    }
+
+    /// Returns the byte offset in the input where the error occurred, if known.
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// Returns the path of the value that failed to parse, if known.
+    pub fn path(&self) -> Option<&JsonPath> {
+        self.path.as_ref()
+    }
+
+    /// Sets the path of the value that failed to parse.
+    pub fn with_path(mut self, path: JsonPath) -> Self {
+        self.path = Some(path);
+        self
+    }
+
+    /// Uses the offset of this error to find the path of the value that failed to parse
+    /// in the original `input`.
+    ///
+    /// Nothing is changed if this error already has a path or doesn't have an offset.
+    pub fn with_path_from(self, input: &[u8]) -> Self {
+        match (&self.path, self.offset) {
+            (None, Some(offset)) => {
+                let path = JsonPath::at_offset(input, offset);
+                self.with_path(path)
+            }
+            _ => self,
+        }
+    }
 }
 
 impl std::error::Error for Error {}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if let Some(offset) = self.offset {
-            write!(f, "Error at offset {}: ", offset)?;
+        match (&self.path, self.offset) {
+            (Some(path), Some(offset)) => write!(f, "Error at {} (offset {}): ", path, offset)?,
+            (Some(path), None) => write!(f, "Error at {}: ", path)?,
+            (None, Some(offset)) => write!(f, "Error at offset {}: ", offset)?,
+            (None, None) => {}
         }
         match &self.reason {
             Custom(msg) => write!(f, "failed to parse JSON: {}", msg),
@@ -64,6 +114,11 @@ impl fmt::Display for Error {
                 token, expected
             ),
             UnexpectedEos => write!(f, "unexpected end of stream"),
+            BufferLimitExceeded(limit) => write!(
+                f,
+                "buffered input exceeded the limit of {} bytes before a token completed",
+                limit
+            ),
         }
     }
 }
@@ -76,9 +131,6 @@ impl From<Utf8Error> for ErrorReason {
 
 impl From<EscapeError> for Error {
     fn from(err: EscapeError) -> Self {
-        Error {
-            reason: ErrorReason::UnescapeFailed(err),
-            offset: None,
-        }
+        Error::new(ErrorReason::UnescapeFailed(err), None)
     }
 }
@@ -0,0 +1,577 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Resumable JSON tokenizer for input that arrives in chunks.
+
+use crate::deserialize::error::{Error, ErrorReason};
+use crate::deserialize::token::Token;
+use crate::deserialize::{JsonTokenIterator, State};
+
+/// Result of asking an [`IncrementalTokenizer`] for its next token.
+#[derive(Debug, PartialEq)]
+pub enum Next<'a> {
+    /// A complete token was parsed.
+    Token(Token<'a>),
+    /// The buffered input ends partway through a token. Feed more input and try again.
+    NeedMoreInput,
+    /// All input has been fed and every token has been returned.
+    EndOfInput,
+}
+
+/// JSON tokenizer that can be fed input one chunk at a time (for example, the `Bytes` chunks
+/// of an HTTP body as they arrive) and yields tokens as soon as they are complete.
+///
+/// This produces the same [`Token`]s as [`json_token_iter`](crate::deserialize::json_token_iter),
+/// and the [`Offset`](crate::deserialize::Offset)s in both tokens and errors are relative to the
+/// start of the overall input rather than the current chunk.
+///
+/// Only the bytes of incomplete tokens are kept between calls, so the amount of memory used is
+/// bounded by the largest single token rather than the whole body. That amount can be capped with
+/// [`IncrementalTokenizer::with_buffer_limit`].
+///
+/// Since tokens borrow from the tokenizer's buffer, this is not an [`Iterator`]. Instead, call
+/// [`IncrementalTokenizer::next_token`] in a loop:
+///
+/// ```
+/// use aws_smithy_json::deserialize::{IncrementalTokenizer, Next, Token};
+///
+/// let mut tokenizer = IncrementalTokenizer::new();
+/// let mut chunks = vec![&b"[1, tr"[..], &b"ue]"[..]].into_iter();
+/// let mut tokens = 0;
+/// loop {
+///     match tokenizer.next_token().unwrap() {
+///         Next::Token(_token) => tokens += 1,
+///         Next::NeedMoreInput => match chunks.next() {
+///             Some(chunk) => tokenizer.feed(chunk).unwrap(),
+///             None => tokenizer.finish(),
+///         },
+///         Next::EndOfInput => break,
+///     }
+/// }
+/// assert_eq!(4, tokens);
+/// ```
+#[derive(Debug)]
+pub struct IncrementalTokenizer {
+    buffer: Vec<u8>,
+    /// Index into `buffer` of the first byte that hasn't been tokenized yet
+    start: usize,
+    /// Number of bytes that have been discarded from the front of `buffer`
+    discarded: usize,
+    state_stack: Vec<State>,
+    /// Copy of `state_stack` to restore when a token turns out to be incomplete
+    saved_state_stack: Vec<State>,
+    buffer_limit: Option<usize>,
+    /// The incomplete token at `start`, if the last call to `next_token` found one
+    pending: Option<Pending>,
+    finished: bool,
+    failed: bool,
+}
+
+/// An incomplete token at the end of the buffer, and how far into the buffer it's known to
+/// still be incomplete.
+///
+/// Retokenizing a long token every time a chunk is fed would be quadratic, so only the newly
+/// fed bytes are checked for something that could complete the token.
+#[derive(Debug)]
+struct Pending {
+    kind: PendingKind,
+    /// Index into `buffer` up to which the token is known to be incomplete
+    checked: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PendingKind {
+    /// Only whitespace (and separators) so far
+    Whitespace,
+    /// A string without a closing quote so far
+    String,
+    /// A number that may continue
+    Number,
+}
+
+impl PendingKind {
+    /// Classifies the incomplete token in `input`, if it's one that can be checked cheaply.
+    fn of(input: &[u8]) -> Option<PendingKind> {
+        let token = match input
+            .iter()
+            .position(|&b| !is_whitespace(b) && b != b',' && b != b':')
+        {
+            Some(token) => &input[token..],
+            None => return Some(PendingKind::Whitespace),
+        };
+        match token[0] {
+            b'"' if token[1..].iter().all(|&b| !ends_string(b)) => Some(PendingKind::String),
+            b'-' | b'0'..=b'9' if token.iter().all(|&b| is_number_byte(b)) => {
+                Some(PendingKind::Number)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns true if `bytes` can't complete (or invalidate) a token of this kind.
+    fn still_incomplete(self, bytes: &[u8]) -> bool {
+        match self {
+            PendingKind::Whitespace => bytes.iter().all(|&b| is_whitespace(b)),
+            PendingKind::String => bytes.iter().all(|&b| !ends_string(b)),
+            PendingKind::Number => bytes.iter().all(|&b| is_number_byte(b)),
+        }
+    }
+}
+
+fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\r' | b'\n')
+}
+
+/// A quote may end a string, and a control character makes it invalid
+fn ends_string(byte: u8) -> bool {
+    byte == b'"' || byte < 0x20
+}
+
+fn is_number_byte(byte: u8) -> bool {
+    matches!(byte, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E')
+}
+
+impl Default for IncrementalTokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IncrementalTokenizer {
+    /// Creates a tokenizer with no input and no limit on how much input can be buffered.
+    pub fn new() -> Self {
+        IncrementalTokenizer {
+            buffer: Vec::new(),
+            start: 0,
+            discarded: 0,
+            state_stack: vec![State::Initial],
+            saved_state_stack: Vec::new(),
+            buffer_limit: None,
+            pending: None,
+            finished: false,
+            failed: false,
+        }
+    }
+
+    /// Limits the number of bytes that can be buffered while waiting for a token to complete.
+    ///
+    /// When a [`feed`](IncrementalTokenizer::feed) would exceed the limit, it fails with
+    /// [`ErrorReason::BufferLimitExceeded`].
+    pub fn with_buffer_limit(mut self, limit: usize) -> Self {
+        self.buffer_limit = Some(limit);
+        self
+    }
+
+    /// Returns the number of bytes fed so far.
+    pub fn bytes_fed(&self) -> usize {
+        self.discarded + self.buffer.len()
+    }
+
+    /// Returns the number of bytes currently buffered and not yet tokenized.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len() - self.start
+    }
+
+    /// Appends the next `chunk` of input.
+    ///
+    /// # Panics
+    /// Panics if called after [`finish`](IncrementalTokenizer::finish).
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<(), Error> {
+        assert!(
+            !self.finished,
+            "cannot feed input after `finish` was called"
+        );
+        if let Some(limit) = self.buffer_limit {
+            if self.buffered() + chunk.len() > limit {
+                self.failed = true;
+                return Err(Error::new(
+                    ErrorReason::BufferLimitExceeded(limit),
+                    Some(self.discarded + self.start),
+                ));
+            }
+        }
+        // Drop the bytes that have already been tokenized so that the buffer only holds
+        // the incomplete token (if any) and the new chunk
+        if self.start > 0 {
+            self.buffer.drain(..self.start);
+            self.discarded += self.start;
+            if let Some(pending) = &mut self.pending {
+                pending.checked -= self.start;
+            }
+            self.start = 0;
+        }
+        self.buffer.extend_from_slice(chunk);
+        Ok(())
+    }
+
+    /// Signals that there is no more input. Any incomplete token left in the buffer will be
+    /// reported as an error by the next call to [`next_token`](IncrementalTokenizer::next_token).
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Returns true if [`next_token`](IncrementalTokenizer::next_token) would return
+    /// [`Next::NeedMoreInput`], without consuming the next token otherwise.
+    ///
+    /// This is useful when the caller has to read more input before borrowing a token, such as
+    /// when the input is read from an async source. It tokenizes the next token twice, so prefer
+    /// matching on `next_token` when possible.
+    pub fn needs_input(&mut self) -> Result<bool, Error> {
+        let start = self.start;
+        match self.next_token()? {
+            Next::NeedMoreInput => Ok(true),
+            Next::EndOfInput => Ok(false),
+            Next::Token(_) => {
+                // Rewind to before the token, with the state saved by `next_token`
+                self.start = start;
+                std::mem::swap(&mut self.state_stack, &mut self.saved_state_stack);
+                Ok(false)
+            }
+        }
+    }
+
+    /// Returns the next token if enough input has been fed to complete it.
+    ///
+    /// Once an error has been returned, all subsequent calls return [`Next::EndOfInput`].
+    pub fn next_token(&mut self) -> Result<Next<'_>, Error> {
+        if self.failed {
+            return Ok(Next::EndOfInput);
+        }
+        if let (Some(pending), false) = (&mut self.pending, self.finished) {
+            if pending
+                .kind
+                .still_incomplete(&self.buffer[pending.checked..])
+            {
+                pending.checked = self.buffer.len();
+                return Ok(Next::NeedMoreInput);
+            }
+        }
+        self.pending = None;
+        self.saved_state_stack.clone_from(&self.state_stack);
+
+        let base_offset = self.discarded + self.start;
+        let input = &self.buffer[self.start..];
+        let mut iter =
+            JsonTokenIterator::resume(input, base_offset, std::mem::take(&mut self.state_stack));
+        let result = iter.next();
+        let consumed = iter.index;
+        self.state_stack = iter.state_stack;
+
+        let incomplete = match &result {
+            None => !self.finished,
+            Some(Err(err)) => {
+                !self.finished
+                    && match err.reason() {
+                        ErrorReason::UnexpectedEos => true,
+                        // A number cut off by the end of the buffer (such as a lone `-`) can
+                        // fail to parse even though it would be valid once the rest arrives.
+                        // The error is at the start of the number, and the iterator discards
+                        // the rest of the input on errors, so check where the number ends.
+                        ErrorReason::InvalidNumber => err
+                            .offset()
+                            .and_then(|offset| input.get(offset.checked_sub(base_offset)?..))
+                            .map(|number| number.iter().all(|&b| is_number_byte(b)))
+                            .unwrap_or(false),
+                        _ => false,
+                    }
+            }
+            // Scalars at the very end of the buffer might continue in the next chunk
+            // (for example, `12` followed by `34`), so wait until there is a byte after them
+            Some(Ok(Token::ValueNumber { .. }))
+            | Some(Ok(Token::ValueBool { .. }))
+            | Some(Ok(Token::ValueNull { .. })) => !self.finished && consumed == input.len(),
+            Some(Ok(_)) => false,
+        };
+        if incomplete {
+            self.pending = PendingKind::of(input).map(|kind| Pending {
+                kind,
+                checked: self.buffer.len(),
+            });
+            std::mem::swap(&mut self.state_stack, &mut self.saved_state_stack);
+            return Ok(Next::NeedMoreInput);
+        }
+
+        match result {
+            None => Ok(Next::EndOfInput),
+            Some(Ok(token)) => {
+                self.start += consumed;
+                Ok(Next::Token(token))
+            }
+            Some(Err(err)) => {
+                self.failed = true;
+                Err(err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IncrementalTokenizer, Next};
+    use crate::deserialize::token::test::{
+        end_array, end_object, object_key, start_array, start_object, value_bool, value_null,
+        value_number, value_string,
+    };
+    use crate::deserialize::{json_token_iter, Error, ErrorReason, Token};
+    use aws_smithy_types::Number;
+    use proptest::prelude::*;
+
+    /// Tokenizes `input` after splitting it into chunks at `splits`, returning the tokens
+    /// (or the error) in the same form that `json_token_iter` yields them.
+    fn tokenize_chunked(
+        input: &[u8],
+        splits: &[usize],
+    ) -> Vec<Option<Result<Token<'static>, Error>>> {
+        let mut chunks = Vec::new();
+        let mut last = 0;
+        for split in splits {
+            chunks.push(&input[last..*split]);
+            last = *split;
+        }
+        chunks.push(&input[last..]);
+        let mut chunks = chunks.into_iter();
+
+        let mut tokenizer = IncrementalTokenizer::new();
+        let mut result = Vec::new();
+        loop {
+            match tokenizer.next_token() {
+                Ok(Next::Token(token)) => result.push(Some(Ok(into_static(token)))),
+                Ok(Next::NeedMoreInput) => match chunks.next() {
+                    Some(chunk) => tokenizer.feed(chunk).unwrap(),
+                    None => tokenizer.finish(),
+                },
+                Ok(Next::EndOfInput) => break,
+                Err(err) => {
+                    result.push(Some(Err(err)));
+                    break;
+                }
+            }
+        }
+        result
+    }
+
+    /// Leaks string contents so that tokens can outlive the tokenizer in tests.
+    fn into_static(token: Token<'_>) -> Token<'static> {
+        use crate::deserialize::EscapedStr;
+        let leak = |value: &str| -> &'static str { Box::leak(value.to_string().into_boxed_str()) };
+        match token {
+            Token::ObjectKey { offset, key } => Token::ObjectKey {
+                offset,
+                key: EscapedStr::new(leak(key.as_escaped_str())),
+            },
+            Token::ValueString { offset, value } => Token::ValueString {
+                offset,
+                value: EscapedStr::new(leak(value.as_escaped_str())),
+            },
+            Token::StartArray { offset } => Token::StartArray { offset },
+            Token::EndArray { offset } => Token::EndArray { offset },
+            Token::StartObject { offset } => Token::StartObject { offset },
+            Token::EndObject { offset } => Token::EndObject { offset },
+            Token::ValueBool { offset, value } => Token::ValueBool { offset, value },
+            Token::ValueNull { offset } => Token::ValueNull { offset },
+            Token::ValueNumber { offset, value } => Token::ValueNumber { offset, value },
+        }
+    }
+
+    #[test]
+    fn empty() {
+        assert!(tokenize_chunked(b"", &[]).is_empty());
+        assert!(tokenize_chunked(b"  ", &[1]).is_empty());
+    }
+
+    #[test]
+    fn tokens_split_across_chunks() {
+        let input = br#"{"foo": [12345, true, null, "a\"b"], "bar": {}}"#;
+        let expected = vec![
+            start_object(0),
+            object_key(1, "foo"),
+            start_array(8),
+            value_number(9, Number::PosInt(12345)),
+            value_bool(16, true),
+            value_null(22),
+            value_string(28, r#"a\"b"#),
+            end_array(34),
+            object_key(37, "bar"),
+            start_object(44),
+            end_object(45),
+            end_object(46),
+        ];
+        assert_eq!(expected, tokenize_chunked(input, &[]));
+        // Split in the middle of a number, a literal, a string, an escape, and a key
+        assert_eq!(expected, tokenize_chunked(input, &[3, 11, 18, 24, 31, 40]));
+        // Split at every single byte
+        let every_byte: Vec<usize> = (1..input.len()).collect();
+        assert_eq!(expected, tokenize_chunked(input, &every_byte));
+    }
+
+    #[test]
+    fn number_at_end_of_chunk_waits_for_more_input() {
+        let mut tokenizer = IncrementalTokenizer::new();
+        tokenizer.feed(b"12").unwrap();
+        assert_eq!(Ok(Next::NeedMoreInput), tokenizer.next_token());
+        tokenizer.feed(b"34").unwrap();
+        assert_eq!(Ok(Next::NeedMoreInput), tokenizer.next_token());
+        tokenizer.finish();
+        assert_eq!(
+            Ok(Next::Token(Token::ValueNumber {
+                offset: crate::deserialize::Offset(0),
+                value: Number::PosInt(1234)
+            })),
+            tokenizer.next_token()
+        );
+        assert_eq!(Ok(Next::EndOfInput), tokenizer.next_token());
+    }
+
+    #[test]
+    fn invalid_number_followed_by_more_input_fails_immediately() {
+        let mut tokenizer = IncrementalTokenizer::new();
+        tokenizer.feed(b"[1.2.3, 4").unwrap();
+        assert_eq!(
+            Ok(Next::Token(Token::StartArray {
+                offset: crate::deserialize::Offset(0)
+            })),
+            tokenizer.next_token()
+        );
+        assert_eq!(
+            Err(Error::new(ErrorReason::InvalidNumber, Some(1))),
+            tokenizer.next_token()
+        );
+    }
+
+    #[test]
+    fn incomplete_string_only_checks_new_input() {
+        let mut tokenizer = IncrementalTokenizer::new();
+        tokenizer.feed(b"\"abc").unwrap();
+        assert_eq!(Ok(Next::NeedMoreInput), tokenizer.next_token());
+        for _ in 0..100 {
+            tokenizer.feed(b"defgh").unwrap();
+            assert_eq!(Ok(Next::NeedMoreInput), tokenizer.next_token());
+            assert_eq!(
+                tokenizer.buffer.len(),
+                tokenizer.pending.as_ref().unwrap().checked
+            );
+        }
+        tokenizer.feed(b"\"").unwrap();
+        assert!(matches!(
+            tokenizer.next_token(),
+            Ok(Next::Token(Token::ValueString { .. }))
+        ));
+        // A control character invalidates the string without waiting for the closing quote
+        let mut tokenizer = IncrementalTokenizer::new();
+        tokenizer.feed(b"\"abc").unwrap();
+        assert_eq!(Ok(Next::NeedMoreInput), tokenizer.next_token());
+        tokenizer.feed(b"d\ne").unwrap();
+        assert!(tokenizer.next_token().is_err());
+    }
+
+    #[test]
+    fn offsets_are_relative_to_the_whole_input() {
+        let input = b"[1, 2, x]";
+        let expected: Vec<_> = json_token_iter(input).map(Some).collect();
+        assert_eq!(
+            Some(Err(Error::new(
+                ErrorReason::UnexpectedToken(
+                    'x',
+                    "'{', '[', '\"', 'null', 'true', 'false', <number>"
+                ),
+                Some(7)
+            ))),
+            *expected.last().unwrap()
+        );
+        assert_eq!(expected, tokenize_chunked(input, &[2, 5, 7]));
+    }
+
+    #[test]
+    fn incomplete_input_is_an_error_once_finished() {
+        assert_eq!(
+            vec![
+                start_array(0),
+                Some(Err(Error::new(ErrorReason::UnexpectedEos, Some(4))))
+            ],
+            tokenize_chunked(b"[\"ab", &[2])
+        );
+    }
+
+    #[test]
+    fn errors_end_the_stream() {
+        let mut tokenizer = IncrementalTokenizer::new();
+        tokenizer.feed(b"]").unwrap();
+        assert!(tokenizer.next_token().is_err());
+        assert_eq!(Ok(Next::EndOfInput), tokenizer.next_token());
+    }
+
+    #[test]
+    fn needs_input_does_not_consume_tokens() {
+        let token =
+            |token: Option<Result<Token<'static>, Error>>| Ok(Next::Token(token.unwrap().unwrap()));
+        let mut tokenizer = IncrementalTokenizer::new();
+        assert_eq!(Ok(true), tokenizer.needs_input());
+        tokenizer.feed(b"[{\"a\"").unwrap();
+        assert_eq!(Ok(false), tokenizer.needs_input());
+        assert_eq!(Ok(false), tokenizer.needs_input());
+        assert_eq!(token(start_array(0)), tokenizer.next_token());
+        assert_eq!(Ok(false), tokenizer.needs_input());
+        assert_eq!(token(start_object(1)), tokenizer.next_token());
+        assert_eq!(token(object_key(2, "a")), tokenizer.next_token());
+        assert_eq!(Ok(true), tokenizer.needs_input());
+        tokenizer.feed(b": 1}]").unwrap();
+        tokenizer.finish();
+        assert_eq!(Ok(false), tokenizer.needs_input());
+        assert_eq!(
+            token(value_number(7, Number::PosInt(1))),
+            tokenizer.next_token()
+        );
+        assert_eq!(token(end_object(8)), tokenizer.next_token());
+        assert_eq!(token(end_array(9)), tokenizer.next_token());
+        assert_eq!(Ok(false), tokenizer.needs_input());
+        assert_eq!(Ok(Next::EndOfInput), tokenizer.next_token());
+    }
+
+    #[test]
+    fn buffer_limit() {
+        let mut tokenizer = IncrementalTokenizer::new().with_buffer_limit(8);
+        tokenizer.feed(b"[\"abc").unwrap();
+        assert_eq!(
+            Ok(Next::Token(Token::StartArray {
+                offset: crate::deserialize::Offset(0)
+            })),
+            tokenizer.next_token()
+        );
+        assert_eq!(Ok(Next::NeedMoreInput), tokenizer.next_token());
+        assert_eq!(4, tokenizer.buffered());
+        // Consumed bytes don't count towards the limit
+        tokenizer.feed(b"defg").unwrap();
+        assert_eq!(
+            Err(Error::new(ErrorReason::BufferLimitExceeded(8), Some(1))),
+            tokenizer.feed(b"h")
+        );
+        assert_eq!(Ok(Next::EndOfInput), tokenizer.next_token());
+        assert_eq!(9, tokenizer.bytes_fed());
+    }
+
+    proptest! {
+        #[test]
+        fn matches_json_token_iter(input in r#"[\[\]{}":,\\ a-z0-9.eE+\-]{0,40}"#, split_seed: Vec<usize>) {
+            let input = input.as_bytes();
+            let mut splits: Vec<usize> = split_seed
+                .into_iter()
+                .filter(|_| !input.is_empty())
+                .map(|split| split % input.len())
+                .collect();
+            splits.sort_unstable();
+            splits.dedup();
+
+            let mut expected: Vec<_> = json_token_iter(input)
+                .map(|token| token.map(into_static))
+                .map(Some)
+                .collect();
+            // The incremental tokenizer stops at the first error
+            if let Some(index) = expected.iter().position(|token| matches!(token, Some(Err(_)))) {
+                expected.truncate(index + 1);
+            }
+            prop_assert_eq!(expected, tokenize_chunked(input, &splits));
+        }
+    }
+}
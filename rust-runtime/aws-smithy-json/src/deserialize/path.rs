@@ -0,0 +1,272 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! JSON paths (such as `$.Items[3].attrs.created`) that locate a value within a document.
+
+use crate::deserialize::json_token_iter;
+use crate::deserialize::token::Token;
+use std::fmt;
+
+/// One step of a [`JsonPath`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PathSegment {
+    /// The member of an object with the given key.
+    Key(String),
+    /// The element of an array at the given index.
+    Index(usize),
+}
+
+/// Location of a value within a JSON document, starting from the root value (`$`).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<PathSegment>,
+}
+
+impl JsonPath {
+    /// Creates a path to the root value.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    /// Returns the segments of this path, starting from the root.
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Appends `segment` to this path.
+    pub fn push(&mut self, segment: PathSegment) {
+        self.segments.push(segment);
+    }
+
+    /// Finds the path of the token at `offset` in `input`.
+    ///
+    /// If `offset` falls between tokens or inside a token that failed to parse, the path of the
+    /// value that was being parsed at that point is returned instead. This re-tokenizes `input`
+    /// up to `offset`, so it's intended for building error messages rather than the happy path.
+    pub fn at_offset(input: &[u8], offset: usize) -> Self {
+        let mut tracker = PathTracker::default();
+        let mut path = JsonPath::root();
+        for token in json_token_iter(input) {
+            match token {
+                Ok(token) if token.offset().0 <= offset => path = tracker.token_path(&token),
+                // The value being parsed when tokenizing failed is only where `offset` is if the
+                // failure didn't happen further along
+                Err(err) if matches!(err.offset(), Some(at) if at <= offset) => {
+                    path = tracker.pending_path();
+                    break;
+                }
+                Ok(_) | Err(_) => break,
+            }
+        }
+        path
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "$")?;
+        for segment in &self.segments {
+            match segment {
+                PathSegment::Key(key) if is_identifier(key) => write!(f, ".{}", key)?,
+                PathSegment::Key(key) => {
+                    write!(f, "['")?;
+                    for chr in key.chars() {
+                        if chr == '\'' || chr == '\\' {
+                            write!(f, "\\")?;
+                        }
+                        write!(f, "{}", chr)?;
+                    }
+                    write!(f, "']")?;
+                }
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns true if `key` can be written after a `.` in a path without quoting.
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some(chr) if chr.is_ascii_alphabetic() || chr == '_')
+        && chars.all(|chr| chr.is_ascii_alphanumeric() || chr == '_')
+}
+
+#[derive(Debug)]
+enum Container {
+    /// An array, along with the index of the next element
+    Array(usize),
+    /// An object, along with the key of the current member
+    Object(Option<String>),
+}
+
+/// Follows a token stream to determine the path of each token.
+#[derive(Debug, Default)]
+struct PathTracker {
+    containers: Vec<Container>,
+    /// Path of the innermost container
+    path: JsonPath,
+}
+
+impl PathTracker {
+    /// Returns the segment for the next value in the current container, advancing past it.
+    fn next_value_segment(&mut self) -> Option<PathSegment> {
+        match self.containers.last_mut() {
+            Some(Container::Array(next)) => {
+                *next += 1;
+                Some(PathSegment::Index(*next - 1))
+            }
+            Some(Container::Object(key)) => key.clone().map(PathSegment::Key),
+            None => None,
+        }
+    }
+
+    /// Returns the path of the value that would be parsed next.
+    fn pending_path(&self) -> JsonPath {
+        let mut path = self.path.clone();
+        match self.containers.last() {
+            Some(Container::Array(next)) => path.push(PathSegment::Index(*next)),
+            Some(Container::Object(Some(key))) => path.push(PathSegment::Key(key.clone())),
+            _ => {}
+        }
+        path
+    }
+
+    /// Advances past `token` and returns its path.
+    fn token_path(&mut self, token: &Token<'_>) -> JsonPath {
+        match token {
+            Token::StartArray { .. } | Token::StartObject { .. } => {
+                if let Some(segment) = self.next_value_segment() {
+                    self.path.push(segment);
+                }
+                self.containers.push(match token {
+                    Token::StartArray { .. } => Container::Array(0),
+                    _ => Container::Object(None),
+                });
+                self.path.clone()
+            }
+            Token::EndArray { .. } | Token::EndObject { .. } => {
+                let path = self.path.clone();
+                self.containers.pop();
+                if !self.containers.is_empty() {
+                    self.path.segments.pop();
+                }
+                path
+            }
+            Token::ObjectKey { key, .. } => {
+                let key = key
+                    .to_unescaped()
+                    .map(|key| key.into_owned())
+                    .unwrap_or_else(|_| key.as_escaped_str().to_string());
+                if let Some(Container::Object(current)) = self.containers.last_mut() {
+                    *current = Some(key);
+                }
+                self.pending_path()
+            }
+            Token::ValueBool { .. }
+            | Token::ValueNull { .. }
+            | Token::ValueNumber { .. }
+            | Token::ValueString { .. } => {
+                let mut path = self.path.clone();
+                if let Some(segment) = self.next_value_segment() {
+                    path.push(segment);
+                }
+                path
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JsonPath, PathSegment};
+    use crate::deserialize::{json_token_iter, Error, ErrorReason};
+
+    fn path_at(input: &str, needle: &str) -> String {
+        let offset = input.find(needle).expect("needle is in input");
+        JsonPath::at_offset(input.as_bytes(), offset).to_string()
+    }
+
+    #[test]
+    fn display() {
+        let mut path = JsonPath::root();
+        assert_eq!("$", path.to_string());
+        path.push(PathSegment::Key("Items".into()));
+        path.push(PathSegment::Index(3));
+        path.push(PathSegment::Key("with space".into()));
+        path.push(PathSegment::Key("it's".into()));
+        path.push(PathSegment::Key("_ok1".into()));
+        assert_eq!("$.Items[3]['with space']['it\\'s']._ok1", path.to_string());
+    }
+
+    #[test]
+    fn paths_of_tokens() {
+        let input =
+            r#"{"Items": [0, 1, 2, {"attrs": {"created": "x", "a\"b": [true]}}], "next": null}"#;
+        assert_eq!("$", path_at(input, "{"));
+        assert_eq!("$.Items", path_at(input, "\"Items\""));
+        assert_eq!("$.Items", path_at(input, "["));
+        assert_eq!("$.Items[0]", path_at(input, "0"));
+        assert_eq!("$.Items[2]", path_at(input, "2"));
+        assert_eq!("$.Items[3]", path_at(input, "{\"attrs"));
+        assert_eq!("$.Items[3].attrs", path_at(input, "\"attrs\""));
+        assert_eq!("$.Items[3].attrs.created", path_at(input, "\"x\""));
+        assert_eq!("$.Items[3].attrs['a\"b'][0]", path_at(input, "true"));
+        assert_eq!("$.Items[3].attrs['a\"b']", path_at(input, "]}}"));
+        assert_eq!("$.Items[3].attrs", path_at(input, "}}]"));
+        assert_eq!("$.Items", path_at(input, "],"));
+        assert_eq!("$.next", path_at(input, "null"));
+        assert_eq!(
+            "$",
+            JsonPath::at_offset(input.as_bytes(), input.len() - 1).to_string()
+        );
+    }
+
+    #[test]
+    fn offset_inside_token() {
+        let input = r#"{"a": ["abcdef"]}"#;
+        assert_eq!("$.a[0]", path_at(input, "cd"));
+    }
+
+    #[test]
+    fn tokenizer_errors() {
+        assert_eq!("$.a[1]", path_at(r#"{"a": [1, x]}"#, "x"));
+        assert_eq!("$.a.b", path_at(r#"{"a": {"b": tru}}"#, "tru"));
+        assert_eq!("$", path_at("", ""));
+        // Tokens before the failure keep their own path
+        assert_eq!("$[0]", path_at("[1 x", "1"));
+        assert_eq!("$", path_at("[1 x", "["));
+    }
+
+    #[test]
+    fn error_with_path() {
+        let input = br#"{"a": [1, "x"]}"#;
+        let err = Error::new(ErrorReason::Custom("bad".into()), Some(10)).with_path_from(input);
+        assert_eq!("$.a[1]", err.path().unwrap().to_string());
+        assert_eq!(
+            "Error at $.a[1] (offset 10): failed to parse JSON: bad",
+            err.to_string()
+        );
+
+        // Errors without an offset can't be located
+        let err = Error::custom("bad").with_path_from(input);
+        assert_eq!(None, err.path());
+
+        // ...unless they are given the offset of the token that was being interpreted
+        let mut tokens = json_token_iter(input);
+        tokens.by_ref().take(4).for_each(drop);
+        assert_eq!(Some(7), tokens.last_token_offset());
+        let err = Error::custom("bad")
+            .or_offset(tokens.last_token_offset())
+            .with_path_from(input);
+        assert_eq!("$.a[0]", err.path().unwrap().to_string());
+        assert_eq!(
+            Some(3),
+            Error::new(ErrorReason::Custom("bad".into()), Some(3))
+                .or_offset(Some(7))
+                .offset()
+        );
+    }
+}
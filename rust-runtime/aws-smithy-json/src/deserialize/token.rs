@@ -143,7 +143,12 @@ macro_rules! expect_value_or_null_fn {
             match token.transpose()? {
                 Some(Token::ValueNull { .. }) => Ok(None),
                 Some(Token::$token { value, .. }) => Ok(Some(value)),
-                _ => Err(Error::custom(concat!(
+                Some(token) => Err(token.error(Cow::Borrowed(concat!(
+                    "expected ",
+                    stringify!($token),
+                    " or ValueNull"
+                )))),
+                None => Err(Error::custom(concat!(
                     "expected ",
                     stringify!($token),
                     " or ValueNull"
@@ -187,7 +192,10 @@ pub fn expect_number_or_null(
                     )
                 }),
         },
-        _ => Err(Error::custom(
+        Some(token) => Err(token.error(Cow::Borrowed(
+            "expected ValueString, ValueNumber, or ValueNull",
+        ))),
+        None => Err(Error::custom(
             "expected ValueString, ValueNumber, or ValueNull",
         )),
     }
@@ -195,12 +203,13 @@ pub fn expect_number_or_null(
 
 /// Expects a [Token::ValueString] or [Token::ValueNull]. If the value is a string, it interprets it as a base64 encoded [Blob] value.
 pub fn expect_blob_or_null(token: Option<Result<Token<'_>, Error>>) -> Result<Option<Blob>, Error> {
+    let offset = token_offset(&token);
     Ok(match expect_string_or_null(token)? {
         Some(value) => Some(Blob::new(base64::decode(value.as_escaped_str()).map_err(
             |err| {
                 Error::new(
                     ErrorReason::Custom(Cow::Owned(format!("failed to decode base64: {}", err))),
-                    None,
+                    offset,
                 )
             },
         )?)),
@@ -215,6 +224,7 @@ pub fn expect_timestamp_or_null(
     token: Option<Result<Token<'_>, Error>>,
     timestamp_format: Format,
 ) -> Result<Option<DateTime>, Error> {
+    let offset = token_offset(&token);
     Ok(match timestamp_format {
         Format::EpochSeconds => {
             expect_number_or_null(token)?.map(|v| DateTime::from_secs_f64(v.to_f64()))
@@ -225,12 +235,20 @@ pub fn expect_timestamp_or_null(
             .map_err(|err| {
                 Error::new(
                     ErrorReason::Custom(Cow::Owned(format!("failed to parse timestamp: {}", err))),
-                    None,
+                    offset,
                 )
             })?,
     })
 }
 
+/// Returns the offset of `token` if it was successfully parsed.
+fn token_offset(token: &Option<Result<Token<'_>, Error>>) -> Option<usize> {
+    match token {
+        Some(Ok(token)) => Some(token.offset().0),
+        _ => None,
+    }
+}
+
 /// Expects and parses a complete document value.
 pub fn expect_document<'a, I>(tokens: &mut Peekable<I>) -> Result<Document, Error>
 where
@@ -523,7 +541,10 @@ pub mod test {
             expect_string_or_null(value_string(0, "test\\n"))
         );
         assert_eq!(
-            Err(Error::custom("expected ValueString or ValueNull")),
+            Err(Error::new(
+                ErrorReason::Custom("expected ValueString or ValueNull".into()),
+                Some(0)
+            )),
             expect_string_or_null(value_bool(0, true))
         );
     }
@@ -536,8 +557,9 @@ pub mod test {
             expect_number_or_null(value_number(0, Number::PosInt(5)))
         );
         assert_eq!(
-            Err(Error::custom(
-                "expected ValueString, ValueNumber, or ValueNull"
+            Err(Error::new(
+                ErrorReason::Custom("expected ValueString, ValueNumber, or ValueNull".into()),
+                Some(0)
             )),
             expect_number_or_null(value_bool(0, true))
         );
@@ -567,7 +589,10 @@ pub mod test {
             expect_blob_or_null(value_string(0, "aGVsbG8h"))
         );
         assert_eq!(
-            Err(Error::custom("expected ValueString or ValueNull")),
+            Err(Error::new(
+                ErrorReason::Custom("expected ValueString or ValueNull".into()),
+                Some(0)
+            )),
             expect_blob_or_null(value_bool(0, true))
         );
     }
@@ -604,7 +629,10 @@ pub mod test {
             expect_timestamp_or_null(value_string(0, "wrong"), Format::EpochSeconds)
         );
         assert_eq!(
-            Err(Error::custom("expected ValueString or ValueNull")),
+            Err(Error::new(
+                ErrorReason::Custom("expected ValueString or ValueNull".into()),
+                Some(0)
+            )),
             expect_timestamp_or_null(value_number(0, Number::Float(0.0)), Format::DateTime)
         );
     }
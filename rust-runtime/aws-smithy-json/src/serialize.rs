@@ -9,13 +9,107 @@ use aws_smithy_types::primitive::Encoder;
 use aws_smithy_types::{DateTime, Document, Number};
 use std::borrow::Cow;
 
-pub struct JsonValueWriter<'a> {
-    output: &'a mut String,
+mod sink;
+
+pub use sink::{BufMutSink, FmtSink, JsonSink};
+
+/// Largest integer that a `f64` can represent exactly along with all of the integers below it.
+const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+/// Controls how whitespace, object keys, and numbers are written.
+///
+/// The default style is compact with no whitespace at all. The `pretty` and `canonical`
+/// options can be combined:
+///
+/// ```
+/// use aws_smithy_json::serialize::{JsonValueWriter, Style};
+/// use aws_smithy_types::{Document, Number};
+///
+/// let document = Document::Object(
+///     vec![
+///         ("b".to_string(), Document::Number(Number::Float(1.0))),
+///         ("a".to_string(), Document::Array(vec![Document::Null])),
+///     ]
+///     .into_iter()
+///     .collect(),
+/// );
+/// let mut output = String::new();
+/// JsonValueWriter::with_style(&mut output, Style::default().pretty().canonical())
+///     .document(&document);
+/// assert_eq!("{\n  \"a\": [\n    null\n  ],\n  \"b\": 1\n}", output);
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Style {
+    pretty: bool,
+    canonical: bool,
 }
 
-impl<'a> JsonValueWriter<'a> {
-    pub fn new(output: &'a mut String) -> Self {
-        JsonValueWriter { output }
+impl Style {
+    /// Compact output with no whitespace. This is the default.
+    pub fn compact() -> Self {
+        Self::default()
+    }
+
+    /// Puts each array element and object member on its own line, indented by two spaces
+    /// per level of nesting, with a space after each `:`.
+    pub fn pretty(mut self) -> Self {
+        self.pretty = true;
+        self
+    }
+
+    /// Makes the output byte-stable so that it can be compared or hashed.
+    ///
+    /// In canonical mode:
+    /// - The members of [`Document`] objects are written in ascending order of their keys.
+    ///   Objects written member-by-member with [`JsonObjectWriter`] are written in the order
+    ///   the members were given.
+    /// - Numbers that have an integer value are written as integers regardless of whether they
+    ///   are a [`Number::Float`], so `1.0`, `1`, and `-0.0` become `1`, `1`, and `0`. This only
+    ///   applies when the integer can be represented exactly by a `f64`.
+    pub fn canonical(mut self) -> Self {
+        self.canonical = true;
+        self
+    }
+
+    /// Returns true if this style is pretty-printed.
+    pub fn is_pretty(&self) -> bool {
+        self.pretty
+    }
+
+    /// Returns true if this style is canonical.
+    pub fn is_canonical(&self) -> bool {
+        self.canonical
+    }
+}
+
+/// Writes a newline followed by the indentation for `depth` if the style is pretty.
+fn newline_indent<W: JsonSink>(output: &mut W, style: Style, depth: usize) {
+    if style.pretty {
+        output.push('\n');
+        for _ in 0..depth {
+            output.push_str("  ");
+        }
+    }
+}
+
+pub struct JsonValueWriter<'a, W: JsonSink = String> {
+    output: &'a mut W,
+    style: Style,
+    depth: usize,
+}
+
+impl<'a, W: JsonSink> JsonValueWriter<'a, W> {
+    pub fn new(output: &'a mut W) -> Self {
+        Self::with_style(output, Style::default())
+    }
+
+    /// Creates a writer that writes a value into `output` in the given `style`.
+    pub fn with_style(output: &'a mut W, style: Style) -> Self {
+        JsonValueWriter {
+            output,
+            style,
+            depth: 0,
+        }
     }
 
     /// Writes a null value.
@@ -45,9 +139,18 @@ impl<'a> JsonValueWriter<'a> {
             Document::Null => self.null(),
             Document::Number(value) => self.number(*value),
             Document::Object(values) => {
+                let canonical = self.style.canonical;
                 let mut object = self.start_object();
-                for (key, value) in values {
-                    object.key(key).document(value);
+                if canonical {
+                    let mut values: Vec<_> = values.iter().collect();
+                    values.sort_unstable_by(|a, b| a.0.cmp(b.0));
+                    for (key, value) in values {
+                        object.key(key).document(value);
+                    }
+                } else {
+                    for (key, value) in values {
+                        object.key(key).document(value);
+                    }
                 }
                 object.finish();
             }
@@ -83,6 +186,12 @@ impl<'a> JsonValueWriter<'a> {
                 self.output.push_str(Encoder::from(value).encode());
             }
             Number::Float(value) => {
+                if self.style.canonical && value.fract() == 0.0 && value.abs() <= MAX_SAFE_INTEGER {
+                    // The cast is exact since the value is an integer within the safe range.
+                    // This also turns `-0.0` into `0`.
+                    self.output.push_str(Encoder::from(value as i64).encode());
+                    return;
+                }
                 let mut encoder: Encoder = value.into();
                 // Nan / infinite values actually get written in quotes as a string value
                 if value.is_infinite() || value.is_nan() {
@@ -109,72 +218,115 @@ impl<'a> JsonValueWriter<'a> {
     }
 
     /// Starts an array.
-    pub fn start_array(self) -> JsonArrayWriter<'a> {
-        JsonArrayWriter::new(self.output)
+    pub fn start_array(self) -> JsonArrayWriter<'a, W> {
+        JsonArrayWriter::nested(self.output, self.style, self.depth)
     }
 
     /// Starts an object.
-    pub fn start_object(self) -> JsonObjectWriter<'a> {
-        JsonObjectWriter::new(self.output)
+    pub fn start_object(self) -> JsonObjectWriter<'a, W> {
+        JsonObjectWriter::nested(self.output, self.style, self.depth)
     }
 }
 
-pub struct JsonObjectWriter<'a> {
-    json: &'a mut String,
+pub struct JsonObjectWriter<'a, W: JsonSink = String> {
+    json: &'a mut W,
+    style: Style,
+    depth: usize,
     started: bool,
 }
 
-impl<'a> JsonObjectWriter<'a> {
-    pub fn new(output: &'a mut String) -> Self {
+impl<'a, W: JsonSink> JsonObjectWriter<'a, W> {
+    pub fn new(output: &'a mut W) -> Self {
+        Self::with_style(output, Style::default())
+    }
+
+    /// Starts an object in `output` that will be written in the given `style`.
+    pub fn with_style(output: &'a mut W, style: Style) -> Self {
+        Self::nested(output, style, 0)
+    }
+
+    fn nested(output: &'a mut W, style: Style, depth: usize) -> Self {
         output.push('{');
         Self {
             json: output,
+            style,
+            depth,
             started: false,
         }
     }
 
     /// Starts a value with the given `key`.
-    pub fn key(&mut self, key: &str) -> JsonValueWriter {
+    pub fn key(&mut self, key: &str) -> JsonValueWriter<'_, W> {
         if self.started {
             self.json.push(',');
         }
         self.started = true;
+        newline_indent(self.json, self.style, self.depth + 1);
 
         self.json.push('"');
         self.json.push_str(&escape_string(key));
-        self.json.push_str("\":");
+        self.json
+            .push_str(if self.style.pretty { "\": " } else { "\":" });
 
-        JsonValueWriter::new(self.json)
+        JsonValueWriter {
+            output: self.json,
+            style: self.style,
+            depth: self.depth + 1,
+        }
     }
 
     /// Finishes the object.
     pub fn finish(self) {
+        if self.started {
+            newline_indent(self.json, self.style, self.depth);
+        }
         self.json.push('}');
     }
 }
 
-pub struct JsonArrayWriter<'a> {
-    json: &'a mut String,
+pub struct JsonArrayWriter<'a, W: JsonSink = String> {
+    json: &'a mut W,
+    style: Style,
+    depth: usize,
     started: bool,
 }
 
-impl<'a> JsonArrayWriter<'a> {
-    pub fn new(output: &'a mut String) -> Self {
+impl<'a, W: JsonSink> JsonArrayWriter<'a, W> {
+    pub fn new(output: &'a mut W) -> Self {
+        Self::with_style(output, Style::default())
+    }
+
+    /// Starts an array in `output` that will be written in the given `style`.
+    pub fn with_style(output: &'a mut W, style: Style) -> Self {
+        Self::nested(output, style, 0)
+    }
+
+    fn nested(output: &'a mut W, style: Style, depth: usize) -> Self {
         output.push('[');
         Self {
             json: output,
+            style,
+            depth,
             started: false,
         }
     }
 
     /// Starts a new value in the array.
-    pub fn value(&mut self) -> JsonValueWriter {
+    pub fn value(&mut self) -> JsonValueWriter<'_, W> {
         self.comma_delimit();
-        JsonValueWriter::new(self.json)
+        newline_indent(self.json, self.style, self.depth + 1);
+        JsonValueWriter {
+            output: self.json,
+            style: self.style,
+            depth: self.depth + 1,
+        }
     }
 
     /// Finishes the array.
     pub fn finish(self) {
+        if self.started {
+            newline_indent(self.json, self.style, self.depth);
+        }
         self.json.push(']');
     }
 
@@ -189,7 +341,7 @@ impl<'a> JsonArrayWriter<'a> {
 #[cfg(test)]
 mod tests {
     use super::{JsonArrayWriter, JsonObjectWriter};
-    use crate::serialize::JsonValueWriter;
+    use crate::serialize::{BufMutSink, FmtSink, JsonValueWriter, Style};
     use aws_smithy_types::date_time::Format;
     use aws_smithy_types::{DateTime, Document, Number};
     use proptest::proptest;
@@ -426,6 +578,127 @@ mod tests {
         );
     }
 
+    fn format_document_with_style(document: &Document, style: Style) -> String {
+        let mut output = String::new();
+        JsonValueWriter::with_style(&mut output, style).document(document);
+        output
+    }
+
+    fn test_document() -> Document {
+        Document::Object(
+            vec![
+                ("zeta".to_string(), Document::Number(Number::Float(2.0))),
+                (
+                    "alpha".to_string(),
+                    Document::Array(vec![
+                        Document::Number(Number::PosInt(1)),
+                        Document::Object(Default::default()),
+                        Document::Array(vec![]),
+                        Document::Object(
+                            vec![("nested".to_string(), Document::Bool(true))]
+                                .into_iter()
+                                .collect(),
+                        ),
+                    ]),
+                ),
+                ("mid".to_string(), Document::String("value".into())),
+            ]
+            .into_iter()
+            .collect(),
+        )
+    }
+
+    #[test]
+    fn pretty() {
+        let mut output = String::new();
+        let mut object = JsonObjectWriter::with_style(&mut output, Style::default().pretty());
+        object.key("empty_object").start_object().finish();
+        object.key("empty_array").start_array().finish();
+        let mut array = object.key("array").start_array();
+        array.value().number(Number::PosInt(1));
+        let mut inner = array.value().start_object();
+        inner.key("foo").string("bar");
+        inner.finish();
+        array.finish();
+        object.finish();
+        assert_eq!(
+            "{\n  \"empty_object\": {},\n  \"empty_array\": [],\n  \"array\": [\n    1,\n    {\n      \"foo\": \"bar\"\n    }\n  ]\n}",
+            &output
+        );
+
+        let mut output = String::new();
+        JsonObjectWriter::with_style(&mut output, Style::default().pretty()).finish();
+        assert_eq!("{}", &output);
+    }
+
+    #[test]
+    fn canonical() {
+        let style = Style::default().canonical();
+        assert_eq!(
+            r#"{"alpha":[1,{},[],{"nested":true}],"mid":"value","zeta":2}"#,
+            format_document_with_style(&test_document(), style)
+        );
+        assert_eq!(
+            "{\n  \"alpha\": [\n    1,\n    {},\n    [],\n    {\n      \"nested\": true\n    }\n  ],\n  \"mid\": \"value\",\n  \"zeta\": 2\n}",
+            format_document_with_style(&test_document(), style.pretty())
+        );
+    }
+
+    #[test]
+    fn canonical_numbers() {
+        let format = |number| {
+            format_document_with_style(&Document::Number(number), Style::compact().canonical())
+        };
+        assert_eq!("1", format(Number::Float(1.0)));
+        assert_eq!("1", format(Number::PosInt(1)));
+        assert_eq!("1", format(Number::NegInt(1)));
+        assert_eq!("0", format(Number::Float(-0.0)));
+        assert_eq!("-5", format(Number::Float(-5.0)));
+        assert_eq!("-1.5", format(Number::Float(-1.5)));
+        assert_eq!(
+            "9007199254740991",
+            format(Number::Float(9007199254740991.0))
+        );
+        assert_eq!("1e300", format(Number::Float(1e300)));
+        assert_eq!("\"NaN\"", format(Number::Float(f64::NAN)));
+        assert_eq!("\"-Infinity\"", format(Number::Float(f64::NEG_INFINITY)));
+    }
+
+    #[test]
+    fn fmt_write_sink() {
+        let mut sink = FmtSink::new(String::new());
+        let mut array = JsonArrayWriter::new(&mut sink);
+        array.value().string("a\nb");
+        array.value().null();
+        array.finish();
+        assert_eq!(r#"["a\nb",null]"#, sink.finish().unwrap());
+
+        struct Failing;
+        impl std::fmt::Write for Failing {
+            fn write_str(&mut self, _: &str) -> std::fmt::Result {
+                Err(std::fmt::Error)
+            }
+        }
+        let mut sink = FmtSink::new(Failing);
+        JsonValueWriter::new(&mut sink).boolean(true);
+        assert!(sink.finish().is_err());
+    }
+
+    #[test]
+    fn buf_mut_sink() {
+        let mut sink = BufMutSink::new(Vec::new());
+        JsonValueWriter::with_style(&mut sink, Style::default().canonical())
+            .document(&test_document());
+        let expected = format_document_with_style(&test_document(), Style::default().canonical());
+        assert_eq!(expected.as_bytes(), &sink.into_inner()[..]);
+
+        let mut sink = BufMutSink::new(bytes::BytesMut::new());
+        let mut object = JsonObjectWriter::with_style(&mut sink, Style::default().pretty());
+        object.key("k").string("ü");
+        object.finish();
+        assert_eq!("{\n  \"k\": \"ü\"\n}".as_bytes(), &sink.into_inner()[..]);
+    }
+
     proptest! {
         #[test]
         fn matches_serde_json_pos_int_format(value: u64) {
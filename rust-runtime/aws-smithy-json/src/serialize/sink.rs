@@ -0,0 +1,94 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use bytes::BufMut;
+use std::fmt;
+
+/// Output that JSON can be written into.
+///
+/// This is implemented for [`String`] directly. Any [`std::fmt::Write`] can be used by wrapping
+/// it in a [`FmtSink`], and any [`bytes::BufMut`] by wrapping it in a [`BufMutSink`].
+pub trait JsonSink {
+    /// Appends `value` to the output.
+    fn push_str(&mut self, value: &str);
+
+    /// Appends a single character to the output.
+    fn push(&mut self, value: char) {
+        self.push_str(value.encode_utf8(&mut [0; 4]));
+    }
+}
+
+impl JsonSink for String {
+    fn push_str(&mut self, value: &str) {
+        String::push_str(self, value);
+    }
+
+    fn push(&mut self, value: char) {
+        String::push(self, value);
+    }
+}
+
+/// Adapts a [`std::fmt::Write`] into a [`JsonSink`].
+///
+/// Since the JSON writers are infallible, the first error returned by the underlying writer is
+/// remembered and all subsequent output is discarded. Call [`FmtSink::finish`] once writing is
+/// complete to find out whether it succeeded.
+#[derive(Debug)]
+pub struct FmtSink<W> {
+    inner: W,
+    failed: bool,
+}
+
+impl<W: fmt::Write> FmtSink<W> {
+    /// Wraps `inner`.
+    pub fn new(inner: W) -> Self {
+        FmtSink {
+            inner,
+            failed: false,
+        }
+    }
+
+    /// Returns the underlying writer, or an error if any write to it failed.
+    pub fn finish(self) -> Result<W, fmt::Error> {
+        match self.failed {
+            true => Err(fmt::Error),
+            false => Ok(self.inner),
+        }
+    }
+}
+
+impl<W: fmt::Write> JsonSink for FmtSink<W> {
+    fn push_str(&mut self, value: &str) {
+        if !self.failed {
+            self.failed = self.inner.write_str(value).is_err();
+        }
+    }
+}
+
+/// Adapts a [`bytes::BufMut`] into a [`JsonSink`].
+///
+/// Like [`BufMut::put_slice`], writing panics if the buffer doesn't have enough remaining capacity.
+#[derive(Debug)]
+pub struct BufMutSink<B> {
+    inner: B,
+}
+
+impl<B: BufMut> BufMutSink<B> {
+    /// Wraps `inner`.
+    pub fn new(inner: B) -> Self {
+        BufMutSink { inner }
+    }
+
+    /// Returns the underlying buffer.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B: BufMut> JsonSink for BufMutSink<B> {
+    fn push_str(&mut self, value: &str) {
+        self.inner.put_slice(value.as_bytes());
+    }
+}
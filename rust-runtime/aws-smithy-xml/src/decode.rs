@@ -10,8 +10,18 @@ use std::error::Error;
 use std::fmt::{Display, Formatter};
 use xmlparser::{ElementEnd, Token, Tokenizer};
 
+pub mod incremental;
+
+pub use incremental::{IncrementalDecoder, Next, XmlEvent};
+
 pub type Depth = usize;
 
+/// Namespace URI that the `xml` prefix is always bound to.
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// Namespace URI of `xmlns` attributes, which declare namespaces.
+const XMLNS_NAMESPACE: &str = "http://www.w3.org/2000/xmlns/";
+
 // in general, these errors are just for reporting what happened, there isn't
 // much value in lots of different match variants
 
@@ -77,6 +87,7 @@ pub struct Attr<'a> {
     name: Name<'a>,
     // attribute values can be escaped (e.g. with double quotes, so we need a Cow)
     value: Cow<'a, str>,
+    namespace: Option<Cow<'a, str>>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -85,6 +96,89 @@ pub struct StartEl<'a> {
     attributes: Vec<Attr<'a>>,
     closed: bool,
     depth: Depth,
+    namespace: Option<Cow<'a, str>>,
+}
+
+/// A namespace prefix bound by an `xmlns` attribute on the element at `depth`
+#[derive(Debug)]
+struct NamespaceBinding<'a> {
+    depth: Depth,
+    prefix: Cow<'a, str>,
+    uri: Cow<'a, str>,
+}
+
+/// The namespace bindings in scope at the current position of a document
+#[derive(Debug, Default)]
+struct NamespaceScopes<'a> {
+    bindings: Vec<NamespaceBinding<'a>>,
+}
+
+impl<'a> NamespaceScopes<'a> {
+    /// Called when an element starts at `depth`. All elements at the same depth or deeper have
+    /// been closed by now, so their bindings are no longer in scope.
+    fn start_element(&mut self, depth: Depth) {
+        while matches!(self.bindings.last(), Some(binding) if binding.depth >= depth) {
+            self.bindings.pop();
+        }
+    }
+
+    /// Records the binding declared by the attribute `prefix:local="value"` of the element at
+    /// `depth` if it's an `xmlns` attribute.
+    fn attribute(&mut self, depth: Depth, prefix: &'a str, local: &'a str, value: Cow<'a, str>) {
+        if let Some(prefix) = bound_prefix(prefix, local) {
+            self.bind(depth, Cow::Borrowed(prefix), value);
+        }
+    }
+
+    /// Binds `prefix` to `uri` for the element at `depth` and its descendants.
+    fn bind(&mut self, depth: Depth, prefix: Cow<'a, str>, uri: Cow<'a, str>) {
+        self.bindings.push(NamespaceBinding { depth, prefix, uri });
+    }
+
+    /// Returns the namespace URI bound to `prefix` (the default namespace if `prefix` is empty).
+    fn resolve(&self, prefix: &str) -> Option<&Cow<'a, str>> {
+        self.bindings
+            .iter()
+            .rev()
+            .find(|binding| binding.prefix == prefix)
+            // `xmlns=""` removes the default namespace
+            .filter(|binding| !binding.uri.is_empty())
+            .map(|binding| &binding.uri)
+    }
+
+    /// Fills in the namespace URIs of `start_el` and its attributes.
+    ///
+    /// `resolved` converts a bound namespace URI into the lifetime of the `StartEl`.
+    fn resolve_start_el<'s, 'e>(
+        &'s self,
+        start_el: &mut StartEl<'e>,
+        resolved: impl Fn(&'s Cow<'a, str>) -> Cow<'e, str>,
+    ) {
+        let lookup = |prefix: &str| match prefix {
+            "xml" => Some(Cow::Borrowed(XML_NAMESPACE)),
+            "xmlns" => Some(Cow::Borrowed(XMLNS_NAMESPACE)),
+            prefix => self.resolve(prefix).map(&resolved),
+        };
+        start_el.namespace = lookup(start_el.name.prefix);
+        for attr in &mut start_el.attributes {
+            attr.namespace = match (attr.name.prefix, attr.name.local) {
+                ("", "xmlns") => Some(Cow::Borrowed(XMLNS_NAMESPACE)),
+                // Unprefixed attributes are never in a namespace, even if there is a default one
+                ("", _) => None,
+                (prefix, _) => lookup(prefix),
+            };
+        }
+    }
+}
+
+/// Returns the prefix bound by the attribute `prefix:local` if it's an `xmlns` attribute
+/// (the empty prefix for the default namespace).
+fn bound_prefix<'a>(prefix: &'a str, local: &'a str) -> Option<&'a str> {
+    match (prefix, local) {
+        ("xmlns", prefix) => Some(prefix),
+        ("", "xmlns") => Some(""),
+        _ => None,
+    }
 }
 
 /// Xml Start Element
@@ -105,6 +199,7 @@ impl<'a> StartEl<'a> {
             attributes: vec![],
             closed: false,
             depth,
+            namespace: None,
         }
     }
 
@@ -118,12 +213,42 @@ impl<'a> StartEl<'a> {
             .map(|attr| attr.value.as_ref())
     }
 
+    /// Retrieve the attribute with the given `local` name in the given `namespace` URI,
+    /// regardless of which prefix the document uses for that namespace.
+    pub fn attr_ns(&self, namespace: &str, local: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attr| attr.name.local == local && attr.namespace.as_deref() == Some(namespace))
+            .map(|attr| attr.value.as_ref())
+    }
+
     /// Returns whether this `StartEl` matches a given name
     /// in `prefix:local` form.
     pub fn matches(&self, pat: &str) -> bool {
         self.name.matches(pat)
     }
 
+    /// Returns whether this `StartEl` has the given `local` name and is in the given `namespace`
+    /// URI, regardless of which prefix the document uses for that namespace.
+    pub fn matches_ns(&self, namespace: &str, local: &str) -> bool {
+        self.name.local == local && self.namespace() == Some(namespace)
+    }
+
+    /// Namespace URI of this element, resolved from the `xmlns` attributes in scope
+    ///
+    /// ```xml
+    /// <a:b xmlns:a="http://example.com">
+    ///                ^^^^^^^^^^^^^^^^^^
+    /// ```
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// Returns true if this element is self-closing (`<a/>`), and therefore has no content
+    pub fn is_self_closing(&self) -> bool {
+        self.closed
+    }
+
     /// Local component of this element's name
     ///
     /// ```xml
@@ -165,6 +290,7 @@ impl<'a> StartEl<'a> {
 pub struct Document<'a> {
     tokenizer: Tokenizer<'a>,
     depth: Depth,
+    namespaces: NamespaceScopes<'a>,
 }
 
 impl<'a> TryFrom<&'a [u8]> for Document<'a> {
@@ -182,9 +308,17 @@ impl<'inp> Document<'inp> {
         Document {
             tokenizer: Tokenizer::from(doc),
             depth: 0,
+            namespaces: NamespaceScopes::default(),
         }
     }
 
+    /// Resolves the namespaces of a start element that was just read
+    fn resolve_namespaces(&self, mut start_el: StartEl<'inp>) -> StartEl<'inp> {
+        self.namespaces
+            .resolve_start_el(&mut start_el, |uri| uri.clone());
+        start_el
+    }
+
     /// "Depth first" iterator
     ///
     /// Unlike [`next_tag()`](ScopedDecoder::next_tag), this method returns the next
@@ -201,7 +335,8 @@ impl<'inp> Document<'inp> {
     /// </Response>
     /// ```
     pub fn next_start_element<'a>(&'a mut self) -> Option<StartEl<'inp>> {
-        next_start_element(self)
+        let start_el = next_start_element(self)?;
+        Some(self.resolve_namespaces(start_el))
     }
 
     /// A scoped reader for the entire document
@@ -260,7 +395,18 @@ impl<'inp> Iterator for Document<'inp> {
                 end: ElementEnd::Empty,
                 ..
             } => self.depth -= 1,
+            Token::Attribute {
+                prefix,
+                local,
+                value,
+                ..
+            } => {
+                let value = unescape(value.as_str()).unwrap_or(Cow::Borrowed(value.as_str()));
+                self.namespaces
+                    .attribute(self.depth - 1, prefix.as_str(), local.as_str(), value);
+            }
             t @ Token::ElementStart { .. } => {
+                self.namespaces.start_element(self.depth);
                 self.depth += 1;
                 // We want the startel and endel to have the same depth, but after the opener,
                 // the parser will be at depth 1. Return the previous depth:
@@ -312,6 +458,7 @@ impl<'inp> ScopedDecoder<'inp, '_> {
     /// ```
     pub fn next_tag<'a>(&'a mut self) -> Option<ScopedDecoder<'inp, 'a>> {
         let next_tag = next_start_element(self)?;
+        let next_tag = self.doc.resolve_namespaces(next_tag);
         Some(self.nested_decoder(next_tag))
     }
 
@@ -376,6 +523,7 @@ fn next_start_element<'a, 'inp>(
                     prefix: prefix.as_str(),
                 },
                 value: unescape(value.as_str()).ok()?,
+                namespace: None,
             }),
             Ok((
                 Token::ElementEnd {
@@ -425,7 +573,7 @@ pub fn try_data<'a, 'inp>(
 
 #[cfg(test)]
 mod test {
-    use crate::decode::{try_data, Attr, Depth, Document, Name, StartEl};
+    use crate::decode::{try_data, Attr, Depth, Document, Name, StartEl, XML_NAMESPACE};
 
     // test helper to create a closed startel
     fn closed<'a>(local: &'a str, prefix: &'a str, depth: Depth) -> StartEl<'a> {
@@ -534,11 +682,43 @@ mod test {
                     prefix: "xsi".into(),
                     local: "type".into()
                 },
-                value: "CanonicalUser".into()
+                value: "CanonicalUser".into(),
+                namespace: None,
             }]
         )
     }
 
+    #[test]
+    fn resolve_namespaces() {
+        let xml = r#"<Response xmlns="urn:default" xmlns:s="urn:s" s:id="1" id="2">
+            <s:Inner xmlns:s="urn:other" xml:lang="en">
+                <Leaf xmlns=""/>
+            </s:Inner>
+            <s:Inner/>
+        </Response>"#;
+        let mut doc = Document::new(xml);
+        let mut root = doc.root_element().unwrap();
+        assert_eq!(Some("urn:default"), root.start_el().namespace());
+        assert!(root.start_el().matches_ns("urn:default", "Response"));
+        assert_eq!(Some("1"), root.start_el().attr_ns("urn:s", "id"));
+        // Unprefixed attributes aren't in the default namespace
+        assert_eq!(None, root.start_el().attr_ns("urn:default", "id"));
+
+        let mut inner = root.next_tag().unwrap();
+        assert!(inner.start_el().matches_ns("urn:other", "Inner"));
+        assert_eq!(Some("en"), inner.start_el().attr_ns(XML_NAMESPACE, "lang"));
+        let leaf = inner.next_tag().unwrap();
+        assert_eq!(None, leaf.start_el().namespace());
+        drop(leaf);
+        assert!(inner.next_tag().is_none());
+        drop(inner);
+
+        // The rebinding of `s` went out of scope with the first `s:Inner`
+        let inner = root.next_tag().unwrap();
+        assert!(inner.start_el().matches_ns("urn:s", "Inner"));
+        assert!(inner.start_el().is_self_closing());
+    }
+
     #[test]
     fn escape_data() {
         let xml = r#"<Response key="&quot;hey&quot;>">&gt;</Response>"#;
@@ -0,0 +1,661 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Pull decoder for XML that arrives in chunks.
+
+use crate::decode::{bound_prefix, Attr, Depth, Name, NamespaceScopes, StartEl, XmlError};
+use crate::unescape::unescape;
+use std::borrow::Cow;
+use std::ops::Range;
+use xmlparser::{ElementEnd, Token, Tokenizer};
+
+/// An XML event returned by [`IncrementalDecoder::next_event`].
+#[derive(Debug, PartialEq)]
+pub enum XmlEvent<'a> {
+    /// The start of an element, including its attributes.
+    ///
+    /// Self-closing elements (`<a/>`) are followed by an [`XmlEvent::EndEl`] like any other element.
+    StartEl(StartEl<'a>),
+    /// The end of the element at `depth`.
+    EndEl { name: Name<'a>, depth: Depth },
+    /// Unescaped character data within an element, including CDATA sections.
+    Text(Cow<'a, str>),
+}
+
+/// Result of asking an [`IncrementalDecoder`] for its next event.
+#[derive(Debug, PartialEq)]
+pub enum Next<'a> {
+    /// A complete event was parsed.
+    Event(XmlEvent<'a>),
+    /// The buffered input ends partway through an event. Feed more input and try again.
+    NeedMoreInput,
+    /// All input has been fed and every event has been returned.
+    EndOfInput,
+}
+
+/// XML decoder that can be fed input one chunk at a time and returns events as soon as they are
+/// complete.
+///
+/// Unlike [`Document`](crate::decode::Document), this doesn't require the whole body to be
+/// buffered. Only the bytes of the current incomplete event are kept between chunks. Namespace
+/// prefixes are resolved the same way, so [`StartEl::namespace`] and [`StartEl::matches_ns`]
+/// can be used on the returned start elements.
+///
+/// Comments, processing instructions, the XML declaration, and `DOCTYPE`s are skipped, as is
+/// whitespace outside of the root element.
+///
+/// By default, an incomplete event can grow without bound as input is fed. Use
+/// [`max_pending_bytes`](IncrementalDecoder::max_pending_bytes) to fail on inputs that never
+/// complete an event instead.
+///
+/// ```
+/// use aws_smithy_xml::decode::{IncrementalDecoder, Next, XmlEvent};
+///
+/// let mut decoder = IncrementalDecoder::new();
+/// let mut chunks = vec![&b"<Keys><Key>a</K"[..], &b"ey><Key>b</Key></Keys>"[..]].into_iter();
+/// let mut keys = Vec::new();
+/// loop {
+///     match decoder.next_event().unwrap() {
+///         Next::Event(XmlEvent::Text(text)) => keys.push(text.into_owned()),
+///         Next::Event(_) => {}
+///         Next::NeedMoreInput => match chunks.next() {
+///             Some(chunk) => decoder.feed(chunk),
+///             None => decoder.finish(),
+///         },
+///         Next::EndOfInput => break,
+///     }
+/// }
+/// assert_eq!(vec!["a", "b"], keys);
+/// ```
+#[derive(Debug, Default)]
+pub struct IncrementalDecoder {
+    buffer: Vec<u8>,
+    /// Index into `buffer` of the first byte that hasn't been decoded yet
+    start: usize,
+    /// How far the search for the end of the event at `start` got
+    scan: Scan,
+    /// Maximum number of bytes an incomplete event can take up in `buffer`
+    max_pending_bytes: Option<usize>,
+    /// Qualified names of the elements that are currently open
+    open: Vec<String>,
+    namespaces: NamespaceScopes<'static>,
+    /// Qualified name of a self-closing element whose end hasn't been returned yet
+    pending_end: Option<String>,
+    /// Qualified name of the most recent end returned for a self-closing element
+    closed_name: String,
+    finished: bool,
+    failed: bool,
+}
+
+/// Progress of the search for the end of the event at the start of the undecoded input, so that
+/// feeding more input doesn't search the same bytes again
+#[derive(Debug, Default)]
+struct Scan {
+    /// Number of undecoded bytes that were searched without finding the end of the event
+    searched: usize,
+    /// Quote of the attribute value that the searched bytes of a start tag end within
+    quote: Option<u8>,
+    /// Terminator of a `DOCTYPE` declaration, once it's known whether it has an internal subset
+    doctype_end: Option<&'static [u8]>,
+}
+
+/// Byte ranges within a start tag
+struct ParsedStartTag {
+    prefix: Range<usize>,
+    local: Range<usize>,
+    attributes: Vec<(Range<usize>, Range<usize>, Range<usize>)>,
+    closed: bool,
+}
+
+impl IncrementalDecoder {
+    /// Creates a decoder with no input.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the number of bytes that an incomplete event can take up.
+    ///
+    /// Once more than `max` bytes have been fed without completing the next event,
+    /// [`next_event`](IncrementalDecoder::next_event) returns an error instead of
+    /// [`Next::NeedMoreInput`].
+    pub fn max_pending_bytes(mut self, max: usize) -> Self {
+        self.max_pending_bytes = Some(max);
+        self
+    }
+
+    /// Appends the next `chunk` of input.
+    ///
+    /// # Panics
+    /// Panics if called after [`finish`](IncrementalDecoder::finish).
+    pub fn feed(&mut self, chunk: &[u8]) {
+        assert!(
+            !self.finished,
+            "cannot feed input after `finish` was called"
+        );
+        // Drop the bytes that have already been decoded
+        if self.start > 0 {
+            self.buffer.drain(..self.start);
+            self.start = 0;
+        }
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Signals that there is no more input.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Returns the next event if enough input has been fed to complete it.
+    ///
+    /// Once an error has been returned, all subsequent calls return [`Next::EndOfInput`].
+    pub fn next_event(&mut self) -> Result<Next<'_>, XmlError> {
+        if self.failed {
+            return Ok(Next::EndOfInput);
+        }
+        let pending_bytes = self.buffer.len() - self.start;
+        match self.advance() {
+            Ok(Some(event)) => self.event(event).map(Next::Event),
+            Ok(None) if self.finished => Ok(Next::EndOfInput),
+            Ok(None) => match self.max_pending_bytes {
+                Some(max) if pending_bytes > max => {
+                    self.failed = true;
+                    Err(XmlError::custom(format!(
+                        "incomplete XML event is larger than the maximum of {} bytes",
+                        max
+                    )))
+                }
+                _ => Ok(Next::NeedMoreInput),
+            },
+            Err(err) => {
+                self.failed = true;
+                Err(err)
+            }
+        }
+    }
+
+    /// Finds the next complete event and updates the decoder's state past it, returning what
+    /// kind of event it is and where it is in the buffer. Returns `None` if more input is needed.
+    ///
+    /// The event is fully validated here so that building it with [`IncrementalDecoder::event`]
+    /// can't fail.
+    fn advance(&mut self) -> Result<Option<Pending>, XmlError> {
+        if let Some(name) = self.pending_end.take() {
+            self.closed_name = name;
+            return Ok(Some(Pending::ClosedEnd));
+        }
+        loop {
+            let rest = &self.buffer[self.start..];
+            if rest.is_empty() {
+                return match (self.finished, self.open.last()) {
+                    (true, Some(name)) => Err(XmlError::custom(format!(
+                        "unexpected end of input: element `{}` was not closed",
+                        name
+                    ))),
+                    _ => Ok(None),
+                };
+            }
+            let unit_start = self.start;
+            if rest[0] != b'<' {
+                let len = match find(rest, b"<", &mut self.scan) {
+                    Some(len) => len,
+                    // Text might continue in the next chunk
+                    None if !self.finished => return Ok(None),
+                    None => rest.len(),
+                };
+                let outside_root = self.open.is_empty();
+                if outside_root && !rest[..len].iter().all(u8::is_ascii_whitespace) {
+                    return Err(XmlError::custom("found text outside of the root element"));
+                }
+                self.consume(len);
+                if outside_root {
+                    continue;
+                }
+                let range = unit_start..unit_start + len;
+                unescape(utf8(&self.buffer[range.clone()])?)?;
+                return Ok(Some(Pending::Text(range)));
+            }
+
+            let could_become =
+                |markup: &[u8]| rest.len() < markup.len() && markup.starts_with(rest);
+            if !self.finished && (could_become(b"<![CDATA[") || could_become(b"<!--")) {
+                // Could still turn out to be a comment or CDATA section. Deciding what kind of
+                // markup this is only once it's known keeps the saved scan progress valid.
+                return Ok(None);
+            }
+            let (terminator, skip): (&[u8], bool) = if rest.starts_with(b"<?") {
+                (b"?>", true)
+            } else if rest.starts_with(b"<!--") {
+                (b"-->", true)
+            } else if rest.starts_with(b"<![CDATA[") {
+                (b"]]>", false)
+            } else if rest.starts_with(b"<!") {
+                // DOCTYPE declarations can contain `>` in an internal subset
+                match doctype_end(rest, &mut self.scan) {
+                    Some(terminator) => (terminator, true),
+                    None => return self.incomplete(),
+                }
+            } else if rest.starts_with(b"</") {
+                (b">", false)
+            } else {
+                match find_tag_end(rest, &mut self.scan) {
+                    Some(len) => {
+                        self.consume(len);
+                        return self.start_tag(unit_start..unit_start + len).map(Some);
+                    }
+                    None => return self.incomplete(),
+                }
+            };
+            let len = match find(rest, terminator, &mut self.scan) {
+                Some(index) => index + terminator.len(),
+                None => return self.incomplete(),
+            };
+            self.consume(len);
+            if skip {
+                continue;
+            }
+            let range = unit_start..unit_start + len;
+            return if self.buffer[range.clone()].starts_with(b"<![CDATA[") {
+                if self.open.is_empty() {
+                    return Err(XmlError::custom("found CDATA outside of the root element"));
+                }
+                let range = range.start + 9..range.end - 3;
+                utf8(&self.buffer[range.clone()])?;
+                Ok(Some(Pending::CData(range)))
+            } else {
+                self.end_tag(range).map(Some)
+            };
+        }
+    }
+
+    /// Marks the next `len` bytes as decoded
+    fn consume(&mut self, len: usize) {
+        self.start += len;
+        self.scan = Scan::default();
+    }
+
+    fn incomplete(&self) -> Result<Option<Pending>, XmlError> {
+        match self.finished {
+            true => Err(XmlError::custom("unexpected end of input inside markup")),
+            false => Ok(None),
+        }
+    }
+
+    fn start_tag(&mut self, range: Range<usize>) -> Result<Pending, XmlError> {
+        let tag = utf8(&self.buffer[range.clone()])?;
+        let mut parsed = ParsedStartTag {
+            prefix: 0..0,
+            local: 0..0,
+            attributes: Vec::new(),
+            closed: false,
+        };
+        for token in Tokenizer::from_fragment(tag, 0..tag.len()) {
+            match token? {
+                Token::ElementStart { prefix, local, .. } => {
+                    parsed.prefix = prefix.range();
+                    parsed.local = local.range();
+                }
+                Token::Attribute {
+                    prefix,
+                    local,
+                    value,
+                    ..
+                } => parsed
+                    .attributes
+                    .push((prefix.range(), local.range(), value.range())),
+                Token::ElementEnd { end, .. } => {
+                    parsed.closed = matches!(end, ElementEnd::Empty);
+                    break;
+                }
+                other => {
+                    return Err(XmlError::custom(format!(
+                        "unexpected token in start tag: {:?}",
+                        other
+                    )))
+                }
+            }
+        }
+
+        let depth = self.open.len();
+        self.namespaces.start_element(depth);
+        for (prefix, local, value) in &parsed.attributes {
+            unescape(&tag[value.clone()])?;
+            // The bindings outlive this part of the buffer, so they have to be copied
+            if let Some(prefix) = bound_prefix(&tag[prefix.clone()], &tag[local.clone()]) {
+                let uri = unescape(&tag[value.clone()])?.into_owned();
+                self.namespaces
+                    .bind(depth, Cow::Owned(prefix.to_string()), Cow::Owned(uri));
+            }
+        }
+        let qualified_name = match parsed.prefix.is_empty() {
+            true => tag[parsed.local.clone()].to_string(),
+            false => tag[parsed.prefix.start..parsed.local.end].to_string(),
+        };
+        if parsed.closed {
+            self.pending_end = Some(qualified_name);
+        } else {
+            self.open.push(qualified_name);
+        }
+        Ok(Pending::StartEl(range, parsed, depth))
+    }
+
+    fn end_tag(&mut self, range: Range<usize>) -> Result<Pending, XmlError> {
+        let tag = utf8(&self.buffer[range.clone()])?;
+        let name = tag[2..tag.len() - 1].trim_end();
+        match self.open.pop() {
+            Some(open) if open == name => {}
+            Some(open) => {
+                return Err(XmlError::custom(format!(
+                    "expected `</{}>` but found `{}`",
+                    open, tag
+                )))
+            }
+            None => {
+                return Err(XmlError::custom(format!(
+                    "found `{}` outside of the root element",
+                    tag
+                )))
+            }
+        }
+        Ok(Pending::EndEl(
+            range.start + 2..range.start + 2 + name.len(),
+        ))
+    }
+
+    /// Builds the event for `pending` out of the buffer. `pending` was already validated by
+    /// [`IncrementalDecoder::advance`].
+    fn event(&self, pending: Pending) -> Result<XmlEvent<'_>, XmlError> {
+        Ok(match pending {
+            Pending::StartEl(range, parsed, depth) => {
+                let tag = utf8(&self.buffer[range])?;
+                let mut start_el = StartEl::new(&tag[parsed.local], &tag[parsed.prefix], depth);
+                start_el.closed = parsed.closed;
+                for (prefix, local, value) in parsed.attributes {
+                    start_el.attributes.push(Attr {
+                        name: Name {
+                            prefix: &tag[prefix],
+                            local: &tag[local],
+                        },
+                        value: unescape(&tag[value])?,
+                        namespace: None,
+                    });
+                }
+                self.namespaces
+                    .resolve_start_el(&mut start_el, |uri| Cow::Borrowed(uri.as_ref()));
+                XmlEvent::StartEl(start_el)
+            }
+            Pending::EndEl(range) => XmlEvent::EndEl {
+                name: split_name(utf8(&self.buffer[range])?),
+                depth: self.open.len(),
+            },
+            Pending::ClosedEnd => XmlEvent::EndEl {
+                name: split_name(&self.closed_name),
+                depth: self.open.len(),
+            },
+            Pending::Text(range) => XmlEvent::Text(unescape(utf8(&self.buffer[range])?)?),
+            Pending::CData(range) => XmlEvent::Text(Cow::Borrowed(utf8(&self.buffer[range])?)),
+        })
+    }
+}
+
+/// An event whose bytes are still in the buffer
+enum Pending {
+    StartEl(Range<usize>, ParsedStartTag, Depth),
+    EndEl(Range<usize>),
+    /// The end of a self-closing element
+    ClosedEnd,
+    Text(Range<usize>),
+    CData(Range<usize>),
+}
+
+fn utf8(bytes: &[u8]) -> Result<&str, XmlError> {
+    std::str::from_utf8(bytes).map_err(XmlError::unhandled)
+}
+
+fn split_name(qualified_name: &str) -> Name<'_> {
+    match qualified_name.find(':') {
+        Some(index) => Name {
+            prefix: &qualified_name[..index],
+            local: &qualified_name[index + 1..],
+        },
+        None => Name {
+            prefix: "",
+            local: qualified_name,
+        },
+    }
+}
+
+/// Returns the index of the first occurrence of `needle` in `haystack`, resuming the search
+/// where `scan` left off
+fn find(haystack: &[u8], needle: &[u8], scan: &mut Scan) -> Option<usize> {
+    // The needle can start within the bytes that were already searched
+    let from = scan.searched.saturating_sub(needle.len() - 1);
+    let found = haystack[from..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|index| from + index);
+    if found.is_none() {
+        scan.searched = haystack.len();
+    }
+    found
+}
+
+/// Returns the terminator of the `DOCTYPE` declaration at the beginning of `input`, or `None` if
+/// it can't be told yet whether the declaration has an internal subset
+fn doctype_end(input: &[u8], scan: &mut Scan) -> Option<&'static [u8]> {
+    if scan.doctype_end.is_none() {
+        match input[scan.searched..]
+            .iter()
+            .position(|byte| *byte == b'[' || *byte == b'>')
+        {
+            Some(index) => {
+                scan.searched += index;
+                scan.doctype_end = Some(match input[scan.searched] {
+                    b'[' => b"]>",
+                    _ => b">",
+                });
+            }
+            None => scan.searched = input.len(),
+        }
+    }
+    scan.doctype_end
+}
+
+/// Returns the length of the start tag at the beginning of `input`, including the closing `>`,
+/// skipping over any `>` within quoted attribute values. The search resumes where `scan` left
+/// off.
+fn find_tag_end(input: &[u8], scan: &mut Scan) -> Option<usize> {
+    for (index, byte) in input.iter().enumerate().skip(scan.searched) {
+        match (scan.quote, byte) {
+            (None, b'"') | (None, b'\'') => scan.quote = Some(*byte),
+            (Some(open), byte) if open == *byte => scan.quote = None,
+            (None, b'>') => return Some(index + 1),
+            _ => {}
+        }
+    }
+    scan.searched = input.len();
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::{IncrementalDecoder, Next, XmlEvent};
+
+    /// Decodes `input` fed in chunks split at `splits` into a readable list of events
+    fn decode(input: &str, splits: &[usize]) -> Result<Vec<String>, String> {
+        let input = input.as_bytes();
+        let mut chunks = Vec::new();
+        let mut last = 0;
+        for split in splits {
+            chunks.push(&input[last..*split]);
+            last = *split;
+        }
+        chunks.push(&input[last..]);
+        let mut chunks = chunks.into_iter();
+
+        let mut decoder = IncrementalDecoder::new();
+        let mut events = Vec::new();
+        loop {
+            match decoder.next_event().map_err(|err| err.to_string())? {
+                Next::Event(XmlEvent::StartEl(start_el)) => events.push(format!(
+                    "start {}:{} ns={} depth={}{}",
+                    start_el.prefix(),
+                    start_el.local(),
+                    start_el.namespace().unwrap_or("-"),
+                    start_el.depth(),
+                    start_el
+                        .attributes
+                        .iter()
+                        .map(|attr| format!(" {}={}", attr.name.local, attr.value))
+                        .collect::<String>()
+                )),
+                Next::Event(XmlEvent::EndEl { name, depth }) => events.push(format!(
+                    "end {}:{} depth={}",
+                    name.prefix, name.local, depth
+                )),
+                Next::Event(XmlEvent::Text(text)) => events.push(format!("text {:?}", text)),
+                Next::NeedMoreInput => match chunks.next() {
+                    Some(chunk) => decoder.feed(chunk),
+                    None => decoder.finish(),
+                },
+                Next::EndOfInput => return Ok(events),
+            }
+        }
+    }
+
+    const DOCUMENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- comment -->
+<ListResult xmlns="http://s3.example.com/" xmlns:x="http://x.example.com/">
+  <Contents><Key>a&amp;b</Key><x:Size a="1 &gt; 0">5</x:Size></Contents>
+  <Empty/><Data><![CDATA[<raw>]]></Data>
+</ListResult>
+"#;
+
+    fn expected() -> Vec<String> {
+        vec![
+            "start :ListResult ns=http://s3.example.com/ depth=0 xmlns=http://s3.example.com/ x=http://x.example.com/",
+            "text \"\\n  \"",
+            "start :Contents ns=http://s3.example.com/ depth=1",
+            "start :Key ns=http://s3.example.com/ depth=2",
+            "text \"a&b\"",
+            "end :Key depth=2",
+            "start x:Size ns=http://x.example.com/ depth=2 a=1 > 0",
+            "text \"5\"",
+            "end x:Size depth=2",
+            "end :Contents depth=1",
+            "text \"\\n  \"",
+            "start :Empty ns=http://s3.example.com/ depth=1",
+            "end :Empty depth=1",
+            "start :Data ns=http://s3.example.com/ depth=1",
+            "text \"<raw>\"",
+            "end :Data depth=1",
+            "text \"\\n\"",
+            "end :ListResult depth=0",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect()
+    }
+
+    #[test]
+    fn whole_document() {
+        assert_eq!(Ok(expected()), decode(DOCUMENT, &[]));
+    }
+
+    #[test]
+    fn byte_at_a_time() {
+        let splits: Vec<usize> = (1..DOCUMENT.len()).collect();
+        let events = decode(DOCUMENT, &splits).unwrap();
+        // Text can be split up into several events when fed byte-by-byte, so join adjacent text
+        let mut joined: Vec<String> = Vec::new();
+        for event in events {
+            match (joined.last_mut(), event.strip_prefix("text ")) {
+                (Some(last), Some(text)) if last.starts_with("text ") => {
+                    last.truncate(last.len() - 1);
+                    last.push_str(&text[1..]);
+                }
+                _ => joined.push(event),
+            }
+        }
+        assert_eq!(expected(), joined);
+    }
+
+    #[test]
+    fn markup_split_across_chunks() {
+        for split in 1..DOCUMENT.len() {
+            let events = decode(DOCUMENT, &[split]).unwrap();
+            let starts = events.iter().filter(|e| e.starts_with("start")).count();
+            assert_eq!(6, starts, "split at {}", split);
+        }
+    }
+
+    #[test]
+    fn markup_containing_gt_split_across_chunks() {
+        let input =
+            r#"<!DOCTYPE a [<!ENTITY b "c">]><!-- d > e --><a f="g > h"><![CDATA[i > j]]></a>"#;
+        let expected = vec![
+            "start :a ns=- depth=0 f=g > h".to_string(),
+            "text \"i > j\"".to_string(),
+            "end :a depth=0".to_string(),
+        ];
+        let splits: Vec<usize> = (1..input.len()).collect();
+        assert_eq!(Ok(expected.clone()), decode(input, &splits));
+        for split in 1..input.len() {
+            assert_eq!(
+                Ok(expected.clone()),
+                decode(input, &[split]),
+                "split at {}",
+                split
+            );
+        }
+    }
+
+    #[test]
+    fn max_pending_bytes() {
+        let mut decoder = IncrementalDecoder::new().max_pending_bytes(8);
+        decoder.feed(b"<a>text</a><b>");
+        // complete events are decoded no matter how much input was fed at once
+        assert!(matches!(decoder.next_event(), Ok(Next::Event(_))));
+        decoder.feed(b"0123");
+        assert!(matches!(decoder.next_event(), Ok(Next::Event(_))));
+        assert!(matches!(decoder.next_event(), Ok(Next::Event(_))));
+        assert!(matches!(decoder.next_event(), Ok(Next::Event(_))));
+        assert!(matches!(decoder.next_event(), Ok(Next::NeedMoreInput)));
+        decoder.feed(b"45678");
+        let err = decoder.next_event().expect_err("pending text is too large");
+        assert!(err.to_string().contains("maximum of 8 bytes"), "{}", err);
+        assert!(matches!(decoder.next_event(), Ok(Next::EndOfInput)));
+    }
+
+    #[test]
+    fn prefix_rebinding() {
+        assert_eq!(
+            Ok(vec![
+                "start a:Root ns=urn:one depth=0 a=urn:one".to_string(),
+                "start a:Child ns=urn:two depth=1 a=urn:two".to_string(),
+                "end a:Child depth=1".to_string(),
+                "start a:Child ns=urn:one depth=1".to_string(),
+                "end a:Child depth=1".to_string(),
+                "end a:Root depth=0".to_string(),
+            ]),
+            decode(
+                r#"<a:Root xmlns:a="urn:one"><a:Child xmlns:a="urn:two"/><a:Child></a:Child></a:Root>"#,
+                &[]
+            )
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert!(decode("<a></b>", &[])
+            .unwrap_err()
+            .contains("expected `</a>`"));
+        assert!(decode("<a>", &[]).unwrap_err().contains("was not closed"));
+        assert!(decode("<a", &[1]).unwrap_err().contains("inside markup"));
+        assert!(decode("text", &[])
+            .unwrap_err()
+            .contains("outside of the root"));
+        assert!(decode("<a b=></a>", &[]).is_err());
+    }
+}
@@ -9,6 +9,9 @@
 use crate::escape::escape;
 use std::fmt::{self, Display, Formatter, Write};
 
+mod sink;
+pub use sink::{BufMutSink, ChunkedSink};
+
 // currently there's actually no way that encoding can fail but give it time :-)
 #[derive(Debug)]
 pub enum Error {}
@@ -45,31 +48,46 @@ impl Display for Error {
 /// assert_eq!(s, "<Root xmlns=\"http://example.com\">hello</Root>");
 /// ```
 ///
+/// The document can be written into any [`std::fmt::Write`], not just a `String`. Use a
+/// [`BufMutSink`] to write directly into a [`bytes::BufMut`], or a [`ChunkedSink`] to hand off
+/// the document in chunks as it's written. Writing panics if the underlying writer returns an
+/// error, so it should be infallible like the ones provided here:
+/// ```rust
+/// use aws_smithy_xml::encode::{ChunkedSink, XmlWriter};
+/// let mut chunks = Vec::new();
+/// let mut sink = ChunkedSink::new(8, |chunk| chunks.push(chunk));
+/// let mut doc = XmlWriter::new(&mut sink);
+/// doc.start_el("Root").finish().data("hello, world");
+/// sink.finish();
+/// assert_eq!(chunks.concat(), b"<Root>hello, world</Root>");
+/// assert_eq!(chunks.len(), 4);
+/// ```
+///
 /// See `tests/handwritten_serializers.rs` for more usage examples.
-pub struct XmlWriter<'a> {
-    doc: &'a mut String,
+pub struct XmlWriter<'a, W: Write = String> {
+    doc: &'a mut W,
 }
 
-impl<'a> XmlWriter<'a> {
-    pub fn new(doc: &'a mut String) -> Self {
+impl<'a, W: Write> XmlWriter<'a, W> {
+    pub fn new(doc: &'a mut W) -> Self {
         Self { doc }
     }
 }
 
-impl<'a> XmlWriter<'a> {
-    pub fn start_el<'b, 'c>(&'c mut self, tag: &'b str) -> ElWriter<'c, 'b> {
+impl<'a, W: Write> XmlWriter<'a, W> {
+    pub fn start_el<'b, 'c>(&'c mut self, tag: &'b str) -> ElWriter<'c, 'b, W> {
         write!(self.doc, "<{}", tag).unwrap();
         ElWriter::new(self.doc, tag)
     }
 }
 
-pub struct ElWriter<'a, 'b> {
+pub struct ElWriter<'a, 'b, W: Write = String> {
     start: &'b str,
-    doc: Option<&'a mut String>,
+    doc: Option<&'a mut W>,
 }
 
-impl<'a, 'b> ElWriter<'a, 'b> {
-    fn new(doc: &'a mut String, start: &'b str) -> ElWriter<'a, 'b> {
+impl<'a, 'b, W: Write> ElWriter<'a, 'b, W> {
+    fn new(doc: &'a mut W, start: &'b str) -> ElWriter<'a, 'b, W> {
         ElWriter {
             start,
             doc: Some(doc),
@@ -91,11 +109,11 @@ impl<'a, 'b> ElWriter<'a, 'b> {
         self
     }
 
-    fn write_end(doc: &mut String) {
+    fn write_end(doc: &mut W) {
         write!(doc, ">").unwrap();
     }
 
-    fn doc<'c>(&'c mut self) -> &'c mut String
+    fn doc<'c>(&'c mut self) -> &'c mut W
     where
         'a: 'c,
     {
@@ -112,7 +130,7 @@ impl<'a, 'b> ElWriter<'a, 'b> {
         self.doc.as_mut().unwrap()
     }
 
-    pub fn finish(mut self) -> ScopeWriter<'a, 'b> {
+    pub fn finish(mut self) -> ScopeWriter<'a, 'b, W> {
         let doc = self.doc.take().unwrap();
         Self::write_end(doc);
         ScopeWriter {
@@ -122,7 +140,7 @@ impl<'a, 'b> ElWriter<'a, 'b> {
     }
 }
 
-impl Drop for ElWriter<'_, '_> {
+impl<W: Write> Drop for ElWriter<'_, '_, W> {
     fn drop(&mut self) {
         if let Some(doc) = self.doc.take() {
             // Calls to write_end() are always preceded by self.doc.take(). The value in self.doc
@@ -134,18 +152,18 @@ impl Drop for ElWriter<'_, '_> {
 }
 
 /// Wrap the construction of a tag pair `<a></a>`
-pub struct ScopeWriter<'a, 'b> {
-    doc: &'a mut String,
+pub struct ScopeWriter<'a, 'b, W: Write = String> {
+    doc: &'a mut W,
     start: &'b str,
 }
 
-impl Drop for ScopeWriter<'_, '_> {
+impl<W: Write> Drop for ScopeWriter<'_, '_, W> {
     fn drop(&mut self) {
         write!(self.doc, "</{}>", self.start).unwrap();
     }
 }
 
-impl ScopeWriter<'_, '_> {
+impl<W: Write> ScopeWriter<'_, '_, W> {
     pub fn data(&mut self, data: &str) {
         self.doc.write_str(escape(data).as_ref()).unwrap();
     }
@@ -154,7 +172,7 @@ impl ScopeWriter<'_, '_> {
         // drop will be called which writes the closer to the document
     }
 
-    pub fn start_el<'b, 'c>(&'c mut self, tag: &'b str) -> ElWriter<'c, 'b> {
+    pub fn start_el<'b, 'c>(&'c mut self, tag: &'b str) -> ElWriter<'c, 'b, W> {
         write!(self.doc, "<{}", tag).unwrap();
         ElWriter::new(self.doc, tag)
     }
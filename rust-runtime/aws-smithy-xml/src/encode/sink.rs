@@ -0,0 +1,127 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use bytes::{BufMut, Bytes, BytesMut};
+use std::fmt;
+
+/// Adapts a [`bytes::BufMut`] so that an [`XmlWriter`](crate::encode::XmlWriter) can write into it.
+///
+/// Like [`BufMut::put_slice`], writing panics if the buffer doesn't have enough remaining capacity.
+#[derive(Debug)]
+pub struct BufMutSink<B> {
+    inner: B,
+}
+
+impl<B: BufMut> BufMutSink<B> {
+    /// Wraps `inner`.
+    pub fn new(inner: B) -> Self {
+        BufMutSink { inner }
+    }
+
+    /// Returns the underlying buffer.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B: BufMut> fmt::Write for BufMutSink<B> {
+    fn write_str(&mut self, value: &str) -> fmt::Result {
+        self.inner.put_slice(value.as_bytes());
+        Ok(())
+    }
+}
+
+/// Splits the output of an [`XmlWriter`](crate::encode::XmlWriter) into chunks of `chunk_size`
+/// bytes, passing each one to a callback as soon as it's full.
+///
+/// This makes it possible to start sending a large document, such as the body of a request,
+/// before it has been completely written. Call [`ChunkedSink::finish`] once the document is
+/// complete to pass along the last partial chunk.
+pub struct ChunkedSink<F> {
+    buffer: BytesMut,
+    chunk_size: usize,
+    on_chunk: F,
+}
+
+impl<F: FnMut(Bytes)> ChunkedSink<F> {
+    /// Creates a sink that passes chunks of `chunk_size` bytes to `on_chunk`.
+    ///
+    /// # Panics
+    /// Panics if `chunk_size` is zero.
+    pub fn new(chunk_size: usize, on_chunk: F) -> Self {
+        assert!(chunk_size > 0, "chunk_size must be greater than zero");
+        ChunkedSink {
+            buffer: BytesMut::with_capacity(chunk_size),
+            chunk_size,
+            on_chunk,
+        }
+    }
+
+    /// Passes any remaining output to the callback as a final, possibly shorter, chunk.
+    pub fn finish(mut self) {
+        if !self.buffer.is_empty() {
+            (self.on_chunk)(self.buffer.split().freeze());
+        }
+    }
+}
+
+impl<F> fmt::Debug for ChunkedSink<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChunkedSink")
+            .field("buffered", &self.buffer.len())
+            .field("chunk_size", &self.chunk_size)
+            .finish()
+    }
+}
+
+impl<F: FnMut(Bytes)> fmt::Write for ChunkedSink<F> {
+    fn write_str(&mut self, value: &str) -> fmt::Result {
+        self.buffer.extend_from_slice(value.as_bytes());
+        while self.buffer.len() >= self.chunk_size {
+            (self.on_chunk)(self.buffer.split_to(self.chunk_size).freeze());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::encode::{BufMutSink, ChunkedSink, XmlWriter};
+    use bytes::Bytes;
+
+    fn write_document<W: std::fmt::Write>(out: &mut W) {
+        let mut doc = XmlWriter::new(out);
+        let mut root = doc.start_el("Delete").finish();
+        for key in ["a", "b&c"] {
+            let mut object = root.start_el("Object").finish();
+            object.start_el("Key").finish().data(key);
+        }
+    }
+
+    const EXPECTED: &str =
+        "<Delete><Object><Key>a</Key></Object><Object><Key>b&amp;c</Key></Object></Delete>";
+
+    #[test]
+    fn buf_mut_sink() {
+        let mut sink = BufMutSink::new(Vec::new());
+        write_document(&mut sink);
+        assert_eq!(EXPECTED.as_bytes(), sink.into_inner());
+    }
+
+    #[test]
+    fn chunked_sink() {
+        for chunk_size in 1..EXPECTED.len() + 2 {
+            let mut chunks: Vec<Bytes> = Vec::new();
+            let mut sink = ChunkedSink::new(chunk_size, |chunk| chunks.push(chunk));
+            write_document(&mut sink);
+            sink.finish();
+
+            assert_eq!(EXPECTED.as_bytes(), chunks.concat());
+            let (last, full) = chunks.split_last().unwrap();
+            assert!(full.iter().all(|chunk| chunk.len() == chunk_size));
+            assert!(!last.is_empty() && last.len() <= chunk_size);
+        }
+    }
+}
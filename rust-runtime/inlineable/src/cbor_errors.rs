@@ -0,0 +1,170 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_smithy_cbor::decode::token::skip_value;
+use aws_smithy_cbor::decode::{cbor_token_iter, Error as DeserializeError, Token};
+use aws_smithy_types::Error as SmithyError;
+use bytes::Bytes;
+use http::{HeaderMap, HeaderValue};
+use std::borrow::Cow;
+
+// currently only used by RpcV2Cbor
+#[allow(unused)]
+pub fn is_error<B>(response: &http::Response<B>) -> bool {
+    !response.status().is_success()
+}
+
+fn sanitize_error_code(error_code: &str) -> &str {
+    // Trim a trailing URL from the error code, beginning with a `:`
+    let error_code = match error_code.find(':') {
+        Some(idx) => &error_code[..idx],
+        None => error_code,
+    };
+
+    // Trim a prefixing namespace from the error code, beginning with a `#`
+    match error_code.find('#') {
+        Some(idx) => &error_code[idx + 1..],
+        None => error_code,
+    }
+}
+
+struct ErrorBody<'a> {
+    code: Option<Cow<'a, str>>,
+    message: Option<Cow<'a, str>>,
+}
+
+fn parse_error_body(bytes: &[u8]) -> Result<ErrorBody<'_>, DeserializeError> {
+    let mut tokens = cbor_token_iter(bytes).peekable();
+    let (mut typ, mut message) = (None, None);
+    if let Some(Token::StartMap { .. }) = tokens.next().transpose()? {
+        loop {
+            match tokens.next().transpose()? {
+                Some(Token::EndMap { .. }) => break,
+                Some(Token::MapKey { key, .. }) => {
+                    if let Some(Ok(Token::ValueString { value, .. })) = tokens.peek() {
+                        match key.as_ref() {
+                            "__type" => typ = Some(value.clone()),
+                            "message" | "Message" | "errorMessage" => message = Some(value.clone()),
+                            _ => {}
+                        }
+                    }
+                    skip_value(&mut tokens)?;
+                }
+                _ => return Err(DeserializeError::custom("expected map key or end map")),
+            }
+        }
+        if tokens.next().is_some() {
+            return Err(DeserializeError::custom(
+                "found more CBOR tokens after completing parsing",
+            ));
+        }
+    }
+    Ok(ErrorBody { code: typ, message })
+}
+
+fn request_id(headers: &HeaderMap<HeaderValue>) -> Option<&str> {
+    headers
+        .get("X-Amzn-Requestid")
+        .and_then(|v| v.to_str().ok())
+}
+
+pub fn parse_generic_error(
+    payload: &Bytes,
+    headers: &HeaderMap<HeaderValue>,
+) -> Result<SmithyError, DeserializeError> {
+    let ErrorBody { code, message } = parse_error_body(payload.as_ref())?;
+
+    let mut err_builder = SmithyError::builder();
+    if let Some(code) = code.as_deref().map(sanitize_error_code) {
+        err_builder.code(code);
+    }
+    if let Some(message) = message {
+        err_builder.message(message);
+    }
+    if let Some(request_id) = request_id(headers) {
+        err_builder.request_id(request_id);
+    }
+    Ok(err_builder.build())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cbor_errors::{parse_error_body, parse_generic_error, sanitize_error_code};
+    use aws_smithy_cbor::encode::CborObjectWriter;
+    use aws_smithy_types::Error;
+    use bytes::Bytes;
+    use std::borrow::Cow;
+
+    fn error_body(fields: &[(&str, &str)]) -> Vec<u8> {
+        let mut output = Vec::new();
+        let mut object = CborObjectWriter::new(&mut output);
+        for (key, value) in fields {
+            object.key(key).string(value);
+        }
+        object.finish();
+        output
+    }
+
+    #[test]
+    fn generic_error() {
+        let response = http::Response::builder()
+            .header("X-Amzn-Requestid", "1234")
+            .body(Bytes::from(error_body(&[
+                ("__type", "aws.protocoltests.rpcv2Cbor#FooError"),
+                ("message", "Go to foo"),
+            ])))
+            .unwrap();
+        assert_eq!(
+            parse_generic_error(response.body(), response.headers()).unwrap(),
+            Error::builder()
+                .code("FooError")
+                .message("Go to foo")
+                .request_id("1234")
+                .build()
+        )
+    }
+
+    #[test]
+    fn error_type() {
+        let body = error_body(&[("__type", "FooError")]);
+        assert_eq!(
+            Some(Cow::Borrowed("FooError")),
+            parse_error_body(&body).unwrap().code
+        );
+    }
+
+    #[test]
+    fn ignore_unrecognized_fields() {
+        let mut body = Vec::new();
+        let mut object = CborObjectWriter::new(&mut body);
+        object.key("asdf").start_array().finish();
+        object.key("__type").string("FooError");
+        object.key("fdsa").start_object().finish();
+        object.finish();
+        assert_eq!(
+            Some(Cow::Borrowed("FooError")),
+            parse_error_body(&body).unwrap().code
+        );
+    }
+
+    #[test]
+    fn empty_body() {
+        let body = parse_error_body(&[]).unwrap();
+        assert_eq!(None, body.code);
+        assert_eq!(None, body.message);
+    }
+
+    #[test]
+    fn sanitize_namespace_and_url() {
+        assert_eq!(
+            sanitize_error_code("aws.protocoltests.rpcv2Cbor#FooError:http://internal.amazon.com/coral/com.amazon.coral.validate/"),
+            "FooError");
+    }
+
+    #[test]
+    fn sanitize_noop() {
+        assert_eq!(sanitize_error_code("FooError"), "FooError");
+    }
+}